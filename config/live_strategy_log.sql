@@ -14,4 +14,19 @@ processing_time_us INTEGER -- Processing time (microseconds), reflecting cache v
 
 -- Basic index
 CREATE INDEX idx_live_strategy_time ON live_strategy_log(timestamp DESC);
-CREATE INDEX idx_live_strategy_symbol ON live_strategy_log(strategy_id, symbol);
\ No newline at end of file
+CREATE INDEX idx_live_strategy_symbol ON live_strategy_log(strategy_id, symbol);
+
+-- Periodic P&L snapshots of a paper trading session, for equity charts that
+-- survive restarts (reuse the session id to continue a session)
+CREATE TABLE live_pnl_snapshot (
+id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+session_id VARCHAR(100) NOT NULL,
+strategy_id VARCHAR(50) NOT NULL,
+timestamp TIMESTAMPTZ NOT NULL,
+portfolio_value DECIMAL(18,8) NOT NULL,
+realized_pnl DECIMAL(18,8) NOT NULL,
+unrealized_pnl DECIMAL(18,8) NOT NULL,
+position_count INTEGER NOT NULL
+);
+
+CREATE INDEX idx_live_pnl_session_time ON live_pnl_snapshot(session_id, timestamp);
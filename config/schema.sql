@@ -90,6 +90,64 @@ CREATE UNIQUE INDEX idx_tick_unique ON tick_data(symbol, trade_id, timestamp);
 -- - Essential for backtesting: ensures performance of historical data analysis
 CREATE INDEX idx_tick_timestamp ON tick_data(timestamp);
 
+-- =================================================================
+-- Symbol Metadata: exchange trading rules per symbol
+-- Populated from the exchange's exchangeInfo; backtests read the lot
+-- size and minimum notional from here instead of hardcoding them
+-- =================================================================
+
+CREATE TABLE symbol_metadata (
+    symbol VARCHAR(20) PRIMARY KEY,
+    base_asset VARCHAR(20) NOT NULL,
+    quote_asset VARCHAR(20) NOT NULL,
+    -- Smallest price increment
+    tick_size DECIMAL(20, 8) NOT NULL,
+    -- Smallest quantity increment
+    step_size DECIMAL(20, 8) NOT NULL,
+    -- Smallest order value in the quote asset; 0 when the exchange sets none
+    min_notional DECIMAL(20, 8) NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- =================================================================
+-- Backtest Results: summary of each completed backtest run
+-- Saved by the CLI so results outlive the process
+-- =================================================================
+
+CREATE TABLE backtest_results (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    strategy_id VARCHAR(50) NOT NULL,
+    strategy_name VARCHAR(100) NOT NULL,
+    symbol VARCHAR(20) NOT NULL,
+    initial_capital DECIMAL(20, 8) NOT NULL,
+    final_value DECIMAL(20, 8) NOT NULL,
+    return_percentage DECIMAL(20, 8) NOT NULL,
+    -- NULL when returns had zero volatility
+    sharpe_ratio DECIMAL(20, 8),
+    max_drawdown DECIMAL(20, 8) NOT NULL,
+    total_trades INTEGER NOT NULL,
+    -- Every fill of the run
+    trades JSONB NOT NULL DEFAULT '[]'
+);
+
+CREATE INDEX idx_backtest_results_symbol_time ON backtest_results(symbol, created_at DESC);
+
+-- =================================================================
+-- Time Bucketing: start of the bucket a timestamp falls in
+-- Buckets are bucket_secs wide and offset origin_secs from the epoch
+-- (weeks start on Monday, 1970-01-05). Every bucketed query uses this
+-- so candles, coverage and volatility agree on bucket boundaries.
+-- =================================================================
+
+CREATE OR REPLACE FUNCTION tick_bucket(ts TIMESTAMPTZ, bucket_secs FLOAT8, origin_secs FLOAT8)
+RETURNS TIMESTAMPTZ AS $$
+    SELECT to_timestamp(
+        floor((EXTRACT(EPOCH FROM ts) - origin_secs) / bucket_secs) * bucket_secs + origin_secs
+    )
+$$ LANGUAGE SQL IMMUTABLE;
+
 -- =================================================================
 -- Design Validation and Performance Testing
 -- =================================================================
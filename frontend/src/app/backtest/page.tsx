@@ -38,7 +38,7 @@ export default function BacktestPage() {
     symbol: '',
     data_count: 10000,
     initial_capital: '10000',
-    commission_rate: '0.001',
+    commission_rate: '0.1',
     short_period: '5',
     long_period: '20',
   });
@@ -58,7 +58,7 @@ export default function BacktestPage() {
     if (params.symbol && params.data_count > 0) {
       validateConfiguration();
     }
-  }, [params.symbol, params.data_count]);
+  }, [params.symbol, params.data_count, params.strategy_id, params.short_period, params.long_period]);
 
   const initializeData = async () => {
     try {
@@ -94,7 +94,12 @@ export default function BacktestPage() {
     try {
       const isValid = await invoke<boolean>('validate_backtest_config', {
         symbol: params.symbol,
-        dataCount: params.data_count
+        dataCount: params.data_count,
+        strategyId: params.strategy_id || null,
+        strategyParams: {
+          short_period: params.short_period,
+          long_period: params.long_period,
+        }
       });
       setConfigValid(isValid);
     } catch (err) {
@@ -139,7 +144,8 @@ export default function BacktestPage() {
     }
   };
 
-  const formatPercentage = (value: string | number) => {
+  const formatPercentage = (value: string | number | null) => {
+    if (value === null) return 'N/A';
     const num = typeof value === 'string' ? parseFloat(value) : value;
     return num.toFixed(2);
   };
@@ -304,11 +310,8 @@ export default function BacktestPage() {
               <label className="block text-sm font-medium mb-1">Commission Rate (%)</label>
               <input
                 type="text"
-                value={(parseFloat(params.commission_rate) * 100).toString()}
-                onChange={(e) => {
-                  const percent = parseFloat(e.target.value) || 0;
-                  setParams({ ...params, commission_rate: (percent / 100).toString() });
-                }}
+                value={params.commission_rate}
+                onChange={(e) => setParams({ ...params, commission_rate: e.target.value })}
                 className="w-full p-2 border rounded dark:bg-gray-800 dark:border-gray-600"
                 placeholder="0.1"
               />
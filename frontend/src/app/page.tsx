@@ -40,6 +40,7 @@ interface OHLCPreview {
   low: string;
   close: string;
   volume: string;
+  quote_volume: string;
   trade_count: number;
 }
 
@@ -164,7 +165,7 @@ export default function Home() {
               symbol: symbolInfo.symbol,
               data_count: Math.min(5000, symbolInfo.records_count),
               initial_capital: "10000",
-              commission_rate: "0.001",
+              commission_rate: "0.1",
               strategy_params: {}
             }
           }) as any;
@@ -27,6 +27,7 @@ export interface BacktestRequest {
   symbol: string;
   data_count: number;
   initial_capital: string;
+  // Percentage as entered, e.g. "0.1" for 0.1%
   commission_rate: string;
   strategy_params: Record<string, string>;
 }
@@ -41,10 +42,15 @@ export interface BacktestResponse {
   winning_trades: number;
   losing_trades: number;
   max_drawdown: string;
-  sharpe_ratio: string;
+  sharpe_ratio: string | null;
+  sortino_ratio: string | null;
+  calmar_ratio: string | null;
+  downside_deviation: string;
+  ulcer_index: string;
+  pain_ratio: string | null;
   volatility: string;
   win_rate: string;
-  profit_factor: string;
+  profit_factor: string | null;
   total_commission: string;
   trades: TradeInfo[];
   equity_curve: string[];
@@ -10,8 +10,12 @@ use trading_common::{
 };
 use rust_decimal::Decimal;
 
+use std::collections::HashMap;
 use std::str::FromStr;
-use tracing::{info, error};
+use tracing::{info, error, warn};
+
+/// Equity curve points returned to the UI when the request sets no cap
+const DEFAULT_MAX_EQUITY_POINTS: usize = 1000;
 
 #[tauri::command]
 pub async fn get_data_info(
@@ -67,15 +71,32 @@ pub async fn validate_backtest_config(
     state: State<'_, AppState>,
     symbol: String,
     data_count: i64,
+    strategy_id: Option<String>,
+    strategy_params: Option<HashMap<String, String>>,
 ) -> Result<bool, String> {
     info!("Validating backtest config for symbol: {}, data_count: {}", symbol, data_count);
+
+    // Make the minimum strategy-aware so indicators can warm up
+    let warmup_period = match strategy_id {
+        Some(id) => {
+            let mut strategy = create_strategy(&id)?;
+            strategy.initialize(strategy_params.unwrap_or_default())?;
+            strategy.warmup_period()
+        }
+        None => 0,
+    };
+
+    if (data_count as usize) < warmup_period {
+        info!("Validation result: false (data_count below warmup period {})", warmup_period);
+        return Ok(false);
+    }
     
     let data_info = state.repository
         .get_backtest_data_info()
         .await
         .map_err(|e| e.to_string())?;
 
-    let is_valid = data_info.has_sufficient_data(&symbol, data_count as u64);
+    let is_valid = data_info.has_sufficient_data(&symbol, data_count as u64, warmup_period);
     info!("Validation result: {}", is_valid);
     
     Ok(is_valid)
@@ -123,8 +144,8 @@ pub async fn run_backtest(
 
     let initial_capital = Decimal::from_str(&request.initial_capital)
         .map_err(|_| "Invalid initial capital")?;
-    let commission_rate = Decimal::from_str(&request.commission_rate)
-        .map_err(|_| "Invalid commission rate")?;
+    let commission_rate = trading_common::parse::parse_percentage(&request.commission_rate)
+        .map_err(|e| format!("Invalid commission rate: {}", e))?;
 
     let mut config = BacktestConfig::new(initial_capital)
         .with_commission_rate(commission_rate);
@@ -132,6 +153,9 @@ pub async fn run_backtest(
     for (key, value) in request.strategy_params {
         config = config.with_param(&key, &value);
     }
+    config = config.with_max_equity_points(
+        request.max_equity_points.unwrap_or(DEFAULT_MAX_EQUITY_POINTS),
+    );
 
     info!("Creating strategy: {}", request.strategy_id);
     let temp_strategy = create_strategy(&request.strategy_id)
@@ -155,8 +179,11 @@ pub async fn run_backtest(
                 timeframe, 
                 candle_count
             ).await {
-                Ok(ohlc_data) if !ohlc_data.is_empty() => {
+                Ok((ohlc_data, coverage)) if !ohlc_data.is_empty() => {
                     info!("Generated {} OHLC candles, running OHLC backtest", ohlc_data.len());
+                    if !coverage.is_complete() {
+                        warn!("Data only covers {}", coverage);
+                    }
                     data_source = format!("OHLC-{}", timeframe.as_str());
                     
                     let strategy = create_strategy(&request.strategy_id)?;
@@ -166,7 +193,8 @@ pub async fn run_backtest(
                             e
                         })?;
 
-                    let result = engine.run_with_ohlc(ohlc_data);
+                    let result = engine.run_with_ohlc(&ohlc_data);
+                    store_equity_csv(&state, &result);
                     return Ok(create_backtest_response(result, data_source));
                 },
                 Ok(_) => {
@@ -202,10 +230,32 @@ pub async fn run_backtest(
             e
         })?;
 
-    let result = engine.run(data);
+    let result = engine.run(&data);
+    store_equity_csv(&state, &result);
     Ok(create_backtest_response(result, data_source))
 }
 
+/// Equity curve of the last backtest as `timestamp,equity` CSV
+#[tauri::command]
+pub async fn export_equity_csv(state: State<'_, AppState>) -> Result<String, String> {
+    state.last_equity_csv
+        .lock()
+        .map_err(|_| "Equity curve store is poisoned".to_string())?
+        .clone()
+        .ok_or_else(|| "No backtest has been run yet".to_string())
+}
+
+fn store_equity_csv(state: &AppState, result: &BacktestResult) {
+    let mut buffer = Vec::new();
+    if let Err(e) = result.export_equity_csv(&mut buffer) {
+        error!("Failed to render equity curve CSV: {}", e);
+        return;
+    }
+    if let Ok(mut last) = state.last_equity_csv.lock() {
+        *last = Some(String::from_utf8_lossy(&buffer).into_owned());
+    }
+}
+
 // 3. Add helper function to commands.rs
 fn create_backtest_response(result: BacktestResult, data_source: String) -> BacktestResponse {
     info!("Backtest completed successfully");
@@ -220,10 +270,15 @@ fn create_backtest_response(result: BacktestResult, data_source: String) -> Back
         winning_trades: result.winning_trades,
         losing_trades: result.losing_trades,
         max_drawdown: result.max_drawdown.to_string(),
-        sharpe_ratio: result.sharpe_ratio.to_string(),
+        sharpe_ratio: result.sharpe_ratio.map(|v| v.to_string()),
+        sortino_ratio: result.sortino_ratio.map(|v| v.to_string()),
+        calmar_ratio: result.calmar_ratio.map(|v| v.to_string()),
+        downside_deviation: result.downside_deviation.to_string(),
+        ulcer_index: result.ulcer_index.to_string(),
+        pain_ratio: result.pain_ratio.map(|v| v.to_string()),
         volatility: result.volatility.to_string(),
         win_rate: result.win_rate.to_string(),
-        profit_factor: result.profit_factor.to_string(),
+        profit_factor: result.profit_factor.map(|v| v.to_string()),
         total_commission: result.total_commission.to_string(),
         data_source, // NEW FIELD
         trades: result.trades.into_iter().map(|trade| TradeInfo {
@@ -238,7 +293,7 @@ fn create_backtest_response(result: BacktestResult, data_source: String) -> Back
             realized_pnl: trade.realized_pnl.map(|pnl| pnl.to_string()),
             commission: trade.commission.to_string(),
         }).collect(),
-        equity_curve: sample_equity_curve(result.equity_curve, 1000),
+        equity_curve: result.equity_curve.iter().map(|v| v.to_string()).collect(),
     }
 }
 
@@ -286,19 +341,9 @@ pub async fn get_ohlc_preview(
     info!("Getting OHLC preview: {} {} count={}", 
           request.symbol, request.timeframe, request.count);
     
-    let timeframe = match request.timeframe.as_str() {
-        "1m" => trading_common::data::types::Timeframe::OneMinute,
-        "5m" => trading_common::data::types::Timeframe::FiveMinutes,
-        "15m" => trading_common::data::types::Timeframe::FifteenMinutes,
-        "30m" => trading_common::data::types::Timeframe::ThirtyMinutes,
-        "1h" => trading_common::data::types::Timeframe::OneHour,
-        "4h" => trading_common::data::types::Timeframe::FourHours,
-        "1d" => trading_common::data::types::Timeframe::OneDay,
-        "1w" => trading_common::data::types::Timeframe::OneWeek,
-        _ => return Err(format!("Invalid timeframe: {}", request.timeframe)),
-    };
+    let timeframe = trading_common::data::types::Timeframe::from_str(&request.timeframe)?;
     
-    let ohlc_data = state.repository
+    let (ohlc_data, _coverage) = state.repository
         .generate_recent_ohlc_for_backtest(&request.symbol, timeframe, request.count)
         .await
         .map_err(|e| {
@@ -318,26 +363,10 @@ pub async fn get_ohlc_preview(
         low: ohlc.low.to_string(),
         close: ohlc.close.to_string(),
         volume: ohlc.volume.to_string(),
+        quote_volume: ohlc.quote_volume.to_string(),
         trade_count: ohlc.trade_count,
     }).collect();
     
     info!("Generated {} OHLC preview records", response.len());
     Ok(response)
 }
-
-fn sample_equity_curve(curve: Vec<rust_decimal::Decimal>, max_points: usize) -> Vec<String> {
-    let len = curve.len();
-    if len <= max_points {
-        return curve.into_iter().map(|v| v.to_string()).collect();
-    }
-    (0..max_points)
-        .map(|i| {
-            let idx = if i == max_points - 1 {
-                len - 1
-            } else {
-                i * (len - 1) / (max_points - 1)
-            };
-            curve[idx].to_string()
-        })
-        .collect()
-}
\ No newline at end of file
@@ -61,7 +61,8 @@ fn main() {
             get_historical_data,
             validate_backtest_config,
             get_strategy_capabilities,
-            get_ohlc_preview
+            get_ohlc_preview,
+            export_equity_csv
         ])
         .setup(|app| {
             tracing::info!("Tauri setup started");
@@ -1,10 +1,12 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use trading_common::data::{repository::TickDataRepository, cache::TieredCache};
 use sqlx::PgPool;
 use std::time::Duration;
 
 pub struct AppState {
     pub repository: Arc<TickDataRepository>,
+    /// Equity curve of the most recent backtest as `timestamp,equity` CSV
+    pub last_equity_csv: Mutex<Option<String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -36,6 +38,7 @@ impl AppState {
 
         Ok(Self {
             repository: Arc::new(repository),
+            last_equity_csv: Mutex::new(None),
         })
     }
 }
@@ -33,8 +33,13 @@ pub struct BacktestRequest {
     pub symbol: String,
     pub data_count: i64,
     pub initial_capital: String,
+    /// Percentage as entered, e.g. "0.1" or "0.1%"
     pub commission_rate: String,
     pub strategy_params: HashMap<String, String>,
+    /// Cap on the returned equity curve points (1000 when unset); long curves
+    /// are downsampled
+    #[serde(default)]
+    pub max_equity_points: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,10 +53,16 @@ pub struct BacktestResponse {
     pub winning_trades: usize,
     pub losing_trades: usize,
     pub max_drawdown: String,
-    pub sharpe_ratio: String,
+    /// None (null) when the metric is undefined for the run
+    pub sharpe_ratio: Option<String>,
+    pub sortino_ratio: Option<String>,
+    pub calmar_ratio: Option<String>,
+    pub downside_deviation: String,
+    pub ulcer_index: String,
+    pub pain_ratio: Option<String>,
     pub volatility: String,
     pub win_rate: String,
-    pub profit_factor: String,
+    pub profit_factor: Option<String>,
     pub total_commission: String,
     pub trades: Vec<TradeInfo>,
     pub equity_curve: Vec<String>,
@@ -103,6 +114,7 @@ pub struct OHLCPreview {
     pub low: String,
     pub close: String,
     pub volume: String,
+    pub quote_volume: String,
     pub trade_count: u64,
 }
 
@@ -0,0 +1,304 @@
+//! Comparing two backtest results, e.g. before and after a refactor.
+
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+
+use super::engine::BacktestResult;
+use super::metrics::format_metric;
+use super::portfolio::Trade;
+
+/// Change in one summary metric between two runs; None where a metric is
+/// undefined for a run
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricDelta {
+    pub name: &'static str,
+    pub a: Option<Decimal>,
+    pub b: Option<Decimal>,
+    pub delta: Option<Decimal>,
+}
+
+/// Trades matched by timestamp + symbol + side
+#[derive(Debug, Clone, Default)]
+pub struct TradeDiff {
+    /// Trades only in the first result
+    pub removed: Vec<Trade>,
+    /// Trades only in the second result
+    pub added: Vec<Trade>,
+    /// Matched trades whose quantity, price or commission differ (a, b)
+    pub changed: Vec<(Trade, Trade)>,
+    /// Matched trades that are identical
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct BacktestDiff {
+    pub metrics: Vec<MetricDelta>,
+    pub trades: TradeDiff,
+}
+
+impl BacktestDiff {
+    /// Metrics whose value differs between the runs
+    pub fn changed_metrics(&self) -> impl Iterator<Item = &MetricDelta> {
+        self.metrics.iter().filter(|metric| metric.a != metric.b)
+    }
+
+    /// True when no metric or trade differs
+    pub fn is_identical(&self) -> bool {
+        self.changed_metrics().next().is_none()
+            && self.trades.removed.is_empty()
+            && self.trades.added.is_empty()
+            && self.trades.changed.is_empty()
+    }
+
+    pub fn print(&self) {
+        println!("BACKTEST DIFF");
+        println!("{}", "=".repeat(60));
+
+        if self.is_identical() {
+            println!("Results are identical");
+            return;
+        }
+
+        println!("{:<20} {:>12} {:>12} {:>12}", "Metric", "A", "B", "Delta");
+        for metric in self.changed_metrics() {
+            let delta = metric
+                .delta
+                .map_or_else(|| "N/A".to_string(), |d| format!("{:+.4}", d));
+            println!(
+                "{:<20} {:>12} {:>12} {:>12}",
+                metric.name,
+                format_metric(metric.a, 4),
+                format_metric(metric.b, 4),
+                delta
+            );
+        }
+        println!();
+
+        println!(
+            "Trades: {} unchanged, {} changed, {} removed, {} added",
+            self.trades.unchanged,
+            self.trades.changed.len(),
+            self.trades.removed.len(),
+            self.trades.added.len()
+        );
+        for (a, b) in &self.trades.changed {
+            println!(
+                "  ~ {} {} {:?}: {} @ {} -> {} @ {}",
+                a.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                a.symbol,
+                a.side,
+                a.quantity,
+                a.price,
+                b.quantity,
+                b.price
+            );
+        }
+        for (marker, trades) in [("-", &self.trades.removed), ("+", &self.trades.added)] {
+            for trade in trades {
+                println!(
+                    "  {} {} {} {:?}: {} @ {}",
+                    marker,
+                    trade.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                    trade.symbol,
+                    trade.side,
+                    trade.quantity,
+                    trade.price
+                );
+            }
+        }
+    }
+}
+
+/// Compare two results metric by metric and trade by trade
+pub fn compare(a: &BacktestResult, b: &BacktestResult) -> BacktestDiff {
+    let optional = |name, a: Option<Decimal>, b: Option<Decimal>| MetricDelta {
+        name,
+        a,
+        b,
+        delta: a.zip(b).map(|(a, b)| b - a),
+    };
+    let metric = |name, a: Decimal, b: Decimal| optional(name, Some(a), Some(b));
+    let count = |n: usize| Decimal::from(n);
+
+    let metrics = vec![
+        metric("final_value", a.final_value, b.final_value),
+        metric("total_pnl", a.total_pnl, b.total_pnl),
+        metric(
+            "return_percentage",
+            a.return_percentage,
+            b.return_percentage,
+        ),
+        metric("total_trades", count(a.total_trades), count(b.total_trades)),
+        metric(
+            "winning_trades",
+            count(a.winning_trades),
+            count(b.winning_trades),
+        ),
+        metric(
+            "losing_trades",
+            count(a.losing_trades),
+            count(b.losing_trades),
+        ),
+        metric("win_rate", a.win_rate, b.win_rate),
+        optional("profit_factor", a.profit_factor, b.profit_factor),
+        metric("max_drawdown", a.max_drawdown, b.max_drawdown),
+        optional("sharpe_ratio", a.sharpe_ratio, b.sharpe_ratio),
+        optional("sortino_ratio", a.sortino_ratio, b.sortino_ratio),
+        optional("calmar_ratio", a.calmar_ratio, b.calmar_ratio),
+        metric(
+            "downside_deviation",
+            a.downside_deviation,
+            b.downside_deviation,
+        ),
+        metric("volatility", a.volatility, b.volatility),
+        metric("total_commission", a.total_commission, b.total_commission),
+    ];
+
+    BacktestDiff {
+        metrics,
+        trades: diff_trades(&a.trades, &b.trades),
+    }
+}
+
+fn diff_trades(a: &[Trade], b: &[Trade]) -> TradeDiff {
+    let key = |trade: &Trade| {
+        (
+            trade.timestamp,
+            trade.symbol.clone(),
+            trade.side.as_db_str(),
+        )
+    };
+
+    // Same-key trades are matched in order of occurrence
+    let mut unmatched_b: HashMap<_, VecDeque<usize>> = HashMap::new();
+    for (index, trade) in b.iter().enumerate() {
+        unmatched_b.entry(key(trade)).or_default().push_back(index);
+    }
+
+    let mut matched_b = vec![false; b.len()];
+    let mut diff = TradeDiff::default();
+    for trade_a in a {
+        match unmatched_b
+            .get_mut(&key(trade_a))
+            .and_then(|queue| queue.pop_front())
+        {
+            Some(index) => {
+                matched_b[index] = true;
+                let trade_b = &b[index];
+                if trade_a.quantity == trade_b.quantity
+                    && trade_a.price == trade_b.price
+                    && trade_a.commission == trade_b.commission
+                {
+                    diff.unchanged += 1;
+                } else {
+                    diff.changed.push((trade_a.clone(), trade_b.clone()));
+                }
+            }
+            None => diff.removed.push(trade_a.clone()),
+        }
+    }
+
+    diff.added = b
+        .iter()
+        .zip(matched_b)
+        .filter(|(_, matched)| !matched)
+        .map(|(trade, _)| trade.clone())
+        .collect();
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::units::{Money, Price, Quantity};
+    use crate::data::types::TradeSide;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    fn trade(seconds: i64, side: TradeSide, price: i64) -> Trade {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        Trade {
+            symbol: "BTCUSDT".to_string(),
+            side,
+            quantity: Quantity::new(Decimal::ONE),
+            price: Price::new(Decimal::from(price)),
+            timestamp: start + Duration::seconds(seconds),
+            realized_pnl: None,
+            commission: Money::ZERO,
+        }
+    }
+
+    fn result(trades: Vec<Trade>, final_value: i64) -> BacktestResult {
+        BacktestResult {
+            initial_capital: Decimal::from(1000),
+            final_value: Decimal::from(final_value),
+            total_pnl: Decimal::from(final_value - 1000),
+            return_percentage: Decimal::ZERO,
+            total_trades: trades.len(),
+            winning_trades: 0,
+            losing_trades: 0,
+            max_drawdown: Decimal::ZERO,
+            ulcer_index: Decimal::ZERO,
+            pain_ratio: None,
+            sharpe_ratio: None,
+            sortino_ratio: None,
+            calmar_ratio: None,
+            downside_deviation: Decimal::ZERO,
+            volatility: Decimal::ZERO,
+            win_rate: Decimal::ZERO,
+            profit_factor: None,
+            avg_trade_duration_seconds: 0.0,
+            total_commission: Decimal::ZERO,
+            positions: HashMap::new(),
+            trades,
+            equity_curve: Vec::new(),
+            equity_timestamps: Vec::new(),
+            strategy_name: "Test".to_string(),
+            suppressed_dust_orders: 0,
+            suppressed_entries: 0,
+            cooldown_suppressed_entries: 0,
+            strategy_logs: Vec::new(),
+            benchmark_symbol: None,
+            beta: None,
+            alpha: None,
+            lookahead_violations: Vec::<DateTime<Utc>>::new(),
+            total_borrow_cost: Decimal::ZERO,
+            fill_quality: Default::default(),
+            kill_switch_triggered_at: None,
+            position_history: Vec::new(),
+            stopped_early: None,
+        }
+    }
+
+    #[test]
+    fn test_single_trade_difference() {
+        let a = result(
+            vec![
+                trade(0, TradeSide::Buy, 100),
+                trade(10, TradeSide::Sell, 110),
+            ],
+            1010,
+        );
+        let b = result(
+            vec![
+                trade(0, TradeSide::Buy, 100),
+                trade(10, TradeSide::Sell, 110),
+                trade(20, TradeSide::Buy, 105),
+            ],
+            1010,
+        );
+
+        let diff = compare(&a, &b);
+        assert!(!diff.is_identical());
+        assert_eq!(diff.trades.unchanged, 2);
+        assert!(diff.trades.removed.is_empty());
+        assert!(diff.trades.changed.is_empty());
+        assert_eq!(diff.trades.added.len(), 1);
+        assert_eq!(diff.trades.added[0].price.value(), Decimal::from(105));
+
+        let changed: Vec<&str> = diff.changed_metrics().map(|m| m.name).collect();
+        assert_eq!(changed, vec!["total_trades"]);
+
+        assert!(compare(&a, &a).is_identical());
+    }
+}
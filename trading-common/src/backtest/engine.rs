@@ -1,28 +1,391 @@
-use crate::backtest::{metrics::BacktestMetrics, portfolio::Portfolio, strategy::Strategy};
-use crate::data::types::TickData;
+use crate::backtest::{
+    execution::{
+        ConflictPolicy, FillQuality, FillQualityTracker, InsufficientFundsPolicy, LimitOrder,
+        ProtectiveExits, SlippageModel,
+    },
+    logger::{StrategyLogEntry, StrategyLogger},
+    metrics::{format_metric, BacktestMetrics},
+    portfolio::{CommissionFn, ContractType, FillContext, Portfolio, Position},
+    results, stats,
+    strategy::{Signal, Strategy},
+    units::{Money, Price, Quantity},
+};
+use crate::data::types::{OHLCData, SymbolMetadata, TickData, TradeSide};
+use chrono::{DateTime, Utc};
 use rust_decimal::prelude::ToPrimitive;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Bars processed between checks of the runtime limit and cancel flag
+const STOP_CHECK_INTERVAL: usize = 256;
+
+/// How the engine sizes `Signal::SizedBuy` orders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionSizer {
+    /// Always buy this quantity
+    Fixed(Decimal),
+    /// Buy this fraction of current equity, e.g. 0.1 for 10%
+    FractionOfEquity(Decimal),
+    /// Buy this much notional in the quote currency
+    FixedNotional(Decimal),
+}
+
+impl PositionSizer {
+    /// Quantity to buy at `price` given current `equity`; zero for a
+    /// non-positive price
+    pub fn quantity(&self, equity: Decimal, price: Decimal) -> Decimal {
+        if price <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        match *self {
+            PositionSizer::Fixed(quantity) => quantity,
+            PositionSizer::FractionOfEquity(fraction) => equity * fraction / price,
+            PositionSizer::FixedNotional(notional) => notional / price,
+        }
+    }
+}
+
+/// When OHLC backtests fill a signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillTiming {
+    /// Fill at the close of the candle that produced the signal.
+    ///
+    /// This is lookahead-biased: the strategy sees the close and trades at it,
+    /// which is not achievable live.
+    #[default]
+    CloseOfSignalCandle,
+    /// Fill at the open of the candle after the signal
+    OpenOfNextCandle,
+}
+
+/// Why a backtest ended before reaching the end of its data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StopReason {
+    /// `max_runtime` was exceeded
+    TimedOut,
+    /// The cancel flag was set
+    Cancelled,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::TimedOut => write!(f, "runtime limit exceeded"),
+            StopReason::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// Builds a fresh strategy that may only see the given candles
+pub type StrategyFactory = Arc<dyn Fn(&[OHLCData]) -> Box<dyn Strategy> + Send + Sync>;
+
+/// Debug-mode check that OHLC signals do not depend on future candles.
+///
+/// For every candle N a fresh strategy is built from candles `..=N`, replayed
+/// over them, and its last signal compared with the one produced in the full
+/// run. Any mismatch means the strategy used data it could not have had. This
+/// re-runs the strategy once per candle (quadratic cost), so keep it for debugging.
+#[derive(Clone)]
+pub struct LookaheadCheck {
+    factory: StrategyFactory,
+}
+
+impl fmt::Debug for LookaheadCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LookaheadCheck").finish_non_exhaustive()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct BacktestConfig {
     pub initial_capital: Decimal,
-    pub commission_rate: Decimal,
+    /// Commission rate for resting limit orders that fill (liquidity makers)
+    pub maker_rate: Decimal,
+    /// Commission rate for market orders, marketable limit orders and
+    /// triggered exits (liquidity takers)
+    pub taker_rate: Decimal,
     pub strategy_params: HashMap<String, String>,
+    /// Number of leading equity points excluded from metric computation.
+    /// The excluded points are still simulated and kept in the equity curve.
+    pub metrics_burn_in: usize,
+    /// Signals whose notional value (see `ContractType::notional`) falls below this
+    /// threshold are ignored as dust orders. Zero disables the check.
+    pub min_trade_notional: Decimal,
+    /// Fill timing for OHLC backtests (tick backtests always fill at the tick price)
+    pub fill_on: FillTiming,
+    /// Lookahead-bias check for OHLC backtests (disabled by default)
+    pub lookahead_check: Option<LookaheadCheck>,
+    /// Per-fill commission function; replaces the maker and taker rates when set
+    pub commission_fn: Option<CommissionFn>,
+    /// Decimal places each fill's commission is rounded to, as a venue
+    /// charging in its quote currency would. None keeps full precision.
+    pub commission_precision: Option<u32>,
+    /// How commission is rounded to `commission_precision`
+    pub commission_rounding: RoundingStrategy,
+    /// Annual borrow rate charged on short positions, accrued per elapsed time
+    pub borrow_rate_annual: Decimal,
+    /// Price impact applied to fills
+    pub slippage: SlippageModel,
+    /// Candles that must pass after a symbol's last trade before an OHLC
+    /// backtest opens a new entry in it; exits are never held back. Zero disables.
+    pub min_bars_between_trades: usize,
+    /// Data points (ticks, candles or multi-symbol bars) after a fill that
+    /// closed with negative realized PnL during which orders that increase
+    /// the absolute position in that symbol, long or short, are ignored;
+    /// exits are never held back. None disables.
+    pub cooldown_after_loss: Option<usize>,
+    /// Linear (quote-settled) or inverse (coin-margined) contract math;
+    /// capital and PnL are in the settlement asset
+    pub contract_type: ContractType,
+    /// Whether orders exceeding available cash or position are rejected or shrunk
+    pub insufficient_funds_policy: InsufficientFundsPolicy,
+    /// Exchange trading rules by symbol: order quantities are rounded down to
+    /// the lot step and orders below the symbol's minimum notional count as dust
+    pub symbol_metadata: HashMap<String, SymbolMetadata>,
+    /// Kill switch: once equity falls this many percent below its peak, all
+    /// positions are closed and later signals are ignored. None disables.
+    pub max_portfolio_drawdown_pct: Option<Decimal>,
+    /// Let sell signals go below zero inventory into a short position.
+    /// Off by default, so backtests are long-only.
+    pub short_selling: bool,
+    /// Largest total short notional as a multiple of equity; sells past it
+    /// follow `insufficient_funds_policy`. Defaults to 1.
+    pub short_margin_ratio: Decimal,
+    /// Annual risk-free rate subtracted in the Sharpe and Sortino ratios,
+    /// spread evenly over `periods_per_year`
+    pub risk_free_rate: Decimal,
+    /// Equity points per year, used to annualize the Sharpe and Sortino
+    /// ratios. The default of 1 reports them per equity point.
+    pub periods_per_year: u32,
+    /// Wall-clock limit for a run; once exceeded the engine stops and
+    /// returns a partial result flagged with `StopReason::TimedOut`
+    pub max_runtime: Option<Duration>,
+    /// Set from another thread to stop a running backtest early
+    pub cancel_flag: Option<Arc<AtomicBool>>,
+    /// How opposing buys and sells for a symbol on the same bar are resolved
+    pub conflict_policy: ConflictPolicy,
+    /// Cap on the equity curve points returned in the result. Longer curves
+    /// are downsampled with LTTB, always keeping the first, last, lowest and
+    /// highest points; metrics are computed on the full curve first. None
+    /// returns every point.
+    pub max_equity_points: Option<usize>,
+    /// Sizes `Signal::SizedBuy` from equity and price at the signal's bar.
+    /// None ignores sized buys.
+    pub position_sizer: Option<PositionSizer>,
 }
 
 impl BacktestConfig {
     pub fn new(initial_capital: Decimal) -> Self {
         Self {
             initial_capital,
-            commission_rate: Decimal::from_str("0.001").unwrap_or(Decimal::ZERO), // 0.1% default
+            maker_rate: Decimal::from_str("0.001").unwrap_or(Decimal::ZERO), // 0.1% default
+            taker_rate: Decimal::from_str("0.001").unwrap_or(Decimal::ZERO),
             strategy_params: HashMap::new(),
+            metrics_burn_in: 0,
+            min_trade_notional: Decimal::ZERO,
+            fill_on: FillTiming::default(),
+            lookahead_check: None,
+            commission_fn: None,
+            commission_precision: None,
+            commission_rounding: RoundingStrategy::MidpointAwayFromZero,
+            borrow_rate_annual: Decimal::ZERO,
+            slippage: SlippageModel::default(),
+            min_bars_between_trades: 0,
+            cooldown_after_loss: None,
+            contract_type: ContractType::default(),
+            insufficient_funds_policy: InsufficientFundsPolicy::default(),
+            symbol_metadata: HashMap::new(),
+            max_portfolio_drawdown_pct: None,
+            short_selling: false,
+            short_margin_ratio: Decimal::ONE,
+            risk_free_rate: Decimal::ZERO,
+            periods_per_year: 1,
+            max_runtime: None,
+            cancel_flag: None,
+            conflict_policy: ConflictPolicy::default(),
+            max_equity_points: None,
+            position_sizer: None,
         }
     }
 
+    /// Charge the same rate for maker and taker fills
     pub fn with_commission_rate(mut self, rate: Decimal) -> Self {
-        self.commission_rate = rate;
+        self.maker_rate = rate;
+        self.taker_rate = rate;
+        self
+    }
+
+    pub fn with_maker_taker_rates(mut self, maker_rate: Decimal, taker_rate: Decimal) -> Self {
+        self.maker_rate = maker_rate;
+        self.taker_rate = taker_rate;
+        self
+    }
+
+    /// Charge commission through a custom function instead of the flat rate
+    pub fn with_commission_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&FillContext) -> Decimal + Send + Sync + 'static,
+    {
+        self.commission_fn = Some(CommissionFn::new(f));
+        self
+    }
+
+    /// Round each fill's commission to `precision` decimal places
+    pub fn with_commission_precision(mut self, precision: u32, rounding: RoundingStrategy) -> Self {
+        self.commission_precision = Some(precision);
+        self.commission_rounding = rounding;
+        self
+    }
+
+    pub fn with_borrow_rate_annual(mut self, rate: Decimal) -> Self {
+        self.borrow_rate_annual = rate;
+        self
+    }
+
+    pub fn with_slippage(mut self, slippage: SlippageModel) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    pub fn with_min_bars_between_trades(mut self, bars: usize) -> Self {
+        self.min_bars_between_trades = bars;
+        self
+    }
+
+    pub fn with_cooldown_after_loss(mut self, bars: usize) -> Self {
+        self.cooldown_after_loss = Some(bars);
+        self
+    }
+
+    pub fn with_contract_type(mut self, contract_type: ContractType) -> Self {
+        self.contract_type = contract_type;
+        self
+    }
+
+    pub fn with_insufficient_funds_policy(mut self, policy: InsufficientFundsPolicy) -> Self {
+        self.insufficient_funds_policy = policy;
+        self
+    }
+
+    pub fn with_symbol_metadata(
+        mut self,
+        metadata: impl IntoIterator<Item = SymbolMetadata>,
+    ) -> Self {
+        self.symbol_metadata.extend(
+            metadata
+                .into_iter()
+                .map(|rules| (rules.symbol.clone(), rules)),
+        );
+        self
+    }
+
+    pub fn with_max_portfolio_drawdown_pct(mut self, pct: Decimal) -> Self {
+        self.max_portfolio_drawdown_pct = Some(pct);
+        self
+    }
+
+    pub fn with_short_selling(mut self, enabled: bool) -> Self {
+        self.short_selling = enabled;
+        self
+    }
+
+    pub fn with_short_margin_ratio(mut self, ratio: Decimal) -> Self {
+        self.short_margin_ratio = ratio;
+        self
+    }
+
+    pub fn with_risk_free_rate(mut self, rate: Decimal) -> Self {
+        self.risk_free_rate = rate;
+        self
+    }
+
+    /// Annualize the Sharpe and Sortino ratios for this many equity points a year
+    pub fn with_periods_per_year(mut self, periods: u32) -> Self {
+        self.periods_per_year = periods.max(1);
+        self
+    }
+
+    pub fn with_max_runtime(mut self, max_runtime: Duration) -> Self {
+        self.max_runtime = Some(max_runtime);
+        self
+    }
+
+    /// Stop the run once `flag` is set; the flag is checked periodically
+    pub fn with_cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(flag);
+        self
+    }
+
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.conflict_policy = policy;
+        self
+    }
+
+    /// Return at most `points` equity curve points (raised to 5, the least
+    /// that fits the endpoints and extremes plus one LTTB pick)
+    pub fn with_max_equity_points(mut self, points: usize) -> Self {
+        self.max_equity_points = Some(points.max(5));
+        self
+    }
+
+    pub fn with_position_sizer(mut self, sizer: PositionSizer) -> Self {
+        self.position_sizer = Some(sizer);
+        self
+    }
+
+    /// Commission rates as printed in a run's header
+    fn commission_summary(&self) -> String {
+        let hundred = Decimal::from(100);
+        if self.maker_rate == self.taker_rate {
+            format!("Commission rate: {}%", self.taker_rate * hundred)
+        } else {
+            format!(
+                "Commission rates: maker {}%, taker {}%",
+                self.maker_rate * hundred,
+                self.taker_rate * hundred
+            )
+        }
+    }
+
+    /// Whether fills deviate from the signal's price or size
+    fn models_execution(&self) -> bool {
+        self.slippage != SlippageModel::None
+            || self.insufficient_funds_policy == InsufficientFundsPolicy::PartialFill
+    }
+
+    pub fn with_metrics_burn_in(mut self, points: usize) -> Self {
+        self.metrics_burn_in = points;
+        self
+    }
+
+    pub fn with_min_trade_notional(mut self, notional: Decimal) -> Self {
+        self.min_trade_notional = notional;
+        self
+    }
+
+    pub fn with_fill_on(mut self, fill_on: FillTiming) -> Self {
+        self.fill_on = fill_on;
+        self
+    }
+
+    /// Enable the lookahead check; `factory` must build the same strategy as
+    /// the one under test, given only the candles it is allowed to see.
+    pub fn with_lookahead_check<F>(mut self, factory: F) -> Self
+    where
+        F: Fn(&[OHLCData]) -> Box<dyn Strategy> + Send + Sync + 'static,
+    {
+        self.lookahead_check = Some(LookaheadCheck {
+            factory: Arc::new(factory),
+        });
         self
     }
 
@@ -33,10 +396,54 @@ impl BacktestConfig {
     }
 }
 
+/// Replays market data through a strategy and fills its signals
+///
+/// `Buy`/`Sell` are market orders: they fill at the bar's price moved
+/// against the trader by the configured `SlippageModel`. `LimitBuy`/`LimitSell`
+/// fill at the current price without slippage when that price is already at or
+/// through the limit (at or below it for buys, at or above it for sells).
+/// Otherwise they are queued and fill at exactly the limit price on the first
+/// later tick that trades at or through it, or the first candle whose low
+/// (buys) or high (sells) reaches it. Queued orders never expire; any still
+/// open at the end of the data are reported and dropped.
 pub struct BacktestEngine {
     portfolio: Portfolio,
     strategy: Box<dyn Strategy>,
     config: BacktestConfig,
+    suppressed_dust_orders: usize,
+    suppressed_entries: usize,
+    /// Data point index of each symbol's most recent fill, checked by OHLC
+    /// backtests against `min_bars_between_trades`
+    last_trade_bar: HashMap<String, usize>,
+    /// Index of the data point being processed
+    current_bar: usize,
+    /// Last data point of each symbol's cooldown after a losing fill
+    loss_cooldowns: HashMap<String, usize>,
+    cooldown_suppressed_entries: usize,
+    strategy_log: StrategyLogger,
+    fill_quality: FillQualityTracker,
+    /// Timestamp of each equity curve point (the initial point shares the first bar's time)
+    equity_timestamps: Vec<DateTime<Utc>>,
+    benchmark: Option<Benchmark>,
+    /// Highest portfolio value seen, for the drawdown kill switch
+    equity_peak: Decimal,
+    /// When the drawdown kill switch halted trading
+    halted_at: Option<DateTime<Utc>>,
+    /// Limit orders waiting for the price to reach their limit, oldest first
+    open_limit_orders: Vec<LimitOrder>,
+    /// Stop-loss and take-profit levels by symbol, dropped once they fire or
+    /// the position is flat
+    protective_exits: HashMap<String, ProtectiveExits>,
+    /// Holdings after each fill, one snapshot per timestamp
+    position_history: Vec<(DateTime<Utc>, HashMap<String, Position>)>,
+    /// Why the last run stopped before the end of its data
+    stopped_early: Option<StopReason>,
+}
+
+/// Benchmark price series used for beta/alpha calculation
+struct Benchmark {
+    symbol: String,
+    prices: Vec<(DateTime<Utc>, Decimal)>,
 }
 
 impl BacktestEngine {
@@ -45,444 +452,2911 @@ impl BacktestEngine {
         strategy.reset();
         strategy.initialize(config.strategy_params.clone())?;
 
-        let portfolio =
-            Portfolio::new(config.initial_capital).with_commission_rate(config.commission_rate);
+        let portfolio = Portfolio::new(config.initial_capital)
+            .with_commission_fn(config.commission_fn.clone())
+            .with_commission_rounding(config.commission_precision, config.commission_rounding)
+            .with_contract_type(config.contract_type)
+            .with_short_selling(config.short_selling)
+            .with_short_margin_ratio(config.short_margin_ratio);
 
         Ok(Self {
             portfolio,
             strategy,
+            suppressed_dust_orders: 0,
+            suppressed_entries: 0,
+            last_trade_bar: HashMap::new(),
+            current_bar: 0,
+            loss_cooldowns: HashMap::new(),
+            cooldown_suppressed_entries: 0,
+            strategy_log: StrategyLogger::new(),
+            fill_quality: FillQualityTracker::default(),
+            equity_timestamps: Vec::new(),
+            benchmark: None,
+            equity_peak: config.initial_capital,
+            halted_at: None,
+            open_limit_orders: Vec::new(),
+            protective_exits: HashMap::new(),
+            position_history: Vec::new(),
+            stopped_early: None,
             config,
         })
     }
 
-    pub fn run(&mut self, data: Vec<TickData>) -> BacktestResult {
+    /// Supply a benchmark price series; beta and alpha are then reported
+    /// against it after aligning it to the equity curve timestamps.
+    pub fn with_benchmark(
+        mut self,
+        symbol: &str,
+        mut prices: Vec<(DateTime<Utc>, Decimal)>,
+    ) -> Self {
+        prices.sort_by_key(|(timestamp, _)| *timestamp);
+        self.benchmark = Some(Benchmark {
+            symbol: symbol.to_string(),
+            prices,
+        });
+        self
+    }
+
+    /// Run over borrowed ticks, so one dataset can back many engines
+    pub fn run(&mut self, data: &[TickData]) -> BacktestResult {
         println!("Starting backtest...");
         println!("Strategy: {}", self.strategy.name());
         println!("Initial capital: ${}", self.portfolio.initial_capital);
         println!("Data points: {}", data.len());
-        println!(
-            "Commission rate: {}%",
-            self.config.commission_rate * Decimal::from(100)
-        );
+        println!("{}", self.config.commission_summary());
         println!("{}", "=".repeat(60));
 
         let mut processed = 0;
         let total = data.len();
         let mut last_progress = 0;
+        let started = Instant::now();
+        self.stopped_early = None;
 
-        for tick in data {
+        for (bar, tick) in data.iter().enumerate() {
+            self.current_bar = bar;
             // Update current price
             self.portfolio.update_price(&tick.symbol, tick.price);
+            self.accrue_borrow_cost(tick.timestamp);
+            self.check_kill_switch(tick.timestamp);
+            self.fill_limit_orders(&tick.symbol, tick.price, tick.price, tick.timestamp);
+            self.trigger_protective_exits(
+                &tick.symbol,
+                tick.price,
+                tick.price,
+                tick.price,
+                tick.timestamp,
+            );
 
             // Execute strategy
-            let signal = self.strategy.on_tick(&tick);
+            self.strategy_log.set_time(tick.timestamp);
+            let signals = self.strategy.on_tick_signals(tick, &mut self.strategy_log);
 
             // Execute trades
-            match signal {
-                crate::backtest::strategy::Signal::Buy { symbol, quantity } => {
-                    if let Err(e) = self
-                        .portfolio
-                        .execute_buy(symbol.clone(), quantity, tick.price, tick.timestamp)
-                    {
-                        println!("Buy failed {}: {}", symbol, e);
-                    } else {
-                        println!("BUY {} {} @ ${}", symbol, quantity, tick.price);
-                    }
-                }
-                crate::backtest::strategy::Signal::Sell { symbol, quantity } => {
-                    if let Err(e) =
-                        self.portfolio
-                            .execute_sell(symbol.clone(), quantity, tick.price, tick.timestamp)
-                    {
-                        println!("Sell failed {}: {}", symbol, e);
-                    } else {
-                        println!("SELL {} {} @ ${}", symbol, quantity, tick.price);
-                    }
-                }
-                crate::backtest::strategy::Signal::Hold => {}
+            for signal in self.resolve_conflicts(signals) {
+                self.execute_signal(signal, tick.price, tick.timestamp);
             }
 
-            self.portfolio.snapshot_equity();
+            self.snapshot_equity(tick.timestamp);
             processed += 1;
 
-            // Progress display
-            let progress = (processed * 100) / total;
-            if progress != last_progress && progress % 10 == 0 {
-                let current_value = self.portfolio.total_value();
-                let current_pnl = self.portfolio.total_pnl();
-                println!(
-                    "Progress: {}% ({}/{}) | Portfolio Value: ${} | P&L: ${}",
-                    progress, processed, total, current_value, current_pnl
-                );
-                last_progress = progress;
+            self.report_progress(processed, total, &mut last_progress);
+
+            if self.check_stop(started, processed, total) {
+                break;
             }
         }
 
-        println!("\n{}", "=".repeat(60));
-
-        // Calculate results
-        let final_value = self.portfolio.total_value();
-        let total_pnl = self.portfolio.total_pnl();
-        let total_return_pct = if self.portfolio.initial_capital > Decimal::ZERO {
-            (total_pnl / self.portfolio.initial_capital) * Decimal::from(100)
-        } else {
-            Decimal::ZERO
-        };
-
-        // Calculate performance metrics
-        let equity_curve = self.portfolio.get_equity_curve();
-        let returns = Self::calculate_returns(&equity_curve);
+        if self.stopped_early.is_none() {
+            if let Some(last) = data.last() {
+                self.finish(last.timestamp);
+            }
+        }
 
-        let max_drawdown = BacktestMetrics::calculate_max_drawdown(&equity_curve);
-        let sharpe_ratio = BacktestMetrics::calculate_sharpe_ratio(&returns, Decimal::ZERO);
-        let volatility = BacktestMetrics::calculate_volatility(&returns);
-        let win_rate = BacktestMetrics::calculate_win_rate(&self.portfolio.trades);
-        let profit_factor = BacktestMetrics::calculate_profit_factor(&self.portfolio.trades);
-        let avg_trade_duration =
-            BacktestMetrics::calculate_average_trade_duration(&self.portfolio.trades);
+        println!("\n{}", "=".repeat(60));
 
-        BacktestResult {
-            initial_capital: self.portfolio.initial_capital,
-            final_value,
-            total_pnl,
-            return_percentage: total_return_pct,
-            total_trades: self.portfolio.trades.len(),
-            winning_trades: self.count_winning_trades(),
-            losing_trades: self.count_losing_trades(),
-            max_drawdown,
-            sharpe_ratio,
-            volatility,
-            win_rate,
-            profit_factor,
-            avg_trade_duration_seconds: avg_trade_duration,
-            total_commission: self.portfolio.total_commission(),
-            positions: self.portfolio.positions.clone(),
-            trades: self.portfolio.trades.clone(),
-            equity_curve,
-            strategy_name: self.strategy.name().to_string(),
-        }
+        self.build_result()
     }
 
-    fn calculate_returns(equity_curve: &[Decimal]) -> Vec<Decimal> {
-        if equity_curve.len() < 2 {
-            return Vec::new();
+    /// Every `STOP_CHECK_INTERVAL` bars, record whether the run should stop
+    /// because the cancel flag is set or `max_runtime` has passed
+    fn check_stop(&mut self, started: Instant, processed: usize, total: usize) -> bool {
+        if !processed.is_multiple_of(STOP_CHECK_INTERVAL) || processed == total {
+            return false;
         }
 
-        equity_curve
-            .windows(2)
-            .map(|window| {
-                if window[0] > Decimal::ZERO {
-                    (window[1] - window[0]) / window[0]
-                } else {
-                    Decimal::ZERO
-                }
-            })
-            .collect()
-    }
+        let reason = if self
+            .config
+            .cancel_flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+        {
+            StopReason::Cancelled
+        } else if self
+            .config
+            .max_runtime
+            .is_some_and(|limit| started.elapsed() >= limit)
+        {
+            StopReason::TimedOut
+        } else {
+            return false;
+        };
 
-    fn count_winning_trades(&self) -> usize {
-        self.portfolio
-            .trades
-            .iter()
-            .filter(|trade| trade.realized_pnl.map_or(false, |pnl| pnl > Decimal::ZERO))
-            .count()
+        println!(
+            "Backtest stopped early ({}) after {}/{} data points; results are partial",
+            reason, processed, total
+        );
+        self.stopped_early = Some(reason);
+        true
     }
 
-    fn count_losing_trades(&self) -> usize {
-        self.portfolio
-            .trades
-            .iter()
-            .filter(|trade| trade.realized_pnl.map_or(false, |pnl| pnl < Decimal::ZERO))
-            .count()
+    /// Print progress at every 10% of the data
+    fn report_progress(&self, processed: usize, total: usize, last_progress: &mut usize) {
+        let progress = (processed * 100) / total;
+        if progress != *last_progress && progress % 10 == 0 {
+            let current_value = self.portfolio.total_value();
+            let current_pnl = self.portfolio.total_pnl();
+            println!(
+                "Progress: {}% ({}/{}) | Portfolio Value: ${} | P&L: ${}",
+                progress, processed, total, current_value, current_pnl
+            );
+            *last_progress = progress;
+        }
     }
 
-    pub fn run_with_ohlc(&mut self, data: Vec<crate::data::types::OHLCData>) -> BacktestResult {
-        println!("Starting OHLC backtest...");
+    /// Run over a time-ordered feed mixing several symbols
+    ///
+    /// Ticks sharing a timestamp form one bar: all their prices are applied
+    /// first, then the strategy sees them together through
+    /// `Strategy::on_tick_multi`. Each signal fills at its own symbol's
+    /// latest price.
+    pub fn run_multi(&mut self, data: &[TickData]) -> BacktestResult {
+        println!("Starting multi-symbol backtest...");
         println!("Strategy: {}", self.strategy.name());
         println!("Initial capital: ${}", self.portfolio.initial_capital);
-        println!("Data points: {} OHLC candles", data.len());
-        println!(
-            "Commission rate: {}%",
-            self.config.commission_rate * Decimal::from(100)
-        );
+        let bars: Vec<&[TickData]> = data.chunk_by(|a, b| a.timestamp == b.timestamp).collect();
+        println!("Data points: {} ticks in {} bars", data.len(), bars.len());
+        println!("{}", self.config.commission_summary());
         println!("{}", "=".repeat(60));
 
         let mut processed = 0;
-        let total = data.len();
+        let total = bars.len();
         let mut last_progress = 0;
+        let started = Instant::now();
+        self.stopped_early = None;
 
-        for ohlc in data {
-            // Update current price using close price
-            self.portfolio.update_price(&ohlc.symbol, ohlc.close);
+        for (index, bar) in bars.iter().enumerate() {
+            self.current_bar = index;
+            let timestamp = bar[0].timestamp;
+            for tick in *bar {
+                self.portfolio.update_price(&tick.symbol, tick.price);
+            }
+            self.accrue_borrow_cost(timestamp);
+            self.check_kill_switch(timestamp);
+            for tick in *bar {
+                self.fill_limit_orders(&tick.symbol, tick.price, tick.price, timestamp);
+                self.trigger_protective_exits(
+                    &tick.symbol,
+                    tick.price,
+                    tick.price,
+                    tick.price,
+                    timestamp,
+                );
+            }
 
-            // Execute strategy with OHLC data
-            let signal = self.strategy.on_ohlc(&ohlc);
-
-            // Execute trades using close price
-            match signal {
-                crate::backtest::strategy::Signal::Buy { symbol, quantity } => {
-                    if let Err(e) = self
-                        .portfolio
-                        .execute_buy(symbol.clone(), quantity, ohlc.close, ohlc.timestamp)
-                    {
-                        println!("Buy failed {}: {}", symbol, e);
-                    } else {
-                        println!("BUY {} {} @ ${}", symbol, quantity, ohlc.close);
-                    }
-                }
-                crate::backtest::strategy::Signal::Sell { symbol, quantity } => {
-                    if let Err(e) =
-                        self.portfolio
-                            .execute_sell(symbol.clone(), quantity, ohlc.close, ohlc.timestamp)
-                    {
-                        println!("Sell failed {}: {}", symbol, e);
-                    } else {
-                        println!("SELL {} {} @ ${}", symbol, quantity, ohlc.close);
-                    }
-                }
-                crate::backtest::strategy::Signal::Hold => {}
+            self.strategy_log.set_time(timestamp);
+            let signals = self.strategy.on_tick_multi(
+                bar,
+                &self.portfolio.snapshot(),
+                &mut self.strategy_log,
+            );
+            for signal in self.resolve_conflicts(signals) {
+                let Some(price) = signal
+                    .symbol()
+                    .and_then(|symbol| self.portfolio.current_prices.get(symbol))
+                    .copied()
+                else {
+                    continue;
+                };
+                self.execute_signal(signal, price, timestamp);
             }
 
-            self.portfolio.snapshot_equity();
+            self.snapshot_equity(timestamp);
             processed += 1;
+            self.report_progress(processed, total, &mut last_progress);
 
-            // Progress display
-            let progress = (processed * 100) / total;
-            if progress != last_progress && progress % 10 == 0 {
-                let current_value = self.portfolio.total_value();
-                let current_pnl = self.portfolio.total_pnl();
-                println!(
-                    "Progress: {}% ({}/{}) | Portfolio Value: ${} | P&L: ${}",
-                    progress, processed, total, current_value, current_pnl
-                );
-                last_progress = progress;
+            if self.check_stop(started, processed, total) {
+                break;
+            }
+        }
+
+        if self.stopped_early.is_none() {
+            if let Some(last) = data.last() {
+                self.finish(last.timestamp);
             }
         }
 
         println!("\n{}", "=".repeat(60));
 
-        // Calculate results (same logic as original run method)
-        let final_value = self.portfolio.total_value();
-        let total_pnl = self.portfolio.total_pnl();
-        let total_return_pct = if self.portfolio.initial_capital > Decimal::ZERO {
-            (total_pnl / self.portfolio.initial_capital) * Decimal::from(100)
-        } else {
-            Decimal::ZERO
-        };
+        self.build_result()
+    }
 
-        // Calculate performance metrics
-        let equity_curve = self.portfolio.get_equity_curve();
-        let returns = Self::calculate_returns(&equity_curve);
+    /// Let the strategy act on the end of data, filling at final prices
+    fn finish(&mut self, timestamp: DateTime<Utc>) {
+        if !self.open_limit_orders.is_empty() {
+            println!(
+                "{} limit order(s) still open at end of data, dropped",
+                self.open_limit_orders.len()
+            );
+            self.open_limit_orders.clear();
+        }
 
-        let max_drawdown = BacktestMetrics::calculate_max_drawdown(&equity_curve);
-        let sharpe_ratio = BacktestMetrics::calculate_sharpe_ratio(&returns, Decimal::ZERO);
-        let volatility = BacktestMetrics::calculate_volatility(&returns);
-        let win_rate = BacktestMetrics::calculate_win_rate(&self.portfolio.trades);
-        let profit_factor = BacktestMetrics::calculate_profit_factor(&self.portfolio.trades);
-        let avg_trade_duration =
-            BacktestMetrics::calculate_average_trade_duration(&self.portfolio.trades);
+        let signals = self.strategy.on_finish(&self.portfolio.snapshot());
+        if signals.is_empty() {
+            return;
+        }
 
-        BacktestResult {
-            initial_capital: self.portfolio.initial_capital,
-            final_value,
-            total_pnl,
-            return_percentage: total_return_pct,
-            total_trades: self.portfolio.trades.len(),
-            winning_trades: self.count_winning_trades(),
-            losing_trades: self.count_losing_trades(),
-            max_drawdown,
-            sharpe_ratio,
-            volatility,
-            win_rate,
-            profit_factor,
-            avg_trade_duration_seconds: avg_trade_duration,
-            total_commission: self.portfolio.total_commission(),
-            positions: self.portfolio.positions.clone(),
-            trades: self.portfolio.trades.clone(),
-            equity_curve,
-            strategy_name: self.strategy.name().to_string(),
+        for signal in self.resolve_conflicts(signals) {
+            let Some(symbol) = signal.symbol() else {
+                continue;
+            };
+            match self.portfolio.current_prices.get(symbol).copied() {
+                Some(price) => {
+                    self.execute_signal(signal, price, timestamp);
+                }
+                None => println!("No final price for {}, skipping {:?}", symbol, signal),
+            }
+        }
+
+        // The last bar is already snapshotted; restate it rather than adding
+        // a second point (and a zero return) at the same time
+        if self.equity_timestamps.last() == Some(&timestamp) {
+            self.portfolio.restate_equity();
+        } else {
+            self.snapshot_equity(timestamp);
         }
     }
-}
 
-#[derive(Debug)]
-pub struct BacktestResult {
-    pub initial_capital: Decimal,
-    pub final_value: Decimal,
-    pub total_pnl: Decimal,
-    pub return_percentage: Decimal,
-    pub total_trades: usize,
-    pub winning_trades: usize,
-    pub losing_trades: usize,
-    pub max_drawdown: Decimal,
-    pub sharpe_ratio: Decimal,
-    pub volatility: Decimal,
-    pub win_rate: Decimal,
-    pub profit_factor: Decimal,
-    pub avg_trade_duration_seconds: f64,
-    pub total_commission: Decimal,
-    pub positions: HashMap<String, crate::backtest::portfolio::Position>,
-    pub trades: Vec<crate::backtest::portfolio::Trade>,
-    pub equity_curve: Vec<Decimal>,
-    pub strategy_name: String,
-}
+    /// Resolve opposing signals for a symbol from one bar under `conflict_policy`,
+    /// after turning sized buys into market buys
+    fn resolve_conflicts(&self, signals: Vec<Signal>) -> Vec<Signal> {
+        let signals = signals
+            .into_iter()
+            .filter_map(|signal| self.size_signal(signal))
+            .collect();
+        let positions = &self.portfolio.positions;
+        self.config.conflict_policy.resolve(signals, |symbol| {
+            positions
+                .get(symbol)
+                .map_or(Decimal::ZERO, |position| position.quantity.value())
+        })
+    }
 
-impl BacktestResult {
-    pub fn print_summary(&self) {
-        println!("BACKTEST RESULTS SUMMARY");
-        println!("{}", "=".repeat(60));
-        println!("Strategy: {}", self.strategy_name);
-        println!("Initial Capital: ${}", self.initial_capital);
-        println!("Final Value: ${}", self.final_value);
-        println!("Total P&L: ${}", self.total_pnl);
-        println!("Return: {:.2}%", self.return_percentage);
-        println!("Total Commission: ${}", self.total_commission);
-        println!();
+    /// Size a `SizedBuy` with `position_sizer` at the symbol's current price,
+    /// dropping it when no sizer is set; other signals pass through
+    fn size_signal(&self, signal: Signal) -> Option<Signal> {
+        let Signal::SizedBuy { symbol } = signal else {
+            return Some(signal);
+        };
+        let sizer = self.config.position_sizer?;
+        let price = *self.portfolio.current_prices.get(&symbol)?;
+        let quantity = sizer.quantity(self.portfolio.total_value(), price);
+        (quantity > Decimal::ZERO).then_some(Signal::Buy { symbol, quantity })
+    }
 
-        println!("TRADING STATISTICS");
-        println!("{}", "-".repeat(30));
-        println!("Total Trades: {}", self.total_trades);
+    /// Charge borrow fees for shorts held since the previous bar
+    fn accrue_borrow_cost(&mut self, timestamp: DateTime<Utc>) {
+        if let Some(previous) = self.equity_timestamps.last() {
+            let elapsed = (timestamp - *previous).num_seconds();
+            self.portfolio
+                .accrue_borrow_cost(self.config.borrow_rate_annual, elapsed);
+        }
+    }
 
-        if self.total_trades > 0 {
-            println!(
-                "Winning Trades: {} ({:.1}%)",
-                self.winning_trades, self.win_rate
-            );
-            println!(
-                "Losing Trades: {} ({:.1}%)",
-                self.losing_trades,
-                100.0 - self.win_rate.to_f64().unwrap_or(0.0)
-            );
-            println!("Profit Factor: {:.2}", self.profit_factor);
-            println!(
-                "Avg Trade Duration: {:.0} seconds",
-                self.avg_trade_duration_seconds
-            );
+    /// Halt trading and close all positions at current prices once equity
+    /// falls `max_portfolio_drawdown_pct` below its peak
+    fn check_kill_switch(&mut self, timestamp: DateTime<Utc>) {
+        let Some(limit) = self.config.max_portfolio_drawdown_pct else {
+            return;
+        };
+        if self.halted_at.is_some() {
+            return;
+        }
+
+        let value = self.portfolio.total_value();
+        self.equity_peak = self.equity_peak.max(value);
+        if self.equity_peak <= Decimal::ZERO
+            || (self.equity_peak - value) * Decimal::from(100) / self.equity_peak < limit
+        {
+            return;
         }
-        println!();
 
-        println!("RISK METRICS");
-        println!("{}", "-".repeat(30));
         println!(
-            "Max Drawdown: {:.2}%",
-            self.max_drawdown * Decimal::from(100)
+            "KILL SWITCH at {}: equity ${} is {}% or more below peak ${}, closing positions",
+            timestamp, value, limit, self.equity_peak
         );
-        println!("Sharpe Ratio: {:.2}", self.sharpe_ratio);
-        println!("Volatility: {:.2}%", self.volatility * Decimal::from(100));
-        println!();
+        let mut open: Vec<(String, Decimal)> = self
+            .portfolio
+            .positions
+            .values()
+            .filter(|position| position.quantity != Quantity::ZERO)
+            .map(|position| (position.symbol.clone(), position.quantity.value()))
+            .collect();
+        open.sort();
+        for (symbol, quantity) in open {
+            let Some(price) = self.portfolio.current_prices.get(&symbol).copied() else {
+                continue;
+            };
+            let rate = self.config.taker_rate;
+            let closed = if quantity > Decimal::ZERO {
+                self.portfolio
+                    .execute_sell(symbol.clone(), quantity, price, rate, timestamp)
+            } else {
+                self.portfolio
+                    .execute_buy(symbol.clone(), -quantity, price, rate, timestamp)
+            };
+            match closed {
+                Ok(()) => self.record_positions(timestamp),
+                Err(e) => println!("Kill switch could not close {}: {}", symbol, e),
+            }
+        }
+        self.open_limit_orders.clear();
+        self.protective_exits.clear();
+        self.halted_at = Some(timestamp);
+    }
 
-        if !self.positions.is_empty() {
-            println!("CURRENT POSITIONS");
-            println!("{}", "-".repeat(30));
-            for (symbol, position) in &self.positions {
-                println!(
-                    "{}: {} @ ${} (Unrealized P&L: ${})",
-                    symbol, position.quantity, position.avg_price, position.unrealized_pnl
-                );
+    /// Snapshot holdings after a fill; fills sharing a timestamp keep only the
+    /// last snapshot
+    fn record_positions(&mut self, timestamp: DateTime<Utc>) {
+        let positions = self.portfolio.positions.clone();
+        match self.position_history.last_mut() {
+            Some((last, snapshot)) if *last == timestamp => *snapshot = positions,
+            _ => self.position_history.push((timestamp, positions)),
+        }
+    }
+
+    fn snapshot_equity(&mut self, timestamp: DateTime<Utc>) {
+        if self.equity_timestamps.is_empty() {
+            // Initial capital point is stamped with the first bar's time
+            self.equity_timestamps.push(timestamp);
+        }
+        self.portfolio.snapshot_equity();
+        self.equity_timestamps.push(timestamp);
+    }
+
+    /// Execute a signal from the current candle, holding back entries that
+    /// come within `min_bars_between_trades` of the symbol's last fill
+    fn execute_candle_signal(&mut self, signal: Signal, price: Decimal, timestamp: DateTime<Utc>) {
+        let min_bars = self.config.min_bars_between_trades;
+        if matches!(signal, Signal::Buy { .. } | Signal::LimitBuy { .. })
+            && signal
+                .symbol()
+                .and_then(|symbol| self.last_trade_bar.get(symbol))
+                .is_some_and(|last| self.current_bar - last < min_bars)
+        {
+            self.suppressed_entries += 1;
+            return;
+        }
+
+        self.execute_signal(signal, price, timestamp);
+    }
+
+    /// Execute a strategy signal against the portfolio at the given price
+    fn execute_signal(&mut self, signal: Signal, price: Decimal, timestamp: DateTime<Utc>) {
+        let (side, symbol, quantity, limit_price) = match signal {
+            Signal::Buy { symbol, quantity } => (TradeSide::Buy, symbol, quantity, None),
+            Signal::Sell { symbol, quantity } => (TradeSide::Sell, symbol, quantity, None),
+            Signal::LimitBuy {
+                symbol,
+                quantity,
+                limit_price,
+            } => (TradeSide::Buy, symbol, quantity, Some(limit_price)),
+            Signal::LimitSell {
+                symbol,
+                quantity,
+                limit_price,
+            } => (TradeSide::Sell, symbol, quantity, Some(limit_price)),
+            Signal::StopLoss {
+                symbol,
+                trigger_price,
+            } => {
+                if self.halted_at.is_none() {
+                    self.protective_exits.entry(symbol).or_default().stop_loss =
+                        Some(trigger_price);
+                }
+                return;
             }
-            println!();
+            Signal::TakeProfit {
+                symbol,
+                trigger_price,
+            } => {
+                if self.halted_at.is_none() {
+                    self.protective_exits.entry(symbol).or_default().take_profit =
+                        Some(trigger_price);
+                }
+                return;
+            }
+            // Sized buys become market buys in `resolve_conflicts`
+            Signal::SizedBuy { .. } | Signal::Hold => return,
+        };
+        if self.halted_at.is_some() {
+            return;
+        }
+        if self.increases_exposure(&symbol, side, quantity) && self.in_loss_cooldown(&symbol) {
+            self.cooldown_suppressed_entries += 1;
+            return;
         }
 
-        if self.total_trades > 0 {
-            println!("RECENT TRADES (Last 5)");
-            println!("{}", "-".repeat(30));
-            for trade in self.trades.iter().rev().take(5) {
-                let pnl_str = trade
-                    .realized_pnl
-                    .map(|pnl| format!("(P&L: ${})", pnl))
-                    .unwrap_or_default();
-                println!(
-                    "{} {} {} @ ${} {}",
-                    trade.timestamp.format("%Y-%m-%d %H:%M:%S"),
-                    match trade.side {
-                        crate::data::types::TradeSide::Buy => "BUY ",
-                        crate::data::types::TradeSide::Sell => "SELL",
-                    },
-                    trade.symbol,
-                    trade.price,
-                    pnl_str
-                );
+        match limit_price {
+            None => {
+                let fill_price = self.config.slippage.fill_price(side, price);
+                let rate = self.config.taker_rate;
+                self.fill_order(side, symbol, quantity, price, fill_price, rate, timestamp);
+            }
+            Some(limit_price) => {
+                let order = LimitOrder {
+                    side,
+                    symbol,
+                    quantity,
+                    limit_price,
+                };
+                // A limit already at or through the price crosses the book
+                if order.reached(price, price) {
+                    let rate = self.config.taker_rate;
+                    self.fill_order(side, order.symbol, quantity, price, price, rate, timestamp);
+                } else {
+                    self.open_limit_orders.push(order);
+                }
             }
         }
+    }
 
-        println!("{}", "=".repeat(60));
+    /// Whether an order would leave a larger absolute position in `symbol`,
+    /// i.e. opens or adds to a long or short rather than reducing one
+    fn increases_exposure(&self, symbol: &str, side: TradeSide, quantity: Decimal) -> bool {
+        let held = self
+            .portfolio
+            .positions
+            .get(symbol)
+            .map_or(Decimal::ZERO, |position| position.quantity.value());
+        let after = match side {
+            TradeSide::Buy => held + quantity,
+            TradeSide::Sell => held - quantity,
+        };
+        after.abs() > held.abs()
     }
 
-    pub fn is_profitable(&self) -> bool {
-        self.total_pnl > Decimal::ZERO
+    /// Whether `symbol` is still cooling down after a losing fill; an expired
+    /// cooldown is dropped
+    fn in_loss_cooldown(&mut self, symbol: &str) -> bool {
+        match self.loss_cooldowns.get(symbol) {
+            Some(&until) if self.current_bar <= until => true,
+            Some(_) => {
+                self.loss_cooldowns.remove(symbol);
+                false
+            }
+            None => false,
+        }
     }
 
-    pub fn calmar_ratio(&self) -> Decimal {
-        BacktestMetrics::calculate_calmar_ratio(
-            self.return_percentage / Decimal::from(100),
-            self.max_drawdown,
-        )
+    /// Fill the queued limit orders of `symbol` that a bar trading between
+    /// `low` and `high` reached, each at its limit price
+    fn fill_limit_orders(
+        &mut self,
+        symbol: &str,
+        low: Decimal,
+        high: Decimal,
+        timestamp: DateTime<Utc>,
+    ) {
+        if self.open_limit_orders.is_empty() {
+            return;
+        }
+        let (reached, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.open_limit_orders)
+            .into_iter()
+            .partition(|order| order.symbol == symbol && order.reached(low, high));
+        self.open_limit_orders = waiting;
+
+        // Queued orders rested on the book, so they fill as makers
+        for order in reached {
+            self.fill_order(
+                order.side,
+                order.symbol,
+                order.quantity,
+                order.limit_price,
+                order.limit_price,
+                self.config.maker_rate,
+                timestamp,
+            );
+        }
     }
 
-    pub fn print_trade_analysis(&self) {
-        if self.trades.is_empty() {
-            println!("No trades executed");
+    /// Sell the whole long position in `symbol` when a bar opening at `open`
+    /// and trading between `low` and `high` breaches its stop-loss or
+    /// take-profit. Levels left on a flat or short position are dropped.
+    fn trigger_protective_exits(
+        &mut self,
+        symbol: &str,
+        open: Decimal,
+        low: Decimal,
+        high: Decimal,
+        timestamp: DateTime<Utc>,
+    ) {
+        let Some(exits) = self.protective_exits.get(symbol) else {
+            return;
+        };
+        let quantity = self
+            .portfolio
+            .positions
+            .get(symbol)
+            .map_or(Decimal::ZERO, |position| position.quantity.value());
+        if quantity <= Decimal::ZERO {
+            self.protective_exits.remove(symbol);
             return;
         }
+        let Some(price) = exits.exit_price(open, low, high) else {
+            return;
+        };
 
-        println!("DETAILED TRADE ANALYSIS");
-        println!("{}", "=".repeat(80));
+        self.protective_exits.remove(symbol);
+        self.fill_order(
+            TradeSide::Sell,
+            symbol.to_string(),
+            quantity,
+            price,
+            price,
+            self.config.taker_rate,
+            timestamp,
+        );
+    }
 
-        let mut buy_trades = Vec::new();
-        let mut sell_trades = Vec::new();
+    /// Size an order against exchange rules and available funds, then fill it
+    /// at `fill_price` paying `commission_rate`; `price` is the reference
+    /// price before slippage
+    #[allow(clippy::too_many_arguments)]
+    fn fill_order(
+        &mut self,
+        side: TradeSide,
+        symbol: String,
+        quantity: Decimal,
+        price: Decimal,
+        fill_price: Decimal,
+        commission_rate: Decimal,
+        timestamp: DateTime<Utc>,
+    ) {
+        let rules = self.config.symbol_metadata.get(&symbol);
+        let quantity = rules.map_or(quantity, |rules| rules.round_quantity(quantity));
+        let below_exchange_minimum = rules.is_some_and(|rules| {
+            quantity <= Decimal::ZERO || self.notional(quantity, price) < rules.min_notional
+        });
+        if below_exchange_minimum || self.is_dust_order(quantity, price) {
+            self.suppressed_dust_orders += 1;
+            return;
+        }
 
-        for trade in &self.trades {
-            match trade.side {
-                crate::data::types::TradeSide::Buy => buy_trades.push(trade),
-                crate::data::types::TradeSide::Sell => sell_trades.push(trade),
+        let fill_quantity = match self.config.insufficient_funds_policy {
+            InsufficientFundsPolicy::Reject => quantity,
+            InsufficientFundsPolicy::PartialFill => {
+                self.fillable_quantity(&symbol, side, quantity, fill_price, commission_rate)
             }
+        };
+
+        let result = if fill_quantity <= Decimal::ZERO {
+            Err("Nothing fillable".to_string())
+        } else {
+            match side {
+                TradeSide::Buy => self.portfolio.execute_buy(
+                    symbol.clone(),
+                    fill_quantity,
+                    fill_price,
+                    commission_rate,
+                    timestamp,
+                ),
+                TradeSide::Sell => self.portfolio.execute_sell(
+                    symbol.clone(),
+                    fill_quantity,
+                    fill_price,
+                    commission_rate,
+                    timestamp,
+                ),
+            }
+        };
+
+        let label = match side {
+            TradeSide::Buy => "BUY",
+            TradeSide::Sell => "SELL",
+        };
+        match result {
+            Ok(()) => {
+                let notional = self.notional(fill_quantity, fill_price);
+                self.fill_quality
+                    .record(quantity, fill_quantity, price, fill_price, notional);
+                println!("{} {} {} @ ${}", label, symbol, fill_quantity, fill_price);
+                self.record_positions(timestamp);
+                self.last_trade_bar.insert(symbol.clone(), self.current_bar);
+                let lost = self
+                    .portfolio
+                    .trades
+                    .last()
+                    .and_then(|trade| trade.realized_pnl)
+                    .is_some_and(|pnl| pnl < Money::ZERO);
+                if let Some(bars) = self.config.cooldown_after_loss.filter(|_| lost) {
+                    self.loss_cooldowns.insert(symbol, self.current_bar + bars);
+                }
+            }
+            Err(e) => println!("{} failed {}: {}", label, symbol, e),
         }
+    }
 
-        println!("Buy Trades: {}", buy_trades.len());
-        println!("Sell Trades: {}", sell_trades.len());
+    /// Largest part of an order the portfolio can cover: held quantity for
+    /// sells (plus the short margin left when short selling), cash net of
+    /// `commission_rate` for buys
+    fn fillable_quantity(
+        &self,
+        symbol: &str,
+        side: TradeSide,
+        quantity: Decimal,
+        price: Decimal,
+        commission_rate: Decimal,
+    ) -> Decimal {
+        match side {
+            TradeSide::Sell => {
+                let held = self
+                    .portfolio
+                    .positions
+                    .get(symbol)
+                    .map_or(Decimal::ZERO, |position| position.quantity.value())
+                    .max(Decimal::ZERO);
+                let shortable = if self.config.short_selling {
+                    self.portfolio.short_capacity(symbol, Price::new(price))
+                } else {
+                    Quantity::ZERO
+                };
+                quantity.min(held + shortable.value())
+            }
+            TradeSide::Buy => {
+                let unit_cost = self
+                    .config
+                    .contract_type
+                    .notional(Quantity::new(Decimal::ONE), Price::new(price))
+                    * (Decimal::ONE + commission_rate);
+                if unit_cost <= Money::ZERO {
+                    return quantity;
+                }
+                let affordable = (self.portfolio.cash / unit_cost).trunc_with_scale(8);
+                quantity.min(affordable.max(Decimal::ZERO))
+            }
+        }
+    }
 
-        if !sell_trades.is_empty() {
-            let profitable_sells = sell_trades
-                .iter()
-                .filter(|t| t.realized_pnl.map_or(false, |pnl| pnl > Decimal::ZERO))
-                .count();
+    /// Value of an order in the settlement asset under the configured contract type
+    fn notional(&self, quantity: Decimal, price: Decimal) -> Decimal {
+        self.config
+            .contract_type
+            .notional(Quantity::new(quantity), Price::new(price))
+            .value()
+    }
 
-            let total_profit: Decimal = sell_trades
-                .iter()
-                .filter_map(|t| t.realized_pnl)
-                .filter(|&pnl| pnl > Decimal::ZERO)
-                .sum();
+    fn is_dust_order(&self, quantity: Decimal, price: Decimal) -> bool {
+        self.config.min_trade_notional > Decimal::ZERO
+            && self.notional(quantity, price) < self.config.min_trade_notional
+    }
+
+    /// Calculate results and performance metrics from the current portfolio state
+    fn build_result(&self) -> BacktestResult {
+        let final_value = self.portfolio.total_value();
+        let total_pnl = self.portfolio.total_pnl();
+        let total_return_pct = if self.portfolio.initial_capital > Decimal::ZERO {
+            (total_pnl / self.portfolio.initial_capital) * Decimal::from(100)
+        } else {
+            Decimal::ZERO
+        };
+
+        // Calculate performance metrics, skipping the burn-in period
+        let equity_curve = self.portfolio.get_equity_curve();
+        let burn_in = self
+            .config
+            .metrics_burn_in
+            .min(equity_curve.len().saturating_sub(1));
+        let metrics_curve = &equity_curve[burn_in..];
+        let returns = Self::calculate_returns(metrics_curve);
+
+        let max_drawdown = BacktestMetrics::calculate_max_drawdown(metrics_curve);
+        let ulcer_index = BacktestMetrics::ulcer_index(metrics_curve);
+        let periods_per_year = Decimal::from(self.config.periods_per_year.max(1));
+        let period_risk_free_rate = self.config.risk_free_rate / periods_per_year;
+        let annualize = |ratio: Decimal| ratio * stats::sqrt(periods_per_year);
+        let sharpe_ratio =
+            BacktestMetrics::calculate_sharpe_ratio(&returns, period_risk_free_rate).map(annualize);
+        let sortino_ratio = BacktestMetrics::calculate_sortino_ratio(
+            &returns,
+            period_risk_free_rate,
+            Decimal::ZERO,
+        )
+        .map(annualize);
+        let downside_deviation =
+            BacktestMetrics::calculate_downside_deviation(&returns, Decimal::ZERO);
+        let calmar_ratio = BacktestMetrics::calculate_calmar_ratio(
+            total_return_pct / Decimal::from(100),
+            max_drawdown,
+        );
+        let volatility = BacktestMetrics::calculate_volatility(&returns);
+        let win_rate = BacktestMetrics::calculate_win_rate(&self.portfolio.trades);
+        let profit_factor = BacktestMetrics::calculate_profit_factor(&self.portfolio.trades);
+        let avg_trade_duration =
+            BacktestMetrics::calculate_average_trade_duration(&self.portfolio.trades);
+        let (beta, alpha) = match self.aligned_benchmark_returns(&equity_curve, burn_in) {
+            Some((strategy_returns, benchmark_returns)) => (
+                Some(BacktestMetrics::beta(&strategy_returns, &benchmark_returns)),
+                Some(BacktestMetrics::alpha(
+                    &strategy_returns,
+                    &benchmark_returns,
+                )),
+            ),
+            None => (None, None),
+        };
+        let (equity_curve, equity_timestamps) = self.downsampled_equity(equity_curve);
+
+        BacktestResult {
+            initial_capital: self.portfolio.initial_capital,
+            final_value,
+            total_pnl,
+            return_percentage: total_return_pct,
+            total_trades: self.portfolio.trades.len(),
+            winning_trades: self.count_winning_trades(),
+            losing_trades: self.count_losing_trades(),
+            max_drawdown,
+            ulcer_index,
+            pain_ratio: BacktestMetrics::calculate_pain_ratio(total_return_pct, ulcer_index),
+            sharpe_ratio,
+            sortino_ratio,
+            calmar_ratio,
+            downside_deviation,
+            volatility,
+            win_rate,
+            profit_factor,
+            avg_trade_duration_seconds: avg_trade_duration,
+            total_commission: self.portfolio.total_commission(),
+            positions: self.portfolio.positions.clone(),
+            position_history: self.position_history.clone(),
+            trades: self.portfolio.trades.clone(),
+            equity_curve,
+            equity_timestamps,
+            strategy_name: self.strategy.name().to_string(),
+            suppressed_dust_orders: self.suppressed_dust_orders,
+            suppressed_entries: self.suppressed_entries,
+            cooldown_suppressed_entries: self.cooldown_suppressed_entries,
+            strategy_logs: self.strategy_log.entries().to_vec(),
+            benchmark_symbol: self.benchmark.as_ref().map(|b| b.symbol.clone()),
+            beta,
+            alpha,
+            lookahead_violations: Vec::new(),
+            total_borrow_cost: self.portfolio.total_borrow_cost(),
+            fill_quality: self.fill_quality.finish(
+                self.portfolio.total_commission(),
+                self.config.models_execution(),
+            ),
+            kill_switch_triggered_at: self.halted_at,
+            stopped_early: self.stopped_early,
+        }
+    }
+
+    /// Equity curve and timestamps capped at `max_equity_points`: LTTB picks
+    /// all but two points and the global minimum and maximum are added back
+    fn downsampled_equity(&self, equity_curve: Vec<Decimal>) -> (Vec<Decimal>, Vec<DateTime<Utc>>) {
+        let timestamps = self.equity_timestamps.clone();
+        let Some(max_points) = self
+            .config
+            .max_equity_points
+            .filter(|&max| equity_curve.len() > max)
+        else {
+            return (equity_curve, timestamps);
+        };
 
-            let total_loss: Decimal = sell_trades
+        let mut indices = stats::lttb_indices(&equity_curve, max_points.saturating_sub(2));
+        let by_value = |&(_, a): &(usize, &Decimal), &(_, b): &(usize, &Decimal)| a.cmp(b);
+        indices.extend(
+            equity_curve
                 .iter()
-                .filter_map(|t| t.realized_pnl)
-                .filter(|&pnl| pnl < Decimal::ZERO)
-                .sum();
+                .enumerate()
+                .min_by(by_value)
+                .map(|(i, _)| i),
+        );
+        indices.extend(
+            equity_curve
+                .iter()
+                .enumerate()
+                .max_by(by_value)
+                .map(|(i, _)| i),
+        );
+        indices.sort_unstable();
+        indices.dedup();
 
-            println!(
-                "Profitable Sells: {} ({:.1}%)",
-                profitable_sells,
-                (profitable_sells as f64 / sell_trades.len() as f64) * 100.0
-            );
-            println!("Total Gross Profit: ${}", total_profit);
-            println!("Total Gross Loss: ${}", total_loss);
+        let timestamps = if timestamps.len() == equity_curve.len() {
+            indices.iter().map(|&i| timestamps[i]).collect()
+        } else {
+            timestamps
+        };
+        (
+            indices.iter().map(|&i| equity_curve[i]).collect(),
+            timestamps,
+        )
+    }
 
-            if profitable_sells > 0 {
-                println!(
-                    "Average Profit per Winning Trade: ${}",
-                    total_profit / Decimal::from(profitable_sells)
-                );
-            }
+    /// Align the benchmark to the equity curve sampling grid (as-of the latest
+    /// benchmark price at or before each equity timestamp) and return paired
+    /// strategy/benchmark returns, skipping the burn-in period.
+    fn aligned_benchmark_returns(
+        &self,
+        equity_curve: &[Decimal],
+        burn_in: usize,
+    ) -> Option<(Vec<Decimal>, Vec<Decimal>)> {
+        let benchmark = self.benchmark.as_ref()?;
+        if benchmark.prices.is_empty() || self.equity_timestamps.len() != equity_curve.len() {
+            return None;
+        }
 
-            let losing_sells = sell_trades.len() - profitable_sells;
-            if losing_sells > 0 {
-                println!(
-                    "Average Loss per Losing Trade: ${}",
-                    total_loss / Decimal::from(losing_sells)
-                );
+        let mut cursor = 0;
+        let mut last_price = None;
+        let aligned: Vec<Option<Decimal>> = self
+            .equity_timestamps
+            .iter()
+            .map(|timestamp| {
+                while cursor < benchmark.prices.len() && benchmark.prices[cursor].0 <= *timestamp {
+                    last_price = Some(benchmark.prices[cursor].1);
+                    cursor += 1;
+                }
+                last_price
+            })
+            .collect();
+
+        let mut strategy_returns = Vec::new();
+        let mut benchmark_returns = Vec::new();
+        for i in (burn_in + 1)..equity_curve.len() {
+            if let (Some(prev), Some(curr)) = (aligned[i - 1], aligned[i]) {
+                if prev > Decimal::ZERO && equity_curve[i - 1] > Decimal::ZERO {
+                    benchmark_returns.push((curr - prev) / prev);
+                    strategy_returns
+                        .push((equity_curve[i] - equity_curve[i - 1]) / equity_curve[i - 1]);
+                }
             }
         }
 
-        println!("{}", "=".repeat(80));
+        if strategy_returns.len() < 2 {
+            return None;
+        }
+        Some((strategy_returns, benchmark_returns))
+    }
+
+    fn calculate_returns(equity_curve: &[Decimal]) -> Vec<Decimal> {
+        if equity_curve.len() < 2 {
+            return Vec::new();
+        }
+
+        equity_curve
+            .windows(2)
+            .map(|window| {
+                if window[0] > Decimal::ZERO {
+                    (window[1] - window[0]) / window[0]
+                } else {
+                    Decimal::ZERO
+                }
+            })
+            .collect()
+    }
+
+    fn count_winning_trades(&self) -> usize {
+        self.portfolio
+            .trades
+            .iter()
+            .filter(|trade| trade.realized_pnl.map_or(false, |pnl| pnl > Money::ZERO))
+            .count()
+    }
+
+    fn count_losing_trades(&self) -> usize {
+        self.portfolio
+            .trades
+            .iter()
+            .filter(|trade| trade.realized_pnl.map_or(false, |pnl| pnl < Money::ZERO))
+            .count()
+    }
+
+    pub fn run_with_ohlc(&mut self, data: &[OHLCData]) -> BacktestResult {
+        println!("Starting OHLC backtest...");
+        println!("Strategy: {}", self.strategy.name());
+        println!("Initial capital: ${}", self.portfolio.initial_capital);
+        println!("Data points: {} OHLC candles", data.len());
+        println!("{}", self.config.commission_summary());
+        println!("{}", "=".repeat(60));
+
+        let mut processed = 0;
+        let total = data.len();
+        let mut last_progress = 0;
+        let started = Instant::now();
+        self.stopped_early = None;
+        // Signals from the previous candle awaiting a fill at this candle's open
+        let mut pending_signals: Vec<Signal> = Vec::new();
+        // Signals produced per candle, kept only when the lookahead check is enabled
+        let mut candle_signals = Vec::new();
+
+        for (bar, ohlc) in data.iter().enumerate() {
+            self.current_bar = bar;
+            if !pending_signals.is_empty() {
+                self.portfolio.update_price(&ohlc.symbol, ohlc.open);
+                for signal in std::mem::take(&mut pending_signals) {
+                    self.execute_candle_signal(signal, ohlc.open, ohlc.timestamp);
+                }
+            }
+
+            // Update current price using close price
+            self.portfolio.update_price(&ohlc.symbol, ohlc.close);
+            self.accrue_borrow_cost(ohlc.timestamp);
+            self.check_kill_switch(ohlc.timestamp);
+            self.fill_limit_orders(&ohlc.symbol, ohlc.low, ohlc.high, ohlc.timestamp);
+            self.trigger_protective_exits(
+                &ohlc.symbol,
+                ohlc.open,
+                ohlc.low,
+                ohlc.high,
+                ohlc.timestamp,
+            );
+
+            // Execute strategy with OHLC data
+            self.strategy_log.set_time(ohlc.timestamp);
+            let signals = self.strategy.on_ohlc_signals(ohlc, &mut self.strategy_log);
+            if self.config.lookahead_check.is_some() {
+                candle_signals.push(signals.clone());
+            }
+            let signals = self.resolve_conflicts(signals);
+
+            match self.config.fill_on {
+                FillTiming::CloseOfSignalCandle => {
+                    for signal in signals {
+                        self.execute_candle_signal(signal, ohlc.close, ohlc.timestamp);
+                    }
+                }
+                FillTiming::OpenOfNextCandle => {
+                    pending_signals = signals
+                        .into_iter()
+                        .filter(|signal| !matches!(signal, Signal::Hold))
+                        .collect();
+                }
+            }
+
+            self.snapshot_equity(ohlc.timestamp);
+            processed += 1;
+
+            self.report_progress(processed, total, &mut last_progress);
+
+            if self.check_stop(started, processed, total) {
+                break;
+            }
+        }
+
+        if !pending_signals.is_empty() {
+            println!("Unfilled signals after last candle: {:?}", pending_signals);
+        }
+        if self.stopped_early.is_none() {
+            if let Some(last) = data.last() {
+                self.finish(last.timestamp);
+            }
+        }
+
+        println!("\n{}", "=".repeat(60));
+
+        let mut result = self.build_result();
+        if let Some(check) = self
+            .config
+            .lookahead_check
+            .as_ref()
+            .filter(|_| self.stopped_early.is_none())
+        {
+            result.lookahead_violations = self.check_lookahead(check, data, &candle_signals);
+            if !result.lookahead_violations.is_empty() {
+                println!(
+                    "WARNING: lookahead bias detected at {} candle(s), first at {}",
+                    result.lookahead_violations.len(),
+                    result.lookahead_violations[0]
+                );
+            }
+        }
+        result
+    }
+
+    /// Replay a fresh strategy over each prefix of `data` and return the
+    /// timestamps where its signal differs from the full-run signal.
+    fn check_lookahead(
+        &self,
+        check: &LookaheadCheck,
+        data: &[OHLCData],
+        candle_signals: &[Vec<Signal>],
+    ) -> Vec<DateTime<Utc>> {
+        let mut violations = Vec::new();
+
+        for (index, expected) in candle_signals.iter().enumerate() {
+            let visible = &data[..=index];
+            let mut strategy = (check.factory)(visible);
+            strategy.reset();
+            if let Err(e) = strategy.initialize(self.config.strategy_params.clone()) {
+                println!("Lookahead check aborted: {}", e);
+                break;
+            }
+
+            // The replay's own messages are discarded
+            let mut log = StrategyLogger::new();
+            let mut signals = Vec::new();
+            for ohlc in visible {
+                signals = strategy.on_ohlc_signals(ohlc, &mut log);
+            }
+
+            if signals != *expected {
+                violations.push(data[index].timestamp);
+            }
+        }
+
+        violations
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BacktestResult {
+    pub initial_capital: Decimal,
+    pub final_value: Decimal,
+    pub total_pnl: Decimal,
+    pub return_percentage: Decimal,
+    pub total_trades: usize,
+    pub winning_trades: usize,
+    pub losing_trades: usize,
+    pub max_drawdown: Decimal,
+    /// Root mean square of percentage drawdowns from the running peak
+    #[serde(default)]
+    pub ulcer_index: Decimal,
+    /// Return % / Ulcer Index; None when there was no drawdown
+    #[serde(default)]
+    pub pain_ratio: Option<Decimal>,
+    /// None when returns have zero volatility
+    pub sharpe_ratio: Option<Decimal>,
+    /// None when no return fell below zero
+    #[serde(default)]
+    pub sortino_ratio: Option<Decimal>,
+    /// Return / max drawdown; None when there was no drawdown
+    #[serde(default)]
+    pub calmar_ratio: Option<Decimal>,
+    /// Root mean square of the negative returns, per equity point
+    #[serde(default)]
+    pub downside_deviation: Decimal,
+    pub volatility: Decimal,
+    pub win_rate: Decimal,
+    /// None when no closed trade lost money
+    pub profit_factor: Option<Decimal>,
+    pub avg_trade_duration_seconds: f64,
+    pub total_commission: Decimal,
+    pub positions: HashMap<String, crate::backtest::portfolio::Position>,
+    pub trades: Vec<crate::backtest::portfolio::Trade>,
+    pub equity_curve: Vec<Decimal>,
+    /// Time of each `equity_curve` point; the initial capital point carries
+    /// the first bar's time
+    #[serde(default)]
+    pub equity_timestamps: Vec<DateTime<Utc>>,
+    pub strategy_name: String,
+    /// Signals ignored because their notional was below `min_trade_notional`
+    pub suppressed_dust_orders: usize,
+    /// OHLC entries held back by `min_bars_between_trades`
+    #[serde(default)]
+    pub suppressed_entries: usize,
+    /// Entries ignored during a `cooldown_after_loss`
+    #[serde(default)]
+    pub cooldown_suppressed_entries: usize,
+    /// Messages the strategy wrote to its `StrategyLogger`, in order
+    #[serde(default)]
+    pub strategy_logs: Vec<StrategyLogEntry>,
+    pub benchmark_symbol: Option<String>,
+    /// Beta of equity returns vs the benchmark (None without a benchmark)
+    pub beta: Option<Decimal>,
+    /// Per-period alpha vs the benchmark (None without a benchmark)
+    pub alpha: Option<Decimal>,
+    /// Candles whose signal changed when future candles were hidden
+    /// (always empty unless the lookahead check is enabled)
+    pub lookahead_violations: Vec<DateTime<Utc>>,
+    /// Borrow fees paid on short positions
+    pub total_borrow_cost: Decimal,
+    /// Slippage, partial fills and commission share of the executed fills
+    #[serde(default)]
+    pub fill_quality: FillQuality,
+    /// When the drawdown kill switch closed all positions and halted trading
+    /// (None if it never triggered)
+    #[serde(default)]
+    pub kill_switch_triggered_at: Option<DateTime<Utc>>,
+    /// Open positions after each fill that changed them, in time order
+    #[serde(default)]
+    pub position_history: Vec<(DateTime<Utc>, HashMap<String, Position>)>,
+    /// Set when the run stopped before the end of its data; every figure
+    /// then covers only the bars processed up to that point
+    #[serde(default)]
+    pub stopped_early: Option<StopReason>,
+}
+
+impl BacktestResult {
+    pub fn print_summary(&self) {
+        println!("BACKTEST RESULTS SUMMARY");
+        println!("{}", "=".repeat(60));
+        println!("Strategy: {}", self.strategy_name);
+        if let Some(reason) = self.stopped_early {
+            println!(
+                "WARNING: partial result, backtest stopped early ({})",
+                reason
+            );
+        }
+        println!("Initial Capital: ${}", self.initial_capital);
+        println!("Final Value: ${}", self.final_value);
+        println!("Total P&L: ${}", self.total_pnl);
+        println!("Return: {:.2}%", self.return_percentage);
+        println!("Total Commission: ${}", self.total_commission);
+        if self.total_borrow_cost > Decimal::ZERO {
+            println!("Total Borrow Cost: ${}", self.total_borrow_cost);
+        }
+        println!();
+
+        println!("TRADING STATISTICS");
+        println!("{}", "-".repeat(30));
+        println!("Total Trades: {}", self.total_trades);
+        if self.suppressed_dust_orders > 0 {
+            println!("Suppressed Dust Orders: {}", self.suppressed_dust_orders);
+        }
+        if self.suppressed_entries > 0 {
+            println!("Suppressed Re-entries: {}", self.suppressed_entries);
+        }
+        if self.cooldown_suppressed_entries > 0 {
+            println!(
+                "Entries Suppressed by Loss Cooldown: {}",
+                self.cooldown_suppressed_entries
+            );
+        }
+        if let Some(timestamp) = self.kill_switch_triggered_at {
+            println!("Kill Switch Triggered: {}", timestamp);
+        }
+        if !self.strategy_logs.is_empty() {
+            println!("Strategy Log Entries: {}", self.strategy_logs.len());
+        }
+
+        if self.total_trades > 0 {
+            println!(
+                "Winning Trades: {} ({:.1}%)",
+                self.winning_trades, self.win_rate
+            );
+            println!(
+                "Losing Trades: {} ({:.1}%)",
+                self.losing_trades,
+                100.0 - self.win_rate.to_f64().unwrap_or(0.0)
+            );
+            println!("Profit Factor: {}", format_metric(self.profit_factor, 2));
+            println!(
+                "Avg Trade Duration: {:.0} seconds",
+                self.avg_trade_duration_seconds
+            );
+        }
+        println!();
+
+        if self.fill_quality.modeled_execution {
+            self.fill_quality.print();
+        }
+
+        println!("RISK METRICS");
+        println!("{}", "-".repeat(30));
+        println!(
+            "Max Drawdown: {:.2}%",
+            self.max_drawdown * Decimal::from(100)
+        );
+        println!("Sharpe Ratio: {}", format_metric(self.sharpe_ratio, 2));
+        println!("Sortino Ratio: {}", format_metric(self.sortino_ratio, 2));
+        println!("Calmar Ratio: {}", format_metric(self.calmar_ratio, 2));
+        println!(
+            "Downside Deviation: {:.2}%",
+            self.downside_deviation * Decimal::from(100)
+        );
+        println!("Ulcer Index: {:.2}", self.ulcer_index);
+        println!("Pain Ratio: {}", format_metric(self.pain_ratio, 2));
+        println!("Volatility: {:.2}%", self.volatility * Decimal::from(100));
+        if let (Some(symbol), Some(beta), Some(alpha)) =
+            (&self.benchmark_symbol, self.beta, self.alpha)
+        {
+            println!("Beta vs {}: {:.2}", symbol, beta);
+            println!("Alpha vs {}: {:.6}", symbol, alpha);
+        }
+        println!();
+
+        if !self.positions.is_empty() {
+            println!("CURRENT POSITIONS");
+            println!("{}", "-".repeat(30));
+            for (symbol, position) in &self.positions {
+                println!(
+                    "{}: {} @ ${} (Unrealized P&L: ${})",
+                    symbol, position.quantity, position.avg_price, position.unrealized_pnl
+                );
+            }
+            println!();
+        }
+
+        if self.total_trades > 0 {
+            println!("RECENT TRADES (Last 5)");
+            println!("{}", "-".repeat(30));
+            for trade in self.trades.iter().rev().take(5) {
+                let pnl_str = trade
+                    .realized_pnl
+                    .map(|pnl| format!("(P&L: ${})", pnl))
+                    .unwrap_or_default();
+                println!(
+                    "{} {} {} @ ${} {}",
+                    trade.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                    match trade.side {
+                        crate::data::types::TradeSide::Buy => "BUY ",
+                        crate::data::types::TradeSide::Sell => "SELL",
+                    },
+                    trade.symbol,
+                    trade.price,
+                    pnl_str
+                );
+            }
+        }
+
+        println!("{}", "=".repeat(60));
+    }
+
+    /// Write the equity curve as `timestamp,equity` CSV rows with a header;
+    /// equity keeps full decimal precision and timestamps are RFC 3339
+    pub fn export_equity_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut csv = csv::Writer::from_writer(writer);
+        csv.write_record(["timestamp", "equity"])?;
+        for (timestamp, equity) in self.equity_timestamps.iter().zip(&self.equity_curve) {
+            csv.write_record([timestamp.to_rfc3339(), equity.to_string()])?;
+        }
+        csv.flush()
+    }
+
+    /// Write `trades.csv` and `equity_curve.csv` into `dir`, creating it if
+    /// needed; decimals are written as strings in full precision
+    pub fn export_csv(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+
+        results::write_trades_csv(
+            fs::File::create(dir.join(results::TRADES_FILE))?,
+            &self.trades,
+        )?;
+
+        self.export_equity_csv(fs::File::create(dir.join(results::EQUITY_CURVE_FILE))?)
+    }
+
+    pub fn is_profitable(&self) -> bool {
+        self.total_pnl > Decimal::ZERO
+    }
+
+    pub fn print_trade_analysis(&self) {
+        if self.trades.is_empty() {
+            println!("No trades executed");
+            return;
+        }
+
+        println!("DETAILED TRADE ANALYSIS");
+        println!("{}", "=".repeat(80));
+
+        let mut buy_trades = Vec::new();
+        let mut sell_trades = Vec::new();
+
+        for trade in &self.trades {
+            match trade.side {
+                crate::data::types::TradeSide::Buy => buy_trades.push(trade),
+                crate::data::types::TradeSide::Sell => sell_trades.push(trade),
+            }
+        }
+
+        println!("Buy Trades: {}", buy_trades.len());
+        println!("Sell Trades: {}", sell_trades.len());
+
+        if !sell_trades.is_empty() {
+            let profitable_sells = sell_trades
+                .iter()
+                .filter(|t| t.realized_pnl.map_or(false, |pnl| pnl > Money::ZERO))
+                .count();
+
+            let total_profit: Money = sell_trades
+                .iter()
+                .filter_map(|t| t.realized_pnl)
+                .filter(|&pnl| pnl > Money::ZERO)
+                .sum();
+
+            let total_loss: Money = sell_trades
+                .iter()
+                .filter_map(|t| t.realized_pnl)
+                .filter(|&pnl| pnl < Money::ZERO)
+                .sum();
+
+            println!(
+                "Profitable Sells: {} ({:.1}%)",
+                profitable_sells,
+                (profitable_sells as f64 / sell_trades.len() as f64) * 100.0
+            );
+            println!("Total Gross Profit: ${}", total_profit);
+            println!("Total Gross Loss: ${}", total_loss);
+
+            if profitable_sells > 0 {
+                println!(
+                    "Average Profit per Winning Trade: ${}",
+                    total_profit / Decimal::from(profitable_sells)
+                );
+            }
+
+            let losing_sells = sell_trades.len() - profitable_sells;
+            if losing_sells > 0 {
+                println!(
+                    "Average Loss per Losing Trade: ${}",
+                    total_loss / Decimal::from(losing_sells)
+                );
+            }
+        }
+
+        println!("{}", "=".repeat(80));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::logger::LogLevel;
+    use crate::backtest::portfolio::{PortfolioSnapshot, Position};
+    use crate::data::types::{OHLCData, Timeframe, TradeSide};
+    use chrono::{Duration, TimeZone};
+
+    /// Emits a predefined signal per tick, holding once the script runs out
+    struct ScriptedStrategy {
+        script: Vec<Signal>,
+        index: usize,
+    }
+
+    impl ScriptedStrategy {
+        fn new(script: Vec<Signal>) -> Self {
+            Self { script, index: 0 }
+        }
+
+        fn buy_and_hold(quantity: i64) -> Self {
+            Self::new(vec![buy(quantity)])
+        }
+    }
+
+    impl Strategy for ScriptedStrategy {
+        fn name(&self) -> &str {
+            "Scripted"
+        }
+
+        fn on_tick(&mut self, _tick: &TickData) -> Signal {
+            let signal = self.script.get(self.index).cloned().unwrap_or(Signal::Hold);
+            self.index += 1;
+            signal
+        }
+
+        fn on_ohlc(&mut self, _ohlc: &OHLCData) -> Signal {
+            let signal = self.script.get(self.index).cloned().unwrap_or(Signal::Hold);
+            self.index += 1;
+            signal
+        }
+
+        fn initialize(&mut self, _params: HashMap<String, String>) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn reset(&mut self) {
+            self.index = 0;
+        }
+    }
+
+    fn buy(quantity: i64) -> Signal {
+        Signal::Buy {
+            symbol: "BTCUSDT".to_string(),
+            quantity: Decimal::from(quantity),
+        }
+    }
+
+    fn sell(quantity: i64) -> Signal {
+        Signal::Sell {
+            symbol: "BTCUSDT".to_string(),
+            quantity: Decimal::from(quantity),
+        }
+    }
+
+    fn create_ticks(prices: &[i64]) -> Vec<TickData> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| {
+                TickData::new(
+                    start + Duration::seconds(i as i64),
+                    "BTCUSDT".to_string(),
+                    Decimal::from(*price),
+                    Decimal::ONE,
+                    TradeSide::Buy,
+                    i.to_string(),
+                    false,
+                )
+            })
+            .collect()
+    }
+
+    fn run_with_config(config: BacktestConfig, prices: &[i64]) -> BacktestResult {
+        run_script(ScriptedStrategy::buy_and_hold(10), config, prices)
+    }
+
+    fn run_script(
+        strategy: ScriptedStrategy,
+        config: BacktestConfig,
+        prices: &[i64],
+    ) -> BacktestResult {
+        let mut engine = BacktestEngine::new(Box::new(strategy), config).unwrap();
+        engine.run(&create_ticks(prices))
+    }
+
+    #[test]
+    fn test_metrics_burn_in_changes_sharpe() {
+        // Volatile warm-up followed by a steady climb
+        let prices = [100, 60, 140, 50, 150, 100, 101, 102, 103, 104, 105, 106];
+        let base = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+
+        let full = run_with_config(base.clone(), &prices);
+        let burned_in = run_with_config(base.with_metrics_burn_in(6), &prices);
+
+        assert_ne!(full.sharpe_ratio, burned_in.sharpe_ratio);
+        assert!(burned_in.sharpe_ratio > full.sharpe_ratio);
+        assert!(burned_in.max_drawdown < full.max_drawdown);
+
+        // Simulation itself is unaffected
+        assert_eq!(full.final_value, burned_in.final_value);
+        assert_eq!(full.equity_curve, burned_in.equity_curve);
+    }
+
+    #[test]
+    fn test_equity_curve_capped_keeps_extremes() {
+        // A saw-tooth with one deep dip and one spike, held throughout
+        let mut prices: Vec<i64> = (0..5000).map(|i| 1000 + (i * 37) % 101).collect();
+        prices[1234] = 10;
+        prices[3210] = 5000;
+        let base = BacktestConfig::new(Decimal::from(100000)).with_commission_rate(Decimal::ZERO);
+
+        let full = run_with_config(base.clone(), &prices);
+        let capped = run_with_config(base.with_max_equity_points(100), &prices);
+
+        assert_eq!(full.equity_curve.len(), prices.len() + 1);
+        assert!(capped.equity_curve.len() <= 100);
+        assert_eq!(capped.equity_timestamps.len(), capped.equity_curve.len());
+        assert!(capped.equity_timestamps.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(capped.equity_curve.first(), full.equity_curve.first());
+        assert_eq!(capped.equity_curve.last(), full.equity_curve.last());
+        for extreme in [
+            full.equity_curve.iter().min().unwrap(),
+            full.equity_curve.iter().max().unwrap(),
+        ] {
+            assert!(capped.equity_curve.contains(extreme));
+        }
+
+        // Metrics come from the full-resolution curve
+        assert_eq!(capped.max_drawdown, full.max_drawdown);
+        assert_eq!(capped.sharpe_ratio, full.sharpe_ratio);
+    }
+
+    #[test]
+    fn test_risk_free_rate_lowers_sharpe() {
+        let prices = [100, 102, 101, 104, 103, 106, 105, 108];
+        let base = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::ZERO)
+            .with_periods_per_year(365);
+
+        let no_rate = run_with_config(base.clone(), &prices);
+        let with_rate = run_with_config(
+            base.with_risk_free_rate(Decimal::from_str("0.05").unwrap()),
+            &prices,
+        );
+
+        let sharpe = no_rate.sharpe_ratio.unwrap();
+        assert!(sharpe > Decimal::ZERO);
+        assert!(with_rate.sharpe_ratio.unwrap() < sharpe);
+        assert!(with_rate.sortino_ratio.unwrap() < no_rate.sortino_ratio.unwrap());
+        assert_eq!(no_rate.equity_curve, with_rate.equity_curve);
+    }
+
+    #[test]
+    fn test_periods_per_year_annualizes_sharpe() {
+        let prices = [100, 102, 101, 104, 103, 106, 105, 108];
+        let base = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+
+        let per_period = run_with_config(base.clone(), &prices);
+        let annualized = run_with_config(base.with_periods_per_year(4), &prices);
+
+        // sqrt(4) = 2
+        assert_eq!(
+            annualized.sharpe_ratio.unwrap(),
+            per_period.sharpe_ratio.unwrap() * Decimal::from(2)
+        );
+    }
+
+    /// Buys once, then sleeps on every bar to simulate an expensive strategy
+    struct SlowStrategy {
+        inner: ScriptedStrategy,
+        delay: std::time::Duration,
+    }
+
+    impl Strategy for SlowStrategy {
+        fn name(&self) -> &str {
+            "Slow"
+        }
+
+        fn on_tick(&mut self, tick: &TickData) -> Signal {
+            std::thread::sleep(self.delay);
+            self.inner.on_tick(tick)
+        }
+
+        fn on_ohlc(&mut self, ohlc: &OHLCData) -> Signal {
+            std::thread::sleep(self.delay);
+            self.inner.on_ohlc(ohlc)
+        }
+
+        fn initialize(&mut self, _params: HashMap<String, String>) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn reset(&mut self) {
+            self.inner.reset();
+        }
+    }
+
+    #[test]
+    fn test_max_runtime_stops_with_partial_result() {
+        let prices = vec![100; STOP_CHECK_INTERVAL * 4];
+        let strategy = SlowStrategy {
+            inner: ScriptedStrategy::buy_and_hold(1),
+            delay: std::time::Duration::from_micros(200),
+        };
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_max_runtime(std::time::Duration::from_millis(1));
+        let mut engine = BacktestEngine::new(Box::new(strategy), config).unwrap();
+        let result = engine.run(&create_ticks(&prices));
+
+        assert_eq!(result.stopped_early, Some(StopReason::TimedOut));
+        // Initial point plus one per processed tick, stopped at the first check
+        assert_eq!(result.equity_curve.len(), STOP_CHECK_INTERVAL + 1);
+        assert_eq!(result.total_trades, 1);
+
+        // Without a limit the same run reaches the end unflagged
+        let complete = run_with_config(BacktestConfig::new(Decimal::from(10000)), &prices);
+        assert_eq!(complete.stopped_early, None);
+        assert_eq!(complete.equity_curve.len(), prices.len() + 1);
+    }
+
+    #[test]
+    fn test_cancel_flag_stops_run() {
+        let prices = vec![100; STOP_CHECK_INTERVAL * 2];
+        let flag = Arc::new(AtomicBool::new(true));
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_max_runtime(std::time::Duration::from_secs(3600))
+            .with_cancel_flag(Arc::clone(&flag));
+
+        let result = run_with_config(config.clone(), &prices);
+        assert_eq!(result.stopped_early, Some(StopReason::Cancelled));
+        assert_eq!(result.equity_curve.len(), STOP_CHECK_INTERVAL + 1);
+
+        flag.store(false, Ordering::Relaxed);
+        assert_eq!(run_with_config(config, &prices).stopped_early, None);
+    }
+
+    #[test]
+    fn test_export_csv_writes_trades_and_equity() {
+        let prices = [100, 103, 99, 108];
+        let script = vec![buy(10), Signal::Hold, sell(4)];
+        let result = run_script(
+            ScriptedStrategy::new(script),
+            BacktestConfig::new(Decimal::from(10000)),
+            &prices,
+        );
+        let dir = std::env::temp_dir().join(format!(
+            "backtest_csv_test_{}_{}",
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+
+        result.export_csv(&dir).unwrap();
+
+        let mut trades = csv::Reader::from_path(dir.join(results::TRADES_FILE)).unwrap();
+        assert_eq!(
+            trades.headers().unwrap(),
+            vec![
+                "timestamp",
+                "symbol",
+                "side",
+                "quantity",
+                "price",
+                "commission",
+                "realized_pnl"
+            ]
+        );
+        let rows: Vec<csv::StringRecord> = trades.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0][2], "BUY");
+        assert_eq!(&rows[0][6], "");
+        assert_eq!(&rows[1][2], "SELL");
+        // Full precision: 0.1% of 4 * 99 = 0.396
+        assert_eq!(
+            Decimal::from_str(&rows[1][5]).unwrap(),
+            result.trades[1].commission.value()
+        );
+        assert_eq!(
+            Decimal::from_str(&rows[1][5]).unwrap(),
+            Decimal::from_str("0.396").unwrap()
+        );
+        assert_eq!(
+            Decimal::from_str(&rows[1][6]).unwrap(),
+            result.trades[1].realized_pnl.unwrap().value()
+        );
+
+        let mut equity = csv::Reader::from_path(dir.join(results::EQUITY_CURVE_FILE)).unwrap();
+        assert_eq!(equity.headers().unwrap(), vec!["timestamp", "equity"]);
+        let rows: Vec<csv::StringRecord> = equity.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), prices.len() + 1);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(
+                DateTime::parse_from_rfc3339(&row[0]).unwrap(),
+                result.equity_timestamps[i]
+            );
+            assert_eq!(Decimal::from_str(&row[1]).unwrap(), result.equity_curve[i]);
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_equity_csv_matches_curve() {
+        let prices = [100, 103, 99, 108];
+        let result = run_with_config(BacktestConfig::new(Decimal::from(10000)), &prices);
+
+        let mut buffer = Vec::new();
+        result.export_equity_csv(&mut buffer).unwrap();
+        let csv = String::from_utf8(buffer).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("timestamp,equity"));
+
+        let rows: Vec<(DateTime<Utc>, Decimal)> = lines
+            .map(|line| {
+                let (timestamp, equity) = line.split_once(',').unwrap();
+                (
+                    DateTime::parse_from_rfc3339(timestamp).unwrap().into(),
+                    Decimal::from_str(equity).unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(rows.len(), result.equity_curve.len());
+        assert_eq!(rows.len(), prices.len() + 1);
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        for (i, (timestamp, equity)) in rows.iter().enumerate() {
+            assert_eq!(*equity, result.equity_curve[i]);
+            assert_eq!(
+                *timestamp,
+                start + Duration::seconds(i.saturating_sub(1) as i64)
+            );
+        }
+    }
+
+    #[test]
+    fn test_flat_run_leaves_ratios_undefined() {
+        let config = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+        let result = run_with_config(config, &[100, 100, 100, 100]);
+
+        assert_eq!(result.sharpe_ratio, None);
+        assert_eq!(result.sortino_ratio, None);
+        assert_eq!(result.calmar_ratio, None);
+        assert_eq!(result.downside_deviation, Decimal::ZERO);
+        assert_eq!(result.profit_factor, None);
+        assert_eq!(result.ulcer_index, Decimal::ZERO);
+        assert_eq!(result.pain_ratio, None);
+
+        let json: serde_json::Value = serde_json::to_value(&result).unwrap();
+        for field in [
+            "sharpe_ratio",
+            "sortino_ratio",
+            "calmar_ratio",
+            "profit_factor",
+            "pain_ratio",
+        ] {
+            assert!(json[field].is_null(), "{} should serialize as null", field);
+        }
+    }
+
+    #[test]
+    fn test_metrics_burn_in_longer_than_run() {
+        let prices = [100, 101, 102];
+        let config = BacktestConfig::new(Decimal::from(10000)).with_metrics_burn_in(100);
+        let result = run_with_config(config, &prices);
+
+        assert_eq!(result.sharpe_ratio, None);
+        assert_eq!(result.max_drawdown, Decimal::ZERO);
+        assert_eq!(result.equity_curve.len(), prices.len() + 1);
+    }
+
+    #[test]
+    fn test_min_trade_notional_suppresses_dust() {
+        let prices = [100, 100, 100, 100];
+        // 10 @ 100 = 1000 notional, 1 @ 100 = 100 notional
+        let script = vec![buy(10), buy(1), sell(1), sell(10)];
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::ZERO)
+            .with_min_trade_notional(Decimal::from(500));
+
+        let result = run_script(ScriptedStrategy::new(script), config, &prices);
+
+        assert_eq!(result.suppressed_dust_orders, 2);
+        assert_eq!(result.total_trades, 2);
+        assert!(result
+            .trades
+            .iter()
+            .all(|t| t.quantity.value() == Decimal::from(10)));
+    }
+
+    #[test]
+    fn test_symbol_metadata_rounds_to_lot_size() {
+        let prices = [100, 100, 100, 100];
+        let script = vec![
+            Signal::Buy {
+                symbol: "BTCUSDT".to_string(),
+                quantity: Decimal::from_str("2.57").unwrap(),
+            },
+            // Rounds down to zero lots
+            Signal::Buy {
+                symbol: "BTCUSDT".to_string(),
+                quantity: Decimal::from_str("0.4").unwrap(),
+            },
+            // 0.5 @ 100 = 50, below the exchange minimum
+            Signal::Sell {
+                symbol: "BTCUSDT".to_string(),
+                quantity: Decimal::from_str("0.5").unwrap(),
+            },
+            Signal::Sell {
+                symbol: "BTCUSDT".to_string(),
+                quantity: Decimal::from_str("2.9").unwrap(),
+            },
+        ];
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::ZERO)
+            .with_symbol_metadata([SymbolMetadata {
+                symbol: "BTCUSDT".to_string(),
+                base_asset: "BTC".to_string(),
+                quote_asset: "USDT".to_string(),
+                tick_size: Decimal::from_str("0.01").unwrap(),
+                step_size: Decimal::from_str("0.5").unwrap(),
+                min_notional: Decimal::from(100),
+                is_active: true,
+            }]);
+
+        let result = run_script(ScriptedStrategy::new(script), config, &prices);
+
+        assert_eq!(result.suppressed_dust_orders, 2);
+        let quantities: Vec<Decimal> = result.trades.iter().map(|t| t.quantity.value()).collect();
+        assert_eq!(quantities, vec![Decimal::new(25, 1), Decimal::new(25, 1)]);
+    }
+
+    #[test]
+    fn test_drawdown_kill_switch_halts_trading() {
+        let prices = [100, 110, 95, 80, 90, 120, 60];
+        let ticks = create_ticks(&prices);
+        // Buy, then keep trying to trade after the crash
+        let script = vec![
+            buy(50),
+            Signal::Hold,
+            Signal::Hold,
+            Signal::Hold,
+            buy(10),
+            sell(10),
+        ];
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::ZERO)
+            .with_max_portfolio_drawdown_pct(Decimal::from(10));
+
+        let result = run_script(ScriptedStrategy::new(script), config, &prices);
+
+        // Peak 10500 at 110; 95 leaves 9750 (7.1% down), 80 leaves 9000 (14.3% down)
+        assert_eq!(result.kill_switch_triggered_at, Some(ticks[3].timestamp));
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(result.trades[1].side, TradeSide::Sell);
+        assert_eq!(result.trades[1].price.value(), Decimal::from(80));
+        assert!(result.positions.is_empty());
+
+        // Flat after the halt: equity stays at the liquidation value
+        assert_eq!(result.equity_curve.len(), prices.len() + 1);
+        assert!(result.equity_curve[4..]
+            .iter()
+            .all(|value| *value == Decimal::from(9000)));
+        assert_eq!(result.final_value, Decimal::from(9000));
+
+        let untouched = run_script(
+            ScriptedStrategy::new(vec![buy(50)]),
+            BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO),
+            &prices,
+        );
+        assert_eq!(untouched.kill_switch_triggered_at, None);
+    }
+
+    #[test]
+    fn test_min_trade_notional_disabled_by_default() {
+        let prices = [100, 100];
+        let script = vec![buy(1), sell(1)];
+        let config = BacktestConfig::new(Decimal::from(10000));
+
+        let result = run_script(ScriptedStrategy::new(script), config, &prices);
+
+        assert_eq!(result.suppressed_dust_orders, 0);
+        assert_eq!(result.total_trades, 2);
+    }
+
+    #[test]
+    fn test_beta_of_strategy_tracking_benchmark() {
+        let prices = [100, 104, 98, 103, 110, 107, 112];
+        let ticks = create_ticks(&prices);
+        let benchmark: Vec<_> = ticks.iter().map(|t| (t.timestamp, t.price)).collect();
+
+        // Fully invested buy-and-hold in the benchmark asset itself
+        let config = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+        let strategy = Box::new(ScriptedStrategy::buy_and_hold(100));
+        let mut engine = BacktestEngine::new(strategy, config)
+            .unwrap()
+            .with_benchmark("BTCUSDT", benchmark);
+        let result = engine.run(&ticks);
+
+        let beta = result.beta.expect("beta should be computed");
+        let alpha = result.alpha.expect("alpha should be computed");
+        assert!((beta - Decimal::ONE).abs() < Decimal::from_str("0.000001").unwrap());
+        assert!(alpha.abs() < Decimal::from_str("0.000001").unwrap());
+        assert_eq!(result.benchmark_symbol.as_deref(), Some("BTCUSDT"));
+    }
+
+    #[test]
+    fn test_beta_absent_without_benchmark() {
+        let config = BacktestConfig::new(Decimal::from(10000));
+        let result = run_with_config(config, &[100, 101, 102]);
+        assert!(result.beta.is_none());
+        assert!(result.alpha.is_none());
+    }
+
+    fn create_candles(open_close: &[(i64, i64)]) -> Vec<OHLCData> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        open_close
+            .iter()
+            .enumerate()
+            .map(|(i, (open, close))| {
+                let (open, close) = (Decimal::from(*open), Decimal::from(*close));
+                OHLCData::new(
+                    start + Duration::minutes(i as i64),
+                    "BTCUSDT".to_string(),
+                    Timeframe::OneMinute,
+                    open,
+                    open.max(close),
+                    open.min(close),
+                    close,
+                    Decimal::ONE,
+                    close,
+                    1,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fill_on_next_open_changes_returns() {
+        let candles = create_candles(&[(100, 100), (110, 120), (120, 130), (125, 125)]);
+        let script = vec![buy(10), Signal::Hold, sell(10)];
+        let base = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+
+        let mut engine = BacktestEngine::new(
+            Box::new(ScriptedStrategy::new(script.clone())),
+            base.clone(),
+        )
+        .unwrap();
+        let at_close = engine.run_with_ohlc(&candles);
+
+        let mut engine = BacktestEngine::new(
+            Box::new(ScriptedStrategy::new(script)),
+            base.with_fill_on(FillTiming::OpenOfNextCandle),
+        )
+        .unwrap();
+        let at_next_open = engine.run_with_ohlc(&candles);
+
+        // Close fills: buy @100, sell @130. Next-open fills: buy @110, sell @125.
+        assert_eq!(at_close.total_pnl, Decimal::from(300));
+        assert_eq!(at_next_open.total_pnl, Decimal::from(150));
+        assert_eq!(at_next_open.trades[0].price.value(), Decimal::from(110));
+        assert_eq!(at_next_open.trades[1].price.value(), Decimal::from(125));
+    }
+
+    #[test]
+    fn test_min_bars_between_trades_holds_back_entries() {
+        let candles = create_candles(&[(100, 100); 8]);
+        // Exit on bar 1, then try to re-enter on every bar after it
+        let script = vec![
+            buy(1),
+            sell(1),
+            buy(1),
+            buy(1),
+            buy(1),
+            sell(1),
+            buy(1),
+            buy(1),
+        ];
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::ZERO)
+            .with_min_bars_between_trades(3);
+
+        let mut engine =
+            BacktestEngine::new(Box::new(ScriptedStrategy::new(script)), config).unwrap();
+        let result = engine.run_with_ohlc(&candles);
+
+        // Bar 0 buys, bar 1 sells immediately (exits are not held back),
+        // bars 2-3 are within 3 bars of it, bar 4 re-enters, bar 5 exits,
+        // bars 6-7 are held back again
+        let timestamps: Vec<_> = result.trades.iter().map(|t| t.timestamp).collect();
+        let expected: Vec<_> = [0, 1, 4, 5].iter().map(|&i| candles[i].timestamp).collect();
+        assert_eq!(timestamps, expected);
+        assert_eq!(result.suppressed_entries, 4);
+    }
+
+    #[test]
+    fn test_losing_exit_starts_cooldown() {
+        // Bar 1 exits at a loss, bar 5 exits at a profit
+        let script = vec![buy(1), sell(1), buy(1), buy(1), buy(1), sell(1), buy(1)];
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::ZERO)
+            .with_cooldown_after_loss(2);
+        let result = run_script(
+            ScriptedStrategy::new(script),
+            config,
+            &[100, 90, 90, 90, 90, 95, 95],
+        );
+
+        // Bars 2-3 are within the cooldown, bar 4 re-enters and the
+        // profitable exit leaves bar 6 free to buy
+        let bars: Vec<i64> = result
+            .trades
+            .iter()
+            .map(|t| t.timestamp.timestamp() - result.trades[0].timestamp.timestamp())
+            .collect();
+        assert_eq!(bars, [0, 1, 4, 5, 6]);
+        assert_eq!(
+            result.trades[1].realized_pnl.map(Money::value),
+            Some(Decimal::from(-10))
+        );
+        assert_eq!(result.cooldown_suppressed_entries, 2);
+    }
+
+    #[test]
+    fn test_cooldown_holds_back_short_entries_but_not_covers() {
+        // Bar 1 covers half the short at a loss; the break-even cover on
+        // bar 2 still fills while the new short on bar 3 is held back
+        let script = vec![sell(2), buy(1), buy(1), sell(1), sell(1)];
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::ZERO)
+            .with_short_selling(true)
+            .with_cooldown_after_loss(2);
+        let result = run_script(
+            ScriptedStrategy::new(script),
+            config,
+            &[100, 110, 100, 110, 110],
+        );
+
+        let bars: Vec<i64> = result
+            .trades
+            .iter()
+            .map(|t| t.timestamp.timestamp() - result.trades[0].timestamp.timestamp())
+            .collect();
+        assert_eq!(bars, [0, 1, 2, 4]);
+        assert_eq!(result.cooldown_suppressed_entries, 1);
+    }
+
+    /// Buys whenever the next candle closes higher: it peeks at future data
+    struct PeekingStrategy {
+        closes: Vec<Decimal>,
+        index: usize,
+    }
+
+    impl PeekingStrategy {
+        fn new(visible: &[OHLCData]) -> Self {
+            Self {
+                closes: visible.iter().map(|c| c.close).collect(),
+                index: 0,
+            }
+        }
+    }
+
+    impl Strategy for PeekingStrategy {
+        fn name(&self) -> &str {
+            "Peeking"
+        }
+
+        fn on_tick(&mut self, _tick: &TickData) -> Signal {
+            Signal::Hold
+        }
+
+        fn on_ohlc(&mut self, ohlc: &OHLCData) -> Signal {
+            let next = self.closes.get(self.index + 1).copied();
+            self.index += 1;
+            match next {
+                Some(next) if next > ohlc.close => buy(1),
+                _ => Signal::Hold,
+            }
+        }
+
+        fn initialize(&mut self, _params: HashMap<String, String>) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_lookahead_check_flags_future_dependent_strategy() {
+        let candles = create_candles(&[(100, 100), (100, 110), (110, 105), (105, 120)]);
+
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_lookahead_check(|visible| Box::new(PeekingStrategy::new(visible)));
+        let mut engine =
+            BacktestEngine::new(Box::new(PeekingStrategy::new(&candles)), config).unwrap();
+        let result = engine.run_with_ohlc(&candles);
+
+        // Buys at candles 0 and 2 relied on the following close
+        assert_eq!(
+            result.lookahead_violations,
+            vec![candles[0].timestamp, candles[2].timestamp]
+        );
+
+        let script = vec![buy(1), Signal::Hold, sell(1)];
+        let replay = script.clone();
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_lookahead_check(move |_| Box::new(ScriptedStrategy::new(replay.clone())));
+        let mut engine =
+            BacktestEngine::new(Box::new(ScriptedStrategy::new(script)), config).unwrap();
+        assert!(engine
+            .run_with_ohlc(&candles)
+            .lookahead_violations
+            .is_empty());
+    }
+
+    #[test]
+    fn test_volume_tiered_commission_fn() {
+        // 0.1% until 10,000 of volume has traded, 0.05% afterwards
+        let tiered = |fill: &FillContext| {
+            let rate = if fill.cumulative_volume >= Decimal::from(10000) {
+                Decimal::from_str("0.0005").unwrap()
+            } else {
+                Decimal::from_str("0.001").unwrap()
+            };
+            fill.quantity * fill.price * rate
+        };
+        let config = BacktestConfig::new(Decimal::from(100000)).with_commission_fn(tiered);
+        let script = vec![buy(100), sell(100), buy(100)];
+        let result = run_script(ScriptedStrategy::new(script), config, &[100, 100, 100]);
+
+        let commissions: Vec<Decimal> =
+            result.trades.iter().map(|t| t.commission.value()).collect();
+        assert_eq!(
+            commissions,
+            vec![Decimal::from(10), Decimal::from(5), Decimal::from(5)]
+        );
+        assert_eq!(result.total_commission, Decimal::from(20));
+    }
+
+    #[test]
+    fn test_commission_rounds_to_precision() {
+        let run = |config: BacktestConfig| {
+            let config = config.with_commission_rate(Decimal::from_str("0.000123").unwrap());
+            run_script(
+                ScriptedStrategy::new(vec![buy(7), sell(7)]),
+                config,
+                &[101, 103, 103],
+            )
+        };
+        let commissions = |result: &BacktestResult| -> Vec<Decimal> {
+            result.trades.iter().map(|t| t.commission.value()).collect()
+        };
+
+        // 707 * 0.000123 = 0.086961 and 721 * 0.000123 = 0.088683
+        let full = run(BacktestConfig::new(Decimal::from(10000)));
+        assert_eq!(
+            commissions(&full),
+            vec![
+                Decimal::from_str("0.086961").unwrap(),
+                Decimal::from_str("0.088683").unwrap()
+            ]
+        );
+
+        let cents = run(BacktestConfig::new(Decimal::from(10000))
+            .with_commission_precision(2, RoundingStrategy::MidpointAwayFromZero));
+        assert_eq!(
+            commissions(&cents),
+            vec![Decimal::new(9, 2), Decimal::new(9, 2)]
+        );
+        assert_eq!(cents.total_commission, Decimal::new(18, 2));
+
+        let truncated = run(BacktestConfig::new(Decimal::from(10000))
+            .with_commission_precision(2, RoundingStrategy::ToZero));
+        assert_eq!(truncated.total_commission, Decimal::new(16, 2));
+        // The rounded fees are what the cash balance paid
+        assert_eq!(
+            truncated.final_value - cents.final_value,
+            Decimal::new(2, 2)
+        );
+    }
+
+    #[test]
+    fn test_position_history_tracks_fills() {
+        let script = vec![buy(10), Signal::Hold, sell(10)];
+        let prices = [100, 110, 120, 130];
+        let config = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+        let result = run_script(ScriptedStrategy::new(script), config, &prices);
+
+        // One snapshot per fill, none for the bars in between
+        let ticks = create_ticks(&prices);
+        let times: Vec<DateTime<Utc>> = result.position_history.iter().map(|(t, _)| *t).collect();
+        assert_eq!(times, vec![ticks[0].timestamp, ticks[2].timestamp]);
+
+        let (_, after_buy) = &result.position_history[0];
+        let position = &after_buy["BTCUSDT"];
+        assert_eq!(position.quantity, Quantity::new(Decimal::from(10)));
+        assert_eq!(position.avg_price, Price::new(Decimal::from(100)));
+
+        // Fully sold: flat again
+        let (_, after_sell) = &result.position_history[1];
+        assert!(after_sell.is_empty());
+    }
+
+    #[test]
+    fn test_short_selling_opens_and_covers_shorts() {
+        let script = vec![sell(10), Signal::Hold, buy(10), sell(10), buy(10)];
+        let prices = [100, 90, 90, 90, 99];
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::ZERO)
+            .with_short_selling(true);
+        let result = run_script(ScriptedStrategy::new(script.clone()), config, &prices);
+
+        // Short 10 at 100, marked at 90: 100 unrealized profit on top of
+        // 11000 cash less the 900 it would cost to buy back
+        assert_eq!(result.equity_curve[2], Decimal::from(10100));
+
+        // Covered at 90 for a profit, then shorted at 90 and covered at 99
+        let realized: Vec<(TradeSide, Option<Decimal>)> = result
+            .trades
+            .iter()
+            .map(|t| (t.side, t.realized_pnl.map(Money::value)))
+            .collect();
+        assert_eq!(
+            realized,
+            vec![
+                (TradeSide::Sell, None),
+                (TradeSide::Buy, Some(Decimal::from(100))),
+                (TradeSide::Sell, None),
+                (TradeSide::Buy, Some(Decimal::from(-90))),
+            ]
+        );
+        assert_eq!(result.total_pnl, Decimal::from(10));
+        assert_eq!(result.final_value, Decimal::from(10010));
+
+        // Long-only by default: the opening sell is rejected
+        let config = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+        let result = run_script(ScriptedStrategy::new(script), config, &prices);
+        assert_eq!(result.trades[0].side, TradeSide::Buy);
+        assert!(result
+            .trades
+            .iter()
+            .all(|t| t.quantity.value() == Decimal::from(10)));
+    }
+
+    #[test]
+    fn test_short_notional_is_capped_by_margin_ratio() {
+        // 150 at 100 is 15000 short against 10000 of equity
+        let run = |policy, ratio| {
+            let config = BacktestConfig::new(Decimal::from(10000))
+                .with_commission_rate(Decimal::ZERO)
+                .with_short_selling(true)
+                .with_short_margin_ratio(ratio)
+                .with_insufficient_funds_policy(policy);
+            run_script(ScriptedStrategy::new(vec![sell(150)]), config, &[100])
+        };
+
+        let rejected = run(InsufficientFundsPolicy::Reject, Decimal::ONE);
+        assert!(rejected.trades.is_empty());
+
+        let partial = run(InsufficientFundsPolicy::PartialFill, Decimal::ONE);
+        assert_eq!(partial.trades[0].quantity.value(), Decimal::from(100));
+        assert_eq!(partial.fill_quality.partial_fills, 1);
+
+        let leveraged = run(InsufficientFundsPolicy::Reject, Decimal::from(2));
+        assert_eq!(leveraged.trades[0].quantity.value(), Decimal::from(150));
+    }
+
+    #[test]
+    fn test_short_capacity_counts_existing_shorts() {
+        let mut portfolio = Portfolio::new(Decimal::from(10000)).with_short_selling(true);
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let sell = |portfolio: &mut Portfolio, symbol: &str, quantity: i64| {
+            portfolio.execute_sell(
+                symbol.to_string(),
+                Decimal::from(quantity),
+                Decimal::from(100),
+                Decimal::ZERO,
+                start,
+            )
+        };
+
+        sell(&mut portfolio, "BTCUSDT", 60).unwrap();
+        assert_eq!(
+            portfolio.short_capacity("ETHUSDT", Price::new(Decimal::from(100))),
+            Quantity::new(Decimal::from(40))
+        );
+        assert!(sell(&mut portfolio, "ETHUSDT", 41).is_err());
+        sell(&mut portfolio, "ETHUSDT", 40).unwrap();
+        assert!(sell(&mut portfolio, "BTCUSDT", 1).is_err());
+    }
+
+    #[test]
+    fn test_short_entry_folds_in_commission() {
+        // 0.1% commission: shorting 10 at 100 receives 999 net
+        let mut portfolio = Portfolio::new(Decimal::from(10000)).with_short_selling(true);
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let rate = Decimal::from_str("0.001").unwrap();
+        portfolio
+            .execute_sell(
+                "BTCUSDT".to_string(),
+                Decimal::from(10),
+                Decimal::from(100),
+                rate,
+                start,
+            )
+            .unwrap();
+
+        let position = &portfolio.positions["BTCUSDT"];
+        assert_eq!(position.quantity, Quantity::new(Decimal::from(-10)));
+        assert_eq!(
+            position.avg_price,
+            Price::new(Decimal::from_str("99.9").unwrap())
+        );
+
+        // Marked at 80 the short is 199 ahead net of the entry fee
+        portfolio.update_price("BTCUSDT", Decimal::from(80));
+        assert_eq!(portfolio.total_unrealized_pnl(), Decimal::from(199));
+        assert_eq!(portfolio.total_value(), Decimal::from(10199));
+        assert_eq!(portfolio.total_pnl(), Decimal::from(199));
+
+        // Covering pays 800 plus 0.8 commission
+        portfolio
+            .execute_buy(
+                "BTCUSDT".to_string(),
+                Decimal::from(10),
+                Decimal::from(80),
+                rate,
+                start,
+            )
+            .unwrap();
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(
+            portfolio.trades[1].realized_pnl.map(Money::value),
+            Some(Decimal::from_str("198.2").unwrap())
+        );
+        assert_eq!(
+            portfolio.cash.value(),
+            Decimal::from_str("10198.2").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_short_position_accrues_borrow_cost() {
+        // 36.5% a year is 0.1% of the short notional per day
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_borrow_rate_annual(Decimal::from_str("0.365").unwrap());
+        let mut engine =
+            BacktestEngine::new(Box::new(ScriptedStrategy::new(Vec::new())), config).unwrap();
+
+        // Seed a 10-unit short opened at 100
+        engine.portfolio.cash += Money::new(Decimal::from(1000));
+        engine.portfolio.positions.insert(
+            "BTCUSDT".to_string(),
+            Position {
+                symbol: "BTCUSDT".to_string(),
+                quantity: Quantity::new(Decimal::from(-10)),
+                avg_price: Price::new(Decimal::from(100)),
+                market_value: Money::new(Decimal::from(-1000)),
+                unrealized_pnl: Money::ZERO,
+            },
+        );
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let ticks: Vec<TickData> = (0..=3)
+            .map(|day| {
+                TickData::new(
+                    start + Duration::days(day),
+                    "BTCUSDT".to_string(),
+                    Decimal::from(100),
+                    Decimal::ONE,
+                    TradeSide::Buy,
+                    format!("day{}", day),
+                    false,
+                )
+            })
+            .collect();
+        let result = engine.run(&ticks);
+
+        // Three days held at a notional of 1000
+        assert_eq!(result.total_borrow_cost, Decimal::from(3));
+        assert_eq!(result.total_pnl, Decimal::from(-3));
+        assert_eq!(result.final_value, Decimal::from(10000 - 3));
+    }
+
+    #[test]
+    fn test_fill_quality_reports_slippage_and_partial_fills() {
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::from_str("0.001").unwrap())
+            .with_slippage(SlippageModel::FixedBps(Decimal::from(10)))
+            .with_insufficient_funds_policy(InsufficientFundsPolicy::PartialFill);
+        // The oversized sell only fills the 10 units held
+        let script = vec![buy(10), Signal::Hold, sell(20)];
+        let result = run_script(ScriptedStrategy::new(script), config, &[100, 100, 200]);
+
+        assert_eq!(
+            result.trades[0].price.value(),
+            Decimal::from_str("100.1").unwrap()
+        );
+        assert_eq!(
+            result.trades[1].price.value(),
+            Decimal::from_str("199.8").unwrap()
+        );
+        assert_eq!(result.trades[1].quantity.value(), Decimal::from(10));
+
+        let quality = &result.fill_quality;
+        assert!(quality.modeled_execution);
+        assert_eq!(quality.fills, 2);
+        assert_eq!(quality.partial_fills, 1);
+        assert_eq!(quality.avg_fill_ratio, Decimal::from_str("0.75").unwrap());
+        assert_eq!(quality.avg_slippage_bps, Decimal::from(10));
+        // 10 * 0.1 on the buy plus 10 * 0.2 on the sell
+        assert_eq!(quality.total_slippage_cost, Decimal::from(3));
+        assert_eq!(quality.total_volume, Decimal::from(2999));
+        assert_eq!(
+            quality.commission_pct_of_volume,
+            Decimal::from_str("0.1").unwrap()
+        );
+    }
+
+    fn limit_buy(quantity: i64, limit_price: i64) -> Signal {
+        Signal::LimitBuy {
+            symbol: "BTCUSDT".to_string(),
+            quantity: Decimal::from(quantity),
+            limit_price: Decimal::from(limit_price),
+        }
+    }
+
+    fn limit_sell(quantity: i64, limit_price: i64) -> Signal {
+        Signal::LimitSell {
+            symbol: "BTCUSDT".to_string(),
+            quantity: Decimal::from(quantity),
+            limit_price: Decimal::from(limit_price),
+        }
+    }
+
+    #[test]
+    fn test_limit_order_waits_for_price_to_cross() {
+        let prices = [100, 98, 96, 94, 97, 103, 106];
+        let ticks = create_ticks(&prices);
+        let script = vec![
+            limit_buy(10, 95),
+            Signal::Hold,
+            Signal::Hold,
+            Signal::Hold,
+            // Already marketable: fills at once at the tick price
+            limit_sell(4, 90),
+            limit_sell(6, 105),
+        ];
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::ZERO)
+            .with_slippage(SlippageModel::FixedBps(Decimal::from(100)));
+
+        let result = run_script(ScriptedStrategy::new(script), config, &prices);
+
+        let fills: Vec<(TradeSide, Decimal, Decimal, DateTime<Utc>)> = result
+            .trades
+            .iter()
+            .map(|t| (t.side, t.quantity.value(), t.price.value(), t.timestamp))
+            .collect();
+        // Queued orders fill at their limit, never with slippage
+        assert_eq!(
+            fills,
+            vec![
+                (
+                    TradeSide::Buy,
+                    Decimal::from(10),
+                    Decimal::from(95),
+                    ticks[3].timestamp
+                ),
+                (
+                    TradeSide::Sell,
+                    Decimal::from(4),
+                    Decimal::from(97),
+                    ticks[4].timestamp
+                ),
+                (
+                    TradeSide::Sell,
+                    Decimal::from(6),
+                    Decimal::from(105),
+                    ticks[6].timestamp
+                ),
+            ]
+        );
+        assert!(result.positions.is_empty());
+    }
+
+    #[test]
+    fn test_resting_limit_fills_pay_maker_rate() {
+        let prices = [100, 98, 94, 110];
+        let script = vec![
+            buy(10),
+            limit_buy(10, 95),
+            // Marketable on arrival, so it takes liquidity
+            limit_sell(5, 90),
+        ];
+        let config = BacktestConfig::new(Decimal::from(10000)).with_maker_taker_rates(
+            Decimal::from_str("0.0001").unwrap(),
+            Decimal::from_str("0.001").unwrap(),
+        );
+
+        let result = run_script(ScriptedStrategy::new(script), config, &prices);
+
+        // Taker 0.1% of 1000, maker 0.01% of 950, taker 0.1% of 470
+        let commissions: Vec<Decimal> =
+            result.trades.iter().map(|t| t.commission.value()).collect();
+        assert_eq!(
+            commissions,
+            [
+                Decimal::ONE,
+                Decimal::from_str("0.095").unwrap(),
+                Decimal::from_str("0.47").unwrap()
+            ]
+        );
+        assert_eq!(result.total_commission, Decimal::from_str("1.565").unwrap());
+    }
+
+    #[test]
+    fn test_limit_order_fills_when_candle_reaches_it() {
+        // Lows: 100, 97, 94; the buy limit at 95 is reached by the third candle
+        let candles = create_candles(&[(100, 100), (100, 97), (97, 94), (94, 99)]);
+        let config = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+        let mut engine = BacktestEngine::new(
+            Box::new(ScriptedStrategy::new(vec![limit_buy(10, 95)])),
+            config,
+        )
+        .unwrap();
+        let result = engine.run_with_ohlc(&candles);
+
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(result.trades[0].price.value(), Decimal::from(95));
+        assert_eq!(result.trades[0].timestamp, candles[2].timestamp);
+    }
+
+    #[test]
+    fn test_queued_limit_fill_starts_min_bars_window() {
+        // The buy limit at 95 rests until the second candle trades down to 94
+        let candles = create_candles(&[(100, 100), (100, 94), (94, 94), (94, 94), (94, 94)]);
+        let script = vec![limit_buy(1, 95), Signal::Hold, buy(1), buy(1), buy(1)];
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::ZERO)
+            .with_min_bars_between_trades(3);
+        let mut engine =
+            BacktestEngine::new(Box::new(ScriptedStrategy::new(script)), config).unwrap();
+        let result = engine.run_with_ohlc(&candles);
+
+        // Bars 2-3 are within 3 bars of the limit fill, bar 4 enters
+        let timestamps: Vec<_> = result.trades.iter().map(|t| t.timestamp).collect();
+        assert_eq!(timestamps, [candles[1].timestamp, candles[4].timestamp]);
+        assert_eq!(result.trades[0].price.value(), Decimal::from(95));
+        assert_eq!(result.suppressed_entries, 2);
+    }
+
+    /// Emits a fixed list of signals per tick
+    struct BatchStrategy {
+        script: Vec<Vec<Signal>>,
+        index: usize,
+    }
+
+    impl Strategy for BatchStrategy {
+        fn name(&self) -> &str {
+            "Batch"
+        }
+
+        fn on_tick(&mut self, _tick: &TickData) -> Signal {
+            Signal::Hold
+        }
+
+        fn on_tick_signals(&mut self, _tick: &TickData, _log: &mut StrategyLogger) -> Vec<Signal> {
+            let signals = self.script.get(self.index).cloned().unwrap_or_default();
+            self.index += 1;
+            signals
+        }
+
+        fn initialize(&mut self, _params: HashMap<String, String>) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_conflict_policy_resolves_opposing_signals() {
+        let run = |policy, script: Vec<Vec<Signal>>| {
+            let config = BacktestConfig::new(Decimal::from(10000))
+                .with_commission_rate(Decimal::ZERO)
+                .with_conflict_policy(policy);
+            let strategy = BatchStrategy { script, index: 0 };
+            let mut engine = BacktestEngine::new(Box::new(strategy), config).unwrap();
+            engine.run(&create_ticks(&[100, 100, 100]))
+        };
+        let held = |result: &BacktestResult| {
+            result
+                .positions
+                .get("BTCUSDT")
+                .map_or(Decimal::ZERO, |position| position.quantity.value())
+        };
+        // Long 10, then a buy and a sell on the same tick
+        let long_then_conflict = || vec![vec![buy(10)], vec![buy(4), sell(10)]];
+
+        let net = run(ConflictPolicy::Net, long_then_conflict());
+        assert_eq!(net.trades.len(), 2);
+        assert_eq!(net.trades[1].side, TradeSide::Sell);
+        assert_eq!(net.trades[1].quantity.value(), Decimal::from(6));
+        assert_eq!(held(&net), Decimal::from(4));
+
+        let cancel = run(ConflictPolicy::Cancel, long_then_conflict());
+        assert_eq!(cancel.trades.len(), 1);
+        assert_eq!(held(&cancel), Decimal::from(10));
+
+        let prefer_flat = run(ConflictPolicy::PreferFlat, long_then_conflict());
+        assert_eq!(prefer_flat.trades.len(), 2);
+        assert_eq!(prefer_flat.trades[1].quantity.value(), Decimal::from(10));
+        assert_eq!(held(&prefer_flat), Decimal::ZERO);
+
+        // Already flat: nothing to flatten, and equal sizes net to nothing
+        let opposing_when_flat = || vec![vec![buy(5), sell(5)]];
+        assert!(run(ConflictPolicy::PreferFlat, opposing_when_flat())
+            .trades
+            .is_empty());
+        assert!(run(ConflictPolicy::Net, opposing_when_flat())
+            .trades
+            .is_empty());
+
+        // One-sided batches are not conflicts
+        let both_buys = run(ConflictPolicy::Cancel, vec![vec![buy(2), buy(3)]]);
+        assert_eq!(both_buys.trades.len(), 2);
+        assert_eq!(held(&both_buys), Decimal::from(5));
+    }
+
+    fn stop_loss(trigger_price: i64) -> Signal {
+        Signal::StopLoss {
+            symbol: "BTCUSDT".to_string(),
+            trigger_price: Decimal::from(trigger_price),
+        }
+    }
+
+    fn take_profit(trigger_price: i64) -> Signal {
+        Signal::TakeProfit {
+            symbol: "BTCUSDT".to_string(),
+            trigger_price: Decimal::from(trigger_price),
+        }
+    }
+
+    #[test]
+    fn test_stop_loss_closes_position_at_trigger() {
+        let prices = [100, 100, 98, 95, 90, 99];
+        let ticks = create_ticks(&prices);
+        // The strategy would sell again at 95 if the stop had not already
+        let script = vec![buy(10), stop_loss(95), Signal::Hold, sell(10)];
+        let config = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+
+        let result = run_script(ScriptedStrategy::new(script), config, &prices);
+
+        assert_eq!(result.trades.len(), 2);
+        let exit = &result.trades[1];
+        assert_eq!(exit.side, TradeSide::Sell);
+        assert_eq!(exit.quantity.value(), Decimal::from(10));
+        assert_eq!(exit.price.value(), Decimal::from(95));
+        assert_eq!(exit.timestamp, ticks[3].timestamp);
+        assert_eq!(
+            exit.realized_pnl.map(Money::value),
+            Some(Decimal::from(-50))
+        );
+        assert_eq!(result.total_pnl, Decimal::from(-50));
+        assert!(result.positions.is_empty());
+    }
+
+    #[test]
+    fn test_take_profit_fills_at_trigger_within_candle() {
+        // Highs: 100, 100, 106, 112; the take-profit at 110 is reached by the
+        // fourth candle, which opened below it
+        let candles = create_candles(&[(100, 100), (100, 100), (100, 106), (104, 112)]);
+        let script = vec![buy(10), take_profit(110), stop_loss(90)];
+        let config = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+        let mut engine =
+            BacktestEngine::new(Box::new(ScriptedStrategy::new(script)), config).unwrap();
+        let result = engine.run_with_ohlc(&candles);
+
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(result.trades[1].price.value(), Decimal::from(110));
+        assert_eq!(result.trades[1].timestamp, candles[3].timestamp);
+        assert_eq!(
+            result.trades[1].realized_pnl.map(Money::value),
+            Some(Decimal::from(100))
+        );
+        assert!(engine.protective_exits.is_empty());
+    }
+
+    #[test]
+    fn test_protective_exits_dropped_when_flat() {
+        let prices = [100, 100, 100, 100, 80, 120];
+        let script = vec![buy(10), stop_loss(90), take_profit(110), sell(10)];
+        let config = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+        let mut engine =
+            BacktestEngine::new(Box::new(ScriptedStrategy::new(script)), config).unwrap();
+        let result = engine.run(&create_ticks(&prices));
+
+        // Only the strategy's own round trip; the levels never fire
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(result.total_pnl, Decimal::ZERO);
+        assert!(engine.protective_exits.is_empty());
+    }
+
+    #[test]
+    fn test_proportional_slippage_raises_cost_and_lowers_proceeds() {
+        let run = |slippage| {
+            let config = BacktestConfig::new(Decimal::from(10000))
+                .with_commission_rate(Decimal::ZERO)
+                .with_slippage(slippage);
+            run_script(
+                ScriptedStrategy::new(vec![buy(10), sell(10)]),
+                config,
+                &[100, 100],
+            )
+        };
+
+        let frictionless = run(SlippageModel::None);
+        assert_eq!(frictionless.total_pnl, Decimal::ZERO);
+
+        let slipped = run(SlippageModel::Proportional(
+            Decimal::from_str("0.01").unwrap(),
+        ));
+        assert_eq!(slipped.trades[0].price.value(), Decimal::from(101));
+        assert_eq!(slipped.trades[1].price.value(), Decimal::from(99));
+        assert_eq!(
+            slipped.trades[1].realized_pnl.map(Money::value),
+            Some(Decimal::from(-20))
+        );
+        assert_eq!(slipped.final_value, Decimal::from(9980));
+    }
+
+    #[test]
+    fn test_oversized_buy_follows_funds_policy() {
+        // 15 units at 1000 need 15000 of the 10000 available
+        let run = |policy| {
+            let config = BacktestConfig::new(Decimal::from(10000))
+                .with_commission_rate(Decimal::ZERO)
+                .with_insufficient_funds_policy(policy);
+            run_script(ScriptedStrategy::new(vec![buy(15)]), config, &[1000])
+        };
+
+        let rejected = run(InsufficientFundsPolicy::Reject);
+        assert!(rejected.trades.is_empty());
+        assert_eq!(rejected.final_value, Decimal::from(10000));
+
+        let partial = run(InsufficientFundsPolicy::PartialFill);
+        assert_eq!(partial.trades.len(), 1);
+        assert_eq!(partial.trades[0].quantity.value(), Decimal::from(10));
+        assert_eq!(partial.fill_quality.partial_fills, 1);
+    }
+
+    #[test]
+    fn test_fraction_of_equity_sizes_buys() {
+        let sized = || Signal::SizedBuy {
+            symbol: "BTCUSDT".to_string(),
+        };
+        let script = vec![sized(), Signal::Hold, sized()];
+        let config = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+        let no_sizer = run_script(
+            ScriptedStrategy::new(script.clone()),
+            config.clone(),
+            &[250],
+        );
+        assert!(no_sizer.trades.is_empty());
+
+        let config =
+            config.with_position_sizer(PositionSizer::FractionOfEquity(Decimal::new(1, 1)));
+        let result = run_script(ScriptedStrategy::new(script), config, &[250, 250, 300]);
+
+        // 10% of 10000 at 250 is 4; then equity is 9000 cash + 4 * 300,
+        // so 10% of 10200 at 300 is 3.4
+        let quantities: Vec<Decimal> = result.trades.iter().map(|t| t.quantity.value()).collect();
+        assert_eq!(quantities, [Decimal::from(4), Decimal::new(34, 1)]);
+
+        let notional = PositionSizer::FixedNotional(Decimal::from(500));
+        assert_eq!(
+            notional.quantity(Decimal::from(10000), Decimal::from(250)),
+            Decimal::TWO
+        );
+        assert_eq!(
+            notional.quantity(Decimal::from(10000), Decimal::ZERO),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_inverse_contract_pnl() {
+        // Long 1000 contracts from 100 to 125
+        let run = |contract_type, commission: &str| {
+            let config = BacktestConfig::new(Decimal::from(100000))
+                .with_commission_rate(Decimal::from_str(commission).unwrap())
+                .with_contract_type(contract_type);
+            run_script(
+                ScriptedStrategy::new(vec![buy(1000), sell(1000)]),
+                config,
+                &[100, 125],
+            )
+        };
+
+        // Linear: 1000 * (125 - 100) in the quote asset
+        let linear = run(ContractType::Linear, "0");
+        assert_eq!(linear.total_pnl, Decimal::from(25000));
+
+        // Inverse: 1000 * (1/100 - 1/125) = 10 - 8 coins
+        let inverse = run(ContractType::Inverse, "0");
+        assert_eq!(inverse.total_pnl, Decimal::from(2));
+        assert_eq!(
+            inverse.trades[1].realized_pnl.map(Money::value),
+            Some(Decimal::from(2))
+        );
+        assert_eq!(inverse.final_value, Decimal::from(100002));
+
+        // Fees are charged on the coin notional: 0.1% of 10 and of 8
+        let with_fees = run(ContractType::Inverse, "0.001");
+        assert_eq!(
+            with_fees.total_commission,
+            Decimal::from_str("0.018").unwrap()
+        );
+        assert_eq!(
+            with_fees.final_value.round_dp(12),
+            Decimal::from_str("100001.982").unwrap()
+        );
+        assert_eq!(
+            with_fees.trades[1]
+                .realized_pnl
+                .unwrap()
+                .value()
+                .round_dp(12),
+            Decimal::from_str("1.982").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fill_quality_without_execution_model() {
+        let config = BacktestConfig::new(Decimal::from(10000));
+        let result = run_script(ScriptedStrategy::buy_and_hold(10), config, &[100, 110]);
+
+        assert!(!result.fill_quality.modeled_execution);
+        assert_eq!(result.fill_quality.fills, 1);
+        assert_eq!(result.fill_quality.avg_slippage_bps, Decimal::ZERO);
+    }
+
+    /// Explains each decision through the backtest logger
+    struct ChattyStrategy;
+
+    impl Strategy for ChattyStrategy {
+        fn name(&self) -> &str {
+            "Chatty"
+        }
+
+        fn on_tick(&mut self, _tick: &TickData) -> Signal {
+            Signal::Hold
+        }
+
+        fn on_tick_logged(&mut self, tick: &TickData, log: &mut StrategyLogger) -> Signal {
+            if tick.price > Decimal::from(100) {
+                log.warn(format!("price {} above band, buying", tick.price));
+                return buy(1);
+            }
+            log.debug("inside band");
+            Signal::Hold
+        }
+
+        fn initialize(&mut self, _params: HashMap<String, String>) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_strategy_logs_collected_in_result() {
+        let ticks = create_ticks(&[100, 105]);
+        let config = BacktestConfig::new(Decimal::from(10000));
+        let mut engine = BacktestEngine::new(Box::new(ChattyStrategy), config).unwrap();
+        let result = engine.run(&ticks);
+
+        assert_eq!(
+            result.strategy_logs,
+            vec![
+                StrategyLogEntry {
+                    timestamp: ticks[0].timestamp,
+                    level: LogLevel::Debug,
+                    message: "inside band".to_string(),
+                },
+                StrategyLogEntry {
+                    timestamp: ticks[1].timestamp,
+                    level: LogLevel::Warn,
+                    message: "price 105 above band, buying".to_string(),
+                },
+            ]
+        );
+        assert_eq!(result.total_trades, 1);
+
+        // Multi-symbol runs reach the logger through the default hook too
+        let config = BacktestConfig::new(Decimal::from(10000));
+        let mut engine = BacktestEngine::new(Box::new(ChattyStrategy), config).unwrap();
+        let multi = engine.run_multi(&ticks);
+        assert_eq!(multi.strategy_logs, result.strategy_logs);
+    }
+
+    /// Buys once, then sells whatever is held when the data ends
+    struct FlattenOnFinish {
+        bought: bool,
+    }
+
+    impl Strategy for FlattenOnFinish {
+        fn name(&self) -> &str {
+            "Flatten"
+        }
+
+        fn on_tick(&mut self, _tick: &TickData) -> Signal {
+            if self.bought {
+                return Signal::Hold;
+            }
+            self.bought = true;
+            buy(5)
+        }
+
+        fn initialize(&mut self, _params: HashMap<String, String>) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn on_finish(&mut self, portfolio: &PortfolioSnapshot) -> Vec<Signal> {
+            portfolio
+                .positions
+                .values()
+                .map(|position| Signal::Sell {
+                    symbol: position.symbol.clone(),
+                    quantity: position.quantity.value(),
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_on_finish_flattens_at_final_price() {
+        let config = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+        let mut engine =
+            BacktestEngine::new(Box::new(FlattenOnFinish { bought: false }), config).unwrap();
+        let ticks = create_ticks(&[100, 105, 120]);
+        let result = engine.run(&ticks);
+
+        assert!(result.positions.is_empty());
+        assert_eq!(result.trades.len(), 2);
+        let closing = &result.trades[1];
+        assert_eq!(closing.side, TradeSide::Sell);
+        assert_eq!(closing.quantity.value(), Decimal::from(5));
+        assert_eq!(closing.price.value(), Decimal::from(120));
+        assert_eq!(closing.timestamp, ticks[2].timestamp);
+        assert_eq!(
+            closing.realized_pnl.map(Money::value),
+            Some(Decimal::from(100))
+        );
+        assert_eq!(result.final_value, Decimal::from(10100));
+        assert_eq!(result.equity_curve.last(), Some(&Decimal::from(10100)));
+        // Initial capital plus one point per tick, the last restated after the close
+        assert_eq!(result.equity_curve.len(), ticks.len() + 1);
+        assert_eq!(result.equity_timestamps.len(), result.equity_curve.len());
     }
 }
@@ -0,0 +1,300 @@
+//! Execution assumptions for backtest fills and a summary of how fills were priced.
+
+use crate::backtest::strategy::Signal;
+use crate::data::types::TradeSide;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const BPS: Decimal = Decimal::from_parts(10000, 0, 0, false, 0);
+
+/// Price impact applied to every backtest fill
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SlippageModel {
+    /// Fill exactly at the reference price
+    #[default]
+    None,
+    /// Fixed basis points against the trader: buys pay more, sells receive less
+    FixedBps(Decimal),
+    /// Fraction of the price against the trader, e.g. 0.001 for 0.1%
+    Proportional(Decimal),
+}
+
+impl SlippageModel {
+    /// Fill price for an order on `side` referenced at `price`
+    pub fn fill_price(&self, side: TradeSide, price: Decimal) -> Decimal {
+        match self {
+            SlippageModel::None => price,
+            SlippageModel::FixedBps(bps) => Self::against(side, price, price * *bps / BPS),
+            SlippageModel::Proportional(fraction) => Self::against(side, price, price * *fraction),
+        }
+    }
+
+    fn against(side: TradeSide, price: Decimal, offset: Decimal) -> Decimal {
+        match side {
+            TradeSide::Buy => price + offset,
+            TradeSide::Sell => price - offset,
+        }
+    }
+}
+
+/// Limit order waiting for the price to reach its limit
+#[derive(Debug, Clone)]
+pub(crate) struct LimitOrder {
+    pub(crate) side: TradeSide,
+    pub(crate) symbol: String,
+    pub(crate) quantity: Decimal,
+    pub(crate) limit_price: Decimal,
+}
+
+impl LimitOrder {
+    /// Whether a bar trading between `low` and `high` reached the limit
+    pub(crate) fn reached(&self, low: Decimal, high: Decimal) -> bool {
+        match self.side {
+            TradeSide::Buy => low <= self.limit_price,
+            TradeSide::Sell => high >= self.limit_price,
+        }
+    }
+}
+
+/// Stop-loss and take-profit levels guarding a long position
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProtectiveExits {
+    pub(crate) stop_loss: Option<Decimal>,
+    pub(crate) take_profit: Option<Decimal>,
+}
+
+impl ProtectiveExits {
+    /// Exit price for a bar opening at `open` and trading between `low` and
+    /// `high`, if it breached a level. The stop is checked first, and a bar
+    /// that gaps through a level fills at its open.
+    pub(crate) fn exit_price(&self, open: Decimal, low: Decimal, high: Decimal) -> Option<Decimal> {
+        if let Some(stop) = self.stop_loss.filter(|stop| low <= *stop) {
+            return Some(stop.min(open));
+        }
+        self.take_profit
+            .filter(|target| high >= *target)
+            .map(|target| target.max(open))
+    }
+}
+
+/// How market buys and sells for the same symbol on the same bar are resolved
+///
+/// Only `Buy` and `Sell` signals conflict; limit orders and protective exits
+/// are always kept. A symbol with signals on one side only is left alone, so
+/// several buys on one bar still fill one after another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Replace the symbol's buys and sells with one order for the net
+    /// quantity; nothing is traded when they cancel out exactly
+    #[default]
+    Net,
+    /// Drop every buy and sell for the symbol
+    Cancel,
+    /// Keep only the side that reduces the open position (sells when long,
+    /// buys when short); nothing is traded when the symbol is flat
+    PreferFlat,
+}
+
+impl ConflictPolicy {
+    /// Resolve conflicting signals from one bar, keeping their order. The
+    /// replacement order takes the place of the symbol's first buy or sell.
+    /// `position` returns the current signed position of a symbol.
+    pub fn resolve(&self, signals: Vec<Signal>, position: impl Fn(&str) -> Decimal) -> Vec<Signal> {
+        if signals.len() < 2 {
+            return signals;
+        }
+
+        // (bought, sold, number of buys, number of sells) by symbol
+        let mut totals: HashMap<String, (Decimal, Decimal, usize, usize)> = HashMap::new();
+        for signal in &signals {
+            match signal {
+                Signal::Buy { symbol, quantity } => {
+                    let entry = totals.entry(symbol.clone()).or_default();
+                    entry.0 += quantity;
+                    entry.2 += 1;
+                }
+                Signal::Sell { symbol, quantity } => {
+                    let entry = totals.entry(symbol.clone()).or_default();
+                    entry.1 += quantity;
+                    entry.3 += 1;
+                }
+                _ => {}
+            }
+        }
+        // Conflicting symbols; the totals are taken when the replacement is emitted
+        let mut conflicts: HashMap<String, Option<(Decimal, Decimal)>> = totals
+            .into_iter()
+            .filter(|(_, (_, _, buys, sells))| *buys > 0 && *sells > 0)
+            .map(|(symbol, (bought, sold, _, _))| (symbol, Some((bought, sold))))
+            .collect();
+        if conflicts.is_empty() {
+            return signals;
+        }
+
+        let mut resolved = Vec::with_capacity(signals.len());
+        for signal in signals {
+            let symbol = match &signal {
+                Signal::Buy { symbol, .. } | Signal::Sell { symbol, .. } => symbol,
+                _ => {
+                    resolved.push(signal);
+                    continue;
+                }
+            };
+            // Emit the replacement once, at the symbol's first market signal
+            let Some(pending) = conflicts.get_mut(symbol) else {
+                resolved.push(signal);
+                continue;
+            };
+            let Some((bought, sold)) = pending.take() else {
+                continue;
+            };
+            let symbol = symbol.clone();
+            let replacement = match self {
+                ConflictPolicy::Net if bought > sold => Some(Signal::Buy {
+                    symbol,
+                    quantity: bought - sold,
+                }),
+                ConflictPolicy::Net if sold > bought => Some(Signal::Sell {
+                    symbol,
+                    quantity: sold - bought,
+                }),
+                ConflictPolicy::Net | ConflictPolicy::Cancel => None,
+                ConflictPolicy::PreferFlat => {
+                    let held = position(&symbol);
+                    if held > Decimal::ZERO {
+                        Some(Signal::Sell {
+                            symbol,
+                            quantity: sold,
+                        })
+                    } else if held < Decimal::ZERO {
+                        Some(Signal::Buy {
+                            symbol,
+                            quantity: bought,
+                        })
+                    } else {
+                        None
+                    }
+                }
+            };
+            resolved.extend(replacement);
+        }
+        resolved
+    }
+}
+
+/// What happens to an order larger than the portfolio can cover
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InsufficientFundsPolicy {
+    /// Reject the whole order
+    #[default]
+    Reject,
+    /// Fill the largest part that available cash (buys) or the held position
+    /// and any short margin left (sells) covers
+    PartialFill,
+}
+
+/// How backtest fills were priced and sized
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FillQuality {
+    /// Whether slippage or partial fills were simulated
+    pub modeled_execution: bool,
+    pub fills: usize,
+    /// Fills smaller than the requested quantity
+    pub partial_fills: usize,
+    /// Mean of filled / requested quantity
+    pub avg_fill_ratio: Decimal,
+    /// Mean adverse price move per fill, in basis points of the reference price
+    pub avg_slippage_bps: Decimal,
+    /// Sum of quantity * |fill price - reference price|
+    pub total_slippage_cost: Decimal,
+    /// Traded notional at fill prices, in the settlement asset
+    pub total_volume: Decimal,
+    /// Commission as a percentage of traded notional
+    pub commission_pct_of_volume: Decimal,
+}
+
+impl FillQuality {
+    pub fn print(&self) {
+        println!("FILL QUALITY");
+        println!("{}", "-".repeat(30));
+        println!("Fills: {}", self.fills);
+        println!(
+            "Partial Fills: {} (avg fill ratio {:.2}%)",
+            self.partial_fills,
+            self.avg_fill_ratio * Decimal::from(100)
+        );
+        println!(
+            "Avg Slippage: {:.2} bps (total ${:.2})",
+            self.avg_slippage_bps, self.total_slippage_cost
+        );
+        println!(
+            "Commission: {:.4}% of ${:.2} volume",
+            self.commission_pct_of_volume, self.total_volume
+        );
+        println!();
+    }
+}
+
+/// Running totals the engine feeds as it fills orders
+#[derive(Debug, Default)]
+pub(crate) struct FillQualityTracker {
+    fills: usize,
+    partial_fills: usize,
+    fill_ratio_sum: Decimal,
+    slippage_bps_sum: Decimal,
+    slippage_cost: Decimal,
+    volume: Decimal,
+}
+
+impl FillQualityTracker {
+    pub(crate) fn record(
+        &mut self,
+        requested: Decimal,
+        filled: Decimal,
+        reference_price: Decimal,
+        fill_price: Decimal,
+        notional: Decimal,
+    ) {
+        let slippage = (fill_price - reference_price).abs();
+
+        self.fills += 1;
+        if filled < requested {
+            self.partial_fills += 1;
+        }
+        if requested > Decimal::ZERO {
+            self.fill_ratio_sum += filled / requested;
+        }
+        if reference_price > Decimal::ZERO {
+            self.slippage_bps_sum += slippage * BPS / reference_price;
+        }
+        self.slippage_cost += slippage * filled;
+        self.volume += notional;
+    }
+
+    pub(crate) fn finish(&self, total_commission: Decimal, modeled_execution: bool) -> FillQuality {
+        let fills = Decimal::from(self.fills);
+        let mean = |sum: Decimal| {
+            if self.fills > 0 {
+                sum / fills
+            } else {
+                Decimal::ZERO
+            }
+        };
+
+        FillQuality {
+            modeled_execution,
+            fills: self.fills,
+            partial_fills: self.partial_fills,
+            avg_fill_ratio: mean(self.fill_ratio_sum),
+            avg_slippage_bps: mean(self.slippage_bps_sum),
+            total_slippage_cost: self.slippage_cost,
+            total_volume: self.volume,
+            commission_pct_of_volume: if self.volume > Decimal::ZERO {
+                total_commission * Decimal::from(100) / self.volume
+            } else {
+                Decimal::ZERO
+            },
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Structured log that strategies write to during a backtest.
+//!
+//! Messages are collected into `BacktestResult::strategy_logs` instead of being
+//! printed, so a strategy's reasoning can be inspected after the run without
+//! mixing into the engine's output.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyLogEntry {
+    /// Time of the tick or candle being processed when the message was written
+    pub timestamp: DateTime<Utc>,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+/// Collects a strategy's messages, stamped with the current data point's time
+#[derive(Debug, Default)]
+pub struct StrategyLogger {
+    now: Option<DateTime<Utc>>,
+    entries: Vec<StrategyLogEntry>,
+}
+
+impl StrategyLogger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn debug(&mut self, message: impl Into<String>) {
+        self.log(LogLevel::Debug, message);
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.log(LogLevel::Info, message);
+    }
+
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.log(LogLevel::Warn, message);
+    }
+
+    pub fn log(&mut self, level: LogLevel, message: impl Into<String>) {
+        self.entries.push(StrategyLogEntry {
+            timestamp: self.now.unwrap_or_else(Utc::now),
+            level,
+            message: message.into(),
+        });
+    }
+
+    pub fn entries(&self) -> &[StrategyLogEntry] {
+        &self.entries
+    }
+
+    /// Stamp subsequent messages with `timestamp`
+    pub(crate) fn set_time(&mut self, timestamp: DateTime<Utc>) {
+        self.now = Some(timestamp);
+    }
+}
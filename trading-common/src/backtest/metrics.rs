@@ -1,25 +1,34 @@
-use rust_decimal::prelude::*;
+use super::stats;
+use super::units::Money;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
 pub struct BacktestMetrics;
 
+/// Format an optional metric with `decimals` places, or "N/A" when undefined
+pub fn format_metric(value: Option<Decimal>, decimals: usize) -> String {
+    value.map_or_else(|| "N/A".to_string(), |v| format!("{:.*}", decimals, v))
+}
+
 impl BacktestMetrics {
     /// Calculate Sharpe ratio
     /// Sharpe Ratio = (Mean Return - Risk Free Rate) / Standard Deviation of Returns
-    pub fn calculate_sharpe_ratio(returns: &[Decimal], risk_free_rate: Decimal) -> Decimal {
+    ///
+    /// None when there are no returns or they have zero volatility.
+    pub fn calculate_sharpe_ratio(returns: &[Decimal], risk_free_rate: Decimal) -> Option<Decimal> {
         if returns.is_empty() {
-            return Decimal::ZERO;
+            return None;
         }
 
         let mean_return = Self::calculate_mean(returns);
         let std_dev = Self::calculate_standard_deviation(returns);
 
         if std_dev == Decimal::ZERO {
-            return Decimal::ZERO;
+            return None;
         }
 
-        (mean_return - risk_free_rate) / std_dev
+        Some((mean_return - risk_free_rate) / std_dev)
     }
 
     /// Calculate maximum drawdown
@@ -57,31 +66,87 @@ impl BacktestMetrics {
     }
 
     /// Calculate Calmar ratio (Annual Return / Max Drawdown)
-    pub fn calculate_calmar_ratio(annual_return: Decimal, max_drawdown: Decimal) -> Decimal {
+    ///
+    /// None when there was no drawdown.
+    pub fn calculate_calmar_ratio(
+        annual_return: Decimal,
+        max_drawdown: Decimal,
+    ) -> Option<Decimal> {
         if max_drawdown == Decimal::ZERO {
+            return None;
+        }
+        Some(annual_return / max_drawdown)
+    }
+
+    /// Ulcer Index: root mean square of the percentage drawdowns from the
+    /// running peak, so deep and long drawdowns weigh more than brief dips
+    pub fn ulcer_index(equity_curve: &[Decimal]) -> Decimal {
+        if equity_curve.is_empty() {
             return Decimal::ZERO;
         }
-        annual_return / max_drawdown
+
+        let mut peak = equity_curve[0];
+        let mut sum_squares = Decimal::ZERO;
+        for &value in equity_curve {
+            peak = peak.max(value);
+            if peak > Decimal::ZERO {
+                let drawdown_pct = (peak - value) * Decimal::from(100) / peak;
+                sum_squares += drawdown_pct * drawdown_pct;
+            }
+        }
+
+        stats::sqrt(sum_squares / Decimal::from(equity_curve.len()))
+    }
+
+    /// Pain ratio (Return % / Ulcer Index)
+    ///
+    /// None when there was no drawdown.
+    pub fn calculate_pain_ratio(return_pct: Decimal, ulcer_index: Decimal) -> Option<Decimal> {
+        if ulcer_index == Decimal::ZERO {
+            return None;
+        }
+        Some(return_pct / ulcer_index)
     }
 
     /// Calculate Sortino ratio (uses downside deviation instead of total volatility)
+    ///
+    /// None when there are no returns or none fall below `target_return`.
     pub fn calculate_sortino_ratio(
         returns: &[Decimal],
         risk_free_rate: Decimal,
         target_return: Decimal,
-    ) -> Decimal {
+    ) -> Option<Decimal> {
         if returns.is_empty() {
-            return Decimal::ZERO;
+            return None;
         }
 
         let mean_return = Self::calculate_mean(returns);
         let downside_deviation = Self::calculate_downside_deviation(returns, target_return);
 
         if downside_deviation == Decimal::ZERO {
+            return None;
+        }
+
+        Some((mean_return - risk_free_rate) / downside_deviation)
+    }
+
+    /// Root mean square of the shortfalls of returns below `target_return`,
+    /// taken over the returns that fall short; zero when none do
+    pub fn calculate_downside_deviation(returns: &[Decimal], target_return: Decimal) -> Decimal {
+        let downside_returns: Vec<Decimal> = returns
+            .iter()
+            .filter(|&&r| r < target_return)
+            .map(|&r| r - target_return)
+            .collect();
+
+        if downside_returns.is_empty() {
             return Decimal::ZERO;
         }
 
-        (mean_return - risk_free_rate) / downside_deviation
+        let downside_variance = downside_returns.iter().map(|x| x * x).sum::<Decimal>()
+            / Decimal::from(downside_returns.len());
+
+        stats::sqrt(downside_variance)
     }
 
     /// Calculate Value at Risk (VaR) at given confidence level
@@ -128,6 +193,42 @@ impl BacktestMetrics {
         mean_excess_return / tracking_error
     }
 
+    /// Calculate beta of strategy returns against benchmark returns
+    /// Beta = Cov(Strategy, Benchmark) / Var(Benchmark)
+    pub fn beta(strategy_returns: &[Decimal], benchmark_returns: &[Decimal]) -> Decimal {
+        if strategy_returns.len() != benchmark_returns.len() || strategy_returns.len() < 2 {
+            return Decimal::ZERO;
+        }
+
+        let strategy_mean = Self::calculate_mean(strategy_returns);
+        let benchmark_mean = Self::calculate_mean(benchmark_returns);
+
+        let (covariance, variance) = strategy_returns.iter().zip(benchmark_returns.iter()).fold(
+            (Decimal::ZERO, Decimal::ZERO),
+            |(cov, var), (s, b)| {
+                let b_dev = b - benchmark_mean;
+                (cov + (s - strategy_mean) * b_dev, var + b_dev * b_dev)
+            },
+        );
+
+        if variance == Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+
+        covariance / variance
+    }
+
+    /// Calculate per-period alpha (Jensen's alpha with zero risk-free rate)
+    /// Alpha = Mean(Strategy) - Beta * Mean(Benchmark)
+    pub fn alpha(strategy_returns: &[Decimal], benchmark_returns: &[Decimal]) -> Decimal {
+        if strategy_returns.len() != benchmark_returns.len() || strategy_returns.len() < 2 {
+            return Decimal::ZERO;
+        }
+
+        let beta = Self::beta(strategy_returns, benchmark_returns);
+        Self::calculate_mean(strategy_returns) - beta * Self::calculate_mean(benchmark_returns)
+    }
+
     /// Calculate win rate (percentage of profitable trades)
     pub fn calculate_win_rate(trades: &[crate::backtest::portfolio::Trade]) -> Decimal {
         if trades.is_empty() {
@@ -136,18 +237,22 @@ impl BacktestMetrics {
 
         let profitable_trades = trades
             .iter()
-            .filter(|trade| trade.realized_pnl.map_or(false, |pnl| pnl > Decimal::ZERO))
+            .filter(|trade| trade.realized_pnl.map_or(false, |pnl| pnl > Money::ZERO))
             .count();
 
         Decimal::from(profitable_trades) / Decimal::from(trades.len()) * Decimal::from(100)
     }
 
     /// Calculate profit factor (total profit / total loss)
-    pub fn calculate_profit_factor(trades: &[crate::backtest::portfolio::Trade]) -> Decimal {
+    ///
+    /// None when no closed trade lost money.
+    pub fn calculate_profit_factor(
+        trades: &[crate::backtest::portfolio::Trade],
+    ) -> Option<Decimal> {
         let (total_profit, total_loss) = trades.iter().filter_map(|trade| trade.realized_pnl).fold(
-            (Decimal::ZERO, Decimal::ZERO),
+            (Money::ZERO, Money::ZERO),
             |(profit, loss), pnl| {
-                if pnl > Decimal::ZERO {
+                if pnl > Money::ZERO {
                     (profit + pnl, loss)
                 } else {
                     (profit, loss - pnl)
@@ -155,15 +260,11 @@ impl BacktestMetrics {
             },
         );
 
-        if total_loss == Decimal::ZERO {
-            return if total_profit > Decimal::ZERO {
-                Decimal::MAX
-            } else {
-                Decimal::ZERO
-            };
+        if total_loss == Money::ZERO {
+            return None;
         }
 
-        total_profit / total_loss
+        Some(total_profit / total_loss)
     }
 
     /// Calculate average trade duration
@@ -199,67 +300,155 @@ impl BacktestMetrics {
     // Helper functions
 
     fn calculate_mean(values: &[Decimal]) -> Decimal {
-        if values.is_empty() {
-            return Decimal::ZERO;
-        }
-        values.iter().sum::<Decimal>() / Decimal::from(values.len())
+        stats::mean(values)
     }
 
     fn calculate_standard_deviation(values: &[Decimal]) -> Decimal {
-        if values.len() < 2 {
-            return Decimal::ZERO;
-        }
+        stats::std_dev(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::units::{Price, Quantity};
+    use std::str::FromStr;
 
-        let mean = Self::calculate_mean(values);
-        let variance = values
+    #[test]
+    fn test_beta_scales_with_exposure() {
+        let benchmark: Vec<Decimal> = ["0.01", "-0.02", "0.03", "0.005"]
             .iter()
-            .map(|x| (x - mean) * (x - mean))
-            .sum::<Decimal>()
-            / Decimal::from(values.len() - 1);
+            .map(|r| Decimal::from_str(r).unwrap())
+            .collect();
+        let half: Vec<Decimal> = benchmark.iter().map(|r| r / Decimal::from(2)).collect();
 
-        // Approximate square root using Newton's method
-        Self::decimal_sqrt(variance)
+        assert_eq!(
+            BacktestMetrics::beta(&half, &benchmark),
+            Decimal::from_str("0.5").unwrap()
+        );
+        assert_eq!(BacktestMetrics::beta(&half, &benchmark[..2]), Decimal::ZERO);
     }
 
-    fn calculate_downside_deviation(returns: &[Decimal], target_return: Decimal) -> Decimal {
-        if returns.is_empty() {
-            return Decimal::ZERO;
-        }
+    #[test]
+    fn test_ulcer_index_of_known_drawdowns() {
+        // Drawdowns from the running peak: 0%, 0%, 10%, 20%, 0%
+        let curve: Vec<Decimal> = [100, 100, 90, 80, 100].map(Decimal::from).to_vec();
+        let ulcer = BacktestMetrics::ulcer_index(&curve);
+        assert_eq!(ulcer, Decimal::from(10));
+        assert_eq!(
+            BacktestMetrics::calculate_pain_ratio(Decimal::from(25), ulcer),
+            Some(Decimal::from_str("2.5").unwrap())
+        );
 
-        let downside_returns: Vec<Decimal> = returns
+        let rising: Vec<Decimal> = [100, 101, 105, 110].map(Decimal::from).to_vec();
+        let ulcer = BacktestMetrics::ulcer_index(&rising);
+        assert_eq!(ulcer, Decimal::ZERO);
+        assert_eq!(
+            BacktestMetrics::calculate_pain_ratio(Decimal::from(10), ulcer),
+            None
+        );
+        assert_eq!(BacktestMetrics::ulcer_index(&[]), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_sortino_of_known_downside() {
+        let returns: Vec<Decimal> = ["0.04", "-0.01", "0.06", "-0.07"]
             .iter()
-            .filter(|&&r| r < target_return)
-            .map(|&r| r - target_return)
+            .map(|r| Decimal::from_str(r).unwrap())
             .collect();
 
-        if downside_returns.is_empty() {
-            return Decimal::ZERO;
-        }
-
-        let downside_variance = downside_returns.iter().map(|x| x * x).sum::<Decimal>()
-            / Decimal::from(downside_returns.len());
+        // Shortfalls -0.01 and -0.07: sqrt((0.0001 + 0.0049) / 2) = 0.05
+        let downside = BacktestMetrics::calculate_downside_deviation(&returns, Decimal::ZERO);
+        assert_eq!(downside, Decimal::from_str("0.05").unwrap());
+        // Mean return 0.005
+        assert_eq!(
+            BacktestMetrics::calculate_sortino_ratio(&returns, Decimal::ZERO, Decimal::ZERO),
+            Some(Decimal::from_str("0.1").unwrap())
+        );
+        assert_eq!(
+            BacktestMetrics::calculate_sortino_ratio(
+                &returns,
+                Decimal::from_str("0.001").unwrap(),
+                Decimal::ZERO
+            ),
+            Some(Decimal::from_str("0.08").unwrap())
+        );
 
-        Self::decimal_sqrt(downside_variance)
+        // Against a 0.05 target the 0.04 return also falls short:
+        // shortfalls -0.01, -0.06 and -0.12
+        assert_eq!(
+            BacktestMetrics::calculate_downside_deviation(
+                &returns,
+                Decimal::from_str("0.05").unwrap()
+            ),
+            stats::sqrt(Decimal::from_str("0.0181").unwrap() / Decimal::from(3))
+        );
     }
 
-    /// Approximate square root for Decimal using Newton's method
-    fn decimal_sqrt(value: Decimal) -> Decimal {
-        if value <= Decimal::ZERO {
-            return Decimal::ZERO;
-        }
+    #[test]
+    fn test_degenerate_ratios_are_undefined() {
+        use crate::backtest::portfolio::Trade;
+        use crate::data::types::TradeSide;
 
-        let mut x = value / Decimal::from(2);
-        let tolerance = Decimal::from_str("0.000001").unwrap();
+        let flat = vec![Decimal::from_str("0.01").unwrap(); 4];
+        let mixed: Vec<Decimal> = ["0.02", "-0.01", "0.03"]
+            .iter()
+            .map(|r| Decimal::from_str(r).unwrap())
+            .collect();
 
-        for _ in 0..50 {
-            // Max iterations
-            let new_x = (x + value / x) / Decimal::from(2);
-            if (new_x - x).abs() < tolerance {
-                break;
-            }
-            x = new_x;
-        }
+        // Sharpe: no returns or zero volatility
+        assert_eq!(
+            BacktestMetrics::calculate_sharpe_ratio(&[], Decimal::ZERO),
+            None
+        );
+        assert_eq!(
+            BacktestMetrics::calculate_sharpe_ratio(&flat, Decimal::ZERO),
+            None
+        );
+        assert!(BacktestMetrics::calculate_sharpe_ratio(&mixed, Decimal::ZERO).is_some());
+
+        // Sortino: no return below the target
+        let sortino = |returns: &[Decimal]| {
+            BacktestMetrics::calculate_sortino_ratio(returns, Decimal::ZERO, Decimal::ZERO)
+        };
+        assert_eq!(sortino(&flat), None);
+        assert!(sortino(&mixed).is_some());
+
+        // Calmar: no drawdown
+        let annual = Decimal::from_str("0.2").unwrap();
+        assert_eq!(
+            BacktestMetrics::calculate_calmar_ratio(annual, Decimal::ZERO),
+            None
+        );
+        assert_eq!(
+            BacktestMetrics::calculate_calmar_ratio(annual, Decimal::from_str("0.1").unwrap()),
+            Some(Decimal::from(2))
+        );
+
+        // Profit factor: no losing trade
+        let closed = |pnl: i64| Trade {
+            symbol: "BTCUSDT".to_string(),
+            side: TradeSide::Sell,
+            quantity: Quantity::new(Decimal::ONE),
+            price: Price::new(Decimal::from(100)),
+            timestamp: chrono::Utc::now(),
+            realized_pnl: Some(Money::new(Decimal::from(pnl))),
+            commission: Money::ZERO,
+        };
+        assert_eq!(BacktestMetrics::calculate_profit_factor(&[]), None);
+        assert_eq!(
+            BacktestMetrics::calculate_profit_factor(&[closed(10), closed(5)]),
+            None
+        );
+        assert_eq!(
+            BacktestMetrics::calculate_profit_factor(&[closed(10), closed(-5)]),
+            Some(Decimal::from(2))
+        );
 
-        x
+        assert_eq!(format_metric(None, 2), "N/A");
+        assert_eq!(
+            format_metric(Some(Decimal::from_str("1.2345").unwrap()), 2),
+            "1.23"
+        );
     }
 }
@@ -1,8 +1,24 @@
+pub mod diff;
 pub mod engine;
+pub mod execution;
+pub mod logger;
 pub mod metrics;
+pub mod params;
 pub mod portfolio;
+pub mod results;
+pub mod stats;
 pub mod strategy;
+pub mod sweep;
+pub mod units;
 
-pub use engine::{BacktestConfig, BacktestEngine, BacktestResult};
-pub use portfolio::{Portfolio, Position, Trade};
+pub use engine::{
+    BacktestConfig, BacktestEngine, BacktestResult, FillTiming, PositionSizer, StopReason,
+};
+pub use execution::{ConflictPolicy, FillQuality, InsufficientFundsPolicy, SlippageModel};
+pub use logger::{LogLevel, StrategyLogEntry, StrategyLogger};
+pub use params::StrategyParams;
+pub use portfolio::{
+    CommissionFn, ContractType, FillContext, Portfolio, PortfolioSnapshot, Position, Trade,
+};
 pub use strategy::{create_strategy, list_strategies, Signal, Strategy, StrategyInfo};
+pub use units::{Money, Price, Quantity};
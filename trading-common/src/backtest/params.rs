@@ -0,0 +1,133 @@
+//! Strategy parameter files for non-interactive backtests.
+//!
+//! A params file names a strategy and its parameters:
+//!
+//! ```json
+//! { "strategy_id": "sma", "params": { "short_period": 5, "long_period": 20 } }
+//! ```
+//!
+//! Values may be strings, numbers or booleans; they are passed to the
+//! strategy as strings, like any other `BacktestConfig::with_param` value.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::engine::BacktestConfig;
+use super::strategy::{create_strategy, Strategy};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyParams {
+    pub strategy_id: String,
+    pub params: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct RawStrategyParams {
+    strategy_id: String,
+    #[serde(default)]
+    params: HashMap<String, serde_json::Value>,
+}
+
+impl StrategyParams {
+    /// Parse a params file's JSON content
+    pub fn from_json(content: &str) -> io::Result<Self> {
+        let raw: RawStrategyParams = serde_json::from_str(content)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let params = raw
+            .params
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    serde_json::Value::String(value) => value,
+                    serde_json::Value::Number(value) => value.to_string(),
+                    serde_json::Value::Bool(value) => value.to_string(),
+                    other => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!(
+                                "Param {} must be a string, number or boolean, got {}",
+                                key, other
+                            ),
+                        ))
+                    }
+                };
+                Ok((key, value))
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self {
+            strategy_id: raw.strategy_id,
+            params,
+        })
+    }
+
+    /// Load a params file
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Create the strategy and initialize it with the params, so unknown
+    /// strategies and values it rejects fail before any data is loaded
+    pub fn create_strategy(&self) -> Result<Box<dyn Strategy>, String> {
+        let mut strategy = create_strategy(&self.strategy_id)?;
+        strategy.initialize(self.params.clone())?;
+        Ok(strategy)
+    }
+
+    /// Add every param to a backtest config
+    pub fn apply(&self, config: BacktestConfig) -> BacktestConfig {
+        self.params
+            .iter()
+            .fold(config, |config, (key, value)| config.with_param(key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_params_file_applies_to_config() {
+        let path =
+            std::env::temp_dir().join(format!("strategy_params_{}.json", std::process::id()));
+        fs::write(
+            &path,
+            r#"{ "strategy_id": "sma", "params": { "short_period": 3, "long_period": "8" } }"#,
+        )
+        .unwrap();
+
+        let params = StrategyParams::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(params.strategy_id, "sma");
+        assert!(params.create_strategy().is_ok());
+
+        let config = params.apply(BacktestConfig::new(Decimal::from(10000)));
+        assert_eq!(config.strategy_params.len(), 2);
+        assert_eq!(config.strategy_params["short_period"], "3");
+        assert_eq!(config.strategy_params["long_period"], "8");
+    }
+
+    #[test]
+    fn test_invalid_params_rejected() {
+        let create = |json: &str| StrategyParams::from_json(json).unwrap().create_strategy();
+
+        assert!(create(r#"{ "strategy_id": "nope" }"#).is_err());
+        assert!(create(r#"{ "strategy_id": "sma", "params": { "short_period": "x" } }"#).is_err());
+        assert!(create(
+            r#"{ "strategy_id": "sma", "params": { "short_period": 20, "long_period": 5 } }"#
+        )
+        .is_err());
+        assert!(create(r#"{ "strategy_id": "sma" }"#).is_ok());
+
+        assert!(StrategyParams::from_json(r#"{ "params": {} }"#).is_err());
+        assert!(StrategyParams::from_json(
+            r#"{ "strategy_id": "sma", "params": { "short_period": [3] } }"#
+        )
+        .is_err());
+    }
+}
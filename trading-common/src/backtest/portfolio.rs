@@ -1,78 +1,271 @@
+use super::units::{Money, Price, Quantity};
 use crate::data::types::TradeSide;
 use chrono::{DateTime, Utc};
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::str::FromStr;
+use std::fmt;
+use std::sync::Arc;
 
+/// Details of a fill passed to a custom commission function
 #[derive(Debug, Clone)]
+pub struct FillContext<'a> {
+    pub symbol: &'a str,
+    pub side: TradeSide,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    /// Notional volume (quantity * price) filled earlier in this backtest
+    pub cumulative_volume: Decimal,
+}
+
+/// Custom per-fill commission, e.g. fees tiered by traded volume.
+/// Overrides the rate passed with each fill when set.
+#[derive(Clone)]
+pub struct CommissionFn(Arc<dyn Fn(&FillContext) -> Decimal + Send + Sync>);
+
+impl CommissionFn {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&FillContext) -> Decimal + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+}
+
+impl fmt::Debug for CommissionFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CommissionFn").finish_non_exhaustive()
+    }
+}
+
+/// How a contract's notional and PnL relate to price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ContractType {
+    /// Spot or USD-margined: notional = quantity * price, settled in the quote asset
+    #[default]
+    Linear,
+    /// Coin-margined: quantity is in contracts worth 1 quote unit each,
+    /// notional = contracts / price, settled in the base asset
+    Inverse,
+}
+
+impl ContractType {
+    /// Value of `quantity` at `price` in the settlement asset
+    pub fn notional(&self, quantity: Quantity, price: Price) -> Money {
+        match self {
+            ContractType::Linear => quantity * price,
+            ContractType::Inverse => Money::new(quantity.value() / price.value()),
+        }
+    }
+
+    /// PnL of a long `quantity` entered at `entry` and marked at `exit`
+    pub fn pnl(&self, quantity: Quantity, entry: Price, exit: Price) -> Money {
+        match self {
+            ContractType::Linear => (exit - entry) * quantity,
+            // quantity * (1/entry - 1/exit), multiplied out before dividing
+            ContractType::Inverse => Money::new(
+                quantity.value() * (exit - entry).value() / (entry.value() * exit.value()),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
     pub symbol: String,
-    pub quantity: Decimal,
-    pub avg_price: Decimal,
-    pub market_value: Decimal,
-    pub unrealized_pnl: Decimal,
+    pub quantity: Quantity,
+    pub avg_price: Price,
+    pub market_value: Money,
+    pub unrealized_pnl: Money,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
     pub symbol: String,
     pub side: TradeSide,
-    pub quantity: Decimal,
-    pub price: Decimal,
+    pub quantity: Quantity,
+    pub price: Price,
     pub timestamp: DateTime<Utc>,
-    pub realized_pnl: Option<Decimal>,
-    pub commission: Decimal,
+    pub realized_pnl: Option<Money>,
+    pub commission: Money,
+}
+
+/// Read-only view of a portfolio handed to strategies
+#[derive(Debug, Clone)]
+pub struct PortfolioSnapshot {
+    pub cash: Money,
+    pub positions: HashMap<String, Position>,
+    pub total_value: Money,
 }
 
 pub struct Portfolio {
     pub initial_capital: Decimal,
-    pub cash: Decimal,
+    pub cash: Money,
     pub positions: HashMap<String, Position>,
     pub trades: Vec<Trade>,
     pub current_prices: HashMap<String, Decimal>,
-    pub commission_rate: Decimal, // e.g., 0.001 for 0.1%
+    commission_fn: Option<CommissionFn>,
+    /// Decimal places and rounding applied to each fill's commission
+    commission_rounding: Option<(u32, RoundingStrategy)>,
+    contract_type: ContractType,
+    cumulative_volume: Money,
+    borrow_cost: Money,
+    /// Whether sells may take a position below zero
+    short_selling: bool,
+    /// Largest total short notional as a multiple of equity
+    short_margin_ratio: Decimal,
     equity_curve: Vec<Decimal>,
 }
 
+const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60;
+
 impl Portfolio {
     pub fn new(initial_capital: Decimal) -> Self {
         Self {
             initial_capital,
-            cash: initial_capital,
+            cash: Money::new(initial_capital),
             positions: HashMap::new(),
             trades: Vec::new(),
             current_prices: HashMap::new(),
-            commission_rate: Decimal::from_str("0.001").unwrap_or(Decimal::ZERO), // 0.1% default
+            commission_fn: None,
+            commission_rounding: None,
+            contract_type: ContractType::default(),
+            cumulative_volume: Money::ZERO,
+            borrow_cost: Money::ZERO,
+            short_selling: false,
+            short_margin_ratio: Decimal::ONE,
             equity_curve: vec![initial_capital],
         }
     }
 
-    pub fn with_commission_rate(mut self, rate: Decimal) -> Self {
-        self.commission_rate = rate;
+    pub fn with_commission_fn(mut self, commission_fn: Option<CommissionFn>) -> Self {
+        self.commission_fn = commission_fn;
+        self
+    }
+
+    /// Round each fill's commission to `precision` decimal places; None
+    /// keeps full precision
+    pub fn with_commission_rounding(
+        mut self,
+        precision: Option<u32>,
+        strategy: RoundingStrategy,
+    ) -> Self {
+        self.commission_rounding = precision.map(|dp| (dp, strategy));
+        self
+    }
+
+    /// Value positions as linear or inverse contracts
+    pub fn with_contract_type(mut self, contract_type: ContractType) -> Self {
+        self.contract_type = contract_type;
+        self
+    }
+
+    /// Let sells exceed the held quantity, opening a short (negative) position
+    pub fn with_short_selling(mut self, enabled: bool) -> Self {
+        self.short_selling = enabled;
+        self
+    }
+
+    /// Cap the notional of all short positions at `ratio` times equity
+    /// (1 by default); sells past the cap are rejected
+    pub fn with_short_margin_ratio(mut self, ratio: Decimal) -> Self {
+        self.short_margin_ratio = ratio;
         self
     }
 
+    pub fn contract_type(&self) -> ContractType {
+        self.contract_type
+    }
+
+    /// Commission for a fill: the custom function if set, else `rate` of
+    /// the notional, rounded to the commission precision if one is set
+    fn commission_for(
+        &self,
+        symbol: &str,
+        side: TradeSide,
+        quantity: Quantity,
+        price: Price,
+        rate: Decimal,
+    ) -> Money {
+        let commission = match &self.commission_fn {
+            Some(CommissionFn(f)) => f(&FillContext {
+                symbol,
+                side,
+                quantity: quantity.value(),
+                price: price.value(),
+                cumulative_volume: self.cumulative_volume.value(),
+            }),
+            None => (self.contract_type.notional(quantity, price) * rate).value(),
+        };
+        Money::new(match self.commission_rounding {
+            Some((dp, strategy)) => commission.round_dp_with_strategy(dp, strategy),
+            None => commission,
+        })
+    }
+
+    /// Mark a position at `price`, updating its market value and unrealized PnL
+    ///
+    /// A linear position is worth quantity * price. An inverse position is
+    /// worth its posted margin plus PnL, so its value moves by the change in PnL.
+    fn mark(contract_type: ContractType, position: &mut Position, price: Price) {
+        let unrealized_pnl = contract_type.pnl(position.quantity, position.avg_price, price);
+        match contract_type {
+            ContractType::Linear => position.market_value = position.quantity * price,
+            ContractType::Inverse => {
+                position.market_value += unrealized_pnl - position.unrealized_pnl
+            }
+        }
+        position.unrealized_pnl = unrealized_pnl;
+    }
+
+    /// Entry price that folds `commission` into the cost of the position,
+    /// so PnL measured from it is net of entry fees
+    fn entry_price(
+        &self,
+        held: Option<(Quantity, Price)>,
+        quantity: Quantity,
+        price: Price,
+        commission: Money,
+    ) -> Price {
+        let (held_quantity, held_price) =
+            held.unwrap_or((Quantity::ZERO, Price::new(Decimal::ONE)));
+        let total_quantity = held_quantity + quantity;
+        match self.contract_type {
+            ContractType::Linear => {
+                (held_quantity * held_price + quantity * price + commission) / total_quantity
+            }
+            // Fees lower the contracts-per-coin paid, which raises the entry price
+            ContractType::Inverse => {
+                let paid = self.contract_type.notional(held_quantity, held_price)
+                    + self.contract_type.notional(quantity, price)
+                    - commission;
+                Price::new(total_quantity.value() / paid.value())
+            }
+        }
+    }
+
     pub fn update_price(&mut self, symbol: &str, price: Decimal) {
         self.current_prices.insert(symbol.to_string(), price);
 
         // Update position market value and unrealized PnL
         if let Some(position) = self.positions.get_mut(symbol) {
-            position.market_value = position.quantity * price;
-            position.unrealized_pnl = (price - position.avg_price) * position.quantity;
+            Self::mark(self.contract_type, position, Price::new(price));
         }
     }
 
+    /// Buy `quantity` at `price`, paying `commission_rate` of the notional
+    /// (e.g. 0.001 for 0.1%) unless a commission function is set
     pub fn execute_buy(
         &mut self,
         symbol: String,
         quantity: Decimal,
         price: Decimal,
+        commission_rate: Decimal,
         timestamp: DateTime<Utc>,
     ) -> Result<(), String> {
-        let cost = quantity * price;
-        let commission = cost * self.commission_rate;
-        let total_cost = cost + commission;
-
+        let (quantity, price) = (Quantity::new(quantity), Price::new(price));
+        let total_cost = self.contract_type.notional(quantity, price)
+            + self.commission_for(&symbol, TradeSide::Buy, quantity, price, commission_rate);
         if total_cost > self.cash {
             return Err(format!(
                 "Insufficient funds: need ${}, available ${}",
@@ -80,91 +273,224 @@ impl Portfolio {
             ));
         }
 
-        self.cash -= total_cost;
+        self.execute(
+            symbol,
+            TradeSide::Buy,
+            quantity,
+            price,
+            commission_rate,
+            timestamp,
+        );
+        Ok(())
+    }
+
+    /// Sell from a long position, or past it into a short when short selling
+    /// is enabled, paying `commission_rate` as in `execute_buy`
+    pub fn execute_sell(
+        &mut self,
+        symbol: String,
+        quantity: Decimal,
+        price: Decimal,
+        commission_rate: Decimal,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), String> {
+        let (quantity, price) = (Quantity::new(quantity), Price::new(price));
+        if !self.short_selling {
+            let available = self
+                .positions
+                .get(&symbol)
+                .map(|position| position.quantity)
+                .ok_or("No position to sell")?;
 
-        match self.positions.get_mut(&symbol) {
-            Some(position) => {
-                let total_quantity = position.quantity + quantity;
-                let total_cost = position.quantity * position.avg_price + cost + commission;
-                position.avg_price = total_cost / total_quantity;
-                position.quantity = total_quantity;
-                position.market_value = total_quantity * price;
-                position.unrealized_pnl = (price - position.avg_price) * total_quantity;
+            if quantity > available {
+                return Err(format!(
+                    "Insufficient position: need {}, available {}",
+                    quantity, available
+                ));
             }
-            None => {
-                self.positions.insert(
-                    symbol.clone(),
-                    Position {
-                        symbol: symbol.clone(),
-                        quantity,
-                        avg_price: price + commission / quantity,
-                        market_value: quantity * price,
-                        unrealized_pnl: Decimal::ZERO,
-                    },
-                );
+        } else {
+            let long = self
+                .positions
+                .get(&symbol)
+                .map_or(Quantity::ZERO, |position| {
+                    position.quantity.max(Quantity::ZERO)
+                });
+            let shorted = quantity - quantity.min(long);
+            let capacity = self.short_capacity(&symbol, price);
+            if shorted > capacity {
+                return Err(format!(
+                    "Short exceeds margin: need {}, available {}",
+                    shorted, capacity
+                ));
             }
         }
 
-        self.trades.push(Trade {
+        self.execute(
             symbol,
-            side: TradeSide::Buy,
+            TradeSide::Sell,
             quantity,
             price,
+            commission_rate,
             timestamp,
-            realized_pnl: None,
-            commission,
-        });
-
+        );
         Ok(())
     }
 
-    pub fn execute_sell(
+    /// Quantity of `symbol` that may still be sold short at `price` before
+    /// the notional of all shorts exceeds equity times the margin ratio
+    pub fn short_capacity(&self, symbol: &str, price: Price) -> Quantity {
+        let shorted: Money = self
+            .positions
+            .values()
+            .filter(|position| position.quantity < Quantity::ZERO)
+            .map(|position| {
+                let mark = if position.symbol == symbol {
+                    price
+                } else {
+                    self.mark_price(position)
+                };
+                self.contract_type.notional(-position.quantity, mark)
+            })
+            .sum();
+        let headroom = Money::new(self.total_value()) * self.short_margin_ratio - shorted;
+        let unit_notional = self
+            .contract_type
+            .notional(Quantity::new(Decimal::ONE), price);
+        if headroom <= Money::ZERO || unit_notional <= Money::ZERO {
+            return Quantity::ZERO;
+        }
+        Quantity::new((headroom / unit_notional).trunc_with_scale(8))
+    }
+
+    /// Current price of a position's symbol, or its entry price before any
+    fn mark_price(&self, position: &Position) -> Price {
+        self.current_prices
+            .get(&position.symbol)
+            .map_or(position.avg_price, |&price| Price::new(price))
+    }
+
+    /// Fill an order: first close against a position on the other side,
+    /// realizing its PnL, then open or extend a position with the rest
+    fn execute(
         &mut self,
         symbol: String,
-        quantity: Decimal,
-        price: Decimal,
+        side: TradeSide,
+        quantity: Quantity,
+        price: Price,
+        commission_rate: Decimal,
         timestamp: DateTime<Utc>,
-    ) -> Result<(), String> {
-        let position = self
+    ) {
+        let contract_type = self.contract_type;
+        let direction = match side {
+            TradeSide::Buy => Decimal::ONE,
+            TradeSide::Sell => Decimal::NEGATIVE_ONE,
+        };
+        let commission = self.commission_for(&symbol, side, quantity, price, commission_rate);
+        self.cumulative_volume += contract_type.notional(quantity, price);
+
+        let held = self
+            .positions
+            .get(&symbol)
+            .map(|position| position.quantity);
+        let closing = match held {
+            Some(held_quantity) if held_quantity * direction < Quantity::ZERO => {
+                quantity.min(held_quantity.abs())
+            }
+            _ => Quantity::ZERO,
+        };
+        let opening = quantity - closing;
+
+        let closing_position = self
             .positions
             .get_mut(&symbol)
-            .ok_or("No position to sell")?;
+            .filter(|_| closing > Quantity::ZERO);
+        let realized_pnl = if let Some(position) = closing_position {
+            let fee = commission.share(closing, quantity);
+            Self::mark(contract_type, position, price);
 
-        if quantity > position.quantity {
-            return Err(format!(
-                "Insufficient position: need {}, available {}",
-                quantity, position.quantity
-            ));
-        }
+            // The closed share of the position's value: proceeds for a long,
+            // the (negative) cost of buying back for a short
+            let released = position.market_value.share(closing, position.quantity.abs());
+            self.cash += released - fee;
 
-        let proceeds = quantity * price;
-        let commission = proceeds * self.commission_rate;
-        let net_proceeds = proceeds - commission;
+            let closed = closing * -direction;
+            let realized_pnl = contract_type.pnl(closed, position.avg_price, price) - fee;
 
-        self.cash += net_proceeds;
+            position.quantity -= closed;
+            if position.quantity == Quantity::ZERO {
+                self.positions.remove(&symbol);
+            } else {
+                position.market_value -= released;
+                Self::mark(contract_type, position, price);
+            }
+            Some(realized_pnl)
+        } else {
+            None
+        };
 
-        // Calculate realized PnL
-        let realized_pnl = (price - position.avg_price) * quantity - commission;
+        if opening > Quantity::ZERO {
+            let fee = commission - commission.share(closing, quantity);
+            let opened = opening * direction;
+            // Buying pays the notional; selling short receives it
+            let cost = contract_type.notional(opening, price) * direction;
+            self.cash -= cost + fee;
 
-        position.quantity -= quantity;
-        if position.quantity == Decimal::ZERO {
-            self.positions.remove(&symbol);
-        } else {
-            position.market_value = position.quantity * price;
-            position.unrealized_pnl = (price - position.avg_price) * position.quantity;
+            let held = self
+                .positions
+                .get(&symbol)
+                .map(|position| (position.quantity, position.avg_price));
+            let avg_price = self.entry_price(held, opened, price, fee);
+            let position = self
+                .positions
+                .entry(symbol.clone())
+                .or_insert_with(|| Position {
+                    symbol: symbol.clone(),
+                    quantity: Quantity::ZERO,
+                    avg_price,
+                    market_value: Money::ZERO,
+                    unrealized_pnl: Money::ZERO,
+                });
+            Self::mark(contract_type, position, price);
+            position.quantity += opened;
+            position.avg_price = avg_price;
+            position.market_value += cost;
+            position.unrealized_pnl = contract_type.pnl(position.quantity, avg_price, price);
         }
 
         self.trades.push(Trade {
             symbol,
-            side: TradeSide::Sell,
+            side,
             quantity,
             price,
             timestamp,
-            realized_pnl: Some(realized_pnl),
+            realized_pnl,
             commission,
         });
+    }
 
-        Ok(())
+    /// Debit borrow fees on short (negative) positions held for `elapsed_seconds`,
+    /// valued at the current price. Returns the amount charged.
+    pub fn accrue_borrow_cost(&mut self, annual_rate: Decimal, elapsed_seconds: i64) -> Decimal {
+        if annual_rate <= Decimal::ZERO || elapsed_seconds <= 0 {
+            return Decimal::ZERO;
+        }
+
+        let short_notional: Money = self
+            .positions
+            .values()
+            .filter(|position| position.quantity < Quantity::ZERO)
+            .map(|position| {
+                self.contract_type
+                    .notional(position.quantity.abs(), self.mark_price(position))
+            })
+            .sum();
+        // Multiply before dividing to keep the year fraction exact
+        let cost = short_notional * annual_rate * Decimal::from(elapsed_seconds)
+            / Decimal::from(SECONDS_PER_YEAR);
+
+        self.cash -= cost;
+        self.borrow_cost += cost;
+        cost.value()
     }
 
     pub fn total_value(&self) -> Decimal {
@@ -174,37 +500,68 @@ impl Portfolio {
             total += position.market_value;
         }
 
-        total
+        total.value()
     }
 
     pub fn total_realized_pnl(&self) -> Decimal {
         self.trades
             .iter()
             .filter_map(|trade| trade.realized_pnl)
-            .sum()
+            .sum::<Money>()
+            .value()
     }
 
     pub fn total_unrealized_pnl(&self) -> Decimal {
-        self.positions.values().map(|pos| pos.unrealized_pnl).sum()
+        self.positions
+            .values()
+            .map(|pos| pos.unrealized_pnl)
+            .sum::<Money>()
+            .value()
     }
 
     pub fn total_pnl(&self) -> Decimal {
-        self.total_realized_pnl() + self.total_unrealized_pnl()
+        self.total_realized_pnl() + self.total_unrealized_pnl() - self.borrow_cost.value()
     }
 
     pub fn total_commission(&self) -> Decimal {
-        self.trades.iter().map(|trade| trade.commission).sum()
+        self.trades
+            .iter()
+            .map(|trade| trade.commission)
+            .sum::<Money>()
+            .value()
+    }
+
+    pub fn total_borrow_cost(&self) -> Decimal {
+        self.borrow_cost.value()
     }
 
     pub fn has_position(&self, symbol: &str) -> bool {
         self.positions.contains_key(symbol)
-            && self.positions.get(symbol).unwrap().quantity > Decimal::ZERO
+            && self.positions.get(symbol).unwrap().quantity > Quantity::ZERO
+    }
+
+    pub fn snapshot(&self) -> PortfolioSnapshot {
+        PortfolioSnapshot {
+            cash: self.cash,
+            positions: self.positions.clone(),
+            total_value: Money::new(self.total_value()),
+        }
     }
 
     pub fn snapshot_equity(&mut self) {
         self.equity_curve.push(self.total_value());
     }
 
+    /// Replace the latest equity point with the current total value, for
+    /// fills that land on an already snapshotted timestamp
+    pub fn restate_equity(&mut self) {
+        let value = self.total_value();
+        match self.equity_curve.last_mut() {
+            Some(last) => *last = value,
+            None => self.equity_curve.push(value),
+        }
+    }
+
     pub fn get_equity_curve(&self) -> Vec<Decimal> {
         self.equity_curve.clone()
     }
@@ -0,0 +1,226 @@
+//! Saving backtest runs to disk.
+//!
+//! Each run gets its own timestamped directory under a results directory:
+//!
+//! ```text
+//! <results_dir>/
+//! ├── index.json                      # One entry per saved run
+//! └── 20240101_120000_rsi_strategy/
+//!     ├── result.json                 # Serialized BacktestResult
+//!     ├── config.json                 # Run configuration
+//!     └── trades.csv                  # One row per trade
+//! ```
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::engine::BacktestResult;
+use super::portfolio::Trade;
+
+pub const INDEX_FILE: &str = "index.json";
+pub const RESULT_FILE: &str = "result.json";
+pub const CONFIG_FILE: &str = "config.json";
+pub const TRADES_FILE: &str = "trades.csv";
+/// Written by `BacktestResult::export_csv` only
+pub const EQUITY_CURVE_FILE: &str = "equity_curve.csv";
+
+/// Summary of one saved run, as listed in `index.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunIndexEntry {
+    pub run_id: String,
+    pub saved_at: DateTime<Utc>,
+    pub strategy_name: String,
+    /// Directory of the run relative to the results directory
+    pub directory: String,
+    pub return_percentage: Decimal,
+    pub total_trades: usize,
+}
+
+/// Save a run's result, configuration and trades into a new directory under
+/// `results_dir`, and append it to the index. Returns the run directory.
+pub fn save_run<C: Serialize>(
+    results_dir: &Path,
+    result: &BacktestResult,
+    config: &C,
+) -> io::Result<PathBuf> {
+    fs::create_dir_all(results_dir)?;
+
+    let saved_at = Utc::now();
+    let base_id = format!(
+        "{}_{}",
+        saved_at.format("%Y%m%d_%H%M%S"),
+        sanitize(&result.strategy_name)
+    );
+
+    // Runs saved within the same second get a numeric suffix
+    let mut run_id = base_id.clone();
+    let mut suffix = 1;
+    while results_dir.join(&run_id).exists() {
+        suffix += 1;
+        run_id = format!("{}_{}", base_id, suffix);
+    }
+    let run_dir = results_dir.join(&run_id);
+    fs::create_dir(&run_dir)?;
+
+    fs::write(run_dir.join(RESULT_FILE), to_json(result)?)?;
+    fs::write(run_dir.join(CONFIG_FILE), to_json(config)?)?;
+    write_trades_csv(fs::File::create(run_dir.join(TRADES_FILE))?, &result.trades)?;
+
+    let mut index = load_index(results_dir)?;
+    index.push(RunIndexEntry {
+        run_id: run_id.clone(),
+        saved_at,
+        strategy_name: result.strategy_name.clone(),
+        directory: run_id,
+        return_percentage: result.return_percentage,
+        total_trades: result.total_trades,
+    });
+    fs::write(results_dir.join(INDEX_FILE), to_json(&index)?)?;
+
+    Ok(run_dir)
+}
+
+/// Read the run index; empty if nothing has been saved yet
+pub fn load_index(results_dir: &Path) -> io::Result<Vec<RunIndexEntry>> {
+    let path = results_dir.join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Load a saved `result.json`
+pub fn load_result(path: &Path) -> io::Result<BacktestResult> {
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write trades as CSV with a header row; decimals keep full precision and
+/// `realized_pnl` is empty for buys
+pub fn write_trades_csv<W: io::Write>(writer: W, trades: &[Trade]) -> io::Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record([
+        "timestamp",
+        "symbol",
+        "side",
+        "quantity",
+        "price",
+        "commission",
+        "realized_pnl",
+    ])?;
+    for trade in trades {
+        csv.write_record([
+            trade.timestamp.to_rfc3339(),
+            trade.symbol.clone(),
+            trade.side.as_db_str().to_string(),
+            trade.quantity.to_string(),
+            trade.price.to_string(),
+            trade.commission.to_string(),
+            trade
+                .realized_pnl
+                .map(|pnl| pnl.to_string())
+                .unwrap_or_default(),
+        ])?;
+    }
+    csv.flush()
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> io::Result<String> {
+    serde_json::to_string_pretty(value).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Lowercase alphanumerics with underscores, for use in directory names
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::engine::{BacktestConfig, BacktestEngine};
+    use crate::backtest::strategy::create_strategy;
+    use crate::data::types::{TickData, TradeSide};
+    use chrono::Duration;
+    use std::collections::HashMap;
+
+    fn temp_results_dir() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "backtest_results_test_{}_{}",
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ))
+    }
+
+    fn run_backtest() -> BacktestResult {
+        let start = Utc::now();
+        // Dip then recovery so the RSI strategy trades
+        let prices = [
+            100, 95, 90, 85, 80, 75, 70, 65, 60, 55, 50, 45, 40, 35, 30, 25, 60, 90, 120,
+        ];
+        let ticks: Vec<TickData> = prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| {
+                TickData::new(
+                    start + Duration::seconds(i as i64),
+                    "BTCUSDT".to_string(),
+                    Decimal::from(*price),
+                    Decimal::ONE,
+                    TradeSide::Buy,
+                    format!("save{}", i),
+                    false,
+                )
+            })
+            .collect();
+
+        let strategy = create_strategy("rsi").unwrap();
+        let config = BacktestConfig::new(Decimal::from(100000));
+        BacktestEngine::new(strategy, config).unwrap().run(&ticks)
+    }
+
+    #[test]
+    fn test_save_run_writes_parseable_files() {
+        let results_dir = temp_results_dir();
+        let result = run_backtest();
+        assert!(result.total_trades > 0);
+
+        let config = HashMap::from([("strategy".to_string(), "rsi".to_string())]);
+        let first = save_run(&results_dir, &result, &config).unwrap();
+        let second = save_run(&results_dir, &result, &config).unwrap();
+        assert_ne!(first, second);
+
+        let loaded = load_result(&first.join(RESULT_FILE)).unwrap();
+        assert_eq!(loaded.final_value, result.final_value);
+        assert_eq!(loaded.trades.len(), result.trades.len());
+
+        let saved_config: HashMap<String, String> =
+            serde_json::from_str(&fs::read_to_string(first.join(CONFIG_FILE)).unwrap()).unwrap();
+        assert_eq!(saved_config, config);
+
+        let csv = fs::read_to_string(first.join(TRADES_FILE)).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), result.trades.len() + 1);
+        assert!(lines[0].starts_with("timestamp,symbol,side"));
+        assert!(lines[1].contains(",BTCUSDT,BUY,"));
+
+        let index = load_index(&results_dir).unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(results_dir.join(&index[1].directory), second);
+        assert_eq!(index[0].total_trades, result.total_trades);
+
+        fs::remove_dir_all(&results_dir).unwrap();
+    }
+}
@@ -0,0 +1,186 @@
+//! Descriptive statistics and downsampling over `Decimal` values.
+//!
+//! Everything here stays in `Decimal` arithmetic so metric calculations never
+//! round-trip through `f64` (no precision loss, no NaN).
+
+use rust_decimal::Decimal;
+
+/// Maximum Newton iterations for `sqrt`; convergence is quadratic so this is generous
+const SQRT_MAX_ITERATIONS: usize = 100;
+
+/// Arithmetic mean, zero for an empty slice
+pub fn mean(values: &[Decimal]) -> Decimal {
+    if values.is_empty() {
+        return Decimal::ZERO;
+    }
+    values.iter().sum::<Decimal>() / Decimal::from(values.len())
+}
+
+/// Sample variance (n - 1 denominator), zero for fewer than two values
+pub fn variance(values: &[Decimal]) -> Decimal {
+    if values.len() < 2 {
+        return Decimal::ZERO;
+    }
+
+    let mean = mean(values);
+    values
+        .iter()
+        .map(|x| (x - mean) * (x - mean))
+        .sum::<Decimal>()
+        / Decimal::from(values.len() - 1)
+}
+
+/// Sample standard deviation
+pub fn std_dev(values: &[Decimal]) -> Decimal {
+    sqrt(variance(values))
+}
+
+/// Square root using Newton's method, zero for non-positive input
+pub fn sqrt(value: Decimal) -> Decimal {
+    if value <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+
+    // Start at or above the root so the iteration decreases monotonically
+    let mut x = if value > Decimal::ONE {
+        value
+    } else {
+        Decimal::ONE
+    };
+
+    for _ in 0..SQRT_MAX_ITERATIONS {
+        let next = (x + value / x) / Decimal::TWO;
+        if next >= x {
+            break;
+        }
+        x = next;
+    }
+
+    x
+}
+
+/// Indices of at most `threshold` points of `values` chosen by
+/// largest-triangle-three-buckets, treating the index as the x coordinate.
+///
+/// The first and last points are always kept; the rest are split into
+/// `threshold - 2` buckets and each bucket keeps the point forming the largest
+/// triangle with the previously kept point and the next bucket's average, which
+/// preserves the visual shape of the series. Returns every index when the
+/// series already fits or `threshold` is below 3.
+pub fn lttb_indices(values: &[Decimal], threshold: usize) -> Vec<usize> {
+    let len = values.len();
+    if threshold >= len || threshold < 3 {
+        return (0..len).collect();
+    }
+
+    let buckets = threshold - 2;
+    let bucket_start = |bucket: usize| 1 + bucket * (len - 2) / buckets;
+    let mut selected = Vec::with_capacity(threshold);
+    selected.push(0);
+
+    let mut previous = 0;
+    for bucket in 0..buckets {
+        let (start, end) = (bucket_start(bucket), bucket_start(bucket + 1));
+        // The last bucket looks ahead to the final point
+        let next = if bucket + 1 < buckets {
+            bucket_start(bucket + 1)..bucket_start(bucket + 2)
+        } else {
+            len - 1..len
+        };
+        let next_count = Decimal::from(next.len());
+        let avg_x = Decimal::from(next.clone().sum::<usize>()) / next_count;
+        let avg_y = values[next].iter().sum::<Decimal>() / next_count;
+
+        let (prev_x, prev_y) = (Decimal::from(previous), values[previous]);
+        let doubled_area = |index: usize| {
+            ((prev_x - avg_x) * (values[index] - prev_y)
+                - (prev_x - Decimal::from(index)) * (avg_y - prev_y))
+                .abs()
+        };
+        let mut best = start;
+        let mut best_area = doubled_area(start);
+        for index in start + 1..end {
+            let area = doubled_area(index);
+            if area > best_area {
+                best = index;
+                best_area = area;
+            }
+        }
+
+        selected.push(best);
+        previous = best;
+    }
+
+    selected.push(len - 1);
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn decs(values: &[&str]) -> Vec<Decimal> {
+        values.iter().map(|v| dec(v)).collect()
+    }
+
+    fn assert_close(actual: Decimal, expected: Decimal, tolerance: &str) {
+        let diff = (actual - expected).abs();
+        assert!(
+            diff <= dec(tolerance),
+            "expected {} got {} (diff {})",
+            expected,
+            actual,
+            diff
+        );
+    }
+
+    #[test]
+    fn test_mean_and_variance() {
+        let values = decs(&["2", "4", "4", "4", "5", "5", "7", "9"]);
+        assert_eq!(mean(&values), dec("5"));
+        // Sum of squared deviations is 32, sample variance 32 / 7
+        assert_close(
+            variance(&values),
+            dec("32") / dec("7"),
+            "0.0000000000000000000001",
+        );
+
+        assert_eq!(mean(&[]), Decimal::ZERO);
+        assert_eq!(variance(&[dec("3")]), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_sqrt_precision() {
+        assert_eq!(sqrt(dec("16")), dec("4"));
+        assert_close(
+            sqrt(dec("2")),
+            dec("1.4142135623730950488016887242"),
+            "0.0000000000000000000001",
+        );
+        // Small values typical of return variances
+        assert_close(
+            sqrt(dec("0.00000004")),
+            dec("0.0002"),
+            "0.0000000000000000000001",
+        );
+        assert_eq!(sqrt(Decimal::ZERO), Decimal::ZERO);
+        assert_eq!(sqrt(dec("-1")), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_std_dev_of_returns() {
+        let returns = decs(&["0.01", "-0.02", "0.015", "0.005"]);
+        // mean = 0.0025, squared deviations sum = 0.000725, sample variance = 0.000241666...
+        assert_eq!(mean(&returns), dec("0.0025"));
+        assert_close(
+            std_dev(&returns),
+            dec("0.0155456317551480"),
+            "0.0000000000000001",
+        );
+    }
+}
@@ -1,27 +1,129 @@
-use crate::data::types::{OHLCData, TickData};
+use crate::backtest::logger::StrategyLogger;
+use crate::backtest::portfolio::PortfolioSnapshot;
+use crate::data::types::{MarketSnapshot, OHLCData, TickData};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Signal {
-    Buy { symbol: String, quantity: Decimal },
-    Sell { symbol: String, quantity: Decimal },
+    Buy {
+        symbol: String,
+        quantity: Decimal,
+    },
+    Sell {
+        symbol: String,
+        quantity: Decimal,
+    },
+    /// Buy a quantity chosen by the backtest's `PositionSizer` from current
+    /// equity and price; ignored when no sizer is configured
+    SizedBuy {
+        symbol: String,
+    },
+    /// Buy at `limit_price` or lower; see `BacktestEngine` for fill semantics
+    LimitBuy {
+        symbol: String,
+        quantity: Decimal,
+        limit_price: Decimal,
+    },
+    /// Sell at `limit_price` or higher; see `BacktestEngine` for fill semantics
+    LimitSell {
+        symbol: String,
+        quantity: Decimal,
+        limit_price: Decimal,
+    },
+    /// Sell the whole long position once the price falls to `trigger_price`;
+    /// replaces any earlier stop-loss for the symbol and is dropped while
+    /// no long position is open
+    StopLoss {
+        symbol: String,
+        trigger_price: Decimal,
+    },
+    /// Sell the whole long position once the price rises to `trigger_price`;
+    /// replaces any earlier take-profit for the symbol and is dropped while
+    /// no long position is open
+    TakeProfit {
+        symbol: String,
+        trigger_price: Decimal,
+    },
     Hold,
 }
 
+impl Signal {
+    /// Symbol the signal trades, None for `Hold`
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            Signal::Buy { symbol, .. }
+            | Signal::Sell { symbol, .. }
+            | Signal::SizedBuy { symbol }
+            | Signal::LimitBuy { symbol, .. }
+            | Signal::LimitSell { symbol, .. }
+            | Signal::StopLoss { symbol, .. }
+            | Signal::TakeProfit { symbol, .. } => Some(symbol),
+            Signal::Hold => None,
+        }
+    }
+}
+
 pub trait Strategy: Send + Sync {
     fn name(&self) -> &str;
     fn on_tick(&mut self, tick: &TickData) -> Signal;
+
+    /// Live entry point with an aggregate of the cached ticks preceding `tick`
+    /// (None when the cache has nothing). Defaults to `on_tick`.
+    fn on_tick_with_market(&mut self, tick: &TickData, _market: Option<&MarketSnapshot>) -> Signal {
+        self.on_tick(tick)
+    }
     fn initialize(&mut self, params: HashMap<String, String>) -> Result<(), String>;
 
+    /// Backtest entry point with a logger whose messages end up in the
+    /// result instead of stdout. Defaults to `on_tick`.
+    fn on_tick_logged(&mut self, tick: &TickData, _log: &mut StrategyLogger) -> Signal {
+        self.on_tick(tick)
+    }
+
+    /// Every signal for a tick, for strategies that adjust several positions
+    /// at once. Opposing signals for one symbol are resolved by the
+    /// backtest's `ConflictPolicy`. Defaults to the single `on_tick_logged`
+    /// signal.
+    fn on_tick_signals(&mut self, tick: &TickData, log: &mut StrategyLogger) -> Vec<Signal> {
+        vec![self.on_tick_logged(tick, log)]
+    }
+
+    /// Multi-symbol entry point used by `BacktestEngine::run_multi`: every
+    /// tick of one timestamp, with the portfolio valued at their prices.
+    /// Defaults to `on_tick_signals` for each tick.
+    fn on_tick_multi(
+        &mut self,
+        ticks: &[TickData],
+        _portfolio: &PortfolioSnapshot,
+        log: &mut StrategyLogger,
+    ) -> Vec<Signal> {
+        ticks
+            .iter()
+            .flat_map(|tick| self.on_tick_signals(tick, log))
+            .filter(|signal| !matches!(signal, Signal::Hold))
+            .collect()
+    }
+
     /// Reset strategy state for new backtest
     fn reset(&mut self) {
         // Default implementation does nothing
         // Strategies can override if needed
     }
 
-    fn on_ohlc(&mut self, _ohlc: &OHLCData) -> Signal {
-        Signal::Hold
+    /// Candle entry point for OHLC backtests. Defaults to `on_tick` with the
+    /// candle's `close_tick`, so tick-only strategies also run on candles.
+    fn on_ohlc(&mut self, ohlc: &OHLCData) -> Signal {
+        self.on_tick(&ohlc.close_tick())
+    }
+
+    /// Candle counterpart of `on_tick_logged`. Defaults to `on_ohlc`.
+    fn on_ohlc_logged(&mut self, ohlc: &OHLCData, _log: &mut StrategyLogger) -> Signal {
+        self.on_ohlc(ohlc)
+    }
+    /// Candle counterpart of `on_tick_signals`. Defaults to `on_ohlc_logged`.
+    fn on_ohlc_signals(&mut self, ohlc: &OHLCData, log: &mut StrategyLogger) -> Vec<Signal> {
+        vec![self.on_ohlc_logged(ohlc, log)]
     }
     fn supports_ohlc(&self) -> bool {
         false
@@ -29,4 +131,15 @@ pub trait Strategy: Send + Sync {
     fn preferred_timeframe(&self) -> Option<crate::data::types::Timeframe> {
         None
     }
+
+    /// Number of data points the strategy needs before it can emit its first signal
+    fn warmup_period(&self) -> usize {
+        0
+    }
+
+    /// Called once after the last data point; returned signals are executed
+    /// at each symbol's final price (e.g. to flatten open positions)
+    fn on_finish(&mut self, _portfolio: &PortfolioSnapshot) -> Vec<Signal> {
+        Vec::new()
+    }
 }
@@ -0,0 +1,194 @@
+use super::base::{Signal, Strategy};
+use crate::backtest::stats;
+use crate::data::types::{OHLCData, TickData, Timeframe};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+
+/// Mean-reversion strategy on Bollinger Bands
+///
+/// The bands sit `num_std` population standard deviations above and below
+/// the moving average of the last `period` closes. Buys when a price closes
+/// below the lower band and sells the position when one closes above the
+/// upper band. It trades long only: a close above the upper band while flat,
+/// or below the lower band while holding, is ignored.
+pub struct BollingerStrategy {
+    period: usize,
+    num_std: Decimal,
+    prices: VecDeque<Decimal>,
+    holding: bool,
+}
+
+impl BollingerStrategy {
+    pub fn new() -> Self {
+        Self {
+            period: 20,
+            num_std: Decimal::TWO,
+            prices: VecDeque::new(),
+            holding: false,
+        }
+    }
+
+    /// (lower, middle, upper) band over the window, once it is full
+    fn bands(&self) -> Option<(Decimal, Decimal, Decimal)> {
+        if self.prices.len() < self.period {
+            return None;
+        }
+
+        let count = Decimal::from(self.period);
+        let mean = self.prices.iter().sum::<Decimal>() / count;
+        let variance = self
+            .prices
+            .iter()
+            .map(|price| (price - mean) * (price - mean))
+            .sum::<Decimal>()
+            / count;
+        let width = stats::sqrt(variance) * self.num_std;
+        Some((mean - width, mean, mean + width))
+    }
+
+    fn on_price(&mut self, symbol: &str, price: Decimal) -> Signal {
+        self.prices.push_back(price);
+        if self.prices.len() > self.period {
+            self.prices.pop_front();
+        }
+        let Some((lower, _, upper)) = self.bands() else {
+            return Signal::Hold;
+        };
+
+        if !self.holding && price < lower {
+            self.holding = true;
+            Signal::Buy {
+                symbol: symbol.to_string(),
+                quantity: Decimal::from(100),
+            }
+        } else if self.holding && price > upper {
+            self.holding = false;
+            Signal::Sell {
+                symbol: symbol.to_string(),
+                quantity: Decimal::from(100),
+            }
+        } else {
+            Signal::Hold
+        }
+    }
+}
+
+impl Strategy for BollingerStrategy {
+    fn name(&self) -> &str {
+        "Bollinger Bands"
+    }
+
+    fn initialize(&mut self, params: HashMap<String, String>) -> Result<(), String> {
+        if let Some(period) = params.get("period") {
+            self.period = period.parse().map_err(|_| "Invalid period")?;
+        }
+        if let Some(num_std) = params.get("num_std") {
+            self.num_std = num_std.parse().map_err(|_| "Invalid num_std")?;
+        }
+
+        if self.period < 2 {
+            return Err("Period must be at least 2".to_string());
+        }
+        if self.num_std <= Decimal::ZERO {
+            return Err("num_std must be greater than 0".to_string());
+        }
+
+        println!(
+            "Bollinger Bands Strategy initialized: period={}, num_std={}",
+            self.period, self.num_std
+        );
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.prices.clear();
+        self.holding = false;
+    }
+
+    fn on_tick(&mut self, tick: &TickData) -> Signal {
+        self.on_price(&tick.symbol, tick.price)
+    }
+
+    fn on_ohlc(&mut self, ohlc: &OHLCData) -> Signal {
+        self.on_price(&ohlc.symbol, ohlc.close)
+    }
+
+    fn supports_ohlc(&self) -> bool {
+        true
+    }
+    fn preferred_timeframe(&self) -> Option<Timeframe> {
+        Some(Timeframe::OneDay)
+    }
+    fn warmup_period(&self) -> usize {
+        self.period
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::strategy::test_support::signal_sequence;
+
+    fn bollinger_strategy(period: &str, num_std: &str) -> BollingerStrategy {
+        let mut strategy = BollingerStrategy::new();
+        strategy
+            .initialize(HashMap::from([
+                ("period".to_string(), period.to_string()),
+                ("num_std".to_string(), num_std.to_string()),
+            ]))
+            .unwrap();
+        strategy
+    }
+
+    #[test]
+    fn test_band_math() {
+        let mut strategy = bollinger_strategy("8", "2");
+        // Mean 5, population variance (9+1+1+1+0+0+4+16) / 8 = 4, std dev 2
+        signal_sequence(&mut strategy, &[2, 4, 4, 4, 5, 5, 7, 9]);
+        assert_eq!(
+            strategy.bands(),
+            Some((Decimal::ONE, Decimal::from(5), Decimal::from(9)))
+        );
+
+        // The window slides: [4, 4, 4, 5, 5, 7, 9, 10] has mean 6 and
+        // population variance 40 / 8 = 5, so the bands are 6 -/+ 2 * sqrt(5)
+        signal_sequence(&mut strategy, &[10]);
+        let (lower, middle, upper) = strategy.bands().unwrap();
+        assert_eq!(middle, Decimal::from(6));
+        let width = Decimal::new(4472135955, 9);
+        assert!((middle - lower - width).abs() < Decimal::new(1, 8));
+        assert!((upper - middle - width).abs() < Decimal::new(1, 8));
+    }
+
+    #[test]
+    fn test_signals_outside_bands() {
+        let mut strategy = bollinger_strategy("4", "1");
+        // Holds until the window fills, buys the drop below the lower band,
+        // ignores a second drop while holding, sells the spike above the upper band
+        let prices = [10, 10, 10, 10, 6, 4, 10, 14];
+        assert_eq!(
+            signal_sequence(&mut strategy, &prices),
+            vec![(4, 'B'), (7, 'S')]
+        );
+
+        strategy.reset();
+        assert_eq!(strategy.bands(), None);
+        assert!(signal_sequence(&mut strategy, &[10, 10, 10]).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_params_rejected() {
+        let init = |period: &str, num_std: &str| {
+            BollingerStrategy::new().initialize(HashMap::from([
+                ("period".to_string(), period.to_string()),
+                ("num_std".to_string(), num_std.to_string()),
+            ]))
+        };
+
+        assert!(init("1", "2").is_err());
+        assert!(init("20", "0").is_err());
+        assert!(init("x", "2").is_err());
+        assert!(init("20", "2.5").is_ok());
+        assert!(BollingerStrategy::new().initialize(HashMap::new()).is_ok());
+    }
+}
@@ -0,0 +1,349 @@
+use super::base::{Signal, Strategy};
+use super::create_strategy;
+use crate::backtest::logger::StrategyLogger;
+use crate::data::types::{MarketSnapshot, OHLCData, TickData, Timeframe};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Combines the signals of several weighted sub-strategies into one
+///
+/// Every tick or candle goes to all members. Buys add their member's weight
+/// to the score and sells subtract it (limit signals vote like their market
+/// counterparts); the ensemble buys when the net score is more than
+/// `threshold` of the total weight and sells when it is below `-threshold`.
+/// The default threshold of 0.5 is a weighted majority.
+///
+/// Parameters: `strategies` is a spec like `sma:2,rsi`, where a missing
+/// weight means 1. Member parameters take the member id as a prefix, e.g.
+/// `sma.short_period=5`.
+pub struct EnsembleStrategy {
+    members: Vec<(Box<dyn Strategy>, Decimal)>,
+    threshold: Decimal,
+}
+
+impl EnsembleStrategy {
+    pub fn new() -> Self {
+        Self {
+            members: Vec::new(),
+            threshold: Decimal::new(5, 1),
+        }
+    }
+
+    /// Parse `id[:weight]` entries separated by commas
+    fn parse_spec(spec: &str) -> Result<Vec<(String, Decimal)>, String> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (id, weight) = match entry.split_once(':') {
+                    Some((id, weight)) => (
+                        id.trim(),
+                        weight
+                            .trim()
+                            .parse::<Decimal>()
+                            .map_err(|_| format!("Invalid weight for {}", id.trim()))?,
+                    ),
+                    None => (entry, Decimal::ONE),
+                };
+                if weight <= Decimal::ZERO {
+                    return Err(format!("Weight for {} must be positive", id));
+                }
+                Ok((id.to_string(), weight))
+            })
+            .collect()
+    }
+
+    /// Net the members' signals for `symbol` into one decision
+    fn vote(&self, symbol: &str, signals: Vec<Signal>) -> Signal {
+        let mut score = Decimal::ZERO;
+        let mut total_weight = Decimal::ZERO;
+        // Weighted quantity sums per side, for the size of the combined order
+        let (mut buy_size, mut buy_weight) = (Decimal::ZERO, Decimal::ZERO);
+        let (mut sell_size, mut sell_weight) = (Decimal::ZERO, Decimal::ZERO);
+
+        for (signal, (_, weight)) in signals.iter().zip(&self.members) {
+            total_weight += weight;
+            match signal {
+                Signal::Buy { quantity, .. } | Signal::LimitBuy { quantity, .. } => {
+                    score += weight;
+                    buy_size += quantity * weight;
+                    buy_weight += weight;
+                }
+                // Votes to buy without a size of its own
+                Signal::SizedBuy { .. } => score += weight,
+                Signal::Sell { quantity, .. } | Signal::LimitSell { quantity, .. } => {
+                    score -= weight;
+                    sell_size += quantity * weight;
+                    sell_weight += weight;
+                }
+                // Protective exits are not votes
+                Signal::StopLoss { .. } | Signal::TakeProfit { .. } | Signal::Hold => {}
+            }
+        }
+
+        let cutoff = self.threshold * total_weight;
+        if score > cutoff && buy_weight.is_zero() {
+            Signal::SizedBuy {
+                symbol: symbol.to_string(),
+            }
+        } else if score > cutoff {
+            Signal::Buy {
+                symbol: symbol.to_string(),
+                quantity: buy_size / buy_weight,
+            }
+        } else if score < -cutoff {
+            Signal::Sell {
+                symbol: symbol.to_string(),
+                quantity: sell_size / sell_weight,
+            }
+        } else {
+            Signal::Hold
+        }
+    }
+
+    fn on_each(
+        &mut self,
+        symbol: &str,
+        mut member_signal: impl FnMut(&mut dyn Strategy) -> Signal,
+    ) -> Signal {
+        let signals = self
+            .members
+            .iter_mut()
+            .map(|(strategy, _)| member_signal(strategy.as_mut()))
+            .collect();
+        self.vote(symbol, signals)
+    }
+}
+
+impl Strategy for EnsembleStrategy {
+    fn name(&self) -> &str {
+        "Ensemble Strategy"
+    }
+
+    fn initialize(&mut self, params: HashMap<String, String>) -> Result<(), String> {
+        if let Some(threshold) = params.get("threshold") {
+            self.threshold = threshold.parse().map_err(|_| "Invalid threshold")?;
+        }
+        if self.threshold < Decimal::ZERO || self.threshold >= Decimal::ONE {
+            return Err("Threshold must be at least 0 and below 1".to_string());
+        }
+
+        let spec = params.get("strategies").map_or("sma,rsi", String::as_str);
+        let mut members = Vec::new();
+        for (id, weight) in Self::parse_spec(spec)? {
+            if id == "ensemble" {
+                return Err("Ensembles cannot be nested".to_string());
+            }
+            let prefix = format!("{}.", id);
+            let member_params = params
+                .iter()
+                .filter_map(|(key, value)| {
+                    key.strip_prefix(&prefix)
+                        .map(|key| (key.to_string(), value.clone()))
+                })
+                .collect();
+
+            let mut strategy = create_strategy(&id)?;
+            strategy
+                .initialize(member_params)
+                .map_err(|e| format!("{}: {}", id, e))?;
+            members.push((strategy, weight));
+        }
+        if members.is_empty() {
+            return Err("Ensemble needs at least one strategy".to_string());
+        }
+        self.members = members;
+
+        println!(
+            "Ensemble Strategy initialized: strategies={}, threshold={}",
+            spec, self.threshold
+        );
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        for (strategy, _) in &mut self.members {
+            strategy.reset();
+        }
+    }
+
+    fn on_tick(&mut self, tick: &TickData) -> Signal {
+        self.on_each(&tick.symbol, |strategy| strategy.on_tick(tick))
+    }
+
+    fn on_tick_with_market(&mut self, tick: &TickData, market: Option<&MarketSnapshot>) -> Signal {
+        self.on_each(&tick.symbol, |strategy| {
+            strategy.on_tick_with_market(tick, market)
+        })
+    }
+
+    fn on_tick_logged(&mut self, tick: &TickData, log: &mut StrategyLogger) -> Signal {
+        self.on_each(&tick.symbol, |strategy| strategy.on_tick_logged(tick, log))
+    }
+
+    fn on_ohlc(&mut self, ohlc: &OHLCData) -> Signal {
+        self.on_each(&ohlc.symbol, |strategy| strategy.on_ohlc(ohlc))
+    }
+
+    fn on_ohlc_logged(&mut self, ohlc: &OHLCData, log: &mut StrategyLogger) -> Signal {
+        self.on_each(&ohlc.symbol, |strategy| strategy.on_ohlc_logged(ohlc, log))
+    }
+
+    fn supports_ohlc(&self) -> bool {
+        self.members
+            .iter()
+            .all(|(strategy, _)| strategy.supports_ohlc())
+    }
+
+    fn preferred_timeframe(&self) -> Option<Timeframe> {
+        self.members
+            .iter()
+            .find_map(|(strategy, _)| strategy.preferred_timeframe())
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.members
+            .iter()
+            .map(|(strategy, _)| strategy.warmup_period())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::types::TradeSide;
+    use chrono::Utc;
+
+    /// Member that replays a fixed list of signals
+    struct Scripted {
+        signals: Vec<Signal>,
+        next: usize,
+    }
+
+    impl Strategy for Scripted {
+        fn name(&self) -> &str {
+            "Scripted"
+        }
+
+        fn initialize(&mut self, _params: HashMap<String, String>) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn reset(&mut self) {
+            self.next = 0;
+        }
+
+        fn on_tick(&mut self, _tick: &TickData) -> Signal {
+            let signal = self.signals.get(self.next).cloned().unwrap_or(Signal::Hold);
+            self.next += 1;
+            signal
+        }
+    }
+
+    fn buy(quantity: i64) -> Signal {
+        Signal::Buy {
+            symbol: "BTCUSDT".to_string(),
+            quantity: Decimal::from(quantity),
+        }
+    }
+
+    fn sell(quantity: i64) -> Signal {
+        Signal::Sell {
+            symbol: "BTCUSDT".to_string(),
+            quantity: Decimal::from(quantity),
+        }
+    }
+
+    fn ensemble(members: Vec<(Vec<Signal>, i64)>) -> EnsembleStrategy {
+        EnsembleStrategy {
+            members: members
+                .into_iter()
+                .map(|(signals, weight)| {
+                    let member: Box<dyn Strategy> = Box::new(Scripted { signals, next: 0 });
+                    (member, Decimal::from(weight))
+                })
+                .collect(),
+            ..EnsembleStrategy::new()
+        }
+    }
+
+    fn tick() -> TickData {
+        TickData::new(
+            Utc::now(),
+            "BTCUSDT".to_string(),
+            Decimal::from(100),
+            Decimal::ONE,
+            TradeSide::Buy,
+            "ens".to_string(),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_agreement_trades_and_disagreement_holds() {
+        let mut strategy = ensemble(vec![
+            (vec![buy(10), buy(10), Signal::Hold, sell(5)], 1),
+            (vec![buy(20), sell(20), buy(20), sell(5)], 1),
+        ]);
+
+        let decisions: Vec<Signal> = (0..4).map(|_| strategy.on_tick(&tick())).collect();
+        assert_eq!(
+            decisions,
+            // Both buy: sized at the weighted mean quantity; opposite votes
+            // cancel; one of two is no majority; both sell
+            vec![buy(15), Signal::Hold, Signal::Hold, sell(5)]
+        );
+
+        // Reset rewinds every member
+        strategy.reset();
+        assert_eq!(strategy.on_tick(&tick()), buy(15));
+    }
+
+    #[test]
+    fn test_weights_decide_split_votes() {
+        let mut strategy = ensemble(vec![
+            (vec![buy(10)], 3),
+            (vec![sell(10)], 1),
+            (vec![Signal::Hold], 1),
+        ]);
+        // Net 2 of 5 is below the default majority, but clears a lower threshold
+        assert_eq!(strategy.on_tick(&tick()), Signal::Hold);
+
+        strategy.reset();
+        strategy.threshold = Decimal::new(3, 1);
+        assert_eq!(strategy.on_tick(&tick()), buy(10));
+    }
+
+    #[test]
+    fn test_initialize_builds_members_from_spec() {
+        let mut strategy = EnsembleStrategy::new();
+        let params = HashMap::from([
+            ("strategies".to_string(), "sma:2, rsi".to_string()),
+            ("sma.short_period".to_string(), "3".to_string()),
+            ("sma.long_period".to_string(), "6".to_string()),
+        ]);
+        strategy.initialize(params).unwrap();
+
+        let weights: Vec<(&str, Decimal)> = strategy
+            .members
+            .iter()
+            .map(|(member, weight)| (member.name(), *weight))
+            .collect();
+        assert_eq!(
+            weights,
+            vec![
+                ("Simple Moving Average", Decimal::from(2)),
+                ("RSI Strategy", Decimal::ONE)
+            ]
+        );
+        // RSI's default 14 periods need 15 prices, more than the SMA
+        assert_eq!(strategy.warmup_period(), 15);
+
+        for spec in ["sma:0", "sma:x", "ensemble", "nope", ""] {
+            let params = HashMap::from([("strategies".to_string(), spec.to_string())]);
+            assert!(EnsembleStrategy::new().initialize(params).is_err());
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use super::base::{Signal, Strategy};
+use crate::data::types::{OHLCData, TickData, Timeframe};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Trend-following strategy on MACD / signal line crossovers
+///
+/// The MACD line is the fast EMA of the price minus the slow EMA, and the
+/// signal line is an EMA of the MACD line. Buys when MACD crosses above its
+/// signal line and sells the position when it crosses below. It trades long
+/// only: a bearish crossover while flat, or a bullish one while holding, is
+/// ignored. Each EMA starts at its first input and is updated in O(1).
+pub struct MacdStrategy {
+    fast_period: usize,
+    slow_period: usize,
+    signal_period: usize,
+    fast_ema: Option<Decimal>,
+    slow_ema: Option<Decimal>,
+    /// Prices folded into the price EMAs
+    prices_seen: usize,
+    /// Signal line EMA, started once the slow EMA has `slow_period` prices
+    signal_ema: Option<Decimal>,
+    macd_seen: usize,
+    /// Last MACD minus signal line, once the signal line is warmed up
+    last_histogram: Option<Decimal>,
+    holding: bool,
+}
+
+impl MacdStrategy {
+    pub fn new() -> Self {
+        Self {
+            fast_period: 12,
+            slow_period: 26,
+            signal_period: 9,
+            fast_ema: None,
+            slow_ema: None,
+            prices_seen: 0,
+            signal_ema: None,
+            macd_seen: 0,
+            last_histogram: None,
+            holding: false,
+        }
+    }
+
+    /// Next value of a `period` EMA, started at `value` when there is none yet
+    fn ema(previous: Option<Decimal>, value: Decimal, period: usize) -> Decimal {
+        match previous {
+            Some(previous) => {
+                previous + (value - previous) * Decimal::TWO / Decimal::from(period + 1)
+            }
+            None => value,
+        }
+    }
+
+    /// Fold a close price into the EMAs, returning MACD minus its signal line
+    /// once both are warmed up
+    fn update_histogram(&mut self, price: Decimal) -> Option<Decimal> {
+        let fast = Self::ema(self.fast_ema, price, self.fast_period);
+        let slow = Self::ema(self.slow_ema, price, self.slow_period);
+        self.fast_ema = Some(fast);
+        self.slow_ema = Some(slow);
+        self.prices_seen += 1;
+        if self.prices_seen < self.slow_period {
+            return None;
+        }
+
+        let macd = fast - slow;
+        let signal = Self::ema(self.signal_ema, macd, self.signal_period);
+        self.signal_ema = Some(signal);
+        self.macd_seen += 1;
+        if self.macd_seen < self.signal_period {
+            return None;
+        }
+        Some(macd - signal)
+    }
+
+    fn on_price(&mut self, symbol: &str, price: Decimal) -> Signal {
+        let Some(histogram) = self.update_histogram(price) else {
+            return Signal::Hold;
+        };
+        let Some(last_histogram) = self.last_histogram.replace(histogram) else {
+            return Signal::Hold;
+        };
+
+        if !self.holding && last_histogram <= Decimal::ZERO && histogram > Decimal::ZERO {
+            self.holding = true;
+            Signal::Buy {
+                symbol: symbol.to_string(),
+                quantity: Decimal::from(100),
+            }
+        } else if self.holding && last_histogram >= Decimal::ZERO && histogram < Decimal::ZERO {
+            self.holding = false;
+            Signal::Sell {
+                symbol: symbol.to_string(),
+                quantity: Decimal::from(100),
+            }
+        } else {
+            Signal::Hold
+        }
+    }
+}
+
+impl Strategy for MacdStrategy {
+    fn name(&self) -> &str {
+        "MACD Strategy"
+    }
+
+    fn initialize(&mut self, params: HashMap<String, String>) -> Result<(), String> {
+        if let Some(fast) = params.get("fast_period") {
+            self.fast_period = fast.parse().map_err(|_| "Invalid fast_period")?;
+        }
+        if let Some(slow) = params.get("slow_period") {
+            self.slow_period = slow.parse().map_err(|_| "Invalid slow_period")?;
+        }
+        if let Some(signal) = params.get("signal_period") {
+            self.signal_period = signal.parse().map_err(|_| "Invalid signal_period")?;
+        }
+
+        if self.fast_period == 0 || self.signal_period == 0 {
+            return Err("Periods must be greater than 0".to_string());
+        }
+        if self.fast_period >= self.slow_period {
+            return Err("Fast period must be less than slow period".to_string());
+        }
+
+        println!(
+            "MACD Strategy initialized: fast={}, slow={}, signal={}",
+            self.fast_period, self.slow_period, self.signal_period
+        );
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.fast_ema = None;
+        self.slow_ema = None;
+        self.prices_seen = 0;
+        self.signal_ema = None;
+        self.macd_seen = 0;
+        self.last_histogram = None;
+        self.holding = false;
+    }
+
+    fn on_tick(&mut self, tick: &TickData) -> Signal {
+        self.on_price(&tick.symbol, tick.price)
+    }
+
+    fn on_ohlc(&mut self, ohlc: &OHLCData) -> Signal {
+        self.on_price(&ohlc.symbol, ohlc.close)
+    }
+
+    fn supports_ohlc(&self) -> bool {
+        true
+    }
+    fn preferred_timeframe(&self) -> Option<Timeframe> {
+        Some(Timeframe::OneDay)
+    }
+    fn warmup_period(&self) -> usize {
+        // The first histogram value needs slow + signal - 1 prices, and a
+        // crossover needs one more
+        self.slow_period + self.signal_period
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::strategy::test_support::signal_sequence;
+
+    fn macd_strategy(fast: &str, slow: &str, signal: &str) -> MacdStrategy {
+        let mut strategy = MacdStrategy::new();
+        strategy
+            .initialize(HashMap::from([
+                ("fast_period".to_string(), fast.to_string()),
+                ("slow_period".to_string(), slow.to_string()),
+                ("signal_period".to_string(), signal.to_string()),
+            ]))
+            .unwrap();
+        strategy
+    }
+
+    #[test]
+    fn test_ema_updates() {
+        assert_eq!(
+            MacdStrategy::ema(None, Decimal::from(10), 3),
+            Decimal::from(10)
+        );
+        // alpha = 2 / (3 + 1) = 0.5
+        assert_eq!(
+            MacdStrategy::ema(Some(Decimal::from(10)), Decimal::from(13), 3),
+            Decimal::new(115, 1)
+        );
+    }
+
+    #[test]
+    fn test_signals_on_crossovers() {
+        let mut strategy = macd_strategy("3", "6", "3");
+        // Down 10 steps, up 10 steps, down 10 steps
+        let prices: Vec<i64> = (0..10)
+            .map(|i| 100 - 2 * i)
+            .chain((0..10).map(|i| 82 + 3 * i))
+            .chain((0..10).map(|i| 109 - 3 * i))
+            .collect();
+
+        // No histogram until slow + signal - 1 = 8 prices; the opening
+        // downtrend's bearish reading is ignored while flat
+        assert_eq!(
+            signal_sequence(&mut strategy, &prices),
+            vec![(10, 'B'), (20, 'S')]
+        );
+
+        // Reset clears the EMAs and the held position
+        strategy.reset();
+        assert_eq!(
+            signal_sequence(&mut strategy, &prices),
+            vec![(10, 'B'), (20, 'S')]
+        );
+    }
+
+    #[test]
+    fn test_no_signal_during_warmup() {
+        let mut strategy = macd_strategy("3", "6", "3");
+        let prices: Vec<i64> = (0..strategy.warmup_period() as i64 - 1)
+            .map(|i| if i < 4 { 100 - i } else { 96 + 5 * i })
+            .collect();
+
+        assert!(signal_sequence(&mut strategy, &prices).is_empty());
+        assert!(strategy.last_histogram.is_some());
+    }
+
+    #[test]
+    fn test_invalid_params_rejected() {
+        let params = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        assert!(MacdStrategy::new()
+            .initialize(params(&[("fast_period", "26"), ("slow_period", "12")]))
+            .is_err());
+        assert!(MacdStrategy::new()
+            .initialize(params(&[("signal_period", "0")]))
+            .is_err());
+        assert!(MacdStrategy::new()
+            .initialize(params(&[("fast_period", "x")]))
+            .is_err());
+        assert!(MacdStrategy::new().initialize(HashMap::new()).is_ok());
+    }
+}
@@ -1,8 +1,20 @@
 pub(crate) mod base;
+mod bollinger;
+mod ensemble;
+mod macd;
+mod range_breakout;
+mod rebalance;
 mod rsi;
 mod sma;
+#[cfg(test)]
+mod test_support;
 
 pub use base::{Signal, Strategy};
+use bollinger::BollingerStrategy;
+use ensemble::EnsembleStrategy;
+use macd::MacdStrategy;
+use range_breakout::RangeBreakoutStrategy;
+pub use rebalance::RebalanceStrategy;
 use rsi::RsiStrategy;
 use sma::SmaStrategy;
 
@@ -17,6 +29,11 @@ pub fn create_strategy(strategy_id: &str) -> Result<Box<dyn Strategy>, String> {
     match strategy_id {
         "sma" => Ok(Box::new(SmaStrategy::new())),
         "rsi" => Ok(Box::new(RsiStrategy::new())),
+        "macd" => Ok(Box::new(MacdStrategy::new())),
+        "bollinger" => Ok(Box::new(BollingerStrategy::new())),
+        "range" => Ok(Box::new(RangeBreakoutStrategy::new())),
+        "ensemble" => Ok(Box::new(EnsembleStrategy::new())),
+        "rebalance" => Ok(Box::new(RebalanceStrategy::new())),
         _ => Err(format!("Unknown strategy: {}", strategy_id)),
     }
 }
@@ -34,6 +51,35 @@ pub fn list_strategies() -> Vec<StrategyInfo> {
             name: "RSI Strategy".to_string(),
             description: "Trading strategy based on Relative Strength Index (RSI)".to_string(),
         },
+        StrategyInfo {
+            id: "macd".to_string(),
+            name: "MACD Strategy".to_string(),
+            description: "Trades MACD crossovers of its signal line (default 12/26/9)".to_string(),
+        },
+        StrategyInfo {
+            id: "bollinger".to_string(),
+            name: "Bollinger Bands".to_string(),
+            description:
+                "Buys closes below the lower band and sells above the upper band (default 20, 2.0)"
+                    .to_string(),
+        },
+        StrategyInfo {
+            id: "range".to_string(),
+            name: "Range Breakout".to_string(),
+            description: "Trades breakouts above or below the recent high/low range".to_string(),
+        },
+        StrategyInfo {
+            id: "ensemble".to_string(),
+            name: "Ensemble Strategy".to_string(),
+            description: "Weighted vote of several strategies (default: SMA and RSI)".to_string(),
+        },
+        StrategyInfo {
+            id: "rebalance".to_string(),
+            name: "Rebalance Strategy".to_string(),
+            description:
+                "Holds symbols at target weights, e.g. targets=BTCUSDT:0.6,ETHUSDT:0.4 (multi-symbol runs)"
+                    .to_string(),
+        },
     ]
 }
 
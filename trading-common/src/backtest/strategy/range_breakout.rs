@@ -0,0 +1,155 @@
+use super::base::{Signal, Strategy};
+use crate::data::types::{MarketSnapshot, TickData};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+
+/// Buys when price breaks above the recent high and sells when it breaks
+/// below the recent low.
+///
+/// Live, the recent range comes from the tick cache aggregate; in backtests
+/// the strategy keeps its own window of the last `lookback` ticks.
+pub struct RangeBreakoutStrategy {
+    lookback: usize,
+    window: VecDeque<TickData>,
+    last_signal: Option<Signal>,
+}
+
+impl RangeBreakoutStrategy {
+    pub fn new() -> Self {
+        Self {
+            lookback: 20,
+            window: VecDeque::new(),
+            last_signal: None,
+        }
+    }
+
+    fn remember(&mut self, tick: &TickData) {
+        self.window.push_back(tick.clone());
+        if self.window.len() > self.lookback {
+            self.window.pop_front();
+        }
+    }
+
+    fn decide(&mut self, tick: &TickData, market: &MarketSnapshot) -> Signal {
+        let holding = matches!(self.last_signal, Some(Signal::Buy { .. }));
+
+        let signal = if tick.price > market.high && !holding {
+            Signal::Buy {
+                symbol: tick.symbol.clone(),
+                quantity: Decimal::from(100),
+            }
+        } else if tick.price < market.low && holding {
+            Signal::Sell {
+                symbol: tick.symbol.clone(),
+                quantity: Decimal::from(100),
+            }
+        } else {
+            return Signal::Hold;
+        };
+
+        self.last_signal = Some(signal.clone());
+        signal
+    }
+}
+
+impl Strategy for RangeBreakoutStrategy {
+    fn name(&self) -> &str {
+        "Range Breakout"
+    }
+
+    fn initialize(&mut self, params: HashMap<String, String>) -> Result<(), String> {
+        if let Some(lookback) = params.get("lookback") {
+            self.lookback = lookback.parse().map_err(|_| "Invalid lookback")?;
+        }
+
+        if self.lookback < 2 {
+            return Err("Lookback must be at least 2 ticks".to_string());
+        }
+
+        println!("Range Breakout initialized: lookback={}", self.lookback);
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.last_signal = None;
+    }
+
+    fn on_tick(&mut self, tick: &TickData) -> Signal {
+        let signal = if self.window.len() >= self.lookback {
+            let recent: Vec<TickData> = self.window.iter().cloned().collect();
+            match MarketSnapshot::from_ticks(&recent) {
+                Some(market) => self.decide(tick, &market),
+                None => Signal::Hold,
+            }
+        } else {
+            Signal::Hold
+        };
+
+        self.remember(tick);
+        signal
+    }
+
+    fn on_tick_with_market(&mut self, tick: &TickData, market: Option<&MarketSnapshot>) -> Signal {
+        match market {
+            Some(market) if market.tick_count >= 2 => {
+                self.remember(tick);
+                self.decide(tick, market)
+            }
+            _ => self.on_tick(tick),
+        }
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.lookback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::types::TradeSide;
+    use chrono::{Duration, Utc};
+
+    fn create_tick(seconds: i64, price: i64) -> TickData {
+        TickData::new(
+            Utc::now() + Duration::seconds(seconds),
+            "BTCUSDT".to_string(),
+            Decimal::from(price),
+            Decimal::ONE,
+            TradeSide::Buy,
+            format!("rb{}", seconds),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_breakout_uses_cached_market_range() {
+        let mut strategy = RangeBreakoutStrategy::new();
+        strategy.initialize(HashMap::new()).unwrap();
+
+        // Recent range from the cache: 100..105
+        let cached: Vec<TickData> = [100, 103, 105, 101]
+            .iter()
+            .enumerate()
+            .map(|(i, price)| create_tick(i as i64, *price))
+            .collect();
+        let market = MarketSnapshot::from_ticks(&cached).unwrap();
+
+        // Inside the range: no trade, even though the strategy's own window is empty
+        let signal = strategy.on_tick_with_market(&create_tick(10, 104), Some(&market));
+        assert_eq!(signal, Signal::Hold);
+
+        let signal = strategy.on_tick_with_market(&create_tick(11, 106), Some(&market));
+        assert!(matches!(signal, Signal::Buy { .. }));
+
+        let signal = strategy.on_tick_with_market(&create_tick(12, 99), Some(&market));
+        assert!(matches!(signal, Signal::Sell { .. }));
+
+        // Without cache data the strategy falls back to its own warm-up window
+        let mut cold = RangeBreakoutStrategy::new();
+        cold.initialize(HashMap::new()).unwrap();
+        let signal = cold.on_tick_with_market(&create_tick(13, 200), None);
+        assert_eq!(signal, Signal::Hold);
+    }
+}
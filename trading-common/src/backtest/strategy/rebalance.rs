@@ -0,0 +1,285 @@
+use super::base::{Signal, Strategy};
+use crate::backtest::logger::StrategyLogger;
+use crate::backtest::portfolio::PortfolioSnapshot;
+use crate::backtest::units::{Money, Price};
+use crate::data::types::TickData;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Holds several symbols at target weights of the portfolio value
+///
+/// On every bar where each target symbol has a price, a symbol whose weight
+/// has drifted more than `threshold` from its target is bought or sold back
+/// to the target; symbols within the band are left alone. Sells come before
+/// buys so their proceeds can fund the buys. Weights left below 1 stay in
+/// cash.
+///
+/// Parameters: `targets` is a spec like `BTCUSDT:0.6,ETHUSDT:0.4` and
+/// `threshold` the allowed drift in weight (default 0.05). The strategy
+/// needs the portfolio, so run it with `BacktestEngine::run_multi`; on its
+/// own `on_tick` only holds.
+pub struct RebalanceStrategy {
+    /// Target weight by symbol, sorted by symbol
+    targets: Vec<(String, Decimal)>,
+    threshold: Decimal,
+    prices: HashMap<String, Decimal>,
+}
+
+impl RebalanceStrategy {
+    pub fn new() -> Self {
+        Self {
+            targets: Vec::new(),
+            threshold: Decimal::new(5, 2),
+            prices: HashMap::new(),
+        }
+    }
+
+    /// Parse `symbol:weight` entries separated by commas
+    fn parse_targets(spec: &str) -> Result<Vec<(String, Decimal)>, String> {
+        let mut targets = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (symbol, weight) = entry
+                    .split_once(':')
+                    .ok_or_else(|| format!("Missing weight for {}", entry))?;
+                let weight = weight
+                    .trim()
+                    .parse::<Decimal>()
+                    .map_err(|_| format!("Invalid weight for {}", symbol.trim()))?;
+                if weight < Decimal::ZERO {
+                    return Err(format!("Weight for {} must not be negative", symbol.trim()));
+                }
+                Ok((symbol.trim().to_string(), weight))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        targets.sort();
+        Ok(targets)
+    }
+
+    /// Orders moving each drifted symbol back to its target weight
+    fn rebalance(&self, portfolio: &PortfolioSnapshot) -> Vec<Signal> {
+        if portfolio.total_value <= Money::ZERO {
+            return Vec::new();
+        }
+
+        let mut sells = Vec::new();
+        let mut buys = Vec::new();
+        for (symbol, target) in &self.targets {
+            let Some(&price) = self.prices.get(symbol) else {
+                // Cannot value the allocation until every symbol has traded
+                return Vec::new();
+            };
+            let held_value = portfolio
+                .positions
+                .get(symbol)
+                .map_or(Money::ZERO, |position| position.market_value);
+            // Value above (positive) or below the target allocation
+            let excess = held_value - portfolio.total_value * *target;
+            if (excess / portfolio.total_value).abs() <= self.threshold || price <= Decimal::ZERO {
+                continue;
+            }
+
+            let quantity = (excess.abs() / Price::new(price))
+                .value()
+                .trunc_with_scale(8);
+            if quantity <= Decimal::ZERO {
+                continue;
+            }
+            let symbol = symbol.clone();
+            if excess > Money::ZERO {
+                sells.push(Signal::Sell { symbol, quantity });
+            } else {
+                buys.push(Signal::Buy { symbol, quantity });
+            }
+        }
+
+        sells.extend(buys);
+        sells
+    }
+}
+
+impl Default for RebalanceStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for RebalanceStrategy {
+    fn name(&self) -> &str {
+        "Rebalance Strategy"
+    }
+
+    fn initialize(&mut self, params: HashMap<String, String>) -> Result<(), String> {
+        if let Some(threshold) = params.get("threshold") {
+            self.threshold = threshold.parse().map_err(|_| "Invalid threshold")?;
+        }
+        if self.threshold < Decimal::ZERO || self.threshold >= Decimal::ONE {
+            return Err("Threshold must be at least 0 and below 1".to_string());
+        }
+
+        let spec = params
+            .get("targets")
+            .ok_or("targets is required, e.g. BTCUSDT:0.6,ETHUSDT:0.4")?;
+        self.targets = Self::parse_targets(spec)?;
+        if self.targets.is_empty() {
+            return Err("targets must name at least one symbol".to_string());
+        }
+        let total_weight: Decimal = self.targets.iter().map(|(_, weight)| weight).sum();
+        if total_weight > Decimal::ONE {
+            return Err(format!("Target weights sum to {}, above 1", total_weight));
+        }
+
+        println!(
+            "Rebalance Strategy initialized: targets={}, threshold={}",
+            spec, self.threshold
+        );
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.prices.clear();
+    }
+
+    fn on_tick(&mut self, tick: &TickData) -> Signal {
+        self.prices.insert(tick.symbol.clone(), tick.price);
+        Signal::Hold
+    }
+
+    fn on_tick_multi(
+        &mut self,
+        ticks: &[TickData],
+        portfolio: &PortfolioSnapshot,
+        _log: &mut StrategyLogger,
+    ) -> Vec<Signal> {
+        for tick in ticks {
+            self.prices.insert(tick.symbol.clone(), tick.price);
+        }
+        self.rebalance(portfolio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::engine::{BacktestConfig, BacktestEngine};
+    use crate::data::types::TradeSide;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn rebalance_strategy() -> RebalanceStrategy {
+        let mut strategy = RebalanceStrategy::new();
+        strategy
+            .initialize(params(&[
+                ("targets", "BTCUSDT:0.6,ETHUSDT:0.4"),
+                ("threshold", "0.05"),
+            ]))
+            .unwrap();
+        strategy
+    }
+
+    /// One bar per entry with a BTC and an ETH tick
+    fn create_bars(prices: &[(i64, i64)]) -> Vec<TickData> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        prices
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (btc, eth))| {
+                let timestamp = start + Duration::minutes(i as i64);
+                [("BTCUSDT", btc), ("ETHUSDT", eth)].map(|(symbol, price)| {
+                    TickData::new(
+                        timestamp,
+                        symbol.to_string(),
+                        Decimal::from(*price),
+                        Decimal::ONE,
+                        TradeSide::Buy,
+                        format!("{}{}", symbol, i),
+                        false,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn run(prices: &[(i64, i64)]) -> crate::backtest::BacktestResult {
+        let config = BacktestConfig::new(Decimal::from(10000))
+            .with_commission_rate(Decimal::ZERO)
+            .with_param("targets", "BTCUSDT:0.6,ETHUSDT:0.4")
+            .with_param("threshold", "0.05");
+        let mut engine = BacktestEngine::new(Box::new(RebalanceStrategy::new()), config).unwrap();
+        engine.run_multi(&create_bars(prices))
+    }
+
+    #[test]
+    fn test_initial_allocation_hits_targets() {
+        let result = run(&[(100, 50)]);
+
+        // 6000 of BTC at 100 and 4000 of ETH at 50
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(
+            result.positions["BTCUSDT"].quantity.value(),
+            Decimal::from(60)
+        );
+        assert_eq!(
+            result.positions["ETHUSDT"].quantity.value(),
+            Decimal::from(80)
+        );
+    }
+
+    #[test]
+    fn test_rebalances_only_past_threshold() {
+        // BTC +10%: 6600 / 10600 = 62.3% weight, a 2.3 point drift
+        let within_band = run(&[(100, 50), (110, 50)]);
+        assert_eq!(within_band.trades.len(), 2);
+
+        // BTC +50%: 9000 / 13000 = 69.2% weight, a 9.2 point drift
+        let drifted = run(&[(100, 50), (150, 50)]);
+        assert_eq!(drifted.trades.len(), 4);
+        let sell = &drifted.trades[2];
+        assert_eq!(sell.symbol, "BTCUSDT");
+        assert_eq!(sell.side, TradeSide::Sell);
+        let buy = &drifted.trades[3];
+        assert_eq!(buy.symbol, "ETHUSDT");
+        assert_eq!(buy.side, TradeSide::Buy);
+
+        // Back at 60/40 of the 13000 portfolio
+        let btc_value = drifted.positions["BTCUSDT"].quantity * Price::new(Decimal::from(150));
+        assert_eq!(btc_value, Money::new(Decimal::from(7800)));
+        let eth_value = drifted.positions["ETHUSDT"].quantity * Price::new(Decimal::from(50));
+        assert_eq!(eth_value, Money::new(Decimal::from(5200)));
+    }
+
+    #[test]
+    fn test_waits_for_every_symbol_price() {
+        let mut strategy = rebalance_strategy();
+        let portfolio = PortfolioSnapshot {
+            cash: Money::new(Decimal::from(10000)),
+            positions: HashMap::new(),
+            total_value: Money::new(Decimal::from(10000)),
+        };
+        let btc_only = &create_bars(&[(100, 50)])[..1];
+
+        assert!(strategy
+            .on_tick_multi(btc_only, &portfolio, &mut StrategyLogger::new())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_invalid_params_rejected() {
+        let init = |pairs: &[(&str, &str)]| RebalanceStrategy::new().initialize(params(pairs));
+
+        assert!(init(&[]).is_err());
+        assert!(init(&[("targets", "BTCUSDT")]).is_err());
+        assert!(init(&[("targets", "BTCUSDT:0.7,ETHUSDT:0.4")]).is_err());
+        assert!(init(&[("targets", "BTCUSDT:-0.1")]).is_err());
+        assert!(init(&[("targets", "BTCUSDT:1"), ("threshold", "1")]).is_err());
+        assert!(init(&[("targets", "BTCUSDT:1")]).is_ok());
+    }
+}
@@ -1,15 +1,26 @@
 use super::base::{Signal, Strategy};
 use crate::data::types::{OHLCData, TickData, Timeframe};
 use rust_decimal::Decimal;
-use std::collections::{HashMap, VecDeque};
-
+use std::collections::HashMap;
+
+/// Mean-reversion strategy on Wilder's RSI
+///
+/// Buys when RSI crosses up through the oversold level and sells the
+/// position when RSI crosses down through the overbought level. It trades
+/// long only: a sell crossing while flat, or a buy crossing while holding,
+/// is ignored.
 pub struct RsiStrategy {
     period: usize,
     oversold: Decimal,
     overbought: Decimal,
-    prices: VecDeque<Decimal>,
-    gains: VecDeque<Decimal>,
-    losses: VecDeque<Decimal>,
+    last_price: Option<Decimal>,
+    /// Sums of the first `period` gains and losses, before smoothing starts
+    seed_gain: Decimal,
+    seed_loss: Decimal,
+    changes: usize,
+    /// Wilder-smoothed average gain and loss, once seeded
+    averages: Option<(Decimal, Decimal)>,
+    last_rsi: Option<Decimal>,
     last_signal: Option<Signal>,
 }
 
@@ -19,29 +30,75 @@ impl RsiStrategy {
             period: 14,
             oversold: Decimal::from(30),
             overbought: Decimal::from(70),
-            prices: VecDeque::new(),
-            gains: VecDeque::new(),
-            losses: VecDeque::new(),
+            last_price: None,
+            seed_gain: Decimal::ZERO,
+            seed_loss: Decimal::ZERO,
+            changes: 0,
+            averages: None,
+            last_rsi: None,
             last_signal: None,
         }
     }
 
-    fn calculate_rsi(&self) -> Option<Decimal> {
-        if self.gains.len() < self.period || self.losses.len() < self.period {
-            return None;
-        }
-
-        let avg_gain: Decimal = self.gains.iter().sum::<Decimal>() / Decimal::from(self.period);
-        let avg_loss: Decimal = self.losses.iter().sum::<Decimal>() / Decimal::from(self.period);
+    /// Fold a close price into the averages, returning the RSI once `period`
+    /// price changes have been seen
+    fn update_rsi(&mut self, price: Decimal) -> Option<Decimal> {
+        let last_price = self.last_price.replace(price)?;
+        let change = price - last_price;
+        let gain = change.max(Decimal::ZERO);
+        let loss = (-change).max(Decimal::ZERO);
+        let period = Decimal::from(self.period);
+
+        let (avg_gain, avg_loss) = match self.averages {
+            // Wilder smoothing: avg = (prev * (period - 1) + current) / period
+            Some((avg_gain, avg_loss)) => (
+                (avg_gain * (period - Decimal::ONE) + gain) / period,
+                (avg_loss * (period - Decimal::ONE) + loss) / period,
+            ),
+            None => {
+                self.seed_gain += gain;
+                self.seed_loss += loss;
+                self.changes += 1;
+                if self.changes < self.period {
+                    return None;
+                }
+                (self.seed_gain / period, self.seed_loss / period)
+            }
+        };
+        self.averages = Some((avg_gain, avg_loss));
 
         if avg_loss == Decimal::ZERO {
             return Some(Decimal::from(100));
         }
-
         let rs = avg_gain / avg_loss;
-        let rsi = Decimal::from(100) - (Decimal::from(100) / (Decimal::from(1) + rs));
+        Some(Decimal::from(100) - Decimal::from(100) / (Decimal::ONE + rs))
+    }
 
-        Some(rsi)
+    fn on_price(&mut self, symbol: &str, price: Decimal) -> Signal {
+        let Some(rsi) = self.update_rsi(price) else {
+            return Signal::Hold;
+        };
+        let Some(last_rsi) = self.last_rsi.replace(rsi) else {
+            return Signal::Hold;
+        };
+
+        let holding = matches!(self.last_signal, Some(Signal::Buy { .. }));
+        let signal = if !holding && last_rsi <= self.oversold && rsi > self.oversold {
+            Signal::Buy {
+                symbol: symbol.to_string(),
+                quantity: Decimal::from(100),
+            }
+        } else if holding && last_rsi >= self.overbought && rsi < self.overbought {
+            Signal::Sell {
+                symbol: symbol.to_string(),
+                quantity: Decimal::from(100),
+            }
+        } else {
+            return Signal::Hold;
+        };
+
+        self.last_signal = Some(signal.clone());
+        signal
     }
 }
 
@@ -61,6 +118,9 @@ impl Strategy for RsiStrategy {
             self.overbought = overbought.parse().map_err(|_| "Invalid overbought")?;
         }
 
+        if self.period == 0 {
+            return Err("Period must be greater than 0".to_string());
+        }
         if self.oversold >= self.overbought {
             return Err("Oversold level must be less than overbought level".to_string());
         }
@@ -73,110 +133,114 @@ impl Strategy for RsiStrategy {
     }
 
     fn reset(&mut self) {
-        self.prices.clear();
-        self.gains.clear();
-        self.losses.clear();
+        self.last_price = None;
+        self.seed_gain = Decimal::ZERO;
+        self.seed_loss = Decimal::ZERO;
+        self.changes = 0;
+        self.averages = None;
+        self.last_rsi = None;
         self.last_signal = None;
     }
 
     fn on_tick(&mut self, tick: &TickData) -> Signal {
-        if let Some(last_price) = self.prices.back() {
-            let change = tick.price - last_price;
-
-            if change > Decimal::ZERO {
-                self.gains.push_back(change);
-                self.losses.push_back(Decimal::ZERO);
-            } else {
-                self.gains.push_back(Decimal::ZERO);
-                self.losses.push_back(-change);
-            }
-
-            // Keep fixed length
-            if self.gains.len() > self.period {
-                self.gains.pop_front();
-                self.losses.pop_front();
-            }
-        }
+        self.on_price(&tick.symbol, tick.price)
+    }
 
-        self.prices.push_back(tick.price);
-        if self.prices.len() > self.period + 1 {
-            self.prices.pop_front();
-        }
+    fn on_ohlc(&mut self, ohlc: &OHLCData) -> Signal {
+        self.on_price(&ohlc.symbol, ohlc.close)
+    }
 
-        if let Some(rsi) = self.calculate_rsi() {
-            // Buy signal when RSI is oversold and we don't have a buy position
-            if rsi < self.oversold && !matches!(self.last_signal, Some(Signal::Buy { .. })) {
-                let signal = Signal::Buy {
-                    symbol: tick.symbol.clone(),
-                    quantity: Decimal::from(100),
-                };
-                self.last_signal = Some(signal.clone());
-                return signal;
-            }
-            // Sell signal when RSI is overbought and we have a buy position
-            else if rsi > self.overbought && matches!(self.last_signal, Some(Signal::Buy { .. }))
-            {
-                let signal = Signal::Sell {
-                    symbol: tick.symbol.clone(),
-                    quantity: Decimal::from(100),
-                };
-                self.last_signal = Some(signal.clone());
-                return signal;
-            }
-        }
+    fn supports_ohlc(&self) -> bool {
+        true
+    }
+    fn preferred_timeframe(&self) -> Option<Timeframe> {
+        Some(Timeframe::OneDay)
+    }
+    fn warmup_period(&self) -> usize {
+        // One extra price is needed to produce `period` price changes
+        self.period + 1
+    }
+}
 
-        Signal::Hold
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::strategy::test_support::signal_sequence;
+
+    fn rsi_strategy(period: &str) -> RsiStrategy {
+        let mut strategy = RsiStrategy::new();
+        strategy
+            .initialize(HashMap::from([("period".to_string(), period.to_string())]))
+            .unwrap();
+        strategy
     }
 
-    fn on_ohlc(&mut self, ohlc: &OHLCData) -> Signal {
-        if let Some(last_price) = self.prices.back() {
-            let change = ohlc.close - last_price;
-
-            if change > Decimal::ZERO {
-                self.gains.push_back(change);
-                self.losses.push_back(Decimal::ZERO);
-            } else {
-                self.gains.push_back(Decimal::ZERO);
-                self.losses.push_back(-change);
-            }
+    #[test]
+    fn test_wilder_rsi_values() {
+        let mut strategy = rsi_strategy("2");
+
+        // Seeded from the first two changes: +2, -1 -> 2 / (2 + 1)
+        assert_eq!(strategy.update_rsi(Decimal::from(10)), None);
+        assert_eq!(strategy.update_rsi(Decimal::from(12)), None);
+        let seeded = strategy.update_rsi(Decimal::from(11)).unwrap();
+        assert_eq!(seeded.round_dp(4), Decimal::new(666667, 4));
+
+        // Then smoothed: gain (1 + 2) / 2 = 1.5, loss (0.5 + 0) / 2 = 0.25
+        let smoothed = strategy.update_rsi(Decimal::from(13)).unwrap();
+        assert_eq!(
+            smoothed,
+            Decimal::from(100) - Decimal::from(100) / Decimal::from(7)
+        );
+    }
 
-            if self.gains.len() > self.period {
-                self.gains.pop_front();
-                self.losses.pop_front();
-            }
-        }
+    #[test]
+    fn test_signals_on_threshold_crossings() {
+        let mut strategy = rsi_strategy("2");
+        let prices = [
+            100, 90, 80, // RSI 0: oversold, but no crossing yet
+            85, // crosses up through 30: buy
+            80, 90, 100, // rises above 70
+            95,  // crosses down through 70: sell
+            90, 80, 70, 80, // oversold again, then crosses up: buy
+        ];
+
+        assert_eq!(
+            signal_sequence(&mut strategy, &prices),
+            vec![(3, 'B'), (7, 'S'), (11, 'B')]
+        );
 
-        self.prices.push_back(ohlc.close);
-        if self.prices.len() > self.period + 1 {
-            self.prices.pop_front();
-        }
+        // Reset clears the averages and the held position
+        strategy.reset();
+        assert_eq!(
+            signal_sequence(&mut strategy, &prices),
+            vec![(3, 'B'), (7, 'S'), (11, 'B')]
+        );
+    }
 
-        if let Some(rsi) = self.calculate_rsi() {
-            if rsi < self.oversold && !matches!(self.last_signal, Some(Signal::Buy { .. })) {
-                let signal = Signal::Buy {
-                    symbol: ohlc.symbol.clone(),
-                    quantity: Decimal::from(100),
-                };
-                self.last_signal = Some(signal.clone());
-                return signal;
-            } else if rsi > self.overbought && matches!(self.last_signal, Some(Signal::Buy { .. }))
-            {
-                let signal = Signal::Sell {
-                    symbol: ohlc.symbol.clone(),
-                    quantity: Decimal::from(100),
-                };
-                self.last_signal = Some(signal.clone());
-                return signal;
-            }
-        }
+    #[test]
+    fn test_crossings_follow_position() {
+        let mut strategy = rsi_strategy("2");
+        // Overbought crossing while flat is ignored; a second oversold
+        // crossing while holding is ignored too
+        let prices = [100, 110, 120, 115, 100, 90, 95, 85, 80, 85];
 
-        Signal::Hold
+        assert_eq!(signal_sequence(&mut strategy, &prices), vec![(6, 'B')]);
     }
 
-    fn supports_ohlc(&self) -> bool {
-        true
-    }
-    fn preferred_timeframe(&self) -> Option<Timeframe> {
-        Some(Timeframe::OneDay)
+    #[test]
+    fn test_invalid_params_rejected() {
+        let mut strategy = RsiStrategy::new();
+        let params = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        assert!(strategy.initialize(params(&[("period", "0")])).is_err());
+        let mut strategy = RsiStrategy::new();
+        assert!(strategy
+            .initialize(params(&[("oversold", "70"), ("overbought", "30")]))
+            .is_err());
     }
 }
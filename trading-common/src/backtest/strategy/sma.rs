@@ -130,4 +130,7 @@ impl Strategy for SmaStrategy {
     fn preferred_timeframe(&self) -> Option<crate::data::types::Timeframe> {
         Some(crate::data::types::Timeframe::OneMinute)
     }
+    fn warmup_period(&self) -> usize {
+        self.long_period
+    }
 }
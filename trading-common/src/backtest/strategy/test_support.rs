@@ -0,0 +1,34 @@
+// Shared fixtures for strategy unit tests
+
+use super::base::{Signal, Strategy};
+use crate::data::types::{TickData, TradeSide};
+use chrono::{Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+
+/// BTCUSDT tick `index` seconds after a fixed start time
+pub fn create_tick(index: i64, price: i64) -> TickData {
+    TickData::new(
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::seconds(index),
+        "BTCUSDT".to_string(),
+        Decimal::from(price),
+        Decimal::ONE,
+        TradeSide::Buy,
+        format!("tick{}", index),
+        false,
+    )
+}
+
+/// Feeds `prices` as ticks and returns the index and side of every buy or sell
+pub fn signal_sequence(strategy: &mut dyn Strategy, prices: &[i64]) -> Vec<(usize, char)> {
+    prices
+        .iter()
+        .enumerate()
+        .filter_map(
+            |(i, price)| match strategy.on_tick(&create_tick(i as i64, *price)) {
+                Signal::Buy { .. } => Some((i, 'B')),
+                Signal::Sell { .. } => Some((i, 'S')),
+                _ => None,
+            },
+        )
+        .collect()
+}
@@ -0,0 +1,155 @@
+//! Parameter sweeps: one strategy run over many parameter sets in parallel.
+//!
+//! Every run reads the same `Arc<[TickData]>`, so memory use does not grow
+//! with the number of combinations.
+
+use crate::backtest::engine::{BacktestConfig, BacktestEngine, BacktestResult};
+use crate::backtest::strategy::create_strategy;
+use crate::data::types::TickData;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Outcome of one parameter combination
+#[derive(Debug)]
+pub struct SweepRun {
+    pub params: HashMap<String, String>,
+    /// Err when the strategy rejects the parameters
+    pub result: Result<BacktestResult, String>,
+}
+
+/// Cartesian product of parameter values, e.g.
+/// `[("short_period", &["5", "10"]), ("long_period", &["20", "50"])]` gives four sets
+pub fn param_grid(axes: &[(&str, &[&str])]) -> Vec<HashMap<String, String>> {
+    let mut grid = vec![HashMap::new()];
+    for (name, values) in axes {
+        grid = grid
+            .into_iter()
+            .flat_map(|params| {
+                values.iter().map(move |value| {
+                    let mut params = params.clone();
+                    params.insert(name.to_string(), value.to_string());
+                    params
+                })
+            })
+            .collect();
+    }
+    grid
+}
+
+/// Backtest `strategy_id` once per parameter set on up to `threads` threads
+///
+/// Parameter sets are layered over `base.strategy_params`. Results come back
+/// in the order of `param_sets`.
+pub fn run_sweep(
+    strategy_id: &str,
+    base: &BacktestConfig,
+    param_sets: Vec<HashMap<String, String>>,
+    data: Arc<[TickData]>,
+    threads: usize,
+) -> Vec<SweepRun> {
+    let total = param_sets.len();
+    let queue = Mutex::new(param_sets.into_iter().enumerate());
+    let finished = Mutex::new(Vec::with_capacity(total));
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, total.max(1)) {
+            let data = Arc::clone(&data);
+            let (queue, finished) = (&queue, &finished);
+            scope.spawn(move || loop {
+                let next = queue.lock().unwrap_or_else(|e| e.into_inner()).next();
+                let Some((index, params)) = next else {
+                    break;
+                };
+                let result = run_one(strategy_id, base, &params, &data);
+                finished
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push((index, SweepRun { params, result }));
+            });
+        }
+    });
+
+    let mut runs = finished.into_inner().unwrap_or_else(|e| e.into_inner());
+    runs.sort_by_key(|(index, _)| *index);
+    runs.into_iter().map(|(_, run)| run).collect()
+}
+
+fn run_one(
+    strategy_id: &str,
+    base: &BacktestConfig,
+    params: &HashMap<String, String>,
+    data: &[TickData],
+) -> Result<BacktestResult, String> {
+    let mut config = base.clone();
+    config
+        .strategy_params
+        .extend(params.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    let strategy = create_strategy(strategy_id)?;
+    let mut engine = BacktestEngine::new(strategy, config)?;
+    Ok(engine.run(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::types::TradeSide;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal::Decimal;
+
+    fn create_ticks() -> Vec<TickData> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..120)
+            .map(|i| {
+                // Two swings so crossovers happen for several period choices
+                let wave = [0, 3, 6, 9, 12, 9, 6, 3, 0, -3, -6, -9, -12, -9, -6, -3];
+                let price = 100 + wave[(i / 4) % wave.len()];
+                TickData::new(
+                    start + Duration::seconds(i as i64),
+                    "BTCUSDT".to_string(),
+                    Decimal::from(price),
+                    Decimal::ONE,
+                    TradeSide::Buy,
+                    i.to_string(),
+                    false,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parallel_sweep_matches_sequential_runs() {
+        let ticks = create_ticks();
+        let data: Arc<[TickData]> = Arc::from(ticks.clone());
+        let base = BacktestConfig::new(Decimal::from(10000));
+        let grid = param_grid(&[
+            ("short_period", &["2", "3", "30"]),
+            ("long_period", &["5", "8"]),
+        ]);
+        assert_eq!(grid.len(), 6);
+
+        let runs = run_sweep("sma", &base, grid.clone(), Arc::clone(&data), 4);
+
+        // Every thread released its handle; the ticks were never copied
+        assert_eq!(Arc::strong_count(&data), 1);
+        assert_eq!(runs.len(), grid.len());
+
+        for (run, params) in runs.iter().zip(&grid) {
+            assert_eq!(&run.params, params);
+            let expected = run_one("sma", &base, params, &ticks);
+            match (&run.result, expected) {
+                (Ok(result), Ok(expected)) => {
+                    assert_eq!(result.final_value, expected.final_value);
+                    assert_eq!(result.total_trades, expected.total_trades);
+                }
+                // short_period 30 is not below long_period
+                (Err(_), Err(_)) => assert_eq!(params["short_period"], "30"),
+                _ => panic!("sweep and sequential run disagree for {:?}", params),
+            }
+        }
+        assert!(runs
+            .iter()
+            .any(|run| run.result.as_ref().is_ok_and(|r| r.total_trades > 0)));
+    }
+}
@@ -0,0 +1,233 @@
+//! Unit-typed decimals for prices, quantities and money.
+//!
+//! Each wraps a `Decimal` and only supports arithmetic whose result has a
+//! meaningful unit: values of one unit add and subtract, a price times a
+//! quantity is money, and money splits back into a price or a quantity.
+//!
+//! ```
+//! use rust_decimal::Decimal;
+//! use trading_common::backtest::units::{Money, Price, Quantity};
+//!
+//! let price = Price::new(Decimal::from(100));
+//! let quantity = Quantity::new(Decimal::from(3));
+//! let cost: Money = price * quantity;
+//! assert_eq!(cost, Money::new(Decimal::from(300)));
+//! assert_eq!(cost / quantity, price);
+//! assert_eq!(cost - cost, Money::ZERO);
+//! ```
+//!
+//! Mixing units does not compile:
+//!
+//! ```compile_fail
+//! use trading_common::backtest::units::{Money, Price, Quantity};
+//!
+//! let _ = Price::ZERO * Price::ZERO;
+//! ```
+//!
+//! ```compile_fail
+//! use trading_common::backtest::units::{Money, Price, Quantity};
+//!
+//! let _ = Money::ZERO + Quantity::ZERO;
+//! ```
+//!
+//! ```compile_fail
+//! use trading_common::backtest::units::{Money, Price, Quantity};
+//!
+//! let _: Money = Quantity::ZERO * Quantity::ZERO;
+//! ```
+//!
+//! Conversions to and from `Decimal` are explicit, so bare decimals only
+//! cross at API boundaries such as the `Portfolio` order methods.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+macro_rules! unit {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        #[derive(
+            Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(Decimal);
+
+        impl $name {
+            pub const ZERO: Self = Self(Decimal::ZERO);
+
+            pub fn new(value: Decimal) -> Self {
+                Self(value)
+            }
+
+            pub fn value(self) -> Decimal {
+                self.0
+            }
+
+            pub fn abs(self) -> Self {
+                Self(self.0.abs())
+            }
+        }
+
+        impl From<Decimal> for $name {
+            fn from(value: Decimal) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for Decimal {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                Self(iter.map(|value| value.0).sum())
+            }
+        }
+
+        /// Scaling by a plain factor keeps the unit
+        impl Mul<Decimal> for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: Decimal) -> Self {
+                Self(self.0 * rhs)
+            }
+        }
+
+        impl Div<Decimal> for $name {
+            type Output = Self;
+
+            fn div(self, rhs: Decimal) -> Self {
+                Self(self.0 / rhs)
+            }
+        }
+
+        /// Dividing two values of the same unit gives a plain ratio
+        impl Div for $name {
+            type Output = Decimal;
+
+            fn div(self, rhs: Self) -> Decimal {
+                self.0 / rhs.0
+            }
+        }
+    };
+}
+
+unit!(
+    /// Price of one unit of an asset in the quote currency
+    Price
+);
+unit!(
+    /// Amount of an asset (or contracts) traded or held
+    Quantity
+);
+unit!(
+    /// Cash, cost, commission or PnL in the settlement currency
+    Money
+);
+
+impl Mul<Quantity> for Price {
+    type Output = Money;
+
+    fn mul(self, rhs: Quantity) -> Money {
+        Money(self.0 * rhs.0)
+    }
+}
+
+impl Mul<Price> for Quantity {
+    type Output = Money;
+
+    fn mul(self, rhs: Price) -> Money {
+        Money(self.0 * rhs.0)
+    }
+}
+
+impl Div<Quantity> for Money {
+    type Output = Price;
+
+    fn div(self, rhs: Quantity) -> Price {
+        Price(self.0 / rhs.0)
+    }
+}
+
+impl Div<Price> for Money {
+    type Output = Quantity;
+
+    fn div(self, rhs: Price) -> Quantity {
+        Quantity(self.0 / rhs.0)
+    }
+}
+
+impl Money {
+    /// The `part / whole` share of this amount, multiplied out before
+    /// dividing, e.g. the commission on part of a fill
+    pub fn share(self, part: Quantity, whole: Quantity) -> Money {
+        Money(self.0 * part.0 / whole.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units_serialize_as_decimals() {
+        let price = Price::new(Decimal::new(10050, 2));
+        assert_eq!(serde_json::to_string(&price).unwrap(), r#""100.50""#);
+        assert_eq!(serde_json::from_str::<Price>(r#""100.50""#).unwrap(), price);
+        assert_eq!(format!("{:.1}", price), "100.5");
+
+        let commission = Money::new(Decimal::from(3));
+        let fill = Quantity::new(Decimal::from(4));
+        assert_eq!(
+            commission.share(Quantity::new(Decimal::ONE), fill),
+            Money::new(Decimal::new(75, 2))
+        );
+        assert_eq!(fill / fill, Decimal::ONE);
+    }
+}
@@ -1,10 +1,14 @@
 use async_trait::async_trait;
-use redis::{Client as RedisClient, Commands, Connection};
+use redis::{
+    aio::MultiplexedConnection, AsyncCommands, Client as RedisClient, Commands, Connection,
+};
+use rust_decimal::Decimal;
 use serde_json;
 use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex};
 use tracing::{debug, error, warn};
 
 use super::types::{DataError, DataResult, TickData};
@@ -30,6 +34,13 @@ pub trait TickDataCache: Send + Sync {
 
     /// Clear all cache
     async fn clear_all(&self) -> DataResult<()>;
+
+    /// Simple moving average of the last `period` cached prices, without
+    /// rescanning them. None until `period` ticks are cached, or when the
+    /// cache keeps no rolling aggregates.
+    fn get_rolling_sma(&self, _symbol: &str, _period: usize) -> Option<Decimal> {
+        None
+    }
 }
 
 // =================================================================
@@ -40,6 +51,10 @@ pub trait TickDataCache: Send + Sync {
 #[derive(Debug, Clone)]
 struct MemoryCacheEntry {
     ticks: VecDeque<TickData>,
+    /// Running sum of every price pushed so far, as of each cached tick
+    price_sums: VecDeque<Decimal>,
+    /// Running sum as of the last evicted tick
+    evicted_sum: Decimal,
     last_access: Instant,
     last_update: Instant,
 }
@@ -49,19 +64,41 @@ impl MemoryCacheEntry {
         let now = Instant::now();
         Self {
             ticks: VecDeque::new(),
+            price_sums: VecDeque::new(),
+            evicted_sum: Decimal::ZERO,
             last_access: now,
             last_update: now,
         }
     }
 
     fn push_tick(&mut self, tick: TickData, max_size: usize) {
+        let running_sum = self.price_sums.back().copied().unwrap_or(self.evicted_sum);
+        self.price_sums.push_back(running_sum + tick.price);
         self.ticks.push_back(tick);
         self.last_update = Instant::now();
 
         // Maintain size limit
         while self.ticks.len() > max_size {
             self.ticks.pop_front();
+            if let Some(sum) = self.price_sums.pop_front() {
+                self.evicted_sum = sum;
+            }
+        }
+    }
+
+    /// Mean of the last `period` prices from the difference of two running sums
+    fn rolling_sma(&self, period: usize) -> Option<Decimal> {
+        let len = self.price_sums.len();
+        if period == 0 || period > len {
+            return None;
         }
+
+        let latest = self.price_sums[len - 1];
+        let before_window = match len - period {
+            0 => self.evicted_sum,
+            start => self.price_sums[start - 1],
+        };
+        Some((latest - before_window) / Decimal::from(period))
     }
 
     fn get_recent(&mut self, limit: usize) -> Vec<TickData> {
@@ -96,6 +133,10 @@ impl InMemoryTickCache {
         }
     }
 
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
     /// Clean up expired cache entries
     pub fn cleanup_expired(&self) {
         if let Ok(mut data) = self.data.write() {
@@ -187,12 +228,67 @@ impl TickDataCache for InMemoryTickCache {
             Err(e) => Err(DataError::Cache(format!("Lock error: {}", e))),
         }
     }
+
+    fn get_rolling_sma(&self, symbol: &str, period: usize) -> Option<Decimal> {
+        let data = self.data.read().ok()?;
+        data.get(symbol)?.rolling_sma(period)
+    }
 }
 
 // =================================================================
 // Redis Cache Implementation
 // =================================================================
 
+/// Live ticks queued for publishing before new ones are dropped
+const PUBLISH_QUEUE_CAPACITY: usize = 10_000;
+/// Longest a single PUBLISH may take before it counts as failed
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Redis pub/sub channel that live ticks for `symbol` are published on
+pub fn tick_channel(symbol: &str) -> String {
+    format!("ticks:{}", symbol)
+}
+
+/// Listen for ticks published by `TieredCache::publish_tick` on the given
+/// symbols' channels, calling `callback` with each until it returns false.
+///
+/// Blocks the calling thread; async callers should run it with
+/// `tokio::task::spawn_blocking`.
+pub fn subscribe_ticks<F>(redis_url: &str, symbols: &[String], mut callback: F) -> DataResult<()>
+where
+    F: FnMut(TickData) -> bool,
+{
+    let client = RedisClient::open(redis_url)
+        .map_err(|e| DataError::Cache(format!("Failed to create Redis client: {}", e)))?;
+    let mut connection = client
+        .get_connection()
+        .map_err(|e| DataError::Cache(format!("Failed to connect to Redis: {}", e)))?;
+    let mut pubsub = connection.as_pubsub();
+    for symbol in symbols {
+        pubsub
+            .subscribe(tick_channel(symbol))
+            .map_err(|e| DataError::Cache(format!("Redis SUBSCRIBE failed: {}", e)))?;
+    }
+    debug!("Subscribed to live ticks for {} symbols", symbols.len());
+
+    loop {
+        let message = pubsub
+            .get_message()
+            .map_err(|e| DataError::Cache(format!("Redis pub/sub read failed: {}", e)))?;
+        let payload: String = message
+            .get_payload()
+            .map_err(|e| DataError::Cache(format!("Invalid pub/sub payload: {}", e)))?;
+        match serde_json::from_str::<TickData>(&payload) {
+            Ok(tick) => {
+                if !callback(tick) {
+                    return Ok(());
+                }
+            }
+            Err(e) => warn!("Failed to deserialize published tick: {}", e),
+        }
+    }
+}
+
 /// Redis cache implementation
 pub struct RedisTickCache {
     #[allow(dead_code)] // Keep client alive to maintain connection
@@ -200,6 +296,10 @@ pub struct RedisTickCache {
     connection: Arc<Mutex<Connection>>,
     max_ticks_per_symbol: usize,
     ttl_seconds: u64,
+    /// Queue of (channel, payload) drained by the publisher task
+    publish_tx: mpsc::Sender<(String, String)>,
+    /// Ticks dropped from a full queue or whose PUBLISH failed or timed out
+    publish_failures: Arc<AtomicU64>,
 }
 
 impl RedisTickCache {
@@ -215,6 +315,18 @@ impl RedisTickCache {
             .get_connection()
             .map_err(|e| DataError::Cache(format!("Failed to connect to Redis: {}", e)))?;
 
+        let publisher = client
+            .get_multiplexed_tokio_connection()
+            .await
+            .map_err(|e| DataError::Cache(format!("Failed to connect to Redis: {}", e)))?;
+        let (publish_tx, publish_rx) = mpsc::channel(PUBLISH_QUEUE_CAPACITY);
+        let publish_failures = Arc::new(AtomicU64::new(0));
+        tokio::spawn(Self::run_publisher(
+            publisher,
+            publish_rx,
+            Arc::clone(&publish_failures),
+        ));
+
         debug!("Connected to Redis at: {}", redis_url);
 
         Ok(Self {
@@ -222,12 +334,60 @@ impl RedisTickCache {
             connection: Arc::new(Mutex::new(connection)),
             max_ticks_per_symbol,
             ttl_seconds,
+            publish_tx,
+            publish_failures,
         })
     }
 
+    /// Publish queued ticks until the cache is dropped, each with a timeout
+    /// so a stalled Redis only backs up the queue
+    async fn run_publisher(
+        mut conn: MultiplexedConnection,
+        mut publish_rx: mpsc::Receiver<(String, String)>,
+        failures: Arc<AtomicU64>,
+    ) {
+        while let Some((channel, payload)) = publish_rx.recv().await {
+            let error = match tokio::time::timeout(
+                PUBLISH_TIMEOUT,
+                conn.publish::<_, _, ()>(&channel, &payload),
+            )
+            .await
+            {
+                Ok(Ok(())) => continue,
+                Ok(Err(e)) => format!("Redis PUBLISH failed: {}", e),
+                Err(_) => format!("Redis PUBLISH timed out after {:?}", PUBLISH_TIMEOUT),
+            };
+            warn!("Failed to publish tick on {}: {}", channel, error);
+            failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_seconds)
+    }
+
     fn get_cache_key(&self, symbol: &str) -> String {
         format!("tick:{}", symbol)
     }
+
+    /// Queue a tick to be published as JSON on its symbol's `tick_channel`
+    /// without waiting on Redis; fails only when the queue is full
+    pub fn publish_tick(&self, tick: &TickData) -> DataResult<()> {
+        let tick_json = serde_json::to_string(tick)
+            .map_err(|e| DataError::Cache(format!("Failed to serialize tick: {}", e)))?;
+
+        self.publish_tx
+            .try_send((tick_channel(&tick.symbol), tick_json))
+            .map_err(|e| {
+                self.publish_failures.fetch_add(1, Ordering::Relaxed);
+                DataError::Cache(format!("Tick publish queue rejected tick: {}", e))
+            })
+    }
+
+    /// Ticks that were not published, whether dropped or failed in Redis
+    pub fn publish_failures(&self) -> u64 {
+        self.publish_failures.load(Ordering::Relaxed)
+    }
 }
 
 #[async_trait]
@@ -361,10 +521,26 @@ impl TieredCache {
         })
     }
 
+    /// Longest time a symbol's ticks stay in either tier without new writes
+    pub fn ttl(&self) -> Duration {
+        self.memory_cache.ttl().max(self.redis_cache.ttl())
+    }
+
     /// Periodically clean expired items from memory cache
     pub fn cleanup_memory(&self) {
         self.memory_cache.cleanup_expired();
     }
+
+    /// Broadcast a live tick to downstream consumers over Redis pub/sub;
+    /// see `subscribe_ticks`. Publishing happens in the background.
+    pub fn publish_tick(&self, tick: &TickData) -> DataResult<()> {
+        self.redis_cache.publish_tick(tick)
+    }
+
+    /// Live ticks that could not be published
+    pub fn publish_failures(&self) -> u64 {
+        self.redis_cache.publish_failures()
+    }
 }
 
 #[async_trait]
@@ -457,6 +633,12 @@ impl TickDataCache for TieredCache {
 
         Ok(())
     }
+
+    /// Served from the memory tier, which holds at most `max_ticks_per_symbol`
+    /// prices per symbol
+    fn get_rolling_sma(&self, symbol: &str, period: usize) -> Option<Decimal> {
+        self.memory_cache.get_rolling_sma(symbol, period)
+    }
 }
 
 #[cfg(test)]
@@ -464,7 +646,6 @@ mod tests {
     use super::*;
     use crate::data::types::TradeSide;
     use chrono::Utc;
-    use rust_decimal::Decimal;
 
     fn create_test_tick(symbol: &str, price: &str, trade_id: &str) -> TickData {
         TickData::new(
@@ -513,4 +694,76 @@ mod tests {
         assert_eq!(ticks[0].price, "50003.0".parse::<Decimal>().unwrap()); // Latest
         assert_eq!(ticks[1].price, "50002.0".parse::<Decimal>().unwrap()); // Second latest
     }
+
+    #[tokio::test]
+    async fn test_rolling_sma_matches_window_mean() {
+        let cache = InMemoryTickCache::new(4, 300);
+        let prices = ["100.5", "101", "99.25", "102", "103.75", "98"];
+
+        for (i, price) in prices.iter().enumerate() {
+            let tick = create_test_tick("BTCUSDT", price, &format!("sma{}", i));
+            cache.push_tick(&tick).await.unwrap();
+        }
+
+        // Manual mean over the last `period` prices
+        let manual = |period: usize| {
+            prices[prices.len() - period..]
+                .iter()
+                .map(|price| price.parse::<Decimal>().unwrap())
+                .sum::<Decimal>()
+                / Decimal::from(period)
+        };
+        for period in 1..=4 {
+            assert_eq!(
+                cache.get_rolling_sma("BTCUSDT", period),
+                Some(manual(period))
+            );
+        }
+
+        // Only max_ticks_per_symbol prices are kept
+        assert_eq!(cache.get_rolling_sma("BTCUSDT", 5), None);
+        assert_eq!(cache.get_rolling_sma("BTCUSDT", 0), None);
+        assert_eq!(cache.get_rolling_sma("ETHUSDT", 1), None);
+
+        let tick = create_test_tick("ETHUSDT", "3000", "sma_eth");
+        cache.push_tick(&tick).await.unwrap();
+        assert_eq!(cache.get_rolling_sma("ETHUSDT", 2), None);
+        assert_eq!(
+            cache.get_rolling_sma("ETHUSDT", 1),
+            Some(Decimal::from(3000))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_published_tick_reaches_subscriber() {
+        dotenv::dotenv().ok();
+        let redis_url = std::env::var("REDIS_URL").expect("REDIS_URL must be set in .env file");
+        let cache = TieredCache::new((10, 60), (&redis_url, 10, 60))
+            .await
+            .unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let url = redis_url.clone();
+        let subscriber = tokio::task::spawn_blocking(move || {
+            subscribe_ticks(&url, &["PUBSUBTEST".to_string()], |tick| {
+                let _ = tx.send(tick);
+                false
+            })
+        });
+
+        // Ticks published before the subscription lands are lost, so repeat
+        let tick = create_test_tick("PUBSUBTEST", "123.45", "pubsub1");
+        let mut received = None;
+        for _ in 0..100 {
+            cache.publish_tick(&tick).unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if let Ok(tick) = rx.try_recv() {
+                received = Some(tick);
+                break;
+            }
+        }
+
+        assert_eq!(received, Some(tick));
+        subscriber.await.unwrap().unwrap();
+    }
 }
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Duration, Utc};
+use std::sync::{Arc, RwLock};
+
+// =================================================================
+// Clock Abstraction
+// =================================================================
+
+/// Source of "now" for time-dependent logic
+///
+/// Production code uses [`SystemClock`]; tests can inject a [`MockClock`]
+/// to make recency checks and other time-based decisions deterministic.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Wall clock backed by `Utc::now()`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Manually controlled clock for tests
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<RwLock<DateTime<Utc>>>,
+}
+
+impl MockClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(RwLock::new(now)),
+        }
+    }
+
+    /// Set the current time
+    pub fn set(&self, now: DateTime<Utc>) {
+        if let Ok(mut guard) = self.now.write() {
+            *guard = now;
+        }
+    }
+
+    /// Move the current time forward (or backward for negative durations)
+    pub fn advance(&self, duration: Duration) {
+        if let Ok(mut guard) = self.now.write() {
+            *guard += duration;
+        }
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.now
+            .read()
+            .map(|guard| *guard)
+            .unwrap_or_else(|e| *e.into_inner())
+    }
+}
+
+/// Shared clock handle used by components that need "now"
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Default clock handle (system time)
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_mock_clock_set_and_advance() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        assert_eq!(clock.now(), start);
+
+        clock.advance(Duration::minutes(90));
+        assert_eq!(clock.now(), start + Duration::minutes(90));
+
+        // Clones share the same underlying time
+        let shared = clock.clone();
+        shared.set(start);
+        assert_eq!(clock.now(), start);
+    }
+}
@@ -1,15 +1,27 @@
-use chrono::{DateTime, Duration, Utc};
+use arrow::array::{BooleanArray, Decimal128Array, StringArray, TimestampMicrosecondArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use parquet::arrow::ArrowWriter;
 use rust_decimal::Decimal;
-use sqlx::{PgPool, QueryBuilder, Row};
-use std::collections::HashMap;
+use sqlx::{PgExecutor, PgPool, QueryBuilder, Row};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
-use crate::data::types::{LiveStrategyLog, OHLCData, Timeframe};
+use crate::data::types::{
+    LivePnlSnapshot, LiveStrategyLog, NewBacktestResult, OHLCData, OhlcCoverage, OrderFlowBucket,
+    StoredBacktestResult, SymbolMetadata, Timeframe,
+};
 
 use super::cache::{TickDataCache, TieredCache};
+use super::clock::{system_clock, SharedClock};
 use super::types::{
-    BacktestDataInfo, DataError, DataResult, DbStats, SymbolDataInfo, TickData, TickQuery,
-    TradeSide,
+    BacktestDataInfo, DataError, DataGap, DataResult, DbStats, MergeSummary, OutlierPolicy,
+    ReadStrategy, SymbolDataInfo, TickData, TickQuery, TradeSide, WriteOrder,
 };
 
 // =================================================================
@@ -19,6 +31,16 @@ use super::types::{
 const DEFAULT_QUERY_LIMIT: u32 = 1000;
 const MAX_QUERY_LIMIT: u32 = 10000;
 const MAX_BATCH_SIZE: usize = 1000;
+/// Cache-first ticks queued for the background database writer before
+/// `insert_tick` waits for it to catch up
+const BACKGROUND_WRITE_QUEUE: usize = 1024;
+
+/// Ticks fetched per requested candle on the first pass of a recent OHLC load
+const OHLC_TICKS_PER_CANDLE: i64 = 100;
+/// Most ticks a recent OHLC load fetches in one pass
+const MAX_OHLC_TICKS: i64 = 2_000_000;
+/// Times a recent OHLC load may double its time window and tick budget
+const MAX_OHLC_WIDENINGS: u32 = 8;
 
 // =================================================================
 // Repository Implementation
@@ -28,12 +50,62 @@ const MAX_BATCH_SIZE: usize = 1000;
 pub struct TickDataRepository {
     pool: PgPool,
     cache: TieredCache,
+    clock: SharedClock,
+    /// Queue of the background database writer in cache-first mode
+    background_writes: Option<mpsc::Sender<TickData>>,
+    outlier_policy: OutlierPolicy,
+    /// How far back a query may start and still be tried against the cache
+    freshness_window: Duration,
 }
 
 impl TickDataRepository {
     /// Create new repository instance
+    ///
+    /// The cache freshness window defaults to the cache TTL.
     pub fn new(pool: PgPool, cache: TieredCache) -> Self {
-        Self { pool, cache }
+        let freshness_window = Duration::from_std(cache.ttl()).unwrap_or(Duration::MAX);
+        Self {
+            pool,
+            cache,
+            freshness_window,
+            clock: system_clock(),
+            background_writes: None,
+            outlier_policy: OutlierPolicy::default(),
+        }
+    }
+
+    /// Only consult the cache for queries starting within `window` of now
+    ///
+    /// Ticks older than the cache TTL have usually been evicted, so a wider
+    /// window mostly adds cache round-trips that miss.
+    pub fn with_freshness_window(mut self, window: Duration) -> Self {
+        self.freshness_window = window;
+        self
+    }
+
+    /// Choose whether `insert_tick` writes the database or the cache first
+    ///
+    /// Cache-first starts a background writer task, so this must be called
+    /// inside a Tokio runtime.
+    pub fn with_write_order(mut self, write_order: WriteOrder) -> Self {
+        self.background_writes = match write_order {
+            WriteOrder::DbFirst => None,
+            WriteOrder::CacheFirst => Some(Self::spawn_background_writer(self.pool.clone())),
+        };
+        self
+    }
+
+    /// Choose whether `batch_insert_validated` drops price outliers or
+    /// rejects their whole batch
+    pub fn with_outlier_policy(mut self, policy: OutlierPolicy) -> Self {
+        self.outlier_policy = policy;
+        self
+    }
+
+    /// Replace the clock used for "now" (defaults to system time)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
     }
 
     /// Get database pool reference
@@ -51,6 +123,11 @@ impl TickDataRepository {
     // =================================================================
 
     /// Insert single tick data
+    ///
+    /// With [`WriteOrder::CacheFirst`] this returns once the cache is updated
+    /// and the tick is queued; a single background task writes queued ticks to
+    /// the database in order and logs rather than returns their errors. When
+    /// the queue is full this waits for the writer to catch up.
     pub async fn insert_tick(&self, tick: &TickData) -> DataResult<()> {
         self.validate_tick_data(tick)?;
 
@@ -59,7 +136,47 @@ impl TickDataRepository {
             tick.symbol, tick.price, tick.trade_id
         );
 
+        if let Some(background_writes) = &self.background_writes {
+            if let Err(e) = self.cache.push_tick(tick).await {
+                warn!("Failed to update cache before insert: {}", e);
+            }
+            if background_writes.send(tick.clone()).await.is_err() {
+                error!("Background tick writer stopped; inserting inline");
+                Self::insert_tick_row(&self.pool, tick).await?;
+            }
+            return Ok(());
+        }
+
         // Insert to database first
+        Self::insert_tick_row(&self.pool, tick).await?;
+
+        // Update cache
+        if let Err(e) = self.cache.push_tick(tick).await {
+            warn!("Failed to update cache after insert: {}", e);
+            // Don't fail the operation if cache update fails
+        }
+
+        debug!("Successfully inserted tick data");
+        Ok(())
+    }
+
+    /// Drain cache-first ticks into the database until the repository is dropped
+    fn spawn_background_writer(pool: PgPool) -> mpsc::Sender<TickData> {
+        let (tx, mut rx) = mpsc::channel::<TickData>(BACKGROUND_WRITE_QUEUE);
+        tokio::spawn(async move {
+            while let Some(tick) = rx.recv().await {
+                if let Err(e) = Self::insert_tick_row(&pool, &tick).await {
+                    error!(
+                        "Background insert of tick {} for {} failed: {}",
+                        tick.trade_id, tick.symbol, e
+                    );
+                }
+            }
+        });
+        tx
+    }
+
+    async fn insert_tick_row(pool: &PgPool, tick: &TickData) -> DataResult<()> {
         sqlx::query!(
             r#"
             INSERT INTO tick_data 
@@ -75,25 +192,260 @@ impl TickDataRepository {
             tick.trade_id,
             tick.is_buyer_maker
         )
-        .execute(&self.pool)
+        .execute(pool)
         .await
         .map_err(|e| {
             error!("Failed to insert tick data: {}", e);
             DataError::Database(e)
         })?;
-
-        // Update cache
-        if let Err(e) = self.cache.push_tick(tick).await {
-            warn!("Failed to update cache after insert: {}", e);
-            // Don't fail the operation if cache update fails
-        }
-
-        debug!("Successfully inserted tick data");
         Ok(())
     }
 
     /// Batch insert tick data with optimized performance
     pub async fn batch_insert(&self, ticks: Vec<TickData>) -> DataResult<usize> {
+        self.batch_insert_inner(ticks, true).await
+    }
+
+    /// Batch insert tick data after screening out price spikes
+    ///
+    /// Ticks are sorted by timestamp, and a tick whose price is more than
+    /// `max_price_jump_pct` percent away from the previous accepted tick of
+    /// its symbol is an outlier. Each symbol starts from its last stored tick
+    /// before the batch's first timestamp, so a spike at the start of a batch
+    /// is caught and a backfilled batch is compared with its own period; with
+    /// nothing stored before it, the batch's first tick is the reference.
+    /// Outliers are handled per the repository's `OutlierPolicy`. Returns the
+    /// inserted count and the dropped outliers.
+    pub async fn batch_insert_validated(
+        &self,
+        mut ticks: Vec<TickData>,
+        max_price_jump_pct: Decimal,
+    ) -> DataResult<(usize, Vec<TickData>)> {
+        ticks.sort_by_key(|tick| tick.timestamp);
+
+        let mut first_timestamps: HashMap<&str, DateTime<Utc>> = HashMap::new();
+        for tick in &ticks {
+            first_timestamps
+                .entry(tick.symbol.as_str())
+                .or_insert(tick.timestamp);
+        }
+        let mut last_price = self.prices_before(&first_timestamps).await?;
+
+        let mut accepted = Vec::with_capacity(ticks.len());
+        let mut outliers = Vec::new();
+        for tick in ticks {
+            let spike = last_price.get(&tick.symbol).is_some_and(|&previous| {
+                (tick.price - previous).abs() * Decimal::from(100) > max_price_jump_pct * previous
+            });
+            if spike {
+                outliers.push(tick);
+            } else {
+                last_price.insert(tick.symbol.clone(), tick.price);
+                accepted.push(tick);
+            }
+        }
+
+        if !outliers.is_empty() {
+            warn!(
+                "{} of {} ticks jump more than {}% from the previous price",
+                outliers.len(),
+                accepted.len() + outliers.len(),
+                max_price_jump_pct
+            );
+            if self.outlier_policy == OutlierPolicy::Error {
+                return Err(DataError::Validation(format!(
+                    "Batch rejected: {} ticks jump more than {}% (first: {} {} at {})",
+                    outliers.len(),
+                    max_price_jump_pct,
+                    outliers[0].symbol,
+                    outliers[0].price,
+                    outliers[0].timestamp
+                )));
+            }
+        }
+
+        let inserted = self.batch_insert(accepted).await?;
+        Ok((inserted, outliers))
+    }
+
+    /// Price of the last stored tick before the given time, per symbol;
+    /// symbols with no earlier tick are left out
+    async fn prices_before(
+        &self,
+        times: &HashMap<&str, DateTime<Utc>>,
+    ) -> DataResult<HashMap<String, Decimal>> {
+        if times.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let (symbols, timestamps): (Vec<String>, Vec<DateTime<Utc>>) = times
+            .iter()
+            .map(|(symbol, timestamp)| (symbol.to_string(), *timestamp))
+            .unzip();
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                s.symbol AS "symbol!",
+                (
+                    SELECT t.price
+                    FROM tick_data t
+                    WHERE t.symbol = s.symbol AND t.timestamp < s.before
+                    ORDER BY t.timestamp DESC
+                    LIMIT 1
+                ) AS price
+            FROM UNNEST($1::varchar[], $2::timestamptz[]) AS s(symbol, before)
+            "#,
+            &symbols[..],
+            &timestamps[..]
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| row.price.map(|price| (row.symbol, price)))
+            .collect())
+    }
+
+    /// Batch insert historical (backfilled) tick data
+    ///
+    /// Skips the cache: backfilled ticks are older than what the cache holds
+    /// and pushing them would displace genuinely recent ticks.
+    pub async fn batch_insert_historical(&self, ticks: Vec<TickData>) -> DataResult<usize> {
+        self.batch_insert_inner(ticks, false).await
+    }
+
+    /// Stream ticks into tick_data with `COPY ... FROM STDIN` (CSV)
+    ///
+    /// Much faster than multi-row INSERT for large batches, but COPY has no
+    /// `ON CONFLICT`: if any tick is already stored the whole COPY is
+    /// rejected, and the ticks go through the INSERT path instead, which
+    /// skips duplicates. Like `batch_insert_historical`, this skips the cache.
+    pub async fn copy_insert(&self, ticks: &[TickData]) -> DataResult<u64> {
+        if ticks.is_empty() {
+            return Ok(0);
+        }
+        for tick in ticks {
+            self.validate_tick_data(tick)?;
+        }
+
+        match self.copy_ticks(ticks).await {
+            Err(DataError::Database(sqlx::Error::Database(e))) if e.is_unique_violation() => {
+                debug!(
+                    "COPY of {} ticks hit stored duplicates, falling back to INSERT",
+                    ticks.len()
+                );
+                let inserted = self.batch_insert_inner(ticks.to_vec(), false).await?;
+                Ok(inserted as u64)
+            }
+            result => result,
+        }
+    }
+
+    async fn copy_ticks(&self, ticks: &[TickData]) -> DataResult<u64> {
+        let mut conn = self.pool.acquire().await?;
+        let mut copy = conn
+            .copy_in_raw(
+                "COPY tick_data (timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker) \
+                 FROM STDIN WITH (FORMAT csv)",
+            )
+            .await?;
+
+        for chunk in ticks.chunks(MAX_BATCH_SIZE) {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for tick in chunk {
+                // Postgres would round sub-microsecond digits; bound
+                // parameters truncate them, so match that
+                writer
+                    .write_record([
+                        tick.timestamp.trunc_subsecs(6).to_rfc3339(),
+                        tick.symbol.clone(),
+                        tick.price.to_string(),
+                        tick.quantity.to_string(),
+                        tick.side.as_db_str().to_string(),
+                        tick.trade_id.clone(),
+                        tick.is_buyer_maker.to_string(),
+                    ])
+                    .map_err(|e| DataError::InvalidFormat(e.to_string()))?;
+            }
+            let data = writer
+                .into_inner()
+                .map_err(|e| DataError::InvalidFormat(e.to_string()))?;
+            copy.send(data).await?;
+        }
+
+        let rows = copy.finish().await?;
+        debug!("COPY inserted {} tick records", rows);
+        Ok(rows)
+    }
+
+    /// Insert historical ticks, first dropping those already stored
+    ///
+    /// For each symbol the stored `(trade_id, timestamp)` pairs inside the
+    /// batch's time range are fetched and matching ticks filtered out
+    /// client-side, so re-running a backfill over existing data does not send
+    /// every row into `ON CONFLICT`. Like `batch_insert_historical`, this
+    /// skips the cache.
+    pub async fn insert_ticks_skipping_existing(
+        &self,
+        ticks: Vec<TickData>,
+    ) -> DataResult<MergeSummary> {
+        let total = ticks.len();
+        let mut by_symbol: HashMap<String, Vec<TickData>> = HashMap::new();
+        for tick in ticks {
+            by_symbol.entry(tick.symbol.clone()).or_default().push(tick);
+        }
+
+        // Timestamps are sent truncated to microseconds, so compare at that
+        let stored_time = |tick: &TickData| tick.timestamp.trunc_subsecs(6);
+
+        let mut fresh = Vec::with_capacity(total);
+        for (symbol, ticks) in by_symbol {
+            let (Some(start), Some(end)) = (
+                ticks.iter().map(stored_time).min(),
+                ticks.iter().map(stored_time).max(),
+            ) else {
+                continue;
+            };
+
+            let existing = sqlx::query!(
+                r#"
+                SELECT trade_id, timestamp
+                FROM tick_data
+                WHERE symbol = $1 AND timestamp >= $2 AND timestamp <= $3
+                "#,
+                symbol,
+                start,
+                end
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            let mut seen: HashSet<(String, DateTime<Utc>)> = existing
+                .into_iter()
+                .map(|row| (row.trade_id, row.timestamp))
+                .collect();
+            fresh.extend(
+                ticks
+                    .into_iter()
+                    .filter(|tick| seen.insert((tick.trade_id.clone(), stored_time(tick)))),
+            );
+        }
+
+        let filtered = total - fresh.len();
+        let inserted = self.batch_insert_inner(fresh, false).await?;
+        debug!(
+            "Skipped {} existing ticks, inserted {} of {}",
+            filtered, inserted, total
+        );
+        Ok(MergeSummary { filtered, inserted })
+    }
+
+    async fn batch_insert_inner(
+        &self,
+        ticks: Vec<TickData>,
+        update_cache: bool,
+    ) -> DataResult<usize> {
         if ticks.is_empty() {
             return Ok(0);
         }
@@ -112,6 +464,10 @@ impl TickDataRepository {
             let inserted = self.batch_insert_chunk(chunk).await?;
             total_inserted += inserted;
 
+            if !update_cache {
+                continue;
+            }
+
             // Update cache for each chunk
             for tick in chunk {
                 if let Err(e) = self.cache.push_tick(tick).await {
@@ -167,7 +523,31 @@ impl TickDataRepository {
             .unwrap_or(DEFAULT_QUERY_LIMIT)
             .min(MAX_QUERY_LIMIT);
 
-        debug!("Querying ticks: symbol={}, limit={}", query.symbol, limit);
+        debug!(
+            "Querying ticks: symbol={}, limit={}, read_strategy={:?}",
+            query.symbol, limit, query.read_strategy
+        );
+
+        match query.read_strategy {
+            ReadStrategy::CacheOnly => {
+                let cached_ticks = self
+                    .cache
+                    .get_recent_ticks(&query.symbol, limit as usize)
+                    .await?;
+                let ticks: Vec<TickData> = cached_ticks
+                    .into_iter()
+                    .filter(|tick| Self::matches_query(tick, query))
+                    .collect();
+                debug!("Retrieved {} ticks from cache only", ticks.len());
+                return Ok(ticks);
+            }
+            ReadStrategy::DbOnly => {
+                let ticks = self.query_ticks_from_db(query).await?;
+                debug!("Retrieved {} ticks from database only", ticks.len());
+                return Ok(ticks);
+            }
+            ReadStrategy::Auto => {}
+        }
 
         // Try cache first for recent data
         if self.is_recent_query(query) {
@@ -198,6 +578,13 @@ impl TickDataRepository {
         Ok(ticks)
     }
 
+    /// Whether a tick satisfies the time range and side filters of a query
+    fn matches_query(tick: &TickData, query: &TickQuery) -> bool {
+        query.start_time.is_none_or(|start| tick.timestamp >= start)
+            && query.end_time.is_none_or(|end| tick.timestamp <= end)
+            && query.trade_side.is_none_or(|side| tick.side == side)
+    }
+
     /// Query ticks directly from database
     async fn query_ticks_from_db(&self, query: &TickQuery) -> DataResult<Vec<TickData>> {
         let limit = query
@@ -328,134 +715,490 @@ impl TickDataRepository {
         Ok(prices)
     }
 
-    // =================================================================
-    // Backtest Specific Query Operations
-    // =================================================================
-
-    /// Get recent N ticks for backtesting (ordered by time ASC)
-    pub async fn get_recent_ticks_for_backtest(
+    /// Get up to `before` ticks strictly before `pivot` and up to `after`
+    /// ticks at or after it, in chronological order
+    pub async fn get_ticks_around(
         &self,
         symbol: &str,
-        count: i64,
+        pivot: DateTime<Utc>,
+        before: usize,
+        after: usize,
     ) -> DataResult<Vec<TickData>> {
-        debug!("Fetching {} recent ticks for backtest: {}", count, symbol);
+        debug!(
+            "Fetching ticks around {} for {}: {} before, {} after",
+            pivot, symbol, before, after
+        );
 
-        let limit = count.min(MAX_QUERY_LIMIT as i64);
+        let before_limit = before.min(MAX_QUERY_LIMIT as usize) as i64;
+        let after_limit = after.min(MAX_QUERY_LIMIT as usize) as i64;
 
-        let rows = sqlx::query!(
+        let before_rows = sqlx::query!(
             r#"
             SELECT timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker
-            FROM tick_data 
-            WHERE symbol = $1
+            FROM tick_data
+            WHERE symbol = $1 AND timestamp < $2
             ORDER BY timestamp DESC
-            LIMIT $2
+            LIMIT $3
             "#,
             symbol,
-            limit
+            pivot,
+            before_limit
         )
         .fetch_all(&self.pool)
         .await?;
 
-        // Convert rows to TickData and reverse to get ASC order (oldest first)
-        let ticks: DataResult<Vec<TickData>> = rows
-            .iter()
-            .map(|row| {
-                Ok(TickData {
-                    timestamp: row.timestamp,
-                    symbol: row.symbol.clone(),
-                    price: row.price,
-                    quantity: row.quantity,
-                    side: self.parse_trade_side(&row.side)?,
-                    trade_id: row.trade_id.clone(),
-                    is_buyer_maker: row.is_buyer_maker,
-                })
-            })
-            .collect();
+        let after_rows = sqlx::query!(
+            r#"
+            SELECT timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker
+            FROM tick_data
+            WHERE symbol = $1 AND timestamp >= $2
+            ORDER BY timestamp ASC
+            LIMIT $3
+            "#,
+            symbol,
+            pivot,
+            after_limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
-        let mut ticks = ticks?;
-        ticks.reverse(); // Reverse to get chronological order (ASC)
+        let mut ticks = Vec::with_capacity(before_rows.len() + after_rows.len());
+        for row in before_rows.iter().rev() {
+            ticks.push(TickData {
+                timestamp: row.timestamp,
+                symbol: row.symbol.clone(),
+                price: row.price,
+                quantity: row.quantity,
+                side: self.parse_trade_side(&row.side)?,
+                trade_id: row.trade_id.clone(),
+                is_buyer_maker: row.is_buyer_maker,
+            });
+        }
+        for row in &after_rows {
+            ticks.push(TickData {
+                timestamp: row.timestamp,
+                symbol: row.symbol.clone(),
+                price: row.price,
+                quantity: row.quantity,
+                side: self.parse_trade_side(&row.side)?,
+                trade_id: row.trade_id.clone(),
+                is_buyer_maker: row.is_buyer_maker,
+            });
+        }
 
-        debug!("Retrieved {} ticks for backtest", ticks.len());
+        debug!("Retrieved {} ticks around {}", ticks.len(), pivot);
         Ok(ticks)
     }
 
-    /// Get historical data for backtesting within time range (ordered by time ASC)
-    pub async fn get_historical_data_for_backtest(
+    /// Interpolated price percentiles (`percentile_cont`) of a symbol's ticks
+    /// in [start, end], returned as `(percentile, price)` in the order requested
+    ///
+    /// Percentiles are fractions in [0, 1], e.g. `&[0.1, 0.5, 0.9]`.
+    pub async fn price_percentiles(
         &self,
         symbol: &str,
-        start_time: DateTime<Utc>,
-        end_time: DateTime<Utc>,
-        limit: Option<i64>,
-    ) -> DataResult<Vec<TickData>> {
-        debug!(
-            "Fetching historical data for backtest: {} from {} to {}",
-            symbol, start_time, end_time
-        );
-
-        let query_limit = limit
-            .unwrap_or(MAX_QUERY_LIMIT as i64)
-            .min(MAX_QUERY_LIMIT as i64);
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        percentiles: &[f64],
+    ) -> DataResult<Vec<(f64, Decimal)>> {
+        if percentiles.is_empty() {
+            return Err(DataError::Validation("No percentiles requested".into()));
+        }
+        if let Some(p) = percentiles.iter().find(|p| !(0.0..=1.0).contains(*p)) {
+            return Err(DataError::Validation(format!(
+                "Percentile {} is outside [0, 1]",
+                p
+            )));
+        }
+        if start > end {
+            return Err(DataError::Validation("start must not be after end".into()));
+        }
 
-        let rows = sqlx::query!(
+        let values = sqlx::query_scalar!(
             r#"
-            SELECT timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker
-            FROM tick_data 
-            WHERE symbol = $1 
-            AND timestamp >= $2 
-            AND timestamp <= $3
-            ORDER BY timestamp ASC
-            LIMIT $4
+            SELECT percentile_cont($4::float8[]) WITHIN GROUP (ORDER BY price::float8)
+            FROM tick_data
+            WHERE symbol = $1 AND timestamp >= $2 AND timestamp <= $3
             "#,
             symbol,
-            start_time,
-            end_time,
-            query_limit
+            start,
+            end,
+            percentiles
         )
-        .fetch_all(&self.pool)
-        .await?;
+        .fetch_one(&self.pool)
+        .await?
+        .ok_or_else(|| {
+            DataError::NotFound(format!(
+                "No ticks for {} between {} and {}",
+                symbol, start, end
+            ))
+        })?;
 
-        let ticks: DataResult<Vec<TickData>> = rows
+        percentiles
             .iter()
-            .map(|row| {
-                Ok(TickData {
-                    timestamp: row.timestamp,
-                    symbol: row.symbol.clone(),
-                    price: row.price,
-                    quantity: row.quantity,
-                    side: self.parse_trade_side(&row.side)?,
-                    trade_id: row.trade_id.clone(),
-                    is_buyer_maker: row.is_buyer_maker,
-                })
-            })
-            .collect();
-
-        let ticks = ticks?;
-        debug!("Retrieved {} historical ticks for backtest", ticks.len());
-        Ok(ticks)
+            .zip(values)
+            .map(|(p, value)| Ok((*p, Decimal::try_from(value)?)))
+            .collect()
     }
 
-    /// Get backtest data information for user selection
-    pub async fn get_backtest_data_info(&self) -> DataResult<BacktestDataInfo> {
-        debug!("Fetching backtest data information");
+    /// Volume-weighted average price of `symbol` over [start, end], or None
+    /// when no ticks fall in the window
+    pub async fn get_vwap(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DataResult<Option<Decimal>> {
+        if start > end {
+            return Err(DataError::Validation("start must not be after end".into()));
+        }
 
-        // Get overall statistics
-        let overall_stats = sqlx::query!(
+        // Summed as numeric so no digit goes through a float
+        let vwap = sqlx::query_scalar!(
             r#"
-            SELECT 
-                COUNT(*) as total_records,
-                COUNT(DISTINCT symbol) as symbols_count,
-                MIN(timestamp) as earliest_time,
-                MAX(timestamp) as latest_time
+            SELECT SUM(price::numeric * quantity::numeric) / NULLIF(SUM(quantity::numeric), 0)
             FROM tick_data
-            "#
+            WHERE symbol = $1 AND timestamp >= $2 AND timestamp <= $3
+            "#,
+            symbol,
+            start,
+            end
         )
         .fetch_one(&self.pool)
         .await?;
 
-        // Get per-symbol statistics
-        let symbol_stats = sqlx::query!(
+        Ok(vwap.map(|vwap| vwap.normalize()))
+    }
+
+    /// VWAP of each `timeframe` bucket between start and end that has ticks,
+    /// as `(bucket start, vwap)` in time order
+    pub async fn get_vwap_series(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        timeframe: Timeframe,
+    ) -> DataResult<Vec<(DateTime<Utc>, Decimal)>> {
+        if start > end {
+            return Err(DataError::Validation("start must not be after end".into()));
+        }
+
+        let rows = sqlx::query!(
             r#"
-            SELECT 
+            SELECT timestamp, price, quantity
+            FROM tick_data
+            WHERE symbol = $1 AND timestamp >= $2 AND timestamp <= $3
+            ORDER BY timestamp ASC
+            "#,
+            symbol,
+            start,
+            end
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // (bucket, sum of price * quantity, sum of quantity)
+        let mut buckets: Vec<(DateTime<Utc>, Decimal, Decimal)> = Vec::new();
+        for row in rows {
+            let bucket = timeframe.align_timestamp(row.timestamp);
+            let notional = row.price * row.quantity;
+            match buckets.last_mut() {
+                Some((last, value, volume)) if *last == bucket => {
+                    *value += notional;
+                    *volume += row.quantity;
+                }
+                _ => buckets.push((bucket, notional, row.quantity)),
+            }
+        }
+
+        Ok(buckets
+            .into_iter()
+            .filter(|(_, _, volume)| *volume > Decimal::ZERO)
+            .map(|(bucket, value, volume)| (bucket, (value / volume).normalize()))
+            .collect())
+    }
+
+    /// Taker buy and sell volume of each `timeframe` bucket between start and
+    /// end that has ticks, in time order. A bucket with trades on one side
+    /// only reports zero volume for the other.
+    pub async fn get_order_flow(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DataResult<Vec<OrderFlowBucket>> {
+        if start > end {
+            return Err(DataError::Validation("start must not be after end".into()));
+        }
+
+        let bucket_secs = timeframe.as_duration().num_seconds() as f64;
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                tick_bucket(timestamp, $4, $5) as "bucket!",
+                side,
+                SUM(quantity) as "volume!"
+            FROM tick_data
+            WHERE symbol = $1 AND timestamp >= $2 AND timestamp <= $3
+            GROUP BY 1, side
+            ORDER BY 1
+            "#,
+            symbol,
+            start,
+            end,
+            bucket_secs,
+            bucket_origin_secs(timeframe)
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Pivot the (bucket, side) rows into one row per bucket
+        let mut buckets: Vec<OrderFlowBucket> = Vec::new();
+        for row in rows {
+            if buckets
+                .last()
+                .is_none_or(|last| last.timestamp != row.bucket)
+            {
+                buckets.push(OrderFlowBucket {
+                    timestamp: row.bucket,
+                    buy_volume: Decimal::ZERO,
+                    sell_volume: Decimal::ZERO,
+                    delta: Decimal::ZERO,
+                });
+            }
+            let bucket = buckets.last_mut().expect("bucket pushed above");
+            match self.parse_trade_side(&row.side)? {
+                TradeSide::Buy => bucket.buy_volume += row.volume,
+                TradeSide::Sell => bucket.sell_volume += row.volume,
+            }
+            bucket.delta = bucket.buy_volume - bucket.sell_volume;
+        }
+
+        Ok(buckets)
+    }
+
+    // =================================================================
+    // Export Operations
+    // =================================================================
+
+    /// Write the ticks of `symbol` between start and end (inclusive) to a
+    /// Parquet file at `path`, returning the number of rows written
+    ///
+    /// Rows are read and written `MAX_BATCH_SIZE` at a time, so memory stays
+    /// bounded for any range. Timestamps are
+    /// UTC microseconds and price/quantity Decimal128(20, 8), matching the
+    /// `tick_data` columns.
+    pub async fn export_parquet(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        path: &Path,
+    ) -> DataResult<u64> {
+        if start > end {
+            return Err(DataError::Validation("start must not be after end".into()));
+        }
+
+        let file = File::create(path)
+            .map_err(|e| DataError::Export(format!("{}: {}", path.display(), e)))?;
+        let schema = tick_export_schema();
+        let mut writer = ArrowWriter::try_new(file, Arc::clone(&schema), None)
+            .map_err(|e| DataError::Export(e.to_string()))?;
+
+        let mut written = 0u64;
+        // Keyset position: (timestamp, trade_id) of the last exported row
+        let mut after: Option<(DateTime<Utc>, String)> = None;
+        loop {
+            let (after_timestamp, after_trade_id) = after.clone().unzip();
+            let rows = sqlx::query!(
+                r#"
+                SELECT timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker
+                FROM tick_data
+                WHERE symbol = $1 AND timestamp >= $2 AND timestamp <= $3
+                  AND ($4::timestamptz IS NULL OR (timestamp, trade_id) > ($4, $5::text))
+                ORDER BY timestamp ASC, trade_id ASC
+                LIMIT $6
+                "#,
+                symbol,
+                start,
+                end,
+                after_timestamp,
+                after_trade_id,
+                MAX_BATCH_SIZE as i64
+            )
+            .fetch_all(&self.pool)
+            .await?;
+            let Some(last) = rows.last() else {
+                break;
+            };
+            after = Some((last.timestamp, last.trade_id.clone()));
+
+            let ticks = rows
+                .iter()
+                .map(|row| {
+                    Ok(TickData {
+                        timestamp: row.timestamp,
+                        symbol: row.symbol.clone(),
+                        price: row.price,
+                        quantity: row.quantity,
+                        side: self.parse_trade_side(&row.side)?,
+                        trade_id: row.trade_id.clone(),
+                        is_buyer_maker: row.is_buyer_maker,
+                    })
+                })
+                .collect::<DataResult<Vec<_>>>()?;
+            let batch = ticks_to_record_batch(&schema, &ticks)?;
+            writer
+                .write(&batch)
+                .map_err(|e| DataError::Export(e.to_string()))?;
+            written += ticks.len() as u64;
+
+            if rows.len() < MAX_BATCH_SIZE {
+                break;
+            }
+        }
+
+        writer
+            .close()
+            .map_err(|e| DataError::Export(e.to_string()))?;
+        info!(
+            "Exported {} {} ticks to {}",
+            written,
+            symbol,
+            path.display()
+        );
+        Ok(written)
+    }
+
+    // =================================================================
+    // Backtest Specific Query Operations
+    // =================================================================
+
+    /// Get recent N ticks for backtesting (ordered by time ASC)
+    pub async fn get_recent_ticks_for_backtest(
+        &self,
+        symbol: &str,
+        count: i64,
+    ) -> DataResult<Vec<TickData>> {
+        debug!("Fetching {} recent ticks for backtest: {}", count, symbol);
+
+        let limit = count.min(MAX_QUERY_LIMIT as i64);
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker
+            FROM tick_data 
+            WHERE symbol = $1
+            ORDER BY timestamp DESC
+            LIMIT $2
+            "#,
+            symbol,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Convert rows to TickData and reverse to get ASC order (oldest first)
+        let ticks: DataResult<Vec<TickData>> = rows
+            .iter()
+            .map(|row| {
+                Ok(TickData {
+                    timestamp: row.timestamp,
+                    symbol: row.symbol.clone(),
+                    price: row.price,
+                    quantity: row.quantity,
+                    side: self.parse_trade_side(&row.side)?,
+                    trade_id: row.trade_id.clone(),
+                    is_buyer_maker: row.is_buyer_maker,
+                })
+            })
+            .collect();
+
+        let mut ticks = ticks?;
+        ticks.reverse(); // Reverse to get chronological order (ASC)
+
+        debug!("Retrieved {} ticks for backtest", ticks.len());
+        Ok(ticks)
+    }
+
+    /// Get historical data for backtesting within time range (ordered by time ASC)
+    pub async fn get_historical_data_for_backtest(
+        &self,
+        symbol: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        limit: Option<i64>,
+    ) -> DataResult<Vec<TickData>> {
+        debug!(
+            "Fetching historical data for backtest: {} from {} to {}",
+            symbol, start_time, end_time
+        );
+
+        let query_limit = limit
+            .unwrap_or(MAX_QUERY_LIMIT as i64)
+            .min(MAX_QUERY_LIMIT as i64);
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker
+            FROM tick_data 
+            WHERE symbol = $1 
+            AND timestamp >= $2 
+            AND timestamp <= $3
+            ORDER BY timestamp ASC
+            LIMIT $4
+            "#,
+            symbol,
+            start_time,
+            end_time,
+            query_limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let ticks: DataResult<Vec<TickData>> = rows
+            .iter()
+            .map(|row| {
+                Ok(TickData {
+                    timestamp: row.timestamp,
+                    symbol: row.symbol.clone(),
+                    price: row.price,
+                    quantity: row.quantity,
+                    side: self.parse_trade_side(&row.side)?,
+                    trade_id: row.trade_id.clone(),
+                    is_buyer_maker: row.is_buyer_maker,
+                })
+            })
+            .collect();
+
+        let ticks = ticks?;
+        debug!("Retrieved {} historical ticks for backtest", ticks.len());
+        Ok(ticks)
+    }
+
+    /// Get backtest data information for user selection
+    pub async fn get_backtest_data_info(&self) -> DataResult<BacktestDataInfo> {
+        debug!("Fetching backtest data information");
+
+        // Get overall statistics
+        let overall_stats = sqlx::query!(
+            r#"
+            SELECT 
+                COUNT(*) as total_records,
+                COUNT(DISTINCT symbol) as symbols_count,
+                MIN(timestamp) as earliest_time,
+                MAX(timestamp) as latest_time
+            FROM tick_data
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        // Get per-symbol statistics
+        let symbol_stats = sqlx::query!(
+            r#"
+            SELECT 
                 symbol,
                 COUNT(*) as records_count,
                 MIN(timestamp) as earliest_time,
@@ -497,49 +1240,294 @@ impl TickDataRepository {
         Ok(info)
     }
 
-    // =================================================================
-    // Maintenance Operations
-    // =================================================================
+    /// Every symbol with stored ticks, sorted by the database collation
+    ///
+    /// Walks `idx_tick_symbol_time` one symbol at a time (a loose index
+    /// scan), so the cost grows with the number of symbols rather than ticks.
+    pub async fn list_symbols(&self) -> DataResult<Vec<String>> {
+        let symbols = sqlx::query_scalar!(
+            r#"
+            WITH RECURSIVE symbols AS (
+                (SELECT symbol FROM tick_data ORDER BY symbol LIMIT 1)
+                UNION ALL
+                SELECT (
+                    SELECT t.symbol FROM tick_data t
+                    WHERE t.symbol > s.symbol
+                    ORDER BY t.symbol
+                    LIMIT 1
+                )
+                FROM symbols s
+                WHERE s.symbol IS NOT NULL
+            )
+            SELECT symbol AS "symbol!" FROM symbols WHERE symbol IS NOT NULL
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
-    /// Clean up old tick data
-    pub async fn cleanup_old_data(&self, days_to_keep: f64) -> DataResult<u64> {
-        info!("Cleaning up tick data older than {} days", days_to_keep);
+        Ok(symbols)
+    }
 
-        let result = sqlx::query!(
+    /// Find ranges within [start_time, end_time] with no ticks for longer than `min_gap`
+    ///
+    /// Leading and trailing holes (between the range bounds and the first/last
+    /// stored tick) are included; an empty range yields a single gap.
+    pub async fn detect_gaps(
+        &self,
+        symbol: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        min_gap: Duration,
+    ) -> DataResult<Vec<DataGap>> {
+        if start_time >= end_time {
+            return Err(DataError::Validation(
+                "start_time must be before end_time".into(),
+            ));
+        }
+
+        let bounds = sqlx::query!(
             r#"
-            WITH deleted AS (
-                DELETE FROM tick_data
-                WHERE timestamp < NOW() - INTERVAL '1 day' * $1
-                RETURNING *
-            )
-            SELECT COUNT(*) as count
-            FROM deleted
+            SELECT MIN(timestamp) as first_time, MAX(timestamp) as last_time
+            FROM tick_data
+            WHERE symbol = $1 AND timestamp >= $2 AND timestamp <= $3
             "#,
-            days_to_keep
+            symbol,
+            start_time,
+            end_time
         )
         .fetch_one(&self.pool)
         .await?;
 
-        let deleted_count = result.count.unwrap_or(0) as u64;
-        info!("Cleaned up {} old tick data records", deleted_count);
-        Ok(deleted_count)
+        let (first_time, last_time) = match (bounds.first_time, bounds.last_time) {
+            (Some(first), Some(last)) => (first, last),
+            _ => {
+                debug!("No data for {} in range, whole range is a gap", symbol);
+                return Ok(vec![DataGap {
+                    start: start_time,
+                    end: end_time,
+                }]);
+            }
+        };
+
+        let min_gap_seconds = min_gap.num_milliseconds() as f64 / 1000.0;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT prev_timestamp as "gap_start!", timestamp as gap_end
+            FROM (
+                SELECT timestamp, LAG(timestamp) OVER (ORDER BY timestamp) as prev_timestamp
+                FROM tick_data
+                WHERE symbol = $1 AND timestamp >= $2 AND timestamp <= $3
+            ) t
+            WHERE prev_timestamp IS NOT NULL
+            AND EXTRACT(EPOCH FROM (timestamp - prev_timestamp))::float8 > $4::float8
+            ORDER BY timestamp
+            "#,
+            symbol,
+            start_time,
+            end_time,
+            min_gap_seconds
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut gaps = Vec::with_capacity(rows.len() + 2);
+        if first_time - start_time > min_gap {
+            gaps.push(DataGap {
+                start: start_time,
+                end: first_time,
+            });
+        }
+        gaps.extend(rows.into_iter().map(|row| DataGap {
+            start: row.gap_start,
+            end: row.gap_end,
+        }));
+        if end_time - last_time > min_gap {
+            gaps.push(DataGap {
+                start: last_time,
+                end: end_time,
+            });
+        }
+
+        debug!("Detected {} gaps for {}", gaps.len(), symbol);
+        Ok(gaps)
     }
 
-    /// Get database statistics
-    pub async fn get_db_stats(&self, symbol: Option<&str>) -> DataResult<DbStats> {
-        let (total_records, earliest_timestamp, latest_timestamp) = if let Some(sym) = symbol {
-            let row = sqlx::query!(
-                r#"
-                SELECT 
-                    COUNT(*) as total_records,
-                    MIN(timestamp) as earliest_timestamp,
-                    MAX(timestamp) as latest_timestamp
+    /// (gap_start, gap_end) of every hole longer than `max_gap` between
+    /// consecutive stored ticks of a symbol, over its whole history
+    ///
+    /// Unlike `detect_gaps` there are no range bounds, so only holes between
+    /// two ticks are reported.
+    pub async fn find_data_gaps(
+        &self,
+        symbol: &str,
+        max_gap: Duration,
+    ) -> DataResult<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        let max_gap_seconds = max_gap.num_milliseconds() as f64 / 1000.0;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT prev_timestamp as "gap_start!", timestamp as gap_end
+            FROM (
+                SELECT timestamp, LAG(timestamp) OVER (ORDER BY timestamp) as prev_timestamp
                 FROM tick_data
                 WHERE symbol = $1
-                "#,
-                sym
-            )
-            .fetch_one(&self.pool)
+            ) t
+            WHERE prev_timestamp IS NOT NULL
+            AND EXTRACT(EPOCH FROM (timestamp - prev_timestamp))::float8 > $2::float8
+            ORDER BY timestamp
+            "#,
+            symbol,
+            max_gap_seconds
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        debug!("Found {} gaps in {} history", rows.len(), symbol);
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.gap_start, row.gap_end))
+            .collect())
+    }
+
+    /// Fraction of the `timeframe` candles in [start, end] that have at least
+    /// one tick, from 0.0 (no data) to 1.0 (every candle)
+    pub async fn data_coverage_ratio(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DataResult<f64> {
+        if start > end {
+            return Err(DataError::Validation("start must not be after end".into()));
+        }
+
+        let bucket = timeframe.as_duration();
+        let expected = (timeframe.align_timestamp(end) - timeframe.align_timestamp(start))
+            .num_seconds()
+            / bucket.num_seconds()
+            + 1;
+
+        let actual = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(DISTINCT tick_bucket(timestamp, $4, $5)) as "count!"
+            FROM tick_data
+            WHERE symbol = $1 AND timestamp >= $2 AND timestamp <= $3
+            "#,
+            symbol,
+            start,
+            end,
+            bucket.num_seconds() as f64,
+            bucket_origin_secs(timeframe)
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(actual as f64 / expected as f64)
+    }
+
+    // =================================================================
+    // Maintenance Operations
+    // =================================================================
+
+    /// Clean up tick data older than `days_to_keep` before the repository clock
+    pub async fn cleanup_old_data(&self, days_to_keep: f64) -> DataResult<u64> {
+        info!("Cleaning up tick data older than {} days", days_to_keep);
+        let cutoff =
+            self.clock.now() - Duration::milliseconds((days_to_keep * 86_400_000.0) as i64);
+
+        let result = sqlx::query!(
+            r#"
+            WITH deleted AS (
+                DELETE FROM tick_data
+                WHERE timestamp < $1
+                RETURNING *
+            )
+            SELECT COUNT(*) as count
+            FROM deleted
+            "#,
+            cutoff
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let deleted_count = result.count.unwrap_or(0) as u64;
+        info!("Cleaned up {} old tick data records", deleted_count);
+        Ok(deleted_count)
+    }
+
+    /// Remove ticks sharing `(symbol, trade_id, timestamp)`, keeping one of each
+    ///
+    /// Duplicates can only exist if data was loaded before `idx_tick_unique`
+    /// was created. Returns the number of rows removed.
+    pub async fn deduplicate(&self, symbol: &str) -> DataResult<u64> {
+        let removed = Self::delete_duplicates(&self.pool, symbol).await?;
+        info!("Removed {} duplicate ticks for {}", removed, symbol);
+        Ok(removed)
+    }
+
+    /// Dry run of `deduplicate`: the number of rows it would remove
+    pub async fn count_duplicates(&self, symbol: &str) -> DataResult<u64> {
+        Self::count_duplicate_rows(&self.pool, symbol).await
+    }
+
+    async fn delete_duplicates<'e, E: PgExecutor<'e>>(
+        executor: E,
+        symbol: &str,
+    ) -> DataResult<u64> {
+        // Keep the physically first copy; each later copy joins against it
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM tick_data a
+            USING tick_data b
+            WHERE a.symbol = $1
+              AND b.symbol = a.symbol
+              AND b.trade_id = a.trade_id
+              AND b.timestamp = a.timestamp
+              AND b.ctid < a.ctid
+            "#,
+            symbol
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn count_duplicate_rows<'e, E: PgExecutor<'e>>(
+        executor: E,
+        symbol: &str,
+    ) -> DataResult<u64> {
+        let row = sqlx::query!(
+            r#"
+            SELECT COUNT(*) - COUNT(DISTINCT (trade_id, timestamp)) as "duplicates!"
+            FROM tick_data
+            WHERE symbol = $1
+            "#,
+            symbol
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(row.duplicates as u64)
+    }
+
+    /// Get database statistics
+    pub async fn get_db_stats(&self, symbol: Option<&str>) -> DataResult<DbStats> {
+        let (total_records, earliest_timestamp, latest_timestamp) = if let Some(sym) = symbol {
+            let row = sqlx::query!(
+                r#"
+                SELECT 
+                    COUNT(*) as total_records,
+                    MIN(timestamp) as earliest_timestamp,
+                    MAX(timestamp) as latest_timestamp
+                FROM tick_data
+                WHERE symbol = $1
+                "#,
+                sym
+            )
+            .fetch_one(&self.pool)
             .await?;
 
             (
@@ -615,10 +1603,8 @@ impl TickDataRepository {
     /// Check if query is for recent data (suitable for cache)
     fn is_recent_query(&self, query: &TickQuery) -> bool {
         if let Some(start_time) = query.start_time {
-            let now = Utc::now();
-            let duration = now - start_time;
-            // Consider "recent" if within last hour
-            duration <= Duration::hours(1)
+            let now = self.clock.now();
+            now - start_time <= self.freshness_window
         } else {
             // If no start time specified, assume it's a recent query
             true
@@ -649,6 +1635,246 @@ impl TickDataRepository {
         Ok(())
     }
 
+    /// Insert many strategy log rows with one statement per chunk
+    pub async fn batch_insert_live_strategy_logs(
+        &self,
+        logs: &[LiveStrategyLog],
+    ) -> DataResult<usize> {
+        let mut inserted = 0;
+        for chunk in logs.chunks(MAX_BATCH_SIZE) {
+            let mut query_builder = QueryBuilder::new(
+                "INSERT INTO live_strategy_log (timestamp, strategy_id, symbol, current_price, signal_type, portfolio_value, total_pnl, cache_hit, processing_time_us) ",
+            );
+            query_builder.push_values(chunk, |mut b, log| {
+                b.push_bind(log.timestamp)
+                    .push_bind(&log.strategy_id)
+                    .push_bind(&log.symbol)
+                    .push_bind(log.current_price)
+                    .push_bind(&log.signal_type)
+                    .push_bind(log.portfolio_value)
+                    .push_bind(log.total_pnl)
+                    .push_bind(log.cache_hit)
+                    .push_bind(log.processing_time_us as i32);
+            });
+
+            let result = query_builder.build().execute(&self.pool).await?;
+            inserted += result.rows_affected() as usize;
+        }
+
+        Ok(inserted)
+    }
+
+    /// Persist a periodic P&L snapshot of a paper trading session
+    pub async fn insert_live_pnl_snapshot(&self, snapshot: &LivePnlSnapshot) -> DataResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO live_pnl_snapshot
+            (session_id, strategy_id, timestamp, portfolio_value,
+             realized_pnl, unrealized_pnl, position_count)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            snapshot.session_id,
+            snapshot.strategy_id,
+            snapshot.timestamp,
+            snapshot.portfolio_value,
+            snapshot.realized_pnl,
+            snapshot.unrealized_pnl,
+            snapshot.position_count as i32
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// All P&L snapshots of a session in time order, for charting equity
+    pub async fn get_live_pnl_snapshots(
+        &self,
+        session_id: &str,
+    ) -> DataResult<Vec<LivePnlSnapshot>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT session_id, strategy_id, timestamp, portfolio_value,
+                   realized_pnl, unrealized_pnl, position_count
+            FROM live_pnl_snapshot
+            WHERE session_id = $1
+            ORDER BY timestamp ASC
+            "#,
+            session_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| LivePnlSnapshot {
+                session_id: row.session_id,
+                strategy_id: row.strategy_id,
+                timestamp: row.timestamp,
+                portfolio_value: row.portfolio_value,
+                realized_pnl: row.realized_pnl,
+                unrealized_pnl: row.unrealized_pnl,
+                position_count: row.position_count as u32,
+            })
+            .collect())
+    }
+
+    /// Store the summary and trades of a completed backtest, returning its row id
+    pub async fn save_backtest_result(&self, result: &NewBacktestResult) -> DataResult<i64> {
+        let total_trades = i32::try_from(result.total_trades).map_err(|_| {
+            DataError::Validation(format!("Too many trades to store: {}", result.total_trades))
+        })?;
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO backtest_results
+            (strategy_id, strategy_name, symbol, initial_capital, final_value,
+             return_percentage, sharpe_ratio, max_drawdown, total_trades, trades)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10::text::jsonb)
+            RETURNING id
+            "#,
+            result.strategy_id,
+            result.strategy_name,
+            result.symbol,
+            result.initial_capital,
+            result.final_value,
+            result.return_percentage,
+            result.sharpe_ratio,
+            result.max_drawdown,
+            total_trades,
+            result.trades.to_string()
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Stored backtest results, newest first, optionally for one symbol only
+    pub async fn get_backtest_results(
+        &self,
+        symbol: Option<&str>,
+    ) -> DataResult<Vec<StoredBacktestResult>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, created_at, strategy_id, strategy_name, symbol, initial_capital,
+                   final_value, return_percentage, sharpe_ratio, max_drawdown,
+                   total_trades, trades::text AS "trades!"
+            FROM backtest_results
+            WHERE $1::text IS NULL OR symbol = $1
+            ORDER BY created_at DESC, id DESC
+            "#,
+            symbol
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(StoredBacktestResult {
+                    id: row.id,
+                    created_at: row.created_at,
+                    strategy_id: row.strategy_id,
+                    strategy_name: row.strategy_name,
+                    symbol: row.symbol,
+                    initial_capital: row.initial_capital,
+                    final_value: row.final_value,
+                    return_percentage: row.return_percentage,
+                    sharpe_ratio: row.sharpe_ratio,
+                    max_drawdown: row.max_drawdown,
+                    total_trades: u32::try_from(row.total_trades).map_err(|_| {
+                        DataError::InvalidFormat(format!(
+                            "Negative total_trades in backtest result {}",
+                            row.id
+                        ))
+                    })?,
+                    trades: serde_json::from_str(&row.trades)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Insert or replace a symbol's trading rules
+    pub async fn upsert_symbol_metadata(&self, metadata: &SymbolMetadata) -> DataResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO symbol_metadata
+            (symbol, base_asset, quote_asset, tick_size, step_size, min_notional, is_active)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (symbol) DO UPDATE SET
+                base_asset = EXCLUDED.base_asset,
+                quote_asset = EXCLUDED.quote_asset,
+                tick_size = EXCLUDED.tick_size,
+                step_size = EXCLUDED.step_size,
+                min_notional = EXCLUDED.min_notional,
+                is_active = EXCLUDED.is_active,
+                updated_at = NOW()
+            "#,
+            metadata.symbol,
+            metadata.base_asset,
+            metadata.quote_asset,
+            metadata.tick_size,
+            metadata.step_size,
+            metadata.min_notional,
+            metadata.is_active
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Trading rules of one symbol, if stored
+    pub async fn get_symbol_metadata(&self, symbol: &str) -> DataResult<Option<SymbolMetadata>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT symbol, base_asset, quote_asset, tick_size, step_size, min_notional, is_active
+            FROM symbol_metadata
+            WHERE symbol = $1
+            "#,
+            symbol
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| SymbolMetadata {
+            symbol: row.symbol,
+            base_asset: row.base_asset,
+            quote_asset: row.quote_asset,
+            tick_size: row.tick_size,
+            step_size: row.step_size,
+            min_notional: row.min_notional,
+            is_active: row.is_active,
+        }))
+    }
+
+    /// Trading rules of every stored symbol, optionally only active ones
+    pub async fn list_symbol_metadata(&self, active_only: bool) -> DataResult<Vec<SymbolMetadata>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT symbol, base_asset, quote_asset, tick_size, step_size, min_notional, is_active
+            FROM symbol_metadata
+            WHERE is_active OR NOT $1
+            ORDER BY symbol
+            "#,
+            active_only
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SymbolMetadata {
+                symbol: row.symbol,
+                base_asset: row.base_asset,
+                quote_asset: row.quote_asset,
+                tick_size: row.tick_size,
+                step_size: row.step_size,
+                min_notional: row.min_notional,
+                is_active: row.is_active,
+            })
+            .collect())
+    }
+
     /// Generate OHLC data from tick data for a specific time range
     pub async fn generate_ohlc_from_ticks(
         &self,
@@ -657,6 +1883,7 @@ impl TickDataRepository {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
         limit: Option<i64>,
+        fill_empty: bool,
     ) -> DataResult<Vec<OHLCData>> {
         debug!(
             "Generating OHLC data: {} {} from {} to {}",
@@ -713,6 +1940,10 @@ impl TickDataRepository {
         // Sort OHLC data by timestamp
         ohlc_data.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
 
+        if fill_empty {
+            ohlc_data = Self::fill_empty_candles(ohlc_data, timeframe);
+        }
+
         debug!(
             "Generated {} OHLC candles for {} {}",
             ohlc_data.len(),
@@ -723,85 +1954,331 @@ impl TickDataRepository {
         Ok(ohlc_data)
     }
 
-    // Time-based query operations for OHLC generation
-
-    /// Get ticks for a specific time duration (ordered by time ASC)
-    pub async fn get_ticks_for_timespan(
+    /// Gap-free candle series covering every `timeframe` bucket from
+    /// `start_time` to `end_time`, both aligned down to the bucket
+    ///
+    /// Every candle covers its whole bucket, including trades before
+    /// `start_time` in the first one and after `end_time` in the last one.
+    /// Buckets without trades are forward-filled with a flat candle:
+    /// open = high = low = close = the previous close, zero volume and zero
+    /// trades. Leading empty buckets take the close of the last trade before
+    /// the range; if the symbol has no earlier trade, the series starts at
+    /// the first bucket with trades instead.
+    pub async fn get_continuous_ohlc(
         &self,
         symbol: &str,
-        duration_hours: i64,
-    ) -> DataResult<Vec<TickData>> {
-        let end_time = Utc::now();
-        let start_time = end_time - Duration::hours(duration_hours);
+        timeframe: Timeframe,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> DataResult<Vec<OHLCData>> {
+        let aligned_start = timeframe.align_timestamp(start_time);
+        let aligned_end = timeframe.align_timestamp(end_time);
 
+        // Aggregated in the database, so no tick limit can cut a bucket short
         let rows = sqlx::query!(
             r#"
-        SELECT timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker
-        FROM tick_data 
-        WHERE symbol = $1
-        AND timestamp >= $2 
-        AND timestamp <= $3
-        ORDER BY timestamp ASC
-        "#,
+            SELECT
+                tick_bucket(timestamp, $4, $5) as "bucket!",
+                (array_agg(price ORDER BY timestamp, trade_id))[1] as "open!",
+                MAX(price) as "high!",
+                MIN(price) as "low!",
+                (array_agg(price ORDER BY timestamp DESC, trade_id DESC))[1] as "close!",
+                SUM(quantity) as "volume!",
+                SUM(price * quantity) as "quote_volume!",
+                COUNT(*) as "trade_count!"
+            FROM tick_data
+            WHERE symbol = $1 AND timestamp >= $2 AND timestamp < $3
+            GROUP BY 1
+            ORDER BY 1
+            "#,
             symbol,
-            start_time,
-            end_time
+            aligned_start,
+            aligned_end + timeframe.as_duration(),
+            timeframe.as_duration().num_seconds() as f64,
+            bucket_origin_secs(timeframe)
         )
         .fetch_all(&self.pool)
         .await?;
-
-        let ticks: DataResult<Vec<TickData>> = rows
-            .iter()
+        let candles = rows
+            .into_iter()
             .map(|row| {
-                Ok(TickData {
-                    timestamp: row.timestamp,
-                    symbol: row.symbol.clone(),
-                    price: row.price,
-                    quantity: row.quantity,
-                    side: self.parse_trade_side(&row.side)?,
-                    trade_id: row.trade_id.clone(),
-                    is_buyer_maker: row.is_buyer_maker,
-                })
+                OHLCData::new(
+                    row.bucket,
+                    symbol.to_string(),
+                    timeframe,
+                    row.open,
+                    row.high,
+                    row.low,
+                    row.close,
+                    row.volume,
+                    row.quote_volume,
+                    row.trade_count as u64,
+                )
             })
             .collect();
 
-        ticks
+        let previous_close = sqlx::query_scalar!(
+            r#"
+            SELECT price
+            FROM tick_data
+            WHERE symbol = $1 AND timestamp < $2
+            ORDER BY timestamp DESC
+            LIMIT 1
+            "#,
+            symbol,
+            aligned_start
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(Self::fill_candle_range(
+            candles,
+            symbol,
+            timeframe,
+            aligned_start,
+            aligned_end,
+            previous_close,
+        ))
     }
 
-    /// Get ticks for a specific time duration with record limit
-    pub async fn get_ticks_for_timespan_limited(
+    /// Annualized realized volatility of `symbol` over [start_time, end_time]
+    ///
+    /// Prices are sampled as the last trade in each `sampling` bucket, with
+    /// empty buckets carrying the previous price forward, and the sample
+    /// standard deviation of the log returns is scaled to a 365-day year
+    /// (crypto trades around the clock). Needs at least two returns.
+    pub async fn realized_volatility(
         &self,
         symbol: &str,
-        duration_hours: i64,
-        max_records: i64,
-    ) -> DataResult<Vec<TickData>> {
-        let end_time = Utc::now();
-        let start_time = end_time - Duration::hours(duration_hours);
-        let limit = max_records.min(MAX_QUERY_LIMIT as i64);
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        sampling: Timeframe,
+    ) -> DataResult<f64> {
+        if start_time >= end_time {
+            return Err(DataError::Validation(
+                "start_time must be before end_time".into(),
+            ));
+        }
+
+        let interval_seconds = sampling.as_duration().num_seconds() as f64;
 
         let rows = sqlx::query!(
             r#"
-        SELECT timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker
-        FROM tick_data 
-        WHERE symbol = $1
-        AND timestamp >= $2 
-        AND timestamp <= $3
-        ORDER BY timestamp ASC
-        LIMIT $4
-        "#,
+            SELECT DISTINCT ON (bucket) bucket as "bucket!", price
+            FROM (
+                SELECT tick_bucket(timestamp, $4, $5) as bucket, timestamp, price
+                FROM tick_data
+                WHERE symbol = $1 AND timestamp >= $2 AND timestamp <= $3
+            ) t
+            ORDER BY bucket, timestamp DESC
+            "#,
             symbol,
             start_time,
             end_time,
-            limit
+            interval_seconds,
+            bucket_origin_secs(sampling)
         )
         .fetch_all(&self.pool)
         .await?;
 
-        let ticks: DataResult<Vec<TickData>> = rows
-            .iter()
-            .map(|row| {
-                Ok(TickData {
-                    timestamp: row.timestamp,
+        // Carry the last price through empty buckets
+        let step = sampling.as_duration();
+        let mut samples: Vec<f64> = Vec::with_capacity(rows.len());
+        let mut previous: Option<(DateTime<Utc>, f64)> = None;
+        for row in rows {
+            let price = row
+                .price
+                .to_string()
+                .parse::<f64>()
+                .map_err(|e| DataError::InvalidFormat(format!("Invalid price: {}", e)))?;
+            if let Some((mut bucket, last_price)) = previous {
+                bucket += step;
+                while bucket < row.bucket {
+                    samples.push(last_price);
+                    bucket += step;
+                }
+            }
+            samples.push(price);
+            previous = Some((row.bucket, price));
+        }
+
+        let returns: Vec<f64> = samples.windows(2).map(|w| (w[1] / w[0]).ln()).collect();
+        if returns.len() < 2 {
+            return Err(DataError::Validation(format!(
+                "Need at least 3 {} samples for realized volatility of {}, got {}",
+                sampling.as_str(),
+                symbol,
+                samples.len()
+            )));
+        }
+
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let periods_per_year = Duration::days(365).num_seconds() as f64 / interval_seconds;
+
+        Ok((variance * periods_per_year).sqrt())
+    }
+
+    // Time-based query operations for OHLC generation
+
+    /// Insert flat candles (O=H=L=C = previous close, zero volume) for empty
+    /// buckets between the first and last candle of a sorted series
+    fn fill_empty_candles(candles: Vec<OHLCData>, timeframe: Timeframe) -> Vec<OHLCData> {
+        let step = timeframe.as_duration();
+        let mut filled: Vec<OHLCData> = Vec::with_capacity(candles.len());
+
+        for candle in candles {
+            if let Some(prev) = filled.last().cloned() {
+                let mut bucket = prev.timestamp + step;
+                while bucket < candle.timestamp {
+                    filled.push(Self::flat_candle(
+                        &prev.symbol,
+                        timeframe,
+                        bucket,
+                        prev.close,
+                    ));
+                    bucket += step;
+                }
+            }
+            filled.push(candle);
+        }
+
+        filled
+    }
+
+    /// One candle per bucket from `start` to `end` (aligned, inclusive) out of
+    /// the sorted candles within them, forward-filling empty buckets from
+    /// `previous_close` and then from each candle's close
+    fn fill_candle_range(
+        candles: Vec<OHLCData>,
+        symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        mut previous_close: Option<Decimal>,
+    ) -> Vec<OHLCData> {
+        let step = timeframe.as_duration();
+        let mut candles = candles.into_iter().peekable();
+        let mut filled = Vec::new();
+
+        let mut bucket = start;
+        while bucket <= end {
+            match candles.next_if(|candle| candle.timestamp == bucket) {
+                Some(candle) => {
+                    previous_close = Some(candle.close);
+                    filled.push(candle);
+                }
+                None => {
+                    if let Some(close) = previous_close {
+                        filled.push(Self::flat_candle(symbol, timeframe, bucket, close));
+                    }
+                }
+            }
+            bucket += step;
+        }
+
+        filled
+    }
+
+    /// Candle of a bucket without trades, flat at `close`
+    fn flat_candle(
+        symbol: &str,
+        timeframe: Timeframe,
+        timestamp: DateTime<Utc>,
+        close: Decimal,
+    ) -> OHLCData {
+        OHLCData::new(
+            timestamp,
+            symbol.to_string(),
+            timeframe,
+            close,
+            close,
+            close,
+            close,
+            Decimal::ZERO,
+            Decimal::ZERO,
+            0,
+        )
+    }
+
+    /// Get ticks for a specific time duration (ordered by time ASC)
+    pub async fn get_ticks_for_timespan(
+        &self,
+        symbol: &str,
+        duration_hours: i64,
+    ) -> DataResult<Vec<TickData>> {
+        let end_time = self.clock.now();
+        let start_time = end_time - Duration::hours(duration_hours);
+
+        let rows = sqlx::query!(
+            r#"
+        SELECT timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker
+        FROM tick_data 
+        WHERE symbol = $1
+        AND timestamp >= $2 
+        AND timestamp <= $3
+        ORDER BY timestamp ASC
+        "#,
+            symbol,
+            start_time,
+            end_time
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let ticks: DataResult<Vec<TickData>> = rows
+            .iter()
+            .map(|row| {
+                Ok(TickData {
+                    timestamp: row.timestamp,
+                    symbol: row.symbol.clone(),
+                    price: row.price,
+                    quantity: row.quantity,
+                    side: self.parse_trade_side(&row.side)?,
+                    trade_id: row.trade_id.clone(),
+                    is_buyer_maker: row.is_buyer_maker,
+                })
+            })
+            .collect();
+
+        ticks
+    }
+
+    /// Get ticks for a specific time duration with record limit
+    pub async fn get_ticks_for_timespan_limited(
+        &self,
+        symbol: &str,
+        duration_hours: i64,
+        max_records: i64,
+    ) -> DataResult<Vec<TickData>> {
+        let end_time = self.clock.now();
+        let start_time = end_time - Duration::hours(duration_hours);
+        let limit = max_records.min(MAX_QUERY_LIMIT as i64);
+
+        let rows = sqlx::query!(
+            r#"
+        SELECT timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker
+        FROM tick_data 
+        WHERE symbol = $1
+        AND timestamp >= $2 
+        AND timestamp <= $3
+        ORDER BY timestamp ASC
+        LIMIT $4
+        "#,
+            symbol,
+            start_time,
+            end_time,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let ticks: DataResult<Vec<TickData>> = rows
+            .iter()
+            .map(|row| {
+                Ok(TickData {
+                    timestamp: row.timestamp,
                     symbol: row.symbol.clone(),
                     price: row.price,
                     quantity: row.quantity,
@@ -815,50 +2292,123 @@ impl TickDataRepository {
         ticks
     }
 
-    /// Generate recent OHLC data for backtesting with time-based approach
+    /// Latest `candle_count` candles for backtesting (chronological), with a
+    /// note on how many of them the stored ticks could fill
+    ///
+    /// The first pass reads the most recent ticks of a window sized for the
+    /// request. While that builds too few candles, the window and the tick
+    /// budget double, until enough candles are built, the window reaches the
+    /// symbol's first tick, or `MAX_OHLC_WIDENINGS` is hit.
     pub async fn generate_recent_ohlc_for_backtest(
         &self,
         symbol: &str,
         timeframe: Timeframe,
         candle_count: u32,
-    ) -> DataResult<Vec<OHLCData>> {
-        // Calculate required time duration
-        let duration_hours = calculate_required_duration_hours(timeframe, candle_count);
-
-        // Set reasonable limits for different timeframes
-        let max_ticks = match timeframe {
-            Timeframe::OneMinute | Timeframe::FiveMinutes => 50000,
-            Timeframe::FifteenMinutes | Timeframe::ThirtyMinutes => 100000,
-            Timeframe::OneHour => 200000,
-            Timeframe::FourHours => 500000,
-            Timeframe::OneDay => 1000000,
-            Timeframe::OneWeek => 2000000,
-        };
+    ) -> DataResult<(Vec<OHLCData>, OhlcCoverage)> {
+        let wanted = candle_count as usize;
+        let end_time = self.clock.now();
+        let earliest = sqlx::query_scalar!(
+            "SELECT MIN(timestamp) FROM tick_data WHERE symbol = $1",
+            symbol
+        )
+        .fetch_one(&self.pool)
+        .await?;
 
-        // Get ticks for the calculated time duration
-        let recent_ticks = self
-            .get_ticks_for_timespan_limited(symbol, duration_hours, max_ticks)
-            .await?;
+        // 20% buffer for data gaps
+        let mut span = timeframe.as_duration() * candle_count as i32 * 6 / 5;
+        let mut budget = (candle_count as i64 * OHLC_TICKS_PER_CANDLE).clamp(1, MAX_OHLC_TICKS);
+        let mut candles = Vec::new();
+
+        if let Some(earliest) = earliest {
+            for pass in 0..=MAX_OHLC_WIDENINGS {
+                let start_time = end_time - span;
+                let ticks = self
+                    .get_latest_ticks_between(symbol, start_time, end_time, budget)
+                    .await?;
+                let truncated = ticks.len() as i64 >= budget;
+
+                candles = candles_from_ticks(&ticks, timeframe);
+                if truncated && !candles.is_empty() {
+                    // The budget cut into the oldest bucket, so it is incomplete
+                    candles.remove(0);
+                }
 
-        if recent_ticks.is_empty() {
-            return Ok(Vec::new());
+                let exhausted = if truncated {
+                    budget >= MAX_OHLC_TICKS
+                } else {
+                    start_time <= earliest
+                };
+                if candles.len() >= wanted || exhausted {
+                    break;
+                }
+                debug!(
+                    "Pass {} built {} of {} {} candles for {}, widening",
+                    pass + 1,
+                    candles.len(),
+                    wanted,
+                    timeframe.as_str(),
+                    symbol
+                );
+                span = span * 2;
+                budget = (budget * 2).min(MAX_OHLC_TICKS);
+            }
         }
 
-        // Use actual data time range for OHLC generation
-        let start_time = recent_ticks[0].timestamp;
-        let end_time = recent_ticks[recent_ticks.len() - 1].timestamp;
+        candles.drain(..candles.len().saturating_sub(wanted));
+        let coverage = OhlcCoverage {
+            timeframe,
+            requested: candle_count,
+            actual: candles.len(),
+            first: candles.first().map(|c| c.timestamp),
+            last: candles.last().map(|c| c.timestamp),
+        };
+        if !coverage.is_complete() {
+            warn!("Short OHLC data for {}: {}", symbol, coverage);
+        }
 
-        // Generate OHLC data from tick data
-        let mut ohlc_data = self
-            .generate_ohlc_from_ticks(symbol, timeframe, start_time, end_time, None)
-            .await?;
+        Ok((candles, coverage))
+    }
 
-        // Sort by timestamp descending and take requested count
-        ohlc_data.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-        ohlc_data.truncate(candle_count as usize);
-        ohlc_data.reverse(); // Return in chronological order
+    /// Most recent `limit` ticks in [start_time, end_time], in time order
+    async fn get_latest_ticks_between(
+        &self,
+        symbol: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        limit: i64,
+    ) -> DataResult<Vec<TickData>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker
+            FROM tick_data
+            WHERE symbol = $1
+            AND timestamp >= $2
+            AND timestamp <= $3
+            ORDER BY timestamp DESC
+            LIMIT $4
+            "#,
+            symbol,
+            start_time,
+            end_time,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
-        Ok(ohlc_data)
+        rows.iter()
+            .rev()
+            .map(|row| {
+                Ok(TickData {
+                    timestamp: row.timestamp,
+                    symbol: row.symbol.clone(),
+                    price: row.price,
+                    quantity: row.quantity,
+                    side: self.parse_trade_side(&row.side)?,
+                    trade_id: row.trade_id.clone(),
+                    is_buyer_maker: row.is_buyer_maker,
+                })
+            })
+            .collect()
     }
 
     /// Get OHLC data statistics for a symbol
@@ -867,51 +2417,116 @@ impl TickDataRepository {
         symbol: &str,
         timeframe: Timeframe,
     ) -> DataResult<(u64, Option<DateTime<Utc>>, Option<DateTime<Utc>>)> {
-        // Get basic tick data info first
-        let stats = self.get_db_stats(Some(symbol)).await?;
-
-        if let (Some(earliest), Some(latest)) = (stats.earliest_timestamp, stats.latest_timestamp) {
-            // Align to timeframe boundaries
-            let aligned_earliest = timeframe.align_timestamp(earliest);
-            let aligned_latest = timeframe.align_timestamp(latest);
+        let bucket_secs = timeframe.as_duration().num_seconds() as f64;
 
-            // Calculate approximate number of candles
-            let duration_diff = aligned_latest - aligned_earliest;
-            let timeframe_duration = timeframe.as_duration();
-
-            let estimated_candles = if timeframe_duration.num_seconds() > 0 {
-                (duration_diff.num_seconds() / timeframe_duration.num_seconds()) as u64
-            } else {
-                0
-            };
+        // Only buckets that contain ticks become candles, so count those
+        // rather than dividing the covered duration by the timeframe
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(DISTINCT bucket) as "candles!",
+                MIN(bucket) as earliest,
+                MAX(bucket) as latest
+            FROM (
+                SELECT tick_bucket(timestamp, $2, $3) as bucket
+                FROM tick_data
+                WHERE symbol = $1
+            ) buckets
+            "#,
+            symbol,
+            bucket_secs,
+            bucket_origin_secs(timeframe)
+        )
+        .fetch_one(&self.pool)
+        .await?;
 
-            Ok((
-                estimated_candles,
-                Some(aligned_earliest),
-                Some(aligned_latest),
-            ))
-        } else {
-            Ok((0, None, None))
-        }
+        Ok((row.candles as u64, row.earliest, row.latest))
     }
 }
 
-/// Calculate required time duration based on timeframe and candle count
-fn calculate_required_duration_hours(timeframe: Timeframe, candle_count: u32) -> i64 {
-    let base_hours = match timeframe {
-        Timeframe::OneMinute => 1,
-        Timeframe::FiveMinutes => 1,
-        Timeframe::FifteenMinutes => 1,
-        Timeframe::ThirtyMinutes => 1,
-        Timeframe::OneHour => 1,
-        Timeframe::FourHours => 4,
-        Timeframe::OneDay => 24,
-        Timeframe::OneWeek => 24 * 7,
+/// Arrow schema of exported ticks; nothing in `tick_data` is nullable
+fn tick_export_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new(
+            "timestamp",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("symbol", DataType::Utf8, false),
+        Field::new("price", DataType::Decimal128(20, 8), false),
+        Field::new("quantity", DataType::Decimal128(20, 8), false),
+        Field::new("side", DataType::Utf8, false),
+        Field::new("trade_id", DataType::Utf8, false),
+        Field::new("is_buyer_maker", DataType::Boolean, false),
+    ]))
+}
+
+/// Columnar batch of ticks in the `tick_export_schema` layout
+fn ticks_to_record_batch(schema: &SchemaRef, ticks: &[TickData]) -> DataResult<RecordBatch> {
+    // Unscaled value at the column scale of 8
+    let decimals = |value: fn(&TickData) -> Decimal| {
+        Decimal128Array::from_iter_values(ticks.iter().map(|tick| {
+            let mut value = value(tick);
+            value.rescale(8);
+            value.mantissa()
+        }))
+        .with_precision_and_scale(20, 8)
+        .map_err(|e| DataError::Export(e.to_string()))
     };
 
-    // Add 20% buffer for data gaps
-    let total_hours = (base_hours * candle_count as i64) as f64 * 1.2;
-    total_hours.ceil() as i64
+    RecordBatch::try_new(
+        Arc::clone(schema),
+        vec![
+            Arc::new(
+                TimestampMicrosecondArray::from_iter_values(
+                    ticks.iter().map(|tick| tick.timestamp.timestamp_micros()),
+                )
+                .with_timezone("UTC"),
+            ),
+            Arc::new(StringArray::from_iter_values(
+                ticks.iter().map(|tick| tick.symbol.as_str()),
+            )),
+            Arc::new(decimals(|tick| tick.price)?),
+            Arc::new(decimals(|tick| tick.quantity)?),
+            Arc::new(StringArray::from_iter_values(
+                ticks.iter().map(|tick| tick.side.as_db_str()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                ticks.iter().map(|tick| tick.trade_id.as_str()),
+            )),
+            Arc::new(BooleanArray::from_iter(
+                ticks.iter().map(|tick| Some(tick.is_buyer_maker)),
+            )),
+        ],
+    )
+    .map_err(|e| DataError::Export(e.to_string()))
+}
+
+/// Epoch offset in seconds of the first `timeframe` bucket, for `tick_bucket`.
+/// Buckets are aligned to the epoch, except weeks which start on Monday
+/// (1970-01-05) to match `Timeframe::align_timestamp`.
+fn bucket_origin_secs(timeframe: Timeframe) -> f64 {
+    if timeframe == Timeframe::OneWeek {
+        Duration::days(4).num_seconds() as f64
+    } else {
+        0.0
+    }
+}
+
+/// Candles of time-ordered ticks, one per `timeframe` bucket that has ticks
+fn candles_from_ticks(ticks: &[TickData], timeframe: Timeframe) -> Vec<OHLCData> {
+    ticks
+        .chunk_by(|a, b| {
+            timeframe.align_timestamp(a.timestamp) == timeframe.align_timestamp(b.timestamp)
+        })
+        .filter_map(|bucket| {
+            OHLCData::from_ticks(
+                bucket,
+                timeframe,
+                timeframe.align_timestamp(bucket[0].timestamp),
+            )
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -961,193 +2576,1200 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_insert_and_read_single_tick() {
-        let repo = create_repository().await;
-        let pool = repo.get_pool();
-        let symbol = "BTCUSDT_TEST_SINGLE";
-
-        // Clean up before test
-        cleanup_database(pool, symbol).await;
+    async fn test_cache_first_insert_does_not_wait_for_db() {
+        let repo = create_repository()
+            .await
+            .with_write_order(WriteOrder::CacheFirst);
+        let symbol = "BTC_TEST_CACHEFIRST";
+        cleanup_database(repo.get_pool(), symbol).await;
+        let tick = create_test_tick(symbol, "50000.0", "cachefirst1", None);
+
+        // An uncommitted insert of the same row makes the repository's write wait
+        let mut blocker = repo.get_pool().begin().await.unwrap();
+        sqlx::query(
+            "INSERT INTO tick_data (timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(tick.timestamp)
+        .bind(&tick.symbol)
+        .bind(tick.price)
+        .bind(tick.quantity)
+        .bind(tick.side.as_db_str())
+        .bind(&tick.trade_id)
+        .bind(tick.is_buyer_maker)
+        .execute(&mut *blocker)
+        .await
+        .unwrap();
 
-        // Insert a single tick
-        let tick = create_test_tick(symbol, "50000.0", "test1", None);
-        repo.insert_tick(&tick)
+        tokio::time::timeout(std::time::Duration::from_secs(2), repo.insert_tick(&tick))
             .await
-            .expect("Failed to insert tick");
+            .expect("cache-first insert waited for the database")
+            .unwrap();
+
+        let cached = repo.get_cache().get_recent_ticks(symbol, 10).await.unwrap();
+        assert!(cached.iter().any(|t| t.trade_id == tick.trade_id));
+        let stored = repo.get_db_stats(Some(symbol)).await.unwrap();
+        assert_eq!(stored.total_records, 0);
+
+        // Once the blocker goes away the background write lands
+        blocker.rollback().await.unwrap();
+        let mut stored = 0;
+        for _ in 0..50 {
+            stored = repo.get_db_stats(Some(symbol)).await.unwrap().total_records;
+            if stored == 1 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(stored, 1);
 
-        // Query the tick
-        let query = TickQuery {
-            symbol: symbol.to_string(),
-            limit: Some(1),
-            start_time: None,
-            end_time: None,
-            trade_side: None,
+        cleanup_database(repo.get_pool(), symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_copy_insert_falls_back_on_duplicates() {
+        let repo = create_repository().await;
+        let symbol = "BTC_TEST_COPY";
+        cleanup_database(repo.get_pool(), symbol).await;
+
+        // Sub-microsecond digits must be cut the way bound parameters are,
+        // or re-sent ticks would not match their stored copies
+        let start =
+            Utc::now().trunc_subsecs(0) - Duration::minutes(5) + Duration::nanoseconds(999_999_999);
+        let tick = |i: i64| {
+            create_test_tick(
+                symbol,
+                "50000.5",
+                &format!("copy{}", i),
+                Some(start + Duration::seconds(i)),
+            )
         };
-        let ticks = repo.get_ticks(&query).await.expect("Failed to query ticks");
 
-        // Verify
-        assert_eq!(ticks.len(), 1);
-        assert_eq!(ticks[0].symbol, symbol);
-        assert_eq!(ticks[0].price, Decimal::from_str("50000.0").unwrap());
-        assert_eq!(ticks[0].trade_id, "test1");
+        let ticks: Vec<TickData> = (0..3).map(tick).collect();
+        assert_eq!(repo.copy_insert(&ticks).await.unwrap(), 3);
+        let stored = repo
+            .get_historical_data_for_backtest(
+                symbol,
+                start - Duration::seconds(1),
+                Utc::now(),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 3);
+        assert_eq!(stored[0].price, Decimal::from_str("50000.5").unwrap());
+        assert_eq!(stored[0].timestamp, ticks[0].timestamp.trunc_subsecs(6));
+
+        // One stored tick plus a new one: COPY is rejected, INSERT adds the new one
+        assert_eq!(repo.copy_insert(&[tick(2), tick(3)]).await.unwrap(), 1);
+        let stored = repo
+            .get_historical_data_for_backtest(
+                symbol,
+                start - Duration::seconds(1),
+                Utc::now(),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 4);
+
+        cleanup_database(repo.get_pool(), symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_list_symbols_once_each() {
+        let repo = create_repository().await;
+        let symbols = ["BTC_TEST_LIST_A", "BTC_TEST_LIST_B", "BTC_TEST_LIST_C"];
+        for symbol in symbols {
+            cleanup_database(repo.get_pool(), symbol).await;
+        }
+
+        let start = Utc::now() - Duration::hours(1);
+        let ticks: Vec<TickData> = symbols
+            .iter()
+            .flat_map(|symbol| {
+                (0..5).map(move |i| {
+                    create_test_tick(
+                        symbol,
+                        "100",
+                        &format!("list{}", i),
+                        Some(start + Duration::seconds(i)),
+                    )
+                })
+            })
+            .collect();
+        repo.batch_insert_historical(ticks).await.unwrap();
+
+        let listed = repo.list_symbols().await.unwrap();
+        for symbol in symbols {
+            assert_eq!(listed.iter().filter(|s| *s == symbol).count(), 1);
+        }
+
+        for symbol in symbols {
+            cleanup_database(repo.get_pool(), symbol).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vwap_over_window_and_buckets() {
+        let repo = create_repository().await;
+        let symbol = "BTC_TEST_VWAP";
+        cleanup_database(repo.get_pool(), symbol).await;
+
+        let start = Timeframe::OneMinute.align_timestamp(Utc::now() - Duration::hours(1));
+        // (seconds after start, price, quantity): two ticks in the first
+        // minute, one in the third
+        let fills = [(0, "100", "1"), (30, "110", "3"), (150, "130", "1")];
+        let ticks: Vec<TickData> = fills
+            .iter()
+            .enumerate()
+            .map(|(i, (offset, price, quantity))| {
+                TickData::new(
+                    start + Duration::seconds(*offset),
+                    symbol.to_string(),
+                    Decimal::from_str(price).unwrap(),
+                    Decimal::from_str(quantity).unwrap(),
+                    TradeSide::Buy,
+                    format!("vwap{}", i),
+                    false,
+                )
+            })
+            .collect();
+        repo.batch_insert_historical(ticks).await.unwrap();
+
+        // (100 * 1 + 110 * 3 + 130 * 1) / 5
+        let end = start + Duration::minutes(5);
+        assert_eq!(
+            repo.get_vwap(symbol, start, end).await.unwrap(),
+            Some(Decimal::from(112))
+        );
+        // Window bounds are inclusive
+        assert_eq!(
+            repo.get_vwap(symbol, start, start + Duration::seconds(30))
+                .await
+                .unwrap(),
+            Some(Decimal::from_str("107.5").unwrap())
+        );
+        let before = start - Duration::hours(1);
+        assert_eq!(repo.get_vwap(symbol, before, before).await.unwrap(), None);
+
+        let series = repo
+            .get_vwap_series(symbol, start, end, Timeframe::OneMinute)
+            .await
+            .unwrap();
+        assert_eq!(
+            series,
+            vec![
+                (start, Decimal::from_str("107.5").unwrap()),
+                (start + Duration::minutes(2), Decimal::from(130)),
+            ]
+        );
+
+        cleanup_database(repo.get_pool(), symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_order_flow_splits_buckets_by_side() {
+        let repo = create_repository().await;
+        let symbol = "BTC_TEST_FLOW";
+        cleanup_database(repo.get_pool(), symbol).await;
+
+        let start = Timeframe::OneMinute.align_timestamp(Utc::now() - Duration::hours(1));
+        // (seconds after start, quantity, side): a mixed first minute, a
+        // sell-only second minute and a buy-only fourth minute
+        let fills = [
+            (0, "1.5", TradeSide::Buy),
+            (10, "2", TradeSide::Sell),
+            (40, "0.5", TradeSide::Buy),
+            (70, "3", TradeSide::Sell),
+            (200, "4", TradeSide::Buy),
+        ];
+        let ticks: Vec<TickData> = fills
+            .iter()
+            .enumerate()
+            .map(|(i, (offset, quantity, side))| {
+                TickData::new(
+                    start + Duration::seconds(*offset),
+                    symbol.to_string(),
+                    Decimal::from(100),
+                    Decimal::from_str(quantity).unwrap(),
+                    *side,
+                    format!("flow{}", i),
+                    *side == TradeSide::Sell,
+                )
+            })
+            .collect();
+        repo.batch_insert_historical(ticks).await.unwrap();
+
+        let bucket = |minutes: i64, buy: i64, sell: i64| OrderFlowBucket {
+            timestamp: start + Duration::minutes(minutes),
+            buy_volume: Decimal::from(buy),
+            sell_volume: Decimal::from(sell),
+            delta: Decimal::from(buy - sell),
+        };
+        let end = start + Duration::minutes(5);
+        let flow = repo
+            .get_order_flow(symbol, Timeframe::OneMinute, start, end)
+            .await
+            .unwrap();
+        assert_eq!(
+            flow,
+            vec![bucket(0, 2, 2), bucket(1, 0, 3), bucket(3, 4, 0)]
+        );
+
+        // Wider buckets merge the sides across minutes
+        let flow = repo
+            .get_order_flow(symbol, Timeframe::FiveMinutes, start, end)
+            .await
+            .unwrap();
+        let total: Decimal = flow.iter().map(|bucket| bucket.delta).sum();
+        assert_eq!(total, Decimal::ONE);
+
+        assert!(repo
+            .get_order_flow(symbol, Timeframe::OneMinute, end, start)
+            .await
+            .is_err());
+
+        cleanup_database(repo.get_pool(), symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_export_parquet_round_trip() {
+        use arrow::array::Array;
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let repo = create_repository().await;
+        let symbol = "BTC_TEST_PARQUET";
+        cleanup_database(repo.get_pool(), symbol).await;
+
+        // More than one page, with ticks sharing timestamps across the page break
+        let start = Utc::now().trunc_subsecs(6) - Duration::hours(1);
+        let count = MAX_BATCH_SIZE + 5;
+        let ticks: Vec<TickData> = (0..count)
+            .map(|i| {
+                TickData::new(
+                    start + Duration::milliseconds((i / 2) as i64),
+                    symbol.to_string(),
+                    Decimal::from_str("50000.12345678").unwrap() + Decimal::from(i as i64),
+                    Decimal::from_str("0.001").unwrap(),
+                    if i % 3 == 1 {
+                        TradeSide::Sell
+                    } else {
+                        TradeSide::Buy
+                    },
+                    format!("pq{:05}", i),
+                    i % 3 == 1,
+                )
+            })
+            .collect();
+        repo.batch_insert_historical(ticks.clone()).await.unwrap();
+
+        let path =
+            std::env::temp_dir().join(format!("ticks_export_{}.parquet", std::process::id()));
+        let end = start + Duration::hours(1);
+        let written = repo
+            .export_parquet(symbol, start, end, &path)
+            .await
+            .unwrap();
+        assert_eq!(written, count as u64);
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+        let batch = arrow::compute::concat_batches(&batches[0].schema(), &batches).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(batch.schema(), tick_export_schema());
+        assert_eq!(batch.num_rows(), count);
+        let column = |name: &str| batch.column_by_name(name).unwrap();
+        let timestamps = column("timestamp")
+            .as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .unwrap();
+        let prices = column("price")
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .unwrap();
+        let sides = column("side")
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let trade_ids = column("trade_id")
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let makers = column("is_buyer_maker")
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .unwrap();
+        assert_eq!(prices.null_count(), 0);
+
+        // Exported in (timestamp, trade_id) order, which is insertion order here
+        for (i, tick) in ticks.iter().enumerate() {
+            assert_eq!(timestamps.value(i), tick.timestamp.timestamp_micros());
+            assert_eq!(
+                Decimal::from_i128_with_scale(prices.value(i), 8),
+                tick.price
+            );
+            assert_eq!(sides.value(i), tick.side.as_db_str());
+            assert_eq!(trade_ids.value(i), tick.trade_id);
+            assert_eq!(makers.value(i), tick.is_buyer_maker);
+        }
+
+        assert!(repo
+            .export_parquet(symbol, end, start, &path)
+            .await
+            .is_err());
+        cleanup_database(repo.get_pool(), symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_price_percentiles() {
+        let repo = create_repository().await;
+        let symbol = "BTC_TEST_PCTILE";
+        cleanup_database(repo.get_pool(), symbol).await;
+
+        // Prices 100..=200 in shuffled order
+        let start = Utc::now() - Duration::hours(1);
+        let ticks: Vec<TickData> = (0..=100)
+            .map(|i| {
+                let price = 100 + (i * 37) % 101;
+                create_test_tick(
+                    symbol,
+                    &price.to_string(),
+                    &format!("pct{}", i),
+                    Some(start + Duration::seconds(i)),
+                )
+            })
+            .collect();
+        repo.batch_insert_historical(ticks).await.unwrap();
+
+        let end = start + Duration::seconds(100);
+        let bands = repo
+            .price_percentiles(symbol, start, end, &[0.0, 0.5, 0.9, 1.0])
+            .await
+            .unwrap();
+        assert_eq!(
+            bands,
+            vec![
+                (0.0, Decimal::from(100)),
+                (0.5, Decimal::from(150)),
+                (0.9, Decimal::from(190)),
+                (1.0, Decimal::from(200)),
+            ]
+        );
+
+        assert!(repo
+            .price_percentiles(symbol, start, end, &[0.5, 1.5])
+            .await
+            .is_err());
+        let before = start - Duration::hours(1);
+        assert!(matches!(
+            repo.price_percentiles(symbol, before, before, &[0.5]).await,
+            Err(DataError::NotFound(_))
+        ));
+
+        cleanup_database(repo.get_pool(), symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_read_single_tick() {
+        let repo = create_repository().await;
+        let pool = repo.get_pool();
+        let symbol = "BTCUSDT_TEST_SINGLE";
+
+        // Clean up before test
+        cleanup_database(pool, symbol).await;
+
+        // Insert a single tick
+        let tick = create_test_tick(symbol, "50000.0", "test1", None);
+        repo.insert_tick(&tick)
+            .await
+            .expect("Failed to insert tick");
+
+        // Query the tick
+        let query = TickQuery {
+            symbol: symbol.to_string(),
+            limit: Some(1),
+            start_time: None,
+            end_time: None,
+            trade_side: None,
+            read_strategy: ReadStrategy::Auto,
+        };
+        let ticks = repo.get_ticks(&query).await.expect("Failed to query ticks");
+
+        // Verify
+        assert_eq!(ticks.len(), 1);
+        assert_eq!(ticks[0].symbol, symbol);
+        assert_eq!(ticks[0].price, Decimal::from_str("50000.0").unwrap());
+        assert_eq!(ticks[0].trade_id, "test1");
+
+        // Clean up
+        cleanup_database(pool, symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_batch_insert_and_read() {
+        let repo = create_repository().await;
+        let pool = repo.get_pool();
+        let symbol = "BTCUSDT_TEST_BATCH";
+
+        // Clean up before test
+        cleanup_database(pool, symbol).await;
+
+        // Prepare batch ticks
+        let ticks = vec![
+            create_test_tick(symbol, "50000.0", "batch1", None),
+            create_test_tick(symbol, "51000.0", "batch2", None),
+            create_test_tick(symbol, "52000.0", "batch3", None),
+        ];
+
+        // Batch insert
+        let inserted_count = repo
+            .batch_insert(ticks.clone())
+            .await
+            .expect("Failed to batch insert");
+        assert_eq!(inserted_count, 3);
+
+        // Query ticks
+        let query = TickQuery {
+            symbol: symbol.to_string(),
+            limit: Some(3),
+            start_time: None,
+            end_time: None,
+            trade_side: None,
+            read_strategy: ReadStrategy::Auto,
+        };
+        let queried_ticks = repo.get_ticks(&query).await.expect("Failed to query ticks");
+
+        // Verify
+        assert_eq!(queried_ticks.len(), 3);
+        assert!(queried_ticks
+            .iter()
+            .any(|t| t.trade_id == "batch1" && t.price == Decimal::from_str("50000.0").unwrap()));
+        assert!(queried_ticks
+            .iter()
+            .any(|t| t.trade_id == "batch2" && t.price == Decimal::from_str("51000.0").unwrap()));
+        assert!(queried_ticks
+            .iter()
+            .any(|t| t.trade_id == "batch3" && t.price == Decimal::from_str("52000.0").unwrap()));
+
+        // Clean up
+        cleanup_database(pool, symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_cache_read_write() {
+        let repo = create_repository().await;
+        let pool = repo.get_pool();
+        let cache = repo.get_cache();
+        let symbol = "BTCUSDT_TEST_CACHE";
+
+        // Clean up before test
+        cleanup_database(pool, symbol).await;
+        cache
+            .clear_symbol(symbol)
+            .await
+            .expect("Failed to clear cache");
+
+        // Insert a tick
+        let tick = create_test_tick(symbol, "50000.0", "cache1", None);
+        repo.insert_tick(&tick)
+            .await
+            .expect("Failed to insert tick");
+
+        // Query from cache
+        let cached_ticks = cache
+            .get_recent_ticks(symbol, 1)
+            .await
+            .expect("Failed to read from cache");
+        assert_eq!(cached_ticks.len(), 1);
+        assert_eq!(cached_ticks[0].symbol, symbol);
+        assert_eq!(cached_ticks[0].price, Decimal::from_str("50000.0").unwrap());
+        assert_eq!(cached_ticks[0].trade_id, "cache1");
+
+        // Query via get_ticks (should hit cache for recent data)
+        let query = TickQuery {
+            symbol: symbol.to_string(),
+            limit: Some(1),
+            start_time: Some(Utc::now() - Duration::hours(1)),
+            end_time: None,
+            trade_side: None,
+            read_strategy: ReadStrategy::Auto,
+        };
+        let ticks = repo.get_ticks(&query).await.expect("Failed to query ticks");
+        assert_eq!(ticks.len(), 1);
+        assert_eq!(ticks[0].symbol, symbol);
+        assert_eq!(ticks[0].price, Decimal::from_str("50000.0").unwrap());
+
+        // Clean up
+        cleanup_database(pool, symbol).await;
+        cache
+            .clear_symbol(symbol)
+            .await
+            .expect("Failed to clear cache");
+    }
+
+    #[tokio::test]
+    async fn test_latest_price() {
+        let repo = create_repository().await;
+        let pool = repo.get_pool();
+        let symbol = "BTCUSDT_TEST_PRICE";
+
+        // Clean up before test
+        cleanup_database(pool, symbol).await;
+
+        // Insert ticks with different timestamps
+        let base_time = Utc::now();
+        let tick1 = create_test_tick(symbol, "50000.0", "price1", Some(base_time));
+        let tick2 = create_test_tick(
+            symbol,
+            "51000.0",
+            "price2",
+            Some(base_time + Duration::seconds(1)),
+        );
+        repo.insert_tick(&tick1)
+            .await
+            .expect("Failed to insert tick1");
+        repo.insert_tick(&tick2)
+            .await
+            .expect("Failed to insert tick2");
+
+        // Query latest price
+        let price = repo
+            .get_latest_price(symbol)
+            .await
+            .expect("Failed to get latest price");
+        assert_eq!(price, Some(Decimal::from_str("51000.0").unwrap()));
+
+        // Clean up
+        cleanup_database(pool, symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_tick_validation() {
+        let repo = create_repository().await;
+
+        let valid_tick = create_test_tick("BTCUSDT", "50000.0", "test1", None);
+        assert!(repo.validate_tick_data(&valid_tick).is_ok());
+
+        let invalid_tick = TickData::new(
+            Utc::now(),
+            "".to_string(),
+            Decimal::from_str("50000.0").unwrap(),
+            Decimal::from_str("1.0").unwrap(),
+            TradeSide::Buy,
+            "test".to_string(),
+            false,
+        );
+        assert!(repo.validate_tick_data(&invalid_tick).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_ticks_around() {
+        let repo = create_repository().await;
+        let pool = repo.get_pool();
+        let symbol = "BTCUSDT_TEST_AROUND";
+
+        cleanup_database(pool, symbol).await;
+
+        // Ten ticks one second apart: around0 .. around9
+        let base_time = Utc::now() - Duration::hours(2);
+        let ticks: Vec<TickData> = (0..10)
+            .map(|i| {
+                create_test_tick(
+                    symbol,
+                    &format!("{}", 50000 + i),
+                    &format!("around{}", i),
+                    Some(base_time + Duration::seconds(i)),
+                )
+            })
+            .collect();
+        repo.batch_insert(ticks)
+            .await
+            .expect("Failed to insert ticks");
+
+        // Pivot exactly on around5: it belongs to the "after" side
+        let pivot = base_time + Duration::seconds(5);
+        let window = repo
+            .get_ticks_around(symbol, pivot, 2, 3)
+            .await
+            .expect("Failed to get ticks around pivot");
+        let ids: Vec<&str> = window.iter().map(|t| t.trade_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["around3", "around4", "around5", "around6", "around7"]
+        );
+
+        // Windows are truncated at the edges of the data
+        let window = repo
+            .get_ticks_around(symbol, base_time + Duration::seconds(1), 5, 1)
+            .await
+            .expect("Failed to get ticks around pivot");
+        let ids: Vec<&str> = window.iter().map(|t| t.trade_id.as_str()).collect();
+        assert_eq!(ids, vec!["around0", "around1"]);
+
+        cleanup_database(pool, symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_read_strategy_selects_source() {
+        let repo = create_repository().await;
+        let pool = repo.get_pool();
+        let cache = repo.get_cache();
+        let symbol = "BTCUSDT_TEST_READ";
+
+        cleanup_database(pool, symbol).await;
+        cache
+            .clear_symbol(symbol)
+            .await
+            .expect("Failed to clear cache");
+
+        // Cache and database deliberately disagree
+        let base_time = Utc::now() - Duration::minutes(5);
+        for i in 0..3 {
+            let tick = create_test_tick(
+                symbol,
+                "50000.0",
+                &format!("cached{}", i),
+                Some(base_time + Duration::seconds(i)),
+            );
+            cache.push_tick(&tick).await.expect("Failed to prime cache");
+        }
+        let db_ticks: Vec<TickData> = (0..5)
+            .map(|i| {
+                create_test_tick(
+                    symbol,
+                    "60000.0",
+                    &format!("db{}", i),
+                    Some(base_time + Duration::seconds(i)),
+                )
+            })
+            .collect();
+        repo.batch_insert_historical(db_ticks)
+            .await
+            .expect("Failed to insert ticks");
+
+        let mut query = TickQuery::new(symbol.to_string());
+        query.limit = Some(3);
+        let all_from =
+            |ticks: &[TickData], prefix: &str| ticks.iter().all(|t| t.trade_id.starts_with(prefix));
+
+        // DbOnly ignores the primed cache and leaves it untouched
+        let ticks = repo
+            .get_ticks(&query.clone().with_read_strategy(ReadStrategy::DbOnly))
+            .await
+            .expect("Failed to query ticks");
+        assert_eq!(ticks.len(), 3);
+        assert!(all_from(&ticks, "db"));
+
+        // CacheOnly never falls back to the database, even when short
+        let mut larger = query.clone().with_read_strategy(ReadStrategy::CacheOnly);
+        larger.limit = Some(5);
+        let ticks = repo
+            .get_ticks(&larger)
+            .await
+            .expect("Failed to query ticks");
+        assert_eq!(ticks.len(), 3);
+        assert!(all_from(&ticks, "cached"));
+
+        // Auto serves a recent query from a full cache
+        let ticks = repo.get_ticks(&query).await.expect("Failed to query ticks");
+        assert_eq!(ticks.len(), 3);
+        assert!(all_from(&ticks, "cached"));
+
+        cleanup_database(pool, symbol).await;
+        cache
+            .clear_symbol(symbol)
+            .await
+            .expect("Failed to clear cache");
+    }
+
+    #[tokio::test]
+    async fn test_find_data_gaps_and_coverage() {
+        let repo = create_repository().await;
+        let pool = repo.get_pool();
+        let symbol = "BTCUSDT_TEST_GAPS";
+
+        cleanup_database(pool, symbol).await;
+
+        // Ticks every 20s in minute 0, then nothing until minute 5
+        let start = Timeframe::OneMinute.align_timestamp(Utc::now() - Duration::hours(3));
+        let offsets = [0, 20, 40, 300, 330];
+        let ticks = offsets
+            .iter()
+            .enumerate()
+            .map(|(i, secs)| {
+                create_test_tick(
+                    symbol,
+                    "100.0",
+                    &format!("gap{}", i),
+                    Some(start + Duration::seconds(*secs)),
+                )
+            })
+            .collect();
+        repo.batch_insert_historical(ticks)
+            .await
+            .expect("Failed to insert ticks");
+
+        let gaps = repo
+            .find_data_gaps(symbol, Duration::seconds(60))
+            .await
+            .expect("Failed to find gaps");
+        assert_eq!(
+            gaps,
+            vec![(start + Duration::seconds(40), start + Duration::minutes(5))]
+        );
+        let gaps = repo
+            .find_data_gaps(symbol, Duration::minutes(10))
+            .await
+            .expect("Failed to find gaps");
+        assert!(gaps.is_empty());
+
+        // Minutes 0 and 5 of the 6 minute candles have ticks
+        let end = start + Duration::seconds(330);
+        let ratio = repo
+            .data_coverage_ratio(symbol, Timeframe::OneMinute, start, end)
+            .await
+            .expect("Failed to compute coverage");
+        assert_eq!(ratio, 2.0 / 6.0);
+        let ratio = repo
+            .data_coverage_ratio(symbol, Timeframe::FiveMinutes, start, end)
+            .await
+            .expect("Failed to compute coverage");
+        assert_eq!(ratio, 1.0);
+
+        cleanup_database(pool, symbol).await;
+        let ratio = repo
+            .data_coverage_ratio(symbol, Timeframe::OneMinute, start, end)
+            .await
+            .expect("Failed to compute coverage");
+        assert_eq!(ratio, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_generate_ohlc_fills_empty_buckets() {
+        let repo = create_repository().await;
+        let pool = repo.get_pool();
+        let symbol = "BTCUSDT_TEST_FILL";
+
+        cleanup_database(pool, symbol).await;
+
+        // Trades in minutes 0 and 3 only
+        let start = Timeframe::OneMinute.align_timestamp(Utc::now() - Duration::hours(3));
+        let ticks = vec![
+            create_test_tick(symbol, "100.0", "fill1", Some(start)),
+            create_test_tick(
+                symbol,
+                "105.0",
+                "fill2",
+                Some(start + Duration::seconds(30)),
+            ),
+            create_test_tick(symbol, "110.0", "fill3", Some(start + Duration::minutes(3))),
+        ];
+        repo.batch_insert_historical(ticks)
+            .await
+            .expect("Failed to insert ticks");
+
+        let end = start + Duration::minutes(3);
+        let gappy = repo
+            .generate_ohlc_from_ticks(symbol, Timeframe::OneMinute, start, end, None, false)
+            .await
+            .expect("Failed to generate OHLC");
+        assert_eq!(gappy.len(), 2);
+        assert_eq!(gappy[0].volume, Decimal::from(2));
+        assert_eq!(gappy[0].quote_volume, Decimal::from(205));
+        assert_eq!(gappy[0].trade_count, 2);
+
+        let filled = repo
+            .generate_ohlc_from_ticks(symbol, Timeframe::OneMinute, start, end, None, true)
+            .await
+            .expect("Failed to generate OHLC");
+        assert_eq!(filled.len(), 4);
+        for (i, candle) in filled.iter().enumerate() {
+            assert_eq!(candle.timestamp, start + Duration::minutes(i as i64));
+        }
+
+        let previous_close = Decimal::from_str("105.0").unwrap();
+        for flat in &filled[1..3] {
+            assert_eq!(flat.open, previous_close);
+            assert_eq!(flat.high, previous_close);
+            assert_eq!(flat.low, previous_close);
+            assert_eq!(flat.close, previous_close);
+            assert_eq!(flat.volume, Decimal::ZERO);
+            assert_eq!(flat.quote_volume, Decimal::ZERO);
+            assert_eq!(flat.trade_count, 0);
+        }
+        assert_eq!(filled[3].close, Decimal::from_str("110.0").unwrap());
+
+        cleanup_database(pool, symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_continuous_ohlc_fills_whole_range() {
+        let repo = create_repository().await;
+        let pool = repo.get_pool();
+        let symbol = "BTCUSDT_TEST_CONT";
+
+        cleanup_database(pool, symbol).await;
+
+        // A trade before the range, trades in minutes 1 and 4 of [0, 6], trades
+        // in the first and last bucket but outside the requested times, and
+        // one at the start of the bucket after the range
+        let start = Timeframe::OneMinute.align_timestamp(Utc::now() - Duration::hours(3));
+        let at = |minutes: i64| Some(start + Duration::minutes(minutes));
+        let ticks = vec![
+            create_test_tick(symbol, "90.0", "cont1", at(-2)),
+            create_test_tick(symbol, "95.0", "cont2", Some(start + Duration::seconds(5))),
+            create_test_tick(symbol, "100.0", "cont3", at(1)),
+            create_test_tick(symbol, "110.0", "cont4", at(4)),
+            create_test_tick(
+                symbol,
+                "120.0",
+                "cont5",
+                Some(start + Duration::minutes(6) + Duration::seconds(50)),
+            ),
+            create_test_tick(symbol, "130.0", "cont6", at(7)),
+        ];
+        repo.batch_insert_historical(ticks)
+            .await
+            .expect("Failed to insert ticks");
+
+        let series = repo
+            .get_continuous_ohlc(
+                symbol,
+                Timeframe::OneMinute,
+                start + Duration::seconds(20),
+                start + Duration::minutes(6) + Duration::seconds(40),
+            )
+            .await
+            .expect("Failed to generate continuous OHLC");
+
+        assert_eq!(series.len(), 7);
+        let expected_closes = ["95", "100", "100", "100", "110", "110", "120"];
+        for (i, (candle, close)) in series.iter().zip(expected_closes).enumerate() {
+            assert_eq!(candle.timestamp, start + Duration::minutes(i as i64));
+            assert_eq!(candle.symbol, symbol);
+            assert_eq!(candle.close, Decimal::from_str(close).unwrap());
+            let traded = matches!(i, 0 | 1 | 4 | 6);
+            assert_eq!(candle.trade_count, traded as u64);
+            if !traded {
+                assert_eq!(candle.open, candle.close);
+                assert_eq!(candle.high, candle.close);
+                assert_eq!(candle.low, candle.close);
+                assert_eq!(candle.volume, Decimal::ZERO);
+            }
+        }
+
+        // Without an earlier trade there is nothing to fill the leading buckets with
+        let earlier = repo
+            .get_continuous_ohlc(
+                symbol,
+                Timeframe::OneMinute,
+                start - Duration::minutes(5),
+                start,
+            )
+            .await
+            .expect("Failed to generate continuous OHLC");
+        let minutes: Vec<i64> = earlier
+            .iter()
+            .map(|candle| (candle.timestamp - start).num_minutes())
+            .collect();
+        assert_eq!(minutes, [-2, -1, 0]);
 
-        // Clean up
         cleanup_database(pool, symbol).await;
     }
 
     #[tokio::test]
-    async fn test_batch_insert_and_read() {
+    async fn test_realized_volatility_of_synthetic_series() {
         let repo = create_repository().await;
         let pool = repo.get_pool();
-        let symbol = "BTCUSDT_TEST_BATCH";
+        let symbol = "BTCUSDT_TEST_RVOL";
 
-        // Clean up before test
         cleanup_database(pool, symbol).await;
 
-        // Prepare batch ticks
-        let ticks = vec![
-            create_test_tick(symbol, "50000.0", "batch1", None),
-            create_test_tick(symbol, "51000.0", "batch2", None),
-            create_test_tick(symbol, "52000.0", "batch3", None),
-        ];
-
-        // Batch insert
-        let inserted_count = repo
-            .batch_insert(ticks.clone())
+        // Log returns alternate +r / -r once per minute: mean 0, sample variance n r^2 / (n - 1)
+        let r = 0.01_f64;
+        let n = 20;
+        let start = Timeframe::OneMinute.align_timestamp(Utc::now() - Duration::hours(3));
+        let ticks: Vec<TickData> = (0..=n)
+            .map(|i| {
+                let price = if i % 2 == 0 { 100.0 } else { 100.0 * r.exp() };
+                create_test_tick(
+                    symbol,
+                    &format!("{:.12}", price),
+                    &format!("rvol{}", i),
+                    Some(start + Duration::minutes(i) + Duration::seconds(10)),
+                )
+            })
+            .collect();
+        repo.batch_insert_historical(ticks)
             .await
-            .expect("Failed to batch insert");
-        assert_eq!(inserted_count, 3);
+            .expect("Failed to insert ticks");
 
-        // Query ticks
-        let query = TickQuery {
-            symbol: symbol.to_string(),
-            limit: Some(3),
-            start_time: None,
-            end_time: None,
-            trade_side: None,
-        };
-        let queried_ticks = repo.get_ticks(&query).await.expect("Failed to query ticks");
+        let end = start + Duration::minutes(n + 1);
+        let vol = repo
+            .realized_volatility(symbol, start, end, Timeframe::OneMinute)
+            .await
+            .expect("Failed to compute volatility");
+
+        let minutes_per_year = 365.0 * 24.0 * 60.0;
+        let expected = (r * r * n as f64 / (n as f64 - 1.0) * minutes_per_year).sqrt();
+        assert!(
+            (vol - expected).abs() / expected < 1e-6,
+            "expected {} got {}",
+            expected,
+            vol
+        );
 
-        // Verify
-        assert_eq!(queried_ticks.len(), 3);
-        assert!(queried_ticks
-            .iter()
-            .any(|t| t.trade_id == "batch1" && t.price == Decimal::from_str("50000.0").unwrap()));
-        assert!(queried_ticks
-            .iter()
-            .any(|t| t.trade_id == "batch2" && t.price == Decimal::from_str("51000.0").unwrap()));
-        assert!(queried_ticks
-            .iter()
-            .any(|t| t.trade_id == "batch3" && t.price == Decimal::from_str("52000.0").unwrap()));
+        // Two samples give one return, which is not enough
+        let short = repo
+            .realized_volatility(
+                symbol,
+                start,
+                start + Duration::seconds(90),
+                Timeframe::OneMinute,
+            )
+            .await;
+        assert!(matches!(short, Err(DataError::Validation(_))));
 
-        // Clean up
         cleanup_database(pool, symbol).await;
     }
 
     #[tokio::test]
-    async fn test_cache_read_write() {
+    async fn test_deduplicate_keeps_one_copy() {
         let repo = create_repository().await;
-        let pool = repo.get_pool();
-        let cache = repo.get_cache();
-        let symbol = "BTCUSDT_TEST_CACHE";
-
-        // Clean up before test
-        cleanup_database(pool, symbol).await;
-        cache
-            .clear_symbol(symbol)
+        let symbol = "BTCUSDT_TEST_DEDUP";
+        let timestamp = Utc::now();
+
+        // Duplicates predate the unique index, so load them into a temporary
+        // tick_data without it. It shadows the shared table for this
+        // connection only and is dropped when the transaction ends.
+        let mut tx = repo.get_pool().begin().await.unwrap();
+        sqlx::query("CREATE TEMP TABLE tick_data (LIKE public.tick_data) ON COMMIT DROP")
+            .execute(&mut *tx)
             .await
-            .expect("Failed to clear cache");
+            .unwrap();
+        for (trade_id, copies) in [("1", 3), ("2", 1), ("3", 2)] {
+            for _ in 0..copies {
+                sqlx::query(
+                    "INSERT INTO tick_data (timestamp, symbol, price, quantity, side, trade_id, is_buyer_maker)
+                     VALUES ($1, $2, 100, 1, 'BUY', $3, false)",
+                )
+                .bind(timestamp)
+                .bind(symbol)
+                .bind(trade_id)
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+            }
+        }
 
-        // Insert a tick
-        let tick = create_test_tick(symbol, "50000.0", "cache1", None);
-        repo.insert_tick(&tick)
+        let dry_run = TickDataRepository::count_duplicate_rows(&mut *tx, symbol)
             .await
-            .expect("Failed to insert tick");
+            .unwrap();
+        assert_eq!(dry_run, 3);
 
-        // Query from cache
-        let cached_ticks = cache
-            .get_recent_ticks(symbol, 1)
+        let removed = TickDataRepository::delete_duplicates(&mut *tx, symbol)
             .await
-            .expect("Failed to read from cache");
-        assert_eq!(cached_ticks.len(), 1);
-        assert_eq!(cached_ticks[0].symbol, symbol);
-        assert_eq!(cached_ticks[0].price, Decimal::from_str("50000.0").unwrap());
-        assert_eq!(cached_ticks[0].trade_id, "cache1");
+            .unwrap();
+        assert_eq!(removed, dry_run);
 
-        // Query via get_ticks (should hit cache for recent data)
-        let query = TickQuery {
-            symbol: symbol.to_string(),
-            limit: Some(1),
-            start_time: Some(Utc::now() - Duration::hours(1)),
-            end_time: None,
-            trade_side: None,
-        };
-        let ticks = repo.get_ticks(&query).await.expect("Failed to query ticks");
-        assert_eq!(ticks.len(), 1);
-        assert_eq!(ticks[0].symbol, symbol);
-        assert_eq!(ticks[0].price, Decimal::from_str("50000.0").unwrap());
+        let remaining: Vec<String> = sqlx::query_scalar(
+            "SELECT trade_id FROM tick_data WHERE symbol = $1 ORDER BY trade_id",
+        )
+        .bind(symbol)
+        .fetch_all(&mut *tx)
+        .await
+        .unwrap();
+        assert_eq!(remaining, vec!["1", "2", "3"]);
+        assert_eq!(
+            TickDataRepository::count_duplicate_rows(&mut *tx, symbol)
+                .await
+                .unwrap(),
+            0
+        );
 
-        // Clean up
-        cleanup_database(pool, symbol).await;
-        cache
-            .clear_symbol(symbol)
+        tx.rollback().await.unwrap();
+        let stats = repo.get_db_stats(Some(symbol)).await.unwrap();
+        assert_eq!(stats.total_records, 0);
+    }
+
+    #[tokio::test]
+    async fn test_ohlc_data_info_skips_empty_buckets() {
+        use chrono::TimeZone;
+
+        let repo = create_repository().await;
+        let symbol = "BTC_TEST_OHLCINFO";
+        cleanup_database(repo.get_pool(), symbol).await;
+
+        // Ticks in minutes 0, 1 and 9 of an hour: three candles over a
+        // ten-minute span, with two ticks sharing the first candle
+        let hour = Utc.with_ymd_and_hms(2024, 3, 6, 10, 0, 0).unwrap();
+        let offsets = [5, 30, 70, 545];
+        let ticks: Vec<TickData> = offsets
+            .iter()
+            .enumerate()
+            .map(|(i, secs)| {
+                let time = hour + Duration::seconds(*secs);
+                create_test_tick(symbol, "100", &format!("info{}", i), Some(time))
+            })
+            .collect();
+        repo.batch_insert(ticks).await.unwrap();
+
+        let (candles, earliest, latest) = repo
+            .get_ohlc_data_info(symbol, Timeframe::OneMinute)
             .await
-            .expect("Failed to clear cache");
+            .unwrap();
+        assert_eq!(candles, 3);
+        assert_eq!(earliest, Some(hour));
+        assert_eq!(latest, Some(hour + Duration::minutes(9)));
+
+        // Weekly buckets start on Monday
+        let (weeks, week_start, _) = repo
+            .get_ohlc_data_info(symbol, Timeframe::OneWeek)
+            .await
+            .unwrap();
+        assert_eq!(weeks, 1);
+        assert_eq!(
+            week_start,
+            Some(Utc.with_ymd_and_hms(2024, 3, 4, 0, 0, 0).unwrap())
+        );
+
+        cleanup_database(repo.get_pool(), symbol).await;
     }
 
     #[tokio::test]
-    async fn test_latest_price() {
+    async fn test_insert_skipping_existing_ticks() {
         let repo = create_repository().await;
-        let pool = repo.get_pool();
-        let symbol = "BTCUSDT_TEST_PRICE";
+        let symbol = "BTCUSDT_TEST_MERGE";
+        cleanup_database(repo.get_pool(), symbol).await;
 
-        // Clean up before test
-        cleanup_database(pool, symbol).await;
+        let base = Utc::now() - Duration::hours(1);
+        let tick = |i: i64| {
+            let time = base + Duration::seconds(i);
+            create_test_tick(symbol, "100", &format!("merge{}", i), Some(time))
+        };
 
-        // Insert ticks with different timestamps
-        let base_time = Utc::now();
-        let tick1 = create_test_tick(symbol, "50000.0", "price1", Some(base_time));
-        let tick2 = create_test_tick(
-            symbol,
-            "51000.0",
-            "price2",
-            Some(base_time + Duration::seconds(1)),
-        );
-        repo.insert_tick(&tick1)
+        // Ticks 0-5 are stored; the next batch overlaps on 3-5 and repeats 6
+        repo.batch_insert_historical((0..6).map(tick).collect())
             .await
-            .expect("Failed to insert tick1");
-        repo.insert_tick(&tick2)
+            .unwrap();
+        let overlapping: Vec<TickData> = (3..10).chain([6]).map(tick).collect();
+
+        let summary = repo
+            .insert_ticks_skipping_existing(overlapping)
             .await
-            .expect("Failed to insert tick2");
+            .unwrap();
+        assert_eq!(
+            summary,
+            MergeSummary {
+                filtered: 4,
+                inserted: 4
+            }
+        );
 
-        // Query latest price
-        let price = repo
-            .get_latest_price(symbol)
+        let stats = repo.get_db_stats(Some(symbol)).await.unwrap();
+        assert_eq!(stats.total_records, 10);
+
+        // A second run finds everything already stored
+        let rerun = repo
+            .insert_ticks_skipping_existing((0..10).map(tick).collect())
             .await
-            .expect("Failed to get latest price");
-        assert_eq!(price, Some(Decimal::from_str("51000.0").unwrap()));
+            .unwrap();
+        assert_eq!(rerun.filtered, 10);
+        assert_eq!(rerun.inserted, 0);
 
-        // Clean up
-        cleanup_database(pool, symbol).await;
+        cleanup_database(repo.get_pool(), symbol).await;
     }
 
     #[tokio::test]
-    async fn test_tick_validation() {
+    async fn test_validated_insert_drops_price_spike() {
         let repo = create_repository().await;
+        let symbol = "BTCUSDT_TEST_SPIKE";
+        cleanup_database(repo.get_pool(), symbol).await;
 
-        let valid_tick = create_test_tick("BTCUSDT", "50000.0", "test1", None);
-        assert!(repo.validate_tick_data(&valid_tick).is_ok());
+        let base = Utc::now() - Duration::hours(1);
+        let tick = |i: i64, price: &str| {
+            let time = base + Duration::seconds(i);
+            create_test_tick(symbol, price, &format!("spike{}", i), Some(time))
+        };
+        // Out of order on purpose; the 150 spike is judged against 101, and
+        // 102 against 101 rather than the spike
+        let ticks = vec![
+            tick(3, "102"),
+            tick(0, "100"),
+            tick(2, "150"),
+            tick(1, "101"),
+        ];
+        let max_jump = Decimal::from(5);
+
+        let strict = repo.with_outlier_policy(OutlierPolicy::Error);
+        assert!(matches!(
+            strict.batch_insert_validated(ticks.clone(), max_jump).await,
+            Err(DataError::Validation(_))
+        ));
+        let stats = strict.get_db_stats(Some(symbol)).await.unwrap();
+        assert_eq!(stats.total_records, 0);
+
+        let repo = strict.with_outlier_policy(OutlierPolicy::Drop);
+        let (inserted, outliers) = repo.batch_insert_validated(ticks, max_jump).await.unwrap();
+        assert_eq!(inserted, 3);
+        assert_eq!(outliers.len(), 1);
+        assert_eq!(outliers[0].trade_id, "spike2");
+
+        let stats = repo.get_db_stats(Some(symbol)).await.unwrap();
+        assert_eq!(stats.total_records, 3);
+
+        cleanup_database(repo.get_pool(), symbol).await;
+    }
 
-        let invalid_tick = TickData::new(
-            Utc::now(),
-            "".to_string(),
-            Decimal::from_str("50000.0").unwrap(),
-            Decimal::from_str("1.0").unwrap(),
-            TradeSide::Buy,
-            "test".to_string(),
-            false,
-        );
-        assert!(repo.validate_tick_data(&invalid_tick).is_err());
+    #[tokio::test]
+    async fn test_validated_insert_seeds_from_tick_before_batch() {
+        let repo = create_repository()
+            .await
+            .with_outlier_policy(OutlierPolicy::Drop);
+        let symbol = "BTCUSDT_TEST_LEAD";
+        cleanup_database(repo.get_pool(), symbol).await;
+
+        let base = Utc::now() - Duration::hours(1);
+        let tick = |i: i64, price: &str| {
+            let time = base + Duration::seconds(i);
+            create_test_tick(symbol, price, &format!("lead{}", i), Some(time))
+        };
+        let max_jump = Decimal::from(5);
+
+        // Nothing stored yet: the first tick is the reference, so a trend
+        // of steps under the threshold is kept whole
+        let trend = vec![
+            tick(0, "100"),
+            tick(1, "104"),
+            tick(2, "108"),
+            tick(3, "112"),
+        ];
+        let (inserted, outliers) = repo.batch_insert_validated(trend, max_jump).await.unwrap();
+        assert_eq!(inserted, 4);
+        assert!(outliers.is_empty());
+
+        // A much later price is stored, then a batch from before it arrives:
+        // it is judged against the stored 112 before it, not the latest 200,
+        // and its leading spike is caught
+        repo.batch_insert(vec![tick(1000, "200")]).await.unwrap();
+        let backfill = vec![tick(4, "150"), tick(5, "113")];
+        let (inserted, outliers) = repo
+            .batch_insert_validated(backfill, max_jump)
+            .await
+            .unwrap();
+        assert_eq!(inserted, 1);
+        assert_eq!(outliers.len(), 1);
+        assert_eq!(outliers[0].trade_id, "lead4");
+
+        let stats = repo.get_db_stats(Some(symbol)).await.unwrap();
+        assert_eq!(stats.total_records, 6);
+
+        cleanup_database(repo.get_pool(), symbol).await;
     }
 
     #[tokio::test]
@@ -1296,4 +3918,334 @@ mod tests {
         // Clean up
         cleanup_database(pool, symbol).await;
     }
+
+    #[tokio::test]
+    async fn test_query_older_than_cache_ttl_skips_cache() {
+        dotenv().ok();
+        let pool = PgPool::connect(&env::var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let cache = TieredCache::new((100, 300), (&env::var("REDIS_URL").unwrap(), 1000, 300))
+            .await
+            .unwrap();
+        let repo = TickDataRepository::new(pool, cache);
+        let symbol = "BTC_TEST_FRESHNESS";
+        cleanup_database(repo.get_pool(), symbol).await;
+
+        // Ticks only in the cache, so any result proves the cache was read
+        let now = Utc::now();
+        for i in 0..3 {
+            let tick = create_test_tick(
+                symbol,
+                "100",
+                &format!("fresh{}", i),
+                Some(now - Duration::seconds(30 - i)),
+            );
+            repo.get_cache().push_tick(&tick).await.unwrap();
+        }
+
+        let mut query = TickQuery::new(symbol.to_string());
+        query.limit = Some(3);
+        query.start_time = Some(now - Duration::minutes(1));
+        assert_eq!(repo.get_ticks(&query).await.unwrap().len(), 3);
+
+        // Within the old one-hour window but past the 300s TTL
+        query.start_time = Some(now - Duration::minutes(10));
+        assert!(!repo.is_recent_query(&query));
+        assert!(repo.get_ticks(&query).await.unwrap().is_empty());
+
+        let widened = repo.with_freshness_window(Duration::hours(1));
+        assert!(widened.is_recent_query(&query));
+    }
+
+    #[tokio::test]
+    async fn test_is_recent_query_uses_injected_clock() {
+        use crate::data::clock::MockClock;
+        use chrono::TimeZone;
+        use std::sync::Arc;
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        let repo = create_repository()
+            .await
+            .with_clock(Arc::new(clock.clone()));
+
+        let mut query = TickQuery::new("BTCUSDT_TEST_CLOCK".to_string());
+        query.start_time = Some(start - Duration::minutes(30));
+        assert!(repo.is_recent_query(&query));
+
+        // Same query is no longer recent once the clock moves past the window
+        clock.advance(Duration::hours(2));
+        assert!(!repo.is_recent_query(&query));
+
+        clock.set(start - Duration::minutes(15));
+        assert!(repo.is_recent_query(&query));
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_old_data_uses_injected_clock() {
+        use crate::data::clock::MockClock;
+        use chrono::TimeZone;
+        use std::sync::Arc;
+
+        // Far enough in the past that no other test data is older than the cutoff
+        let now = Utc.with_ymd_and_hms(2001, 1, 1, 0, 0, 0).unwrap();
+        let repo = create_repository()
+            .await
+            .with_clock(Arc::new(MockClock::new(now)));
+        let symbol = "BTC_TEST_RETENTION";
+        cleanup_database(repo.get_pool(), symbol).await;
+
+        let ticks = vec![
+            create_test_tick(symbol, "100", "old1", Some(now - Duration::days(3))),
+            create_test_tick(symbol, "101", "new1", Some(now - Duration::hours(12))),
+        ];
+        repo.batch_insert_historical(ticks).await.unwrap();
+
+        assert_eq!(repo.cleanup_old_data(1.0).await.unwrap(), 1);
+        let remaining: Vec<String> =
+            sqlx::query_scalar("SELECT trade_id FROM tick_data WHERE symbol = $1")
+                .bind(symbol)
+                .fetch_all(repo.get_pool())
+                .await
+                .unwrap();
+        assert_eq!(remaining, vec!["new1".to_string()]);
+
+        cleanup_database(repo.get_pool(), symbol).await;
+    }
+
+    /// Tick-only strategy that buys one unit on its first tick
+    struct FirstTickBuyer {
+        bought: bool,
+    }
+
+    impl crate::backtest::Strategy for FirstTickBuyer {
+        fn name(&self) -> &str {
+            "First Tick Buyer"
+        }
+
+        fn on_tick(&mut self, tick: &TickData) -> crate::backtest::Signal {
+            if std::mem::replace(&mut self.bought, true) {
+                return crate::backtest::Signal::Hold;
+            }
+            crate::backtest::Signal::Buy {
+                symbol: tick.symbol.clone(),
+                quantity: Decimal::ONE,
+            }
+        }
+
+        fn initialize(&mut self, _params: HashMap<String, String>) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ohlc_backtest_of_tick_strategy() {
+        use crate::backtest::{BacktestConfig, BacktestEngine};
+        use crate::data::clock::MockClock;
+        use chrono::TimeZone;
+        use std::sync::Arc;
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let repo = create_repository()
+            .await
+            .with_clock(Arc::new(MockClock::new(now)));
+        let symbol = "BTC_TEST_OHLC_BT";
+        cleanup_database(repo.get_pool(), symbol).await;
+
+        // Five one-minute candles closing at 100, 102, ..., 108
+        let ticks: Vec<TickData> = (0..10)
+            .map(|i| {
+                let time = now - Duration::minutes(5) + Duration::seconds(30 * i);
+                let price = (100 + 2 * (i / 2)).to_string();
+                create_test_tick(symbol, &price, &format!("ohlcbt{}", i), Some(time))
+            })
+            .collect();
+        repo.batch_insert_historical(ticks).await.unwrap();
+
+        let (candles, _) = repo
+            .generate_recent_ohlc_for_backtest(symbol, Timeframe::OneMinute, 5)
+            .await
+            .unwrap();
+        assert_eq!(candles.len(), 5);
+
+        let config = BacktestConfig::new(Decimal::from(10000)).with_commission_rate(Decimal::ZERO);
+        let mut engine =
+            BacktestEngine::new(Box::new(FirstTickBuyer { bought: false }), config).unwrap();
+        let result = engine.run_with_ohlc(&candles);
+
+        // The strategy sees the first candle as a tick at its close
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(result.trades[0].price.value(), Decimal::from(100));
+        assert_eq!(result.trades[0].timestamp, candles[0].timestamp);
+        assert_eq!(result.final_value, Decimal::from(10008));
+
+        cleanup_database(repo.get_pool(), symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_recent_ohlc_widens_for_dense_ticks() {
+        use crate::data::clock::MockClock;
+        use chrono::TimeZone;
+        use std::sync::Arc;
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let repo = create_repository()
+            .await
+            .with_clock(Arc::new(MockClock::new(now)));
+        let symbol = "BTC_TEST_WIDEN";
+        cleanup_database(repo.get_pool(), symbol).await;
+
+        // Four minutes of 150 ticks a minute: the first pass's budget of
+        // 100 ticks per candle only reaches two minutes back
+        let ticks: Vec<TickData> = (0..600)
+            .map(|i| {
+                let time = now - Duration::minutes(4) + Duration::milliseconds(400 * i);
+                create_test_tick(symbol, "100", &format!("widen{}", i), Some(time))
+            })
+            .collect();
+        repo.batch_insert_historical(ticks).await.unwrap();
+
+        let (candles, coverage) = repo
+            .generate_recent_ohlc_for_backtest(symbol, Timeframe::OneMinute, 3)
+            .await
+            .unwrap();
+        let buckets: Vec<(DateTime<Utc>, Decimal)> =
+            candles.iter().map(|c| (c.timestamp, c.volume)).collect();
+        assert_eq!(
+            buckets,
+            (1..=3)
+                .rev()
+                .map(|m| (now - Duration::minutes(m), Decimal::from(150)))
+                .collect::<Vec<_>>()
+        );
+        assert!(coverage.is_complete());
+        assert!(candles
+            .iter()
+            .all(|c| c.quote_volume == Decimal::from(15000) && c.trade_count == 150));
+
+        // Asking for more than is stored returns what there is, and says so
+        let (candles, coverage) = repo
+            .generate_recent_ohlc_for_backtest(symbol, Timeframe::OneMinute, 10)
+            .await
+            .unwrap();
+        assert_eq!(candles.len(), 4);
+        assert_eq!(
+            coverage,
+            OhlcCoverage {
+                timeframe: Timeframe::OneMinute,
+                requested: 10,
+                actual: 4,
+                first: Some(now - Duration::minutes(4)),
+                last: Some(now - Duration::minutes(1)),
+            }
+        );
+        assert!(!coverage.is_complete());
+
+        cleanup_database(repo.get_pool(), symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_upsert_and_query_symbol_metadata() {
+        let repo = create_repository().await;
+        let symbol = "BTC_TEST_SYMMETA";
+        sqlx::query("DELETE FROM symbol_metadata WHERE symbol = $1")
+            .bind(symbol)
+            .execute(repo.get_pool())
+            .await
+            .unwrap();
+
+        let mut metadata = SymbolMetadata {
+            symbol: symbol.to_string(),
+            base_asset: "BTC".to_string(),
+            quote_asset: "USDT".to_string(),
+            tick_size: Decimal::from_str("0.01").unwrap(),
+            step_size: Decimal::from_str("0.00001").unwrap(),
+            min_notional: Decimal::from(5),
+            is_active: true,
+        };
+        repo.upsert_symbol_metadata(&metadata).await.unwrap();
+        assert_eq!(
+            repo.get_symbol_metadata(symbol).await.unwrap(),
+            Some(metadata.clone())
+        );
+
+        // A second upsert replaces the rules instead of adding a row
+        metadata.step_size = Decimal::from_str("0.001").unwrap();
+        metadata.is_active = false;
+        repo.upsert_symbol_metadata(&metadata).await.unwrap();
+        assert_eq!(
+            repo.get_symbol_metadata(symbol).await.unwrap(),
+            Some(metadata.clone())
+        );
+
+        let all = repo.list_symbol_metadata(false).await.unwrap();
+        assert_eq!(all.iter().filter(|m| m.symbol == symbol).count(), 1);
+        let active = repo.list_symbol_metadata(true).await.unwrap();
+        assert!(active.iter().all(|m| m.symbol != symbol));
+        assert_eq!(
+            repo.get_symbol_metadata("NO_SUCH_SYMBOL").await.unwrap(),
+            None
+        );
+
+        sqlx::query("DELETE FROM symbol_metadata WHERE symbol = $1")
+            .bind(symbol)
+            .execute(repo.get_pool())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_backtest_result() {
+        let repo = create_repository().await;
+        let symbol = "BTC_TEST_BTRESULT";
+        sqlx::query!("DELETE FROM backtest_results WHERE symbol = $1", symbol)
+            .execute(repo.get_pool())
+            .await
+            .unwrap();
+
+        let result = NewBacktestResult {
+            strategy_id: "sma".to_string(),
+            strategy_name: "Simple Moving Average".to_string(),
+            symbol: symbol.to_string(),
+            initial_capital: Decimal::from(100000),
+            final_value: Decimal::from_str("100123.123456789").unwrap(),
+            return_percentage: Decimal::from_str("0.123123456789").unwrap(),
+            sharpe_ratio: None,
+            max_drawdown: Decimal::from_str("1.5").unwrap(),
+            total_trades: 2,
+            trades: serde_json::json!([
+                {"side": "Buy", "quantity": "1", "price": "100"},
+                {"side": "Sell", "quantity": "1", "price": "110"},
+            ]),
+        };
+
+        let id = repo.save_backtest_result(&result).await.unwrap();
+        let stored = repo.get_backtest_results(Some(symbol)).await.unwrap();
+        assert_eq!(stored.len(), 1);
+        let stored = &stored[0];
+        assert_eq!(stored.id, id);
+        assert_eq!(stored.strategy_id, "sma");
+        assert_eq!(stored.strategy_name, result.strategy_name);
+        assert_eq!(stored.initial_capital, result.initial_capital);
+        // Columns keep 8 decimal places
+        assert_eq!(stored.final_value, result.final_value.round_dp(8));
+        assert_eq!(
+            stored.return_percentage,
+            result.return_percentage.round_dp(8)
+        );
+        assert_eq!(stored.sharpe_ratio, None);
+        assert_eq!(stored.max_drawdown, result.max_drawdown);
+        assert_eq!(stored.total_trades, 2);
+        assert_eq!(stored.trades, result.trades);
+
+        // Unfiltered listing includes it too
+        let all = repo.get_backtest_results(None).await.unwrap();
+        assert!(all.iter().any(|r| r.id == id));
+
+        sqlx::query!("DELETE FROM backtest_results WHERE symbol = $1", symbol)
+            .execute(repo.get_pool())
+            .await
+            .unwrap();
+    }
 }
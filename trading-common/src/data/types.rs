@@ -1,6 +1,7 @@
 use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use thiserror::Error;
 
 // =================================================================
@@ -76,6 +77,15 @@ pub struct DbStats {
     pub latest_timestamp: Option<DateTime<Utc>>,
 }
 
+/// Outcome of an insert that skips ticks already stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeSummary {
+    /// Ticks dropped before inserting because they were already stored
+    /// or repeated within the batch
+    pub filtered: usize,
+    pub inserted: usize,
+}
+
 // =================================================================
 // TradeSide Implementation for Database Integration
 // =================================================================
@@ -94,6 +104,40 @@ impl TradeSide {
 // Query parameter type
 // =================================================================
 
+/// Where a tick query is served from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadStrategy {
+    /// Cache for recent queries when it holds enough ticks, database otherwise
+    #[default]
+    Auto,
+    /// Cache only; may return fewer ticks than requested
+    CacheOnly,
+    /// Database only; the cache is neither read nor updated
+    DbOnly,
+}
+
+/// Order in which `insert_tick` updates the database and the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteOrder {
+    /// Database, then cache: a tick readers can see is always durable
+    #[default]
+    DbFirst,
+    /// Cache, then a background database write: readers see the tick
+    /// immediately, but it is lost if the process stops before the write
+    /// lands, and a failed write is only logged
+    CacheFirst,
+}
+
+/// What `batch_insert_validated` does with price outliers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutlierPolicy {
+    /// Insert the rest of the batch and return the outliers
+    #[default]
+    Drop,
+    /// Reject the whole batch with a validation error, inserting nothing
+    Error,
+}
+
 /// TickData Query parameters
 #[derive(Debug, Clone)]
 pub struct TickQuery {
@@ -102,6 +146,7 @@ pub struct TickQuery {
     pub end_time: Option<DateTime<Utc>>,
     pub limit: Option<u32>,
     pub trade_side: Option<TradeSide>,
+    pub read_strategy: ReadStrategy,
 }
 
 impl TickQuery {
@@ -112,8 +157,14 @@ impl TickQuery {
             end_time: None,
             limit: None,
             trade_side: None,
+            read_strategy: ReadStrategy::default(),
         }
     }
+
+    pub fn with_read_strategy(mut self, read_strategy: ReadStrategy) -> Self {
+        self.read_strategy = read_strategy;
+        self
+    }
 }
 
 // =================================================================
@@ -145,6 +196,9 @@ pub enum DataError {
 
     #[error("Configuration error: {0}")]
     Config(String),
+
+    #[error("Export error: {0}")]
+    Export(String),
 }
 
 pub type DataResult<T> = Result<T, DataError>;
@@ -185,11 +239,40 @@ impl BacktestDataInfo {
     }
 
     /// Check if has sufficient data for backtesting
-    pub fn has_sufficient_data(&self, symbol: &str, min_records: u64) -> bool {
+    ///
+    /// The effective minimum is the larger of `min_records` and the strategy's
+    /// warmup period, so indicators can warm up before the data runs out.
+    pub fn has_sufficient_data(
+        &self,
+        symbol: &str,
+        min_records: u64,
+        warmup_period: usize,
+    ) -> bool {
+        let required = Self::required_records(min_records, warmup_period);
         self.get_symbol_info(symbol)
-            .map(|info| info.records_count >= min_records)
+            .map(|info| info.records_count >= required)
             .unwrap_or(false)
     }
+
+    /// Minimum number of records needed given a base minimum and a strategy warmup
+    pub fn required_records(min_records: u64, warmup_period: usize) -> u64 {
+        min_records.max(warmup_period as u64)
+    }
+}
+
+/// A time range with no stored ticks for a symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataGap {
+    /// Last known tick before the gap (or the requested range start)
+    pub start: DateTime<Utc>,
+    /// First known tick after the gap (or the requested range end)
+    pub end: DateTime<Utc>,
+}
+
+impl DataGap {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -205,6 +288,127 @@ pub struct LiveStrategyLog {
     pub processing_time_us: u64,
 }
 
+/// Point-in-time P&L of a live paper trading session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LivePnlSnapshot {
+    pub session_id: String,
+    pub strategy_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub portfolio_value: Decimal,
+    pub realized_pnl: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub position_count: u32,
+}
+
+/// Summary of a completed backtest run to insert into the backtest_results table
+#[derive(Debug, Clone)]
+pub struct NewBacktestResult {
+    pub strategy_id: String,
+    pub strategy_name: String,
+    pub symbol: String,
+    pub initial_capital: Decimal,
+    pub final_value: Decimal,
+    pub return_percentage: Decimal,
+    pub sharpe_ratio: Option<Decimal>,
+    pub max_drawdown: Decimal,
+    pub total_trades: usize,
+    /// Every fill of the run, serialized by the caller
+    pub trades: serde_json::Value,
+}
+
+/// Summary of a completed backtest run, stored in the backtest_results table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredBacktestResult {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub strategy_id: String,
+    pub strategy_name: String,
+    pub symbol: String,
+    pub initial_capital: Decimal,
+    pub final_value: Decimal,
+    pub return_percentage: Decimal,
+    /// None when returns had zero volatility
+    pub sharpe_ratio: Option<Decimal>,
+    pub max_drawdown: Decimal,
+    pub total_trades: u32,
+    /// Every fill of the run, as saved
+    pub trades: serde_json::Value,
+}
+
+/// Exchange trading rules for one symbol, stored in the symbol_metadata table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolMetadata {
+    pub symbol: String,
+    pub base_asset: String,
+    pub quote_asset: String,
+    /// Smallest price increment
+    pub tick_size: Decimal,
+    /// Smallest quantity increment (lot size)
+    pub step_size: Decimal,
+    /// Smallest order value in the quote asset; zero when the exchange sets none
+    pub min_notional: Decimal,
+    pub is_active: bool,
+}
+
+impl SymbolMetadata {
+    /// Round a quantity down to a whole number of lot steps
+    pub fn round_quantity(&self, quantity: Decimal) -> Decimal {
+        if self.step_size <= Decimal::ZERO {
+            return quantity;
+        }
+        (quantity / self.step_size).floor() * self.step_size
+    }
+}
+
+/// How many of the requested backtest candles the stored ticks could fill
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OhlcCoverage {
+    pub timeframe: Timeframe,
+    pub requested: u32,
+    pub actual: usize,
+    /// Start of the first and last candle returned
+    pub first: Option<DateTime<Utc>>,
+    pub last: Option<DateTime<Utc>>,
+}
+
+impl OhlcCoverage {
+    pub fn is_complete(&self) -> bool {
+        self.actual >= self.requested as usize
+    }
+}
+
+impl std::fmt::Display for OhlcCoverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} requested {} candles",
+            self.actual,
+            self.requested,
+            self.timeframe.as_str()
+        )?;
+        if let (Some(first), Some(last)) = (self.first, self.last) {
+            write!(
+                f,
+                ", {} to {}",
+                first.format("%Y-%m-%d %H:%M"),
+                last.format("%Y-%m-%d %H:%M")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Taker buy and sell volume of one time bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderFlowBucket {
+    /// Start of the bucket
+    pub timestamp: DateTime<Utc>,
+    pub buy_volume: Decimal,
+    pub sell_volume: Decimal,
+    /// `buy_volume - sell_volume`
+    pub delta: Decimal,
+}
+
 /// Time frame for OHLC data
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Timeframe {
@@ -218,6 +422,25 @@ pub enum Timeframe {
     OneWeek,
 }
 
+impl FromStr for Timeframe {
+    type Err = String;
+
+    /// Parse the `as_str` form, e.g. "1m" or "4h"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1m" => Ok(Timeframe::OneMinute),
+            "5m" => Ok(Timeframe::FiveMinutes),
+            "15m" => Ok(Timeframe::FifteenMinutes),
+            "30m" => Ok(Timeframe::ThirtyMinutes),
+            "1h" => Ok(Timeframe::OneHour),
+            "4h" => Ok(Timeframe::FourHours),
+            "1d" => Ok(Timeframe::OneDay),
+            "1w" => Ok(Timeframe::OneWeek),
+            other => Err(format!("Invalid timeframe: {}", other)),
+        }
+    }
+}
+
 impl Timeframe {
     pub fn as_duration(&self) -> Duration {
         match self {
@@ -338,7 +561,11 @@ pub struct OHLCData {
     pub high: Decimal,
     pub low: Decimal,
     pub close: Decimal,
+    /// Traded quantity in the base asset
     pub volume: Decimal,
+    /// Traded value in the quote asset, the sum of price * quantity
+    #[serde(default)]
+    pub quote_volume: Decimal,
     pub trade_count: u64,
 }
 
@@ -352,6 +579,7 @@ impl OHLCData {
         low: Decimal,
         close: Decimal,
         volume: Decimal,
+        quote_volume: Decimal,
         trade_count: u64,
     ) -> Self {
         Self {
@@ -363,6 +591,7 @@ impl OHLCData {
             low,
             close,
             volume,
+            quote_volume,
             trade_count,
         }
     }
@@ -383,6 +612,7 @@ impl OHLCData {
         let mut low = ticks[0].price;
         let close = ticks[ticks.len() - 1].price;
         let mut volume = Decimal::ZERO;
+        let mut quote_volume = Decimal::ZERO;
 
         for tick in ticks {
             if tick.price > high {
@@ -392,6 +622,7 @@ impl OHLCData {
                 low = tick.price;
             }
             volume += tick.quantity;
+            quote_volume += tick.price * tick.quantity;
         }
 
         Some(OHLCData::new(
@@ -403,7 +634,383 @@ impl OHLCData {
             low,
             close,
             volume,
+            quote_volume,
             ticks.len() as u64,
         ))
     }
+
+    /// The candle as a single trade of its whole volume at the close,
+    /// stamped with the candle's timestamp
+    pub fn close_tick(&self) -> TickData {
+        TickData::new(
+            self.timestamp,
+            self.symbol.clone(),
+            self.close,
+            self.volume,
+            TradeSide::Buy,
+            format!("ohlc-{}", self.timestamp.timestamp_millis()),
+            false,
+        )
+    }
+}
+
+/// Aggregate one symbol's `from` candles, in time order, into `to` candles:
+/// the first open, last close, extreme high and low, and summed volumes.
+/// `to` must be a whole multiple of `from`; partial buckets are kept.
+pub fn resample_ohlc(
+    candles: &[OHLCData],
+    from: Timeframe,
+    to: Timeframe,
+) -> DataResult<Vec<OHLCData>> {
+    let from_secs = from.as_duration().num_seconds() as u64;
+    let to_secs = to.as_duration().num_seconds() as u64;
+    if to_secs < from_secs || !to_secs.is_multiple_of(from_secs) {
+        return Err(DataError::Validation(format!(
+            "Cannot resample {} candles into {}: not a whole multiple",
+            from.as_str(),
+            to.as_str()
+        )));
+    }
+
+    let mut resampled: Vec<OHLCData> = Vec::new();
+    for candle in candles {
+        if candle.timeframe != from {
+            return Err(DataError::Validation(format!(
+                "Expected {} candles, got a {} candle at {}",
+                from.as_str(),
+                candle.timeframe.as_str(),
+                candle.timestamp
+            )));
+        }
+        let bucket = to.align_timestamp(candle.timestamp);
+        match resampled.last_mut() {
+            Some(last) if last.symbol != candle.symbol => {
+                return Err(DataError::Validation(format!(
+                    "Cannot resample mixed symbols {} and {}",
+                    last.symbol, candle.symbol
+                )));
+            }
+            Some(last) if last.timestamp == bucket => {
+                last.high = last.high.max(candle.high);
+                last.low = last.low.min(candle.low);
+                last.close = candle.close;
+                last.volume += candle.volume;
+                last.quote_volume += candle.quote_volume;
+                last.trade_count += candle.trade_count;
+            }
+            _ => resampled.push(OHLCData {
+                timestamp: bucket,
+                timeframe: to,
+                ..candle.clone()
+            }),
+        }
+    }
+    Ok(resampled)
+}
+
+// =================================================================
+// Market Snapshot
+// =================================================================
+
+/// Aggregate of recent ticks for one symbol, as served from the tick cache
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketSnapshot {
+    pub symbol: String,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub last_price: Decimal,
+    pub volume: Decimal,
+    pub tick_count: usize,
+    pub first_timestamp: DateTime<Utc>,
+    pub last_timestamp: DateTime<Utc>,
+}
+
+impl MarketSnapshot {
+    /// Aggregate ticks in any order; None when there are no ticks
+    pub fn from_ticks(ticks: &[TickData]) -> Option<Self> {
+        let first = ticks.iter().min_by_key(|tick| tick.timestamp)?;
+        let last = ticks.iter().max_by_key(|tick| tick.timestamp)?;
+
+        let mut high = first.price;
+        let mut low = first.price;
+        let mut volume = Decimal::ZERO;
+        for tick in ticks {
+            high = high.max(tick.price);
+            low = low.min(tick.price);
+            volume += tick.quantity;
+        }
+
+        Some(Self {
+            symbol: first.symbol.clone(),
+            high,
+            low,
+            last_price: last.price,
+            volume,
+            tick_count: ticks.len(),
+            first_timestamp: first.timestamp,
+            last_timestamp: last.timestamp,
+        })
+    }
+
+    /// High minus low
+    pub fn range(&self) -> Decimal {
+        self.high - self.low
+    }
+}
+
+/// Estimate the current (bid, ask) from recent trades
+///
+/// Sell-aggressor trades (`is_buyer_maker`) hit the bid and buy-aggressor
+/// trades lift the ask, so the most recent price of each side approximates
+/// the top of book. Returns `None` when either side is missing or its last
+/// trade is more than `max_age` older than the newest tick. If the two
+/// estimates cross, the staler side is dropped in favour of the fresher
+/// one, giving a zero-width quote.
+pub fn infer_quote(ticks: &[TickData], max_age: Duration) -> Option<(Decimal, Decimal)> {
+    let newest = ticks.iter().map(|t| t.timestamp).max()?;
+    let latest_of = |is_buyer_maker: bool| {
+        ticks
+            .iter()
+            .filter(|t| t.is_buyer_maker == is_buyer_maker)
+            .max_by_key(|t| t.timestamp)
+    };
+
+    let bid_tick = latest_of(true)?;
+    let ask_tick = latest_of(false)?;
+
+    if newest - bid_tick.timestamp > max_age || newest - ask_tick.timestamp > max_age {
+        return None;
+    }
+
+    if bid_tick.price <= ask_tick.price {
+        Some((bid_tick.price, ask_tick.price))
+    } else if bid_tick.timestamp >= ask_tick.timestamp {
+        Some((bid_tick.price, bid_tick.price))
+    } else {
+        Some((ask_tick.price, ask_tick.price))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::strategy::create_strategy;
+    use std::collections::HashMap;
+
+    fn create_data_info(symbol: &str, records_count: u64) -> BacktestDataInfo {
+        BacktestDataInfo {
+            total_records: records_count,
+            symbols_count: 1,
+            earliest_time: None,
+            latest_time: None,
+            symbol_info: vec![SymbolDataInfo {
+                symbol: symbol.to_string(),
+                records_count,
+                earliest_time: None,
+                latest_time: None,
+                min_price: None,
+                max_price: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_sufficient_data_accounts_for_strategy_warmup() {
+        let data_info = create_data_info("BTCUSDT", 150);
+
+        let mut strategy = create_strategy("sma").unwrap();
+        let params = HashMap::from([
+            ("short_period".to_string(), "50".to_string()),
+            ("long_period".to_string(), "200".to_string()),
+        ]);
+        strategy.initialize(params).unwrap();
+        assert_eq!(strategy.warmup_period(), 200);
+
+        // Passes the flat minimum but not the 200-period warmup
+        assert!(data_info.has_sufficient_data("BTCUSDT", 100, 0));
+        assert!(!data_info.has_sufficient_data("BTCUSDT", 100, strategy.warmup_period()));
+
+        let default_sma = create_strategy("sma").unwrap();
+        assert!(data_info.has_sufficient_data("BTCUSDT", 100, default_sma.warmup_period()));
+        assert!(!data_info.has_sufficient_data("ETHUSDT", 100, 0));
+    }
+
+    #[test]
+    fn test_market_snapshot_from_cached_ticks() {
+        let base = Utc::now();
+        let tick = |secs: i64, price: i64| {
+            TickData::new(
+                base + chrono::Duration::seconds(secs),
+                "BTCUSDT".to_string(),
+                Decimal::from(price),
+                Decimal::ONE,
+                TradeSide::Buy,
+                format!("snap{}", secs),
+                false,
+            )
+        };
+        // Cache order is newest first
+        let ticks = vec![tick(3, 101), tick(2, 104), tick(1, 98), tick(0, 100)];
+
+        let snapshot = MarketSnapshot::from_ticks(&ticks).unwrap();
+        assert_eq!(snapshot.high, Decimal::from(104));
+        assert_eq!(snapshot.low, Decimal::from(98));
+        assert_eq!(snapshot.last_price, Decimal::from(101));
+        assert_eq!(snapshot.volume, Decimal::from(4));
+        assert_eq!(snapshot.range(), Decimal::from(6));
+        assert_eq!(snapshot.first_timestamp, base);
+
+        assert!(MarketSnapshot::from_ticks(&[]).is_none());
+    }
+
+    #[test]
+    fn test_infer_quote_from_mixed_sides() {
+        let max_age = chrono::Duration::seconds(5);
+        let dec = |s: &str| s.parse::<Decimal>().unwrap();
+        let tick = |ms: i64, price: &str, is_buyer_maker: bool| {
+            TickData::new(
+                DateTime::from_timestamp_millis(1_700_000_000_000 + ms).unwrap(),
+                "BTCUSDT".to_string(),
+                dec(price),
+                Decimal::ONE,
+                if is_buyer_maker {
+                    TradeSide::Sell
+                } else {
+                    TradeSide::Buy
+                },
+                ms.to_string(),
+                is_buyer_maker,
+            )
+        };
+
+        // Latest sell aggressor sets the bid, latest buy aggressor the ask
+        let ticks = vec![
+            tick(0, "99.0", true),
+            tick(100, "101.0", false),
+            tick(200, "100.0", true),
+            tick(300, "100.5", false),
+        ];
+        let quote = Some((dec("100.0"), dec("100.5")));
+        assert_eq!(infer_quote(&ticks, max_age), quote);
+
+        // Input order does not matter, only timestamps
+        let mut reversed = ticks.clone();
+        reversed.reverse();
+        assert_eq!(infer_quote(&reversed, max_age), quote);
+
+        // One-sided flow gives no quote
+        let sells = vec![tick(0, "100", true), tick(100, "99", true)];
+        assert_eq!(infer_quote(&sells, max_age), None);
+        assert_eq!(infer_quote(&[], max_age), None);
+
+        // A side last seen too long ago is not trusted
+        let stale = vec![tick(0, "100.0", true), tick(5_001, "100.5", false)];
+        assert_eq!(infer_quote(&stale, max_age), None);
+        assert!(infer_quote(&stale, chrono::Duration::seconds(6)).is_some());
+
+        // Crossed estimates collapse onto the fresher side
+        let crossed = vec![tick(0, "101.0", false), tick(100, "102.0", true)];
+        assert_eq!(
+            infer_quote(&crossed, max_age),
+            Some((dec("102.0"), dec("102.0")))
+        );
+    }
+
+    #[test]
+    fn test_ohlc_from_ticks_aggregates_volume() {
+        let base = Utc::now();
+        let tick = |secs: i64, price: &str, quantity: &str| {
+            TickData::new(
+                base + chrono::Duration::seconds(secs),
+                "BTCUSDT".to_string(),
+                price.parse().unwrap(),
+                quantity.parse().unwrap(),
+                TradeSide::Buy,
+                format!("agg{}", secs),
+                false,
+            )
+        };
+        let ticks = vec![
+            tick(0, "100", "0.5"),
+            tick(1, "102", "1.25"),
+            tick(2, "99", "2"),
+        ];
+
+        let candle = OHLCData::from_ticks(&ticks, Timeframe::OneMinute, base).unwrap();
+        assert_eq!(
+            candle.volume,
+            ticks.iter().map(|t| t.quantity).sum::<Decimal>()
+        );
+        // 50 + 127.5 + 198
+        assert_eq!(candle.quote_volume, Decimal::new(3755, 1));
+        assert_eq!(candle.trade_count, 3);
+        assert_eq!(
+            (candle.high, candle.low),
+            (Decimal::from(102), Decimal::from(99))
+        );
+
+        // Candles stored before quote volume existed still deserialize
+        let mut json = serde_json::to_value(&candle).unwrap();
+        json.as_object_mut().unwrap().remove("quote_volume");
+        let legacy: OHLCData = serde_json::from_value(json).unwrap();
+        assert_eq!(legacy.quote_volume, Decimal::ZERO);
+        assert_eq!(legacy.volume, candle.volume);
+    }
+
+    #[test]
+    fn test_resample_five_minute_candles_to_hour() {
+        use chrono::TimeZone;
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        let candles: Vec<OHLCData> = (0..12)
+            .map(|i| {
+                let high = if i == 7 { 120 } else { 110 };
+                let low = if i == 3 { 80 } else { 90 };
+                OHLCData::new(
+                    start + chrono::Duration::minutes(5 * i),
+                    "BTCUSDT".to_string(),
+                    Timeframe::FiveMinutes,
+                    Decimal::from(100 + i),
+                    Decimal::from(high),
+                    Decimal::from(low),
+                    Decimal::from(101 + i),
+                    Decimal::from(i + 1),
+                    Decimal::from(100 * (i + 1)),
+                    2,
+                )
+            })
+            .collect();
+
+        let hourly = resample_ohlc(&candles, Timeframe::FiveMinutes, Timeframe::OneHour).unwrap();
+        assert_eq!(hourly.len(), 1);
+        let candle = &hourly[0];
+        assert_eq!(
+            (candle.timestamp, candle.timeframe),
+            (start, Timeframe::OneHour)
+        );
+        assert_eq!(
+            (candle.open, candle.high, candle.low, candle.close),
+            (
+                Decimal::from(100),
+                Decimal::from(120),
+                Decimal::from(80),
+                Decimal::from(112)
+            )
+        );
+        // 1 + 2 + ... + 12
+        assert_eq!(candle.volume, Decimal::from(78));
+        assert_eq!(candle.quote_volume, Decimal::from(7800));
+        assert_eq!(candle.trade_count, 24);
+
+        // Finer targets are not whole multiples
+        assert!(matches!(
+            resample_ohlc(&hourly, Timeframe::OneHour, Timeframe::FifteenMinutes),
+            Err(DataError::Validation(_))
+        ));
+        // Candles must be of the stated timeframe
+        assert!(matches!(
+            resample_ohlc(&candles, Timeframe::OneMinute, Timeframe::OneHour),
+            Err(DataError::Validation(_))
+        ));
+    }
 }
@@ -0,0 +1,81 @@
+//! Parsing of user-entered numbers shared by the CLI and the desktop app.
+
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ParseError {
+    #[error("Empty input")]
+    Empty,
+
+    #[error("'{0}' is not a number")]
+    Invalid(String),
+
+    #[error("{0}% is outside 0-100%")]
+    OutOfRange(Decimal),
+}
+
+/// Parse a percentage such as `0.1`, `0.1%` or `0,1` into a fraction (0.001)
+///
+/// The number is read straight into a `Decimal`, so what was typed is kept
+/// exactly. A comma is accepted as the decimal separator when the input has
+/// no dot.
+pub fn parse_percentage(input: &str) -> Result<Decimal, ParseError> {
+    let trimmed = input.trim();
+    let number = trimmed.strip_suffix('%').unwrap_or(trimmed).trim_end();
+    if number.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let number = if number.contains('.') {
+        number.to_string()
+    } else {
+        number.replacen(',', ".", 1)
+    };
+    let percent =
+        Decimal::from_str(&number).map_err(|_| ParseError::Invalid(trimmed.to_string()))?;
+    if percent < Decimal::ZERO || percent > Decimal::ONE_HUNDRED {
+        return Err(ParseError::OutOfRange(percent));
+    }
+
+    Ok(percent / Decimal::ONE_HUNDRED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_percentage() {
+        let rate = |s: &str| Decimal::from_str(s).unwrap();
+
+        assert_eq!(parse_percentage("0.1"), Ok(rate("0.001")));
+        assert_eq!(parse_percentage(" 0.1% "), Ok(rate("0.001")));
+        assert_eq!(parse_percentage("0.075 %"), Ok(rate("0.00075")));
+        assert_eq!(parse_percentage("0,1"), Ok(rate("0.001")));
+        assert_eq!(parse_percentage("0"), Ok(Decimal::ZERO));
+        assert_eq!(parse_percentage("100%"), Ok(Decimal::ONE));
+        // No f64 round trip: every digit survives
+        assert_eq!(parse_percentage("0.0123456789"), Ok(rate("0.000123456789")));
+
+        assert_eq!(parse_percentage(""), Err(ParseError::Empty));
+        assert_eq!(parse_percentage("  % "), Err(ParseError::Empty));
+        for invalid in ["abc", "0.1%%", "1,000.5", "0,1,2", "--1"] {
+            assert_eq!(
+                parse_percentage(invalid),
+                Err(ParseError::Invalid(invalid.to_string())),
+                "{}",
+                invalid
+            );
+        }
+        assert_eq!(
+            parse_percentage("-0.1"),
+            Err(ParseError::OutOfRange(rate("-0.1")))
+        );
+        assert_eq!(
+            parse_percentage("150%"),
+            Err(ParseError::OutOfRange(Decimal::from(150)))
+        );
+    }
+}
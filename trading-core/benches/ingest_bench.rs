@@ -0,0 +1,103 @@
+use chrono::{Duration, Utc};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use dotenv::dotenv;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::runtime::Runtime;
+use trading_core::data::{
+    cache::TieredCache,
+    repository::TickDataRepository,
+    types::{DataResult, TickData, TradeSide},
+};
+
+const SYMBOL: &str = "BTCUSDT_INGEST_BENCH";
+
+/// Distinct trade ids across iterations, so no batch hits ON CONFLICT
+static NEXT_TRADE: AtomicU64 = AtomicU64::new(0);
+
+fn fresh_ticks(count: usize) -> Vec<TickData> {
+    let start = Utc::now() - Duration::days(1);
+    (0..count)
+        .map(|i| {
+            let id = NEXT_TRADE.fetch_add(1, Ordering::Relaxed);
+            let is_buyer_maker = id % 2 == 1;
+            TickData::new(
+                start + Duration::microseconds(i as i64),
+                SYMBOL.to_string(),
+                Decimal::new(5_000_000 + (id % 1000) as i64, 2),
+                Decimal::new(15, 3),
+                if is_buyer_maker {
+                    TradeSide::Sell
+                } else {
+                    TradeSide::Buy
+                },
+                format!("ingest{}", id),
+                is_buyer_maker,
+            )
+        })
+        .collect()
+}
+
+async fn setup_repository() -> DataResult<TickDataRepository> {
+    dotenv().ok();
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let redis_url = std::env::var("REDIS_URL").expect("REDIS_URL must be set");
+    let pool = PgPool::connect(&database_url)
+        .await
+        .map_err(trading_core::data::types::DataError::Database)?;
+    let cache = TieredCache::new((1000, 300), (&redis_url, 10000, 3600)).await?;
+    Ok(TickDataRepository::new(pool, cache))
+}
+
+async fn cleanup_database(pool: &PgPool) {
+    sqlx::query("DELETE FROM tick_data WHERE symbol = $1")
+        .bind(SYMBOL)
+        .execute(pool)
+        .await
+        .expect("Failed to cleanup database");
+}
+
+/// COPY vs multi-row INSERT (without the cache update) for large batches
+fn ingest_benchmarks(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let repo = rt
+        .block_on(setup_repository())
+        .expect("Failed to setup repository");
+    rt.block_on(cleanup_database(repo.get_pool()));
+    let repo = &repo;
+
+    let mut group = c.benchmark_group("tick_ingest");
+    group.sample_size(10);
+
+    for count in [10_000, 100_000] {
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(BenchmarkId::new("copy", count), &count, |b, &count| {
+            b.to_async(&rt).iter_batched(
+                || fresh_ticks(count),
+                |ticks| async move {
+                    repo.copy_insert(&ticks).await.unwrap();
+                },
+                BatchSize::PerIteration,
+            );
+        });
+
+        group.bench_with_input(BenchmarkId::new("insert", count), &count, |b, &count| {
+            b.to_async(&rt).iter_batched(
+                || fresh_ticks(count),
+                |ticks| async move {
+                    repo.batch_insert_historical(ticks).await.unwrap();
+                },
+                BatchSize::PerIteration,
+            );
+        });
+
+        rt.block_on(cleanup_database(repo.get_pool()));
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, ingest_benchmarks);
+criterion_main!(benches);
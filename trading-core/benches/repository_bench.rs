@@ -8,7 +8,7 @@ use tokio::runtime::Runtime;
 use trading_core::data::{
     cache::{TickDataCache, TieredCache},
     repository::TickDataRepository,
-    types::{DataResult, TickData, TickQuery, TradeSide},
+    types::{DataResult, ReadStrategy, TickData, TickQuery, TradeSide},
 };
 
 fn create_test_tick(
@@ -122,6 +122,7 @@ fn repository_benchmarks(c: &mut Criterion) {
                 start_time: Some(Utc::now() - Duration::minutes(30)),
                 end_time: None,
                 trade_side: None,
+                read_strategy: ReadStrategy::Auto,
             };
             repo.get_ticks(black_box(&query)).await.unwrap();
         });
@@ -136,6 +137,7 @@ fn repository_benchmarks(c: &mut Criterion) {
                 start_time: Some(Utc::now() - Duration::days(1)),
                 end_time: None,
                 trade_side: None,
+                read_strategy: ReadStrategy::Auto,
             };
             repo.get_ticks(black_box(&query)).await.unwrap();
         });
@@ -7,6 +7,9 @@ pub struct Database {
     pub max_connections: u32,
     pub min_connections: u32,
     pub max_lifetime: u64,
+    /// Store live tick batches with COPY instead of multi-row INSERT
+    #[serde(default)]
+    pub copy_ingest: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,6 +36,228 @@ pub struct PaperTrading {
     pub enabled: bool,
     pub strategy: String,
     pub initial_capital: f64,
+    /// Commission per fill as a fraction of notional (0.001 = 0.1%)
+    #[serde(default)]
+    pub commission_rate: f64,
+    /// Seconds between persisted P&L snapshots; none are written when unset
+    #[serde(default)]
+    pub snapshot_interval_secs: Option<u64>,
+    /// Session that snapshots are grouped under; set it to resume a session
+    /// after a restart (defaults to the strategy name and start time)
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Most trades per symbol within `trade_window_secs`; unlimited when unset
+    #[serde(default)]
+    pub max_trades_per_window: Option<usize>,
+    #[serde(default = "default_trade_window_secs")]
+    pub trade_window_secs: u64,
+    /// Simulated order latency: a signal fills at the first tick this many
+    /// milliseconds later; fills are immediate when unset
+    #[serde(default)]
+    pub fill_latency_ms: Option<u64>,
+    /// Fill at the bid or ask inferred from trades at most this many
+    /// milliseconds old; fills are at the trade price when unset
+    #[serde(default)]
+    pub quote_max_age_ms: Option<u64>,
+    /// Strategy log rows written to the database per batch
+    #[serde(default = "default_log_batch_size")]
+    pub log_batch_size: usize,
+    /// Seconds a strategy log row may wait for its batch to fill
+    #[serde(default = "default_log_batch_time_secs")]
+    pub log_batch_time_secs: u64,
+}
+
+fn default_trade_window_secs() -> u64 {
+    60
+}
+
+fn default_log_batch_size() -> usize {
+    100
+}
+
+fn default_log_batch_time_secs() -> u64 {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExchangeSettings {
+    /// Exchange to collect from: binance or coinbase (`venue` is accepted as
+    /// the key's former name)
+    #[serde(default = "default_exchange_name", alias = "venue")]
+    pub name: String,
+    /// Raw WebSocket messages buffered between the socket reader and the parser
+    #[serde(default = "default_message_buffer_size")]
+    pub message_buffer_size: usize,
+    /// Round ingested prices/quantities to the symbol's tick and step size
+    #[serde(default = "default_normalize_precision")]
+    pub normalize_precision: bool,
+    /// Timeout of each REST request, in seconds
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    /// Tries of a historical trade request before giving up on timeouts, 429 and 5xx
+    #[serde(default = "default_max_request_attempts")]
+    pub max_request_attempts: u32,
+    /// Consecutive failed WebSocket connections before giving up; 0 retries forever
+    #[serde(default = "default_max_reconnect_attempts")]
+    pub max_reconnect_attempts: u32,
+    /// Cap on the doubling delay between reconnects, in seconds
+    #[serde(default = "default_max_reconnect_delay_secs")]
+    pub max_reconnect_delay_secs: u64,
+}
+
+fn default_exchange_name() -> String {
+    "binance".to_string()
+}
+
+fn default_message_buffer_size() -> usize {
+    10_000
+}
+
+fn default_normalize_precision() -> bool {
+    true
+}
+
+fn default_request_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_request_attempts() -> u32 {
+    3
+}
+
+fn default_max_reconnect_attempts() -> u32 {
+    10
+}
+
+fn default_max_reconnect_delay_secs() -> u64 {
+    60
+}
+
+impl Default for ExchangeSettings {
+    fn default() -> Self {
+        Self {
+            name: default_exchange_name(),
+            message_buffer_size: default_message_buffer_size(),
+            normalize_precision: default_normalize_precision(),
+            request_timeout_secs: default_request_timeout_secs(),
+            max_request_attempts: default_max_request_attempts(),
+            max_reconnect_attempts: default_max_reconnect_attempts(),
+            max_reconnect_delay_secs: default_max_reconnect_delay_secs(),
+        }
+    }
+}
+
+/// Storage coalescing for high-frequency symbols; prices are relative changes
+#[derive(Debug, Default, Deserialize)]
+pub struct CoalescingSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    pub min_price_change: Option<f64>,
+    pub min_quantity: Option<f64>,
+    pub min_interval_ms: Option<u64>,
+}
+
+/// Capacities of the live service's broadcast channels
+#[derive(Debug, Deserialize)]
+pub struct ChannelSettings {
+    #[serde(default = "default_shutdown_capacity")]
+    pub shutdown_capacity: usize,
+    /// Price updates buffered per subscriber; slower subscribers skip ahead
+    #[serde(default = "default_price_capacity")]
+    pub price_capacity: usize,
+}
+
+fn default_shutdown_capacity() -> usize {
+    16
+}
+
+fn default_price_capacity() -> usize {
+    1024
+}
+
+impl Default for ChannelSettings {
+    fn default() -> Self {
+        Self {
+            shutdown_capacity: default_shutdown_capacity(),
+            price_capacity: default_price_capacity(),
+        }
+    }
+}
+
+/// Sampled info-level tick logging in live mode; off unless a cadence is set
+#[derive(Debug, Default, Deserialize)]
+pub struct HeartbeatSettings {
+    /// Log each symbol's price and tick count every N ticks
+    pub every_ticks: Option<u64>,
+    /// Log each symbol's price and tick count at least every N seconds
+    pub every_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BacktestSettings {
+    /// Directory that saved backtest runs are written under
+    #[serde(default = "default_results_dir")]
+    pub results_dir: String,
+    /// Save every CLI backtest run without needing `--save`
+    #[serde(default)]
+    pub auto_save: bool,
+}
+
+fn default_results_dir() -> String {
+    "backtest_results".to_string()
+}
+
+impl Default for BacktestSettings {
+    fn default() -> Self {
+        Self {
+            results_dir: default_results_dir(),
+            auto_save: false,
+        }
+    }
+}
+
+/// HTTP readiness probe and metrics endpoint for the live collector
+#[derive(Debug, Deserialize)]
+pub struct HealthSettings {
+    /// Port serving `GET /ready`; the probe is off when unset
+    #[serde(default)]
+    pub readiness_port: Option<u16>,
+    /// Seconds after start during which DB and cache connectivity is enough
+    #[serde(default = "default_startup_grace_secs")]
+    pub startup_grace_secs: u64,
+    /// Maximum age of the last tick once the grace period is over
+    #[serde(default = "default_max_tick_age_secs")]
+    pub max_tick_age_secs: u64,
+    /// Port serving `GET /metrics` and `GET /health`; off when unset
+    #[serde(default)]
+    pub metrics_port: Option<u16>,
+    /// Maximum age of the last batch flush for `/health` to report healthy
+    #[serde(default = "default_max_flush_age_secs")]
+    pub max_flush_age_secs: u64,
+}
+
+fn default_startup_grace_secs() -> u64 {
+    120
+}
+
+fn default_max_tick_age_secs() -> u64 {
+    60
+}
+
+fn default_max_flush_age_secs() -> u64 {
+    60
+}
+
+impl Default for HealthSettings {
+    fn default() -> Self {
+        Self {
+            readiness_port: None,
+            startup_grace_secs: default_startup_grace_secs(),
+            max_tick_age_secs: default_max_tick_age_secs(),
+            metrics_port: None,
+            max_flush_age_secs: default_max_flush_age_secs(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -41,6 +266,18 @@ pub struct Settings {
     pub cache: Cache,
     pub symbols: Vec<String>,
     pub paper_trading: PaperTrading,
+    #[serde(default)]
+    pub exchange: ExchangeSettings,
+    #[serde(default)]
+    pub coalescing: CoalescingSettings,
+    #[serde(default)]
+    pub heartbeat: HeartbeatSettings,
+    #[serde(default)]
+    pub channels: ChannelSettings,
+    #[serde(default)]
+    pub backtest: BacktestSettings,
+    #[serde(default)]
+    pub health: HealthSettings,
 }
 
 impl Settings {
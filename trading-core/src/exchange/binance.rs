@@ -2,26 +2,76 @@
 
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 use tokio::time::sleep;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
-use tracing::{debug, error, info, warn};
+use tracing::{debug, info, warn};
 
 use super::{
     errors::ExchangeError,
+    reconnect::run_with_reconnect,
     traits::Exchange,
-    types::{BinanceStreamMessage, BinanceSubscribeMessage, BinanceTradeMessage},
-    utils::{build_binance_trade_streams, convert_binance_to_tick_data},
+    types::{
+        BinanceAggTrade, BinanceExchangeInfo, BinanceKlineMessage, BinanceStreamMessage,
+        BinanceSubscribeMessage, BinanceTradeMessage, HistoricalTradeParams, OrderBook,
+        ReconnectPolicy, RetryPolicy, SymbolPrecision,
+    },
+    utils::{
+        binance_kline_interval, binance_kline_stream, convert_binance_agg_trade_to_tick_data,
+        convert_binance_depth, convert_binance_kline_to_ohlc, convert_binance_to_tick_data,
+        normalize_tick_precision, symbol_metadata, symbol_precision, validate_binance_symbol,
+    },
+    venue::Venue,
 };
-use trading_common::data::types::TickData;
+use trading_common::data::types::{OHLCData, SymbolMetadata, TickData, Timeframe};
 
 // Constants
 const BINANCE_WS_URL: &str = "wss://stream.binance.com:9443/stream";
-const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const BINANCE_REST_URL: &str = "https://api.binance.com";
+/// aggTrades rejects startTime/endTime windows of one hour or more
+const AGG_TRADES_MAX_WINDOW_MS: i64 = 60 * 60 * 1000 - 1;
+const AGG_TRADES_MAX_LIMIT: u32 = 1000;
+/// Pause between aggTrades pages, keeping a long paginated fetch well under
+/// the request weight limit
+const AGG_TRADES_PAGE_DELAY: Duration = Duration::from_millis(100);
+const DEFAULT_MESSAGE_BUFFER_SIZE: usize = 10_000;
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a failed `exchangeInfo` fetch is remembered before retrying
+const PRECISION_RETRY_DELAY: Duration = Duration::from_secs(300);
+/// Longest `Retry-After` a rate-limited request waits out; a longer one
+/// (e.g. an IP ban) fails the request instead
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+type TradeCallback = Arc<dyn Fn(TickData) + Send + Sync>;
+/// Parses one raw stream message and forwards whatever it carries
+type MessageHandler = Arc<dyn Fn(&str) + Send + Sync>;
+type PrecisionMap = Arc<HashMap<String, SymbolPrecision>>;
 
 /// Binance exchange implementation
 pub struct BinanceExchange {
     ws_url: String,
+    rest_url: String,
+    http_client: reqwest::Client,
+    /// Capacity of the raw message buffer between socket reader and parser
+    message_buffer_size: usize,
+    /// Frames discarded because the message buffer was full
+    dropped_frames: Arc<AtomicU64>,
+    /// Round ingested prices/quantities to each symbol's tick and step size
+    normalize_precision: bool,
+    /// Symbol precision already loaded from `exchangeInfo`
+    precision_cache: Mutex<HashMap<String, SymbolPrecision>>,
+    /// Until when precision lookups are skipped after a failed fetch
+    precision_retry_at: Mutex<Option<Instant>>,
+    /// Timeout of a single REST request
+    request_timeout: Duration,
+    /// Retries of historical trade requests that time out or hit 429/5xx
+    retry_policy: RetryPolicy,
+    /// Reconnects of the WebSocket after the connection fails
+    reconnect_policy: ReconnectPolicy,
 }
 
 impl BinanceExchange {
@@ -29,11 +79,373 @@ impl BinanceExchange {
     pub fn new() -> Self {
         Self {
             ws_url: BINANCE_WS_URL.to_string(),
+            rest_url: BINANCE_REST_URL.to_string(),
+            http_client: reqwest::Client::new(),
+            message_buffer_size: DEFAULT_MESSAGE_BUFFER_SIZE,
+            dropped_frames: Arc::new(AtomicU64::new(0)),
+            normalize_precision: true,
+            precision_cache: Mutex::new(HashMap::new()),
+            precision_retry_at: Mutex::new(None),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            retry_policy: RetryPolicy::default(),
+            reconnect_policy: ReconnectPolicy::default(),
+        }
+    }
+
+    /// Set the capacity of the buffer between socket reads and message dispatch
+    pub fn with_message_buffer(mut self, size: usize) -> Self {
+        self.message_buffer_size = size.max(1);
+        self
+    }
+
+    /// Enable or disable tick/step size normalization; when disabled, prices
+    /// and quantities are kept exactly as the exchange formatted them
+    pub fn with_precision_normalization(mut self, enabled: bool) -> Self {
+        self.normalize_precision = enabled;
+        self
+    }
+
+    /// Set the timeout of each REST request
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Set how historical trade requests retry transient failures
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Set how the WebSocket reconnects after a failed connection; with no
+    /// attempt limit the collector keeps retrying through long outages
+    pub fn with_reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = policy;
+        self
+    }
+
+    /// Request `exchangeInfo` for the given symbols
+    async fn fetch_exchange_info(
+        &self,
+        symbols: &[&String],
+    ) -> Result<BinanceExchangeInfo, ExchangeError> {
+        let url = format!("{}/api/v3/exchangeInfo", self.rest_url);
+        let response = self
+            .http_client
+            .get(&url)
+            .query(&[("symbols", serde_json::to_string(symbols)?)])
+            .timeout(self.request_timeout)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ExchangeError::ApiError(format!(
+                "exchangeInfo request failed with {}: {}",
+                status, body
+            )));
+        }
+
+        Ok(serde_json::from_str(&response.text().await?)?)
+    }
+
+    /// Fetch tick/step sizes for symbols not yet cached
+    async fn fetch_symbol_precision(&self, symbols: &[String]) -> Result<(), ExchangeError> {
+        let missing: Vec<&String> = {
+            let cache = self
+                .precision_cache
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            symbols.iter().filter(|s| !cache.contains_key(*s)).collect()
+        };
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let info = self.fetch_exchange_info(&missing).await?;
+        let mut cache = self
+            .precision_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        for symbol_info in &info.symbols {
+            cache.insert(symbol_info.symbol.clone(), symbol_precision(symbol_info)?);
+        }
+        Ok(())
+    }
+
+    /// Precision for the given symbols, empty when normalization is off or unavailable
+    async fn precision_for(&self, symbols: &[String]) -> PrecisionMap {
+        if !self.normalize_precision {
+            return Arc::new(HashMap::new());
+        }
+
+        let retry_at = *self
+            .precision_retry_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if retry_at.is_none_or(|retry_at| Instant::now() >= retry_at) {
+            if let Err(e) = self.fetch_symbol_precision(symbols).await {
+                warn!(
+                    "Could not load symbol precision, storing raw values for {:?}: {}",
+                    PRECISION_RETRY_DELAY, e
+                );
+                *self
+                    .precision_retry_at
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner()) =
+                    Some(Instant::now() + PRECISION_RETRY_DELAY);
+            }
+        }
+
+        let cache = self
+            .precision_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        Arc::new(
+            symbols
+                .iter()
+                .filter_map(|s| cache.get(s).map(|p| (s.clone(), *p)))
+                .collect(),
+        )
+    }
+
+    /// Fetch aggregate trades for a single time window (< 1 hour)
+    async fn fetch_agg_trades(
+        &self,
+        symbol: &str,
+        start_ms: i64,
+        end_ms: i64,
+        limit: u32,
+        params: &HistoricalTradeParams,
+    ) -> Result<Vec<BinanceAggTrade>, ExchangeError> {
+        self.request_agg_trades(
+            &[
+                ("symbol", symbol.to_string()),
+                ("startTime", start_ms.to_string()),
+                ("endTime", end_ms.to_string()),
+                ("limit", limit.to_string()),
+            ],
+            params,
+        )
+        .await
+    }
+
+    /// Fetch up to `limit` aggregate trades starting at aggregate trade `from_id`
+    async fn fetch_agg_trades_from_id(
+        &self,
+        symbol: &str,
+        from_id: u64,
+        limit: u32,
+        params: &HistoricalTradeParams,
+    ) -> Result<Vec<BinanceAggTrade>, ExchangeError> {
+        self.request_agg_trades(
+            &[
+                ("symbol", symbol.to_string()),
+                ("fromId", from_id.to_string()),
+                ("limit", limit.to_string()),
+            ],
+            params,
+        )
+        .await
+    }
+
+    /// Request `aggTrades`, retrying timeouts, 5xx and 429 per the retry
+    /// policy; a 429 waits out its `Retry-After` header when one is sent,
+    /// up to `MAX_RETRY_AFTER`. The caller claims the first try from
+    /// `params`; retries wait on its rate limiter too.
+    async fn request_agg_trades(
+        &self,
+        query: &[(&str, String)],
+        params: &HistoricalTradeParams,
+    ) -> Result<Vec<BinanceAggTrade>, ExchangeError> {
+        let url = format!("{}/api/v3/aggTrades", self.rest_url);
+
+        let mut attempt = 1;
+        loop {
+            let mut retry_after = None;
+            let error = match self
+                .http_client
+                .get(&url)
+                .query(&query)
+                .timeout(self.request_timeout)
+                .send()
+                .await
+            {
+                Ok(response) if response.status().is_success() => {
+                    let body = response.text().await?;
+                    return Ok(serde_json::from_str(&body)?);
+                }
+                Ok(response) if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                    retry_after = response
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| value.trim().parse().ok())
+                        .map(Duration::from_secs);
+                    let body = response.text().await.unwrap_or_default();
+                    ExchangeError::RateLimit(format!("aggTrades request got 429: {}", body))
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    let error = ExchangeError::ApiError(format!(
+                        "aggTrades request failed with {}: {}",
+                        status, body
+                    ));
+                    // Other 4xx responses will not succeed on a retry
+                    if !status.is_server_error() {
+                        return Err(error);
+                    }
+                    error
+                }
+                Err(e) if e.is_timeout() || e.is_connect() || e.is_request() => e.into(),
+                Err(e) => return Err(e.into()),
+            };
+
+            if attempt >= self.retry_policy.max_attempts
+                || retry_after.is_some_and(|delay| delay > MAX_RETRY_AFTER)
+            {
+                return Err(error);
+            }
+            let delay = retry_after.unwrap_or_else(|| self.retry_policy.backoff(attempt));
+            warn!(
+                "aggTrades request {:?} failed (attempt {}/{}), retrying in {:?}: {}",
+                query, attempt, self.retry_policy.max_attempts, delay, error
+            );
+            sleep(delay).await;
+            params.throttle().await;
+            attempt += 1;
+        }
+    }
+
+    /// Fetch up to `params.limit` trades from the start of the range, past the
+    /// 1000 trades one aggTrades request returns: after the first page, follow
+    /// aggregate trade IDs (`fromId`) until the limit is reached, the range
+    /// ends or there are no newer trades, pausing between requests
+    pub async fn fetch_historical_trades_paginated(
+        &self,
+        symbol: &str,
+        params: &HistoricalTradeParams,
+    ) -> Result<Vec<TickData>, ExchangeError> {
+        let symbol = validate_binance_symbol(symbol)?;
+        let limit = params.limit.max(1);
+        let end_ms = params.end_time.timestamp_millis();
+        let page_limit = |collected: usize| (limit - collected as u32).min(AGG_TRADES_MAX_LIMIT);
+
+        let mut trades = self
+            .first_agg_trades_page(&symbol, params, page_limit(0))
+            .await?;
+        while let Some(last) = trades.last().filter(|_| trades.len() < limit as usize) {
+            // The time scan above already drew on the same request cap
+            if !params.claim_request().await {
+                warn!(
+                    "Stopped paging {} history after {} of {} trades (request cap)",
+                    symbol,
+                    trades.len(),
+                    limit
+                );
+                break;
+            }
+
+            let from_id = last.agg_trade_id + 1;
+            let wanted = page_limit(trades.len());
+            sleep(AGG_TRADES_PAGE_DELAY).await;
+            let page = self
+                .fetch_agg_trades_from_id(&symbol, from_id, wanted, params)
+                .await?;
+
+            let exhausted = page.len() < wanted as usize;
+            let in_range = page
+                .iter()
+                .take_while(|trade| trade.trade_time as i64 <= end_ms)
+                .count();
+            let past_end = in_range < page.len();
+            trades.extend(page.into_iter().take(in_range));
+            if exhausted || past_end {
+                break;
+            }
+        }
+
+        debug!(
+            "Fetched {} historical trades for {} ({} requests against the cap)",
+            trades.len(),
+            symbol,
+            params.requests_issued()
+        );
+        self.agg_trades_to_ticks(&symbol, trades).await
+    }
+
+    /// First page of aggregate trades in the range, walking hour-sized
+    /// windows from its start until one has trades; each window is claimed
+    /// from the request cap and rate limiter in `params`
+    async fn first_agg_trades_page(
+        &self,
+        symbol: &str,
+        params: &HistoricalTradeParams,
+        limit: u32,
+    ) -> Result<Vec<BinanceAggTrade>, ExchangeError> {
+        let end_ms = params.end_time.timestamp_millis();
+        let mut window_start = params.start_time.timestamp_millis();
+
+        // Walk hour-sized windows until one has trades, so callers can page by time
+        while window_start <= end_ms {
+            if !params.claim_request().await {
+                warn!(
+                    "Stopped scanning {} history at {} after {} requests (request cap)",
+                    symbol,
+                    window_start,
+                    params.requests_issued()
+                );
+                break;
+            }
+
+            let window_end = (window_start + AGG_TRADES_MAX_WINDOW_MS).min(end_ms);
+            let trades = self
+                .fetch_agg_trades(symbol, window_start, window_end, limit, params)
+                .await?;
+
+            if !trades.is_empty() {
+                debug!(
+                    "Fetched {} historical trades for {} starting at {}",
+                    trades.len(),
+                    symbol,
+                    window_start
+                );
+                return Ok(trades);
+            }
+
+            window_start = window_end + 1;
         }
+
+        Ok(Vec::new())
+    }
+
+    /// Convert aggregate trades to ticks, rounded to the symbol's precision
+    async fn agg_trades_to_ticks(
+        &self,
+        symbol: &str,
+        trades: Vec<BinanceAggTrade>,
+    ) -> Result<Vec<TickData>, ExchangeError> {
+        if trades.is_empty() {
+            return Ok(Vec::new());
+        }
+        let symbol = symbol.to_string();
+        let precision = self.precision_for(std::slice::from_ref(&symbol)).await;
+        trades
+            .into_iter()
+            .map(|trade| {
+                let mut tick = convert_binance_agg_trade_to_tick_data(&symbol, trade)?;
+                if let Some(p) = precision.get(&symbol) {
+                    normalize_tick_precision(&mut tick, p);
+                }
+                Ok(tick)
+            })
+            .collect()
     }
 
     /// Parse WebSocket message and extract trade data
-    fn parse_trade_message(&self, text: &str) -> Result<TickData, ExchangeError> {
+    fn parse_trade_message(text: &str) -> Result<TickData, ExchangeError> {
         // First try to parse as stream message (combined streams format)
         if let Ok(stream_msg) = serde_json::from_str::<BinanceStreamMessage>(text) {
             return convert_binance_to_tick_data(stream_msg.data);
@@ -61,80 +473,106 @@ impl BinanceExchange {
         )))
     }
 
+    /// Parse a kline stream message, returning the candle only once it has closed
+    fn parse_kline_message(
+        text: &str,
+        timeframe: Timeframe,
+    ) -> Result<Option<OHLCData>, ExchangeError> {
+        let msg = match serde_json::from_str::<BinanceStreamMessage<BinanceKlineMessage>>(text) {
+            Ok(stream_msg) => stream_msg.data,
+            Err(_) => match serde_json::from_str::<BinanceKlineMessage>(text) {
+                Ok(kline_msg) => kline_msg,
+                Err(_) => {
+                    // Subscription confirmations carry a "result" field
+                    let value: serde_json::Value = serde_json::from_str(text)?;
+                    if value.get("result").is_some() {
+                        debug!("Received subscription confirmation: {}", text);
+                        return Ok(None);
+                    }
+                    return Err(ExchangeError::ParseError(format!(
+                        "Unable to parse kline message: {}",
+                        text
+                    )));
+                }
+            },
+        };
+
+        if !msg.kline.is_closed {
+            return Ok(None);
+        }
+        convert_binance_kline_to_ohlc(msg, timeframe).map(Some)
+    }
+
+    /// Handler that parses trade messages, normalizes their precision and
+    /// passes them to `callback`
+    fn trade_handler(callback: TradeCallback, precision: PrecisionMap) -> MessageHandler {
+        Arc::new(move |text| match Self::parse_trade_message(text) {
+            Ok(mut tick_data) => {
+                if let Some(p) = precision.get(&tick_data.symbol) {
+                    normalize_tick_precision(&mut tick_data, p);
+                }
+                callback(tick_data)
+            }
+            Err(e) => warn!("Parse error: {}", e),
+        })
+    }
+
     /// Handle WebSocket connection with reconnection logic
     async fn handle_websocket_connection(
         &self,
-        symbols: &[String],
-        callback: Box<dyn Fn(TickData) + Send + Sync>,
-        mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+        streams: &[String],
+        handler: MessageHandler,
+        shutdown_rx: tokio::sync::broadcast::Receiver<()>,
     ) -> Result<(), ExchangeError> {
-        let streams = build_binance_trade_streams(symbols)?;
         info!(
             "Connecting to Binance WebSocket with {} streams",
             streams.len()
         );
 
-        let mut reconnect_attempts = 0;
-        const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+        let attempts = AtomicU32::new(0);
+        run_with_reconnect(
+            Venue::Binance,
+            self.reconnect_policy,
+            &attempts,
+            shutdown_rx,
+            |shutdown_rx| self.connect_and_subscribe(streams, &handler, shutdown_rx, &attempts),
+        )
+        .await
+    }
 
-        loop {
-            // Check for shutdown signal before each connection attempt
-            if shutdown_rx.try_recv().is_ok() {
-                info!("Shutdown signal received, stopping WebSocket connection attempts");
-                return Ok(());
+    /// Spawn the task that hands buffered messages to the handler
+    fn spawn_dispatcher(
+        mut message_rx: mpsc::Receiver<String>,
+        handler: MessageHandler,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(text) = message_rx.recv().await {
+                handler(&text);
             }
+        })
+    }
 
-            match self
-                .connect_and_subscribe(&streams, &callback, shutdown_rx.resubscribe())
-                .await
-            {
-                Ok(()) => {
-                    info!(
-                        "WebSocket connection ended normally - checking if shutdown was requested"
-                    );
-
-                    // If connection ended normally, it's likely due to shutdown signal
-                    // Exit the reconnection loop
-                    return Ok(());
-                }
-                Err(e) => {
-                    reconnect_attempts += 1;
-                    error!(
-                        "WebSocket connection failed (attempt {}): {}",
-                        reconnect_attempts, e
-                    );
-
-                    if reconnect_attempts >= MAX_RECONNECT_ATTEMPTS {
-                        return Err(ExchangeError::NetworkError(format!(
-                            "Max reconnection attempts ({}) exceeded",
-                            MAX_RECONNECT_ATTEMPTS
-                        )));
-                    }
-
-                    warn!("Attempting to reconnect in {:?}...", RECONNECT_DELAY);
-
-                    // Wait for reconnect delay or shutdown signal
-                    tokio::select! {
-                        _ = sleep(RECONNECT_DELAY) => {
-                            // Continue to retry
-                            continue;
-                        }
-                        _ = shutdown_rx.recv() => {
-                            info!("Shutdown signal received during reconnect delay");
-                            return Ok(());
-                        }
-                    }
-                }
+    /// Hand a raw message to the dispatcher without waiting, counting it as dropped if the buffer is full
+    fn buffer_message(&self, message_tx: &mpsc::Sender<String>, text: String) {
+        if let Err(mpsc::error::TrySendError::Full(_)) = message_tx.try_send(text) {
+            let dropped = self.dropped_frames.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped.is_power_of_two() {
+                warn!(
+                    "WebSocket message buffer full, {} frames dropped so far",
+                    dropped
+                );
             }
         }
     }
 
-    /// Connect to WebSocket and handle subscription
+    /// Connect to WebSocket and handle subscription; `reconnect_attempts` is
+    /// reset once the subscription is sent, so only consecutive failures count
     async fn connect_and_subscribe(
         &self,
         streams: &[String],
-        callback: &Box<dyn Fn(TickData) + Send + Sync>,
+        handler: &MessageHandler,
         mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+        reconnect_attempts: &AtomicU32,
     ) -> Result<(), ExchangeError> {
         // Establish WebSocket connection
         let (ws_stream, _) = connect_async(&self.ws_url)
@@ -159,31 +597,35 @@ impl BinanceExchange {
             })?;
 
         info!("Subscription sent for {} streams", streams.len());
+        reconnect_attempts.store(0, Ordering::SeqCst);
 
-        // Message processing loop
-        loop {
+        // Parsing and callbacks run on a separate task so socket reads never wait on them
+        let (message_tx, message_rx) = mpsc::channel::<String>(self.message_buffer_size);
+        let dispatcher = Self::spawn_dispatcher(message_rx, Arc::clone(handler));
+
+        // Message reading loop
+        let result = loop {
             tokio::select! {
                 msg = read.next() => {
                     match msg {
                         Some(Ok(Message::Text(text))) => {
-                            match self.parse_trade_message(&text) {
-                                Ok(tick_data) => callback(tick_data),
-                                Err(e) => warn!("Parse error: {}", e),
-                            }
+                            self.buffer_message(&message_tx, text);
                         }
                         Some(Ok(Message::Ping(ping))) => {
-                            write.send(Message::Pong(ping)).await?;
+                            if let Err(e) = write.send(Message::Pong(ping)).await {
+                                break Err(e.into());
+                            }
                         }
                         Some(Ok(Message::Close(_))) => {
                             info!("WebSocket closed by server");
-                            break;
+                            break Ok(());
                         }
                         Some(Err(e)) => {
-                            return Err(ExchangeError::WebSocketError(e.to_string()));
+                            break Err(ExchangeError::WebSocketError(e.to_string()));
                         }
                         None => {
                             info!("WebSocket stream ended");
-                            break;
+                            break Ok(());
                         }
                         _ => continue,
                     }
@@ -194,17 +636,27 @@ impl BinanceExchange {
                     if let Err(e) = write.send(Message::Close(None)).await {
                         warn!("Failed to send close frame: {}", e);
                     }
-                    break;
+                    break Ok(());
                 }
             }
+        };
+
+        // Let the dispatcher drain what is already buffered
+        drop(message_tx);
+        if let Err(e) = dispatcher.await {
+            warn!("Message dispatcher task failed: {}", e);
         }
 
-        Ok(())
+        result
     }
 }
 
 #[async_trait]
 impl Exchange for BinanceExchange {
+    fn venue(&self) -> Venue {
+        Venue::Binance
+    }
+
     async fn subscribe_trades(
         &self,
         symbols: &[String],
@@ -222,10 +674,105 @@ impl Exchange for BinanceExchange {
             symbols
         );
 
+        let streams = Venue::Binance.trade_streams(symbols)?;
+        let upper: Vec<String> = symbols.iter().map(|s| s.to_uppercase()).collect();
+        let precision = self.precision_for(&upper).await;
+        let handler = Self::trade_handler(Arc::from(callback), precision);
+
         // This will run indefinitely with reconnection logic
-        self.handle_websocket_connection(symbols, callback, shutdown_rx.resubscribe())
+        self.handle_websocket_connection(&streams, handler, shutdown_rx.resubscribe())
+            .await
+    }
+
+    async fn subscribe_klines(
+        &self,
+        symbols: &[String],
+        interval: Timeframe,
+        callback: Box<dyn Fn(OHLCData) + Send + Sync>,
+        shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<(), ExchangeError> {
+        if symbols.is_empty() {
+            return Err(ExchangeError::InvalidSymbol(
+                "No symbols provided".to_string(),
+            ));
+        }
+
+        info!(
+            "Starting Binance {} kline subscription for symbols: {:?}",
+            binance_kline_interval(interval),
+            symbols
+        );
+
+        let streams = symbols
+            .iter()
+            .map(|symbol| binance_kline_stream(symbol, interval))
+            .collect::<Result<Vec<_>, _>>()?;
+        let handler: MessageHandler =
+            Arc::new(
+                move |text| match Self::parse_kline_message(text, interval) {
+                    Ok(Some(ohlc)) => callback(ohlc),
+                    Ok(None) => {}
+                    Err(e) => warn!("Parse error: {}", e),
+                },
+            );
+
+        self.handle_websocket_connection(&streams, handler, shutdown_rx.resubscribe())
             .await
     }
+
+    fn dropped_frames(&self) -> u64 {
+        self.dropped_frames.load(Ordering::Relaxed)
+    }
+
+    async fn get_symbol_metadata(
+        &self,
+        symbols: &[String],
+    ) -> Result<Vec<SymbolMetadata>, ExchangeError> {
+        let upper: Vec<String> = symbols.iter().map(|s| s.to_uppercase()).collect();
+        let info = self
+            .fetch_exchange_info(&upper.iter().collect::<Vec<_>>())
+            .await?;
+        info.symbols.iter().map(symbol_metadata).collect()
+    }
+
+    async fn get_order_book(&self, symbol: &str, depth: u16) -> Result<OrderBook, ExchangeError> {
+        let symbol = validate_binance_symbol(symbol)?;
+        let url = format!("{}/api/v3/depth", self.rest_url);
+        let response = self
+            .http_client
+            .get(&url)
+            .query(&[("symbol", symbol), ("limit", depth.to_string())])
+            .timeout(self.request_timeout)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ExchangeError::ApiError(format!(
+                "depth request failed with {}: {}",
+                status, body
+            )));
+        }
+
+        convert_binance_depth(serde_json::from_str(&response.text().await?)?)
+    }
+
+    async fn get_historical_trades(
+        &self,
+        symbol: &str,
+        params: &HistoricalTradeParams,
+    ) -> Result<Vec<TickData>, ExchangeError> {
+        if params.limit > AGG_TRADES_MAX_LIMIT {
+            return self.fetch_historical_trades_paginated(symbol, params).await;
+        }
+
+        let symbol = validate_binance_symbol(symbol)?;
+        let trades = self
+            .first_agg_trades_page(&symbol, params, params.limit.max(1))
+            .await?;
+        self.agg_trades_to_ticks(&symbol, trades).await
+    }
 }
 
 impl Default for BinanceExchange {
@@ -243,8 +790,6 @@ mod tests {
 
     #[test]
     fn test_parse_trade_message() {
-        let exchange = BinanceExchange::new();
-
         // Test combined stream message format
         let stream_msg = r#"{
             "stream": "btcusdt@trade",
@@ -263,7 +808,7 @@ mod tests {
             }
         }"#;
 
-        let tick_data = exchange.parse_trade_message(stream_msg).unwrap();
+        let tick_data = BinanceExchange::parse_trade_message(stream_msg).unwrap();
 
         assert_eq!(tick_data.symbol, "BTCUSDT");
         assert_eq!(tick_data.price, Decimal::from_str("50000.00").unwrap());
@@ -275,8 +820,6 @@ mod tests {
 
     #[test]
     fn test_parse_direct_trade_message() {
-        let exchange = BinanceExchange::new();
-
         // Test direct trade message format
         let trade_msg = r#"{
             "e": "trade",
@@ -292,7 +835,7 @@ mod tests {
             "M": true
         }"#;
 
-        let tick_data = exchange.parse_trade_message(trade_msg).unwrap();
+        let tick_data = BinanceExchange::parse_trade_message(trade_msg).unwrap();
 
         assert_eq!(tick_data.symbol, "ETHUSDT");
         assert_eq!(tick_data.price, Decimal::from_str("3000.50").unwrap());
@@ -302,14 +845,12 @@ mod tests {
 
     #[test]
     fn test_parse_subscription_confirmation() {
-        let exchange = BinanceExchange::new();
-
         let confirmation_msg = r#"{
             "result": null,
             "id": 1
         }"#;
 
-        let result = exchange.parse_trade_message(confirmation_msg);
+        let result = BinanceExchange::parse_trade_message(confirmation_msg);
         assert!(result.is_err());
 
         // Should be a parse error indicating it's a control message
@@ -319,4 +860,519 @@ mod tests {
             panic!("Expected ParseError with control message indication");
         }
     }
+
+    #[test]
+    fn test_parse_kline_message() {
+        // Captured from wss://stream.binance.com:9443/stream?streams=btcusdt@kline_1m
+        let closed = r#"{
+            "stream": "btcusdt@kline_1m",
+            "data": {
+                "e": "kline",
+                "E": 1700000060001,
+                "s": "BTCUSDT",
+                "k": {
+                    "t": 1700000040000,
+                    "T": 1700000099999,
+                    "s": "BTCUSDT",
+                    "i": "1m",
+                    "f": 3276591023,
+                    "L": 3276591310,
+                    "o": "36504.01000000",
+                    "c": "36511.99000000",
+                    "h": "36515.00000000",
+                    "l": "36500.10000000",
+                    "v": "12.34560000",
+                    "n": 288,
+                    "x": true,
+                    "q": "450687.31946710",
+                    "V": "6.01230000",
+                    "Q": "219481.27105960",
+                    "B": "0"
+                }
+            }
+        }"#;
+
+        let ohlc = BinanceExchange::parse_kline_message(closed, Timeframe::OneMinute)
+            .unwrap()
+            .unwrap();
+        assert_eq!(ohlc.symbol, "BTCUSDT");
+        assert_eq!(ohlc.timeframe, Timeframe::OneMinute);
+        assert_eq!(ohlc.timestamp.timestamp_millis(), 1700000040000);
+        assert_eq!(ohlc.open, Decimal::from_str("36504.01").unwrap());
+        assert_eq!(ohlc.high, Decimal::from_str("36515").unwrap());
+        assert_eq!(ohlc.low, Decimal::from_str("36500.1").unwrap());
+        assert_eq!(ohlc.close, Decimal::from_str("36511.99").unwrap());
+        assert_eq!(ohlc.volume, Decimal::from_str("12.3456").unwrap());
+        assert_eq!(
+            ohlc.quote_volume,
+            Decimal::from_str("450687.3194671").unwrap()
+        );
+        assert_eq!(ohlc.trade_count, 288);
+
+        // The same candle while still open is not emitted
+        let open = closed.replace(r#""x": true"#, r#""x": false"#);
+        assert!(
+            BinanceExchange::parse_kline_message(&open, Timeframe::OneMinute)
+                .unwrap()
+                .is_none()
+        );
+
+        let confirmation = r#"{"result":null,"id":1}"#;
+        assert!(
+            BinanceExchange::parse_kline_message(confirmation, Timeframe::OneMinute)
+                .unwrap()
+                .is_none()
+        );
+
+        assert_eq!(
+            binance_kline_stream("BTCUSDT", Timeframe::FourHours).unwrap(),
+            "btcusdt@kline_4h"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_slow_callback_drops_frames_instead_of_blocking() {
+        use std::sync::atomic::AtomicUsize;
+        use std::time::Instant;
+        use tokio::net::TcpListener;
+
+        const FRAME_COUNT: usize = 200;
+
+        // Local WebSocket server that bursts trade frames after the subscription
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let _subscription = ws.next().await;
+            for i in 0..FRAME_COUNT {
+                let frame = format!(
+                    r#"{{"e":"trade","E":1672515782136,"s":"BTCUSDT","t":{},"p":"50000.00","q":"0.001","b":1,"a":2,"T":1672515782136,"m":false,"M":true}}"#,
+                    i
+                );
+                ws.send(Message::Text(frame)).await.unwrap();
+            }
+            ws.send(Message::Close(None)).await.unwrap();
+        });
+
+        let exchange = BinanceExchange {
+            ws_url: format!("ws://{}", addr),
+            ..BinanceExchange::new()
+        }
+        .with_message_buffer(4);
+
+        let delivered = Arc::new(AtomicUsize::new(0));
+        let delivered_clone = Arc::clone(&delivered);
+        let callback: TradeCallback = Arc::new(move |_tick| {
+            // Simulate slow downstream processing
+            std::thread::sleep(Duration::from_millis(20));
+            delivered_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let started = Instant::now();
+        exchange
+            .connect_and_subscribe(
+                &["btcusdt@trade".to_string()],
+                &BinanceExchange::trade_handler(callback, PrecisionMap::default()),
+                shutdown_rx,
+                &AtomicU32::new(0),
+            )
+            .await
+            .unwrap();
+
+        // Processing every frame would take FRAME_COUNT * 20ms = 4s
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let dropped = exchange.dropped_frames();
+        assert!(dropped > 0);
+        assert_eq!(
+            dropped as usize + delivered.load(Ordering::SeqCst),
+            FRAME_COUNT
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_attempts_reset_after_connecting() {
+        use std::sync::atomic::AtomicUsize;
+        use tokio::net::TcpListener;
+
+        // Each of the first four connections drops right after the subscription
+        // without a close frame; the fifth closes cleanly
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = Arc::clone(&connections);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                let _subscription = ws.next().await;
+                if accepted.fetch_add(1, Ordering::SeqCst) == 4 {
+                    ws.send(Message::Close(None)).await.unwrap();
+                }
+            }
+        });
+
+        let policy = ReconnectPolicy {
+            max_attempts: Some(2),
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            warn_every: 1,
+        };
+        let exchange = BinanceExchange {
+            ws_url: format!("ws://{}", addr),
+            ..BinanceExchange::new()
+        }
+        .with_reconnect_policy(policy);
+        let handler: MessageHandler = Arc::new(|_| {});
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+
+        // Four failures in a row would exceed two attempts, but each one
+        // follows a successful connection
+        exchange
+            .handle_websocket_connection(&["btcusdt@trade".to_string()], handler, shutdown_rx)
+            .await
+            .unwrap();
+        assert_eq!(connections.load(Ordering::SeqCst), 5);
+
+        // Connections that never open do count up to the limit
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let closed_url = format!("ws://{}", closed.local_addr().unwrap());
+        drop(closed);
+        let exchange = BinanceExchange {
+            ws_url: closed_url,
+            ..BinanceExchange::new()
+        }
+        .with_reconnect_policy(policy);
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let result = exchange
+            .handle_websocket_connection(
+                &["btcusdt@trade".to_string()],
+                Arc::new(|_| {}),
+                shutdown_rx,
+            )
+            .await;
+        assert!(matches!(result, Err(ExchangeError::NetworkError(_))));
+    }
+
+    #[test]
+    fn test_unlimited_reconnect_delay_is_capped() {
+        let policy = ReconnectPolicy {
+            max_attempts: None,
+            ..ReconnectPolicy::default()
+        };
+        assert!(!policy.exhausted(u32::MAX));
+        assert_eq!(policy.delay(1), Duration::from_secs(5));
+        assert_eq!(policy.delay(3), Duration::from_secs(20));
+        assert_eq!(policy.delay(1_000), policy.max_delay);
+        assert!(policy.should_warn(1) && policy.should_warn(20));
+        assert!(!policy.should_warn(11));
+        assert!(ReconnectPolicy::default().exhausted(10));
+    }
+
+    /// Local HTTP server answering the i-th request with `responses[i]`
+    /// (status line, which may carry extra header lines, and body), repeating
+    /// the last one; returns the request lines it has served
+    async fn mock_rest_server(
+        responses: Vec<(&'static str, &'static str)>,
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let served = Arc::clone(&requests);
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request_line = String::from_utf8_lossy(&request)
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                let index = {
+                    let mut served = served.lock().unwrap();
+                    served.push(request_line);
+                    (served.len() - 1).min(responses.len() - 1)
+                };
+                let (status, body) = responses[index];
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (format!("http://{}", addr), requests)
+    }
+
+    fn retrying_exchange(rest_url: String) -> BinanceExchange {
+        BinanceExchange {
+            rest_url,
+            ..BinanceExchange::new()
+        }
+        .with_precision_normalization(false)
+        .with_request_timeout(Duration::from_secs(5))
+        .with_retry_policy(RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(10),
+        })
+    }
+
+    fn history_params() -> HistoricalTradeParams {
+        let start = chrono::DateTime::from_timestamp_millis(1672515782000).unwrap();
+        HistoricalTradeParams::new(start, start + chrono::Duration::minutes(1))
+    }
+
+    #[tokio::test]
+    async fn test_historical_page_retries_transient_errors() {
+        let (rest_url, requests) = mock_rest_server(vec![
+            ("503 Service Unavailable", "{}"),
+            ("429 Too Many Requests", "{}"),
+            (
+                "200 OK",
+                r#"[{"a":1,"p":"50000.00","q":"0.001","f":100,"l":100,"T":1672515782136,"m":false,"M":true}]"#,
+            ),
+        ])
+        .await;
+
+        let ticks = retrying_exchange(rest_url)
+            .get_historical_trades("BTCUSDT", &history_params())
+            .await
+            .unwrap();
+
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert_eq!(ticks.len(), 1);
+        assert_eq!(ticks[0].trade_id, "100");
+        assert_eq!(ticks[0].price, Decimal::from(50000));
+    }
+
+    /// aggTrades response of consecutive trades with IDs in `ids`, one per millisecond
+    fn agg_trades_page(ids: std::ops::RangeInclusive<u64>) -> &'static str {
+        let trades: Vec<String> = ids
+            .map(|id| {
+                format!(
+                    r#"{{"a":{id},"p":"50000.00","q":"0.001","f":{id},"l":{id},"T":{},"m":false,"M":true}}"#,
+                    1672515782000 + id
+                )
+            })
+            .collect();
+        Box::leak(format!("[{}]", trades.join(",")).into_boxed_str())
+    }
+
+    #[tokio::test]
+    async fn test_paginated_history_follows_trade_ids() {
+        let (rest_url, requests) = mock_rest_server(vec![
+            ("200 OK", agg_trades_page(1..=1000)),
+            ("200 OK", agg_trades_page(1001..=1500)),
+        ])
+        .await;
+
+        let params = history_params().with_limit(1500);
+        let ticks = retrying_exchange(rest_url)
+            .get_historical_trades("BTCUSDT", &params)
+            .await
+            .unwrap();
+
+        let ids: Vec<u64> = ticks.iter().map(|t| t.trade_id.parse().unwrap()).collect();
+        assert_eq!(ids, (1..=1500).collect::<Vec<_>>());
+
+        // The first page is found by time, the second continues after its last ID
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].contains("startTime=") && requests[0].contains("limit=1000"));
+        assert!(requests[1].contains("fromId=1001") && requests[1].contains("limit=500"));
+        assert!(!requests[1].contains("startTime="));
+    }
+
+    #[tokio::test]
+    async fn test_paginated_history_shares_request_cap_with_time_scan() {
+        let (rest_url, requests) = mock_rest_server(vec![
+            ("200 OK", "[]"),
+            ("200 OK", agg_trades_page(1..=1000)),
+            ("200 OK", agg_trades_page(1001..=1500)),
+        ])
+        .await;
+
+        let start = chrono::DateTime::from_timestamp_millis(1672515782000).unwrap();
+        let params = HistoricalTradeParams::new(start, start + chrono::Duration::hours(2))
+            .with_limit(1500)
+            .with_max_requests(Some(2));
+        let ticks = retrying_exchange(rest_url)
+            .get_historical_trades("BTCUSDT", &params)
+            .await
+            .unwrap();
+
+        // The empty window and the first page use up the cap
+        assert_eq!(ticks.len(), 1000);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_calls_sharing_a_request_count_share_the_cap() {
+        let (rest_url, requests) = mock_rest_server(vec![("200 OK", "[]")]).await;
+        let exchange = retrying_exchange(rest_url);
+
+        // A quiet ten-hour range, fetched twice the way backfill pages through
+        // one; clones of the params share its request count
+        let start = chrono::DateTime::from_timestamp_millis(1672515782000).unwrap();
+        let params = HistoricalTradeParams::new(start, start + chrono::Duration::hours(10))
+            .with_max_requests(Some(4));
+
+        for _ in 0..2 {
+            let ticks = exchange
+                .get_historical_trades("BTCUSDT", &params.clone())
+                .await
+                .unwrap();
+            assert!(ticks.is_empty());
+        }
+
+        // Four window requests in total, not four per call
+        assert_eq!(requests.lock().unwrap().len(), 4);
+        assert_eq!(params.requests_issued(), 4);
+        assert!(params.requests_exhausted());
+    }
+
+    #[tokio::test]
+    async fn test_time_scan_waits_on_rate_limiter() {
+        let (rest_url, requests) = mock_rest_server(vec![
+            ("200 OK", "[]"),
+            ("200 OK", "[]"),
+            ("200 OK", "[]"),
+            ("200 OK", agg_trades_page(1..=2)),
+        ])
+        .await;
+
+        // Three empty hour windows before the one with trades
+        let start = chrono::DateTime::from_timestamp_millis(1672515782000).unwrap();
+        let interval = Duration::from_millis(50);
+        let params = HistoricalTradeParams::new(start, start + chrono::Duration::hours(10))
+            .with_rate_limiter(Arc::new(crate::exchange::RateLimiter::with_interval(
+                interval,
+            )));
+
+        let started = std::time::Instant::now();
+        let ticks = retrying_exchange(rest_url)
+            .get_historical_trades("BTCUSDT", &params)
+            .await
+            .unwrap();
+
+        // Each of the four window requests took its own limiter slot
+        assert_eq!(ticks.len(), 2);
+        assert_eq!(requests.lock().unwrap().len(), 4);
+        assert!(started.elapsed() >= interval * 3);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_waits_for_retry_after() {
+        let (rest_url, requests) = mock_rest_server(vec![
+            ("429 Too Many Requests\r\nRetry-After: 1", "{}"),
+            ("200 OK", agg_trades_page(1..=2)),
+        ])
+        .await;
+
+        let started = std::time::Instant::now();
+        let ticks = retrying_exchange(rest_url.clone())
+            .get_historical_trades("BTCUSDT", &history_params())
+            .await
+            .unwrap();
+        // The retry policy alone would wait 1ms
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(ticks.len(), 2);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        // Still rate limited after the last attempt
+        let (rest_url, requests) =
+            mock_rest_server(vec![("429 Too Many Requests\r\nRetry-After: 0", "{}")]).await;
+        let result = retrying_exchange(rest_url)
+            .get_historical_trades("BTCUSDT", &history_params())
+            .await;
+        assert!(matches!(result, Err(ExchangeError::RateLimit(_))));
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        // A Retry-After past the cap is not waited out
+        let (rest_url, requests) =
+            mock_rest_server(vec![("429 Too Many Requests\r\nRetry-After: 3600", "{}")]).await;
+        let result = retrying_exchange(rest_url)
+            .get_historical_trades("BTCUSDT", &history_params())
+            .await;
+        assert!(matches!(result, Err(ExchangeError::RateLimit(_))));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_precision_fetch_is_not_retried_immediately() {
+        let (rest_url, requests) =
+            mock_rest_server(vec![("500 Internal Server Error", "{}")]).await;
+        let exchange = retrying_exchange(rest_url).with_precision_normalization(true);
+        let symbols = ["BTCUSDT".to_string()];
+
+        assert!(exchange.precision_for(&symbols).await.is_empty());
+        assert!(exchange.precision_for(&symbols).await.is_empty());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        // Once the delay has passed the next lookup tries again
+        *exchange.precision_retry_at.lock().unwrap() = Some(Instant::now());
+        exchange.precision_for(&symbols).await;
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_order_book_levels_sorted_best_first() {
+        // Captured from /api/v3/depth?symbol=BTCUSDT&limit=5
+        let depth = r#"{"lastUpdateId":48126394521,"bids":[["67231.01000000","1.48362000"],["67231.00000000","0.00750000"],["67230.86000000","0.00016000"],["67230.52000000","0.02000000"],["67230.00000000","0.27811000"]],"asks":[["67231.02000000","3.01217000"],["67231.03000000","0.00010000"],["67231.36000000","0.00008000"],["67231.50000000","0.18000000"],["67232.00000000","0.04461000"]]}"#;
+        let (rest_url, requests) = mock_rest_server(vec![("200 OK", depth)]).await;
+
+        let book = retrying_exchange(rest_url)
+            .get_order_book("btcusdt", 5)
+            .await
+            .unwrap();
+
+        assert!(
+            requests.lock().unwrap()[0].starts_with("GET /api/v3/depth?symbol=BTCUSDT&limit=5 ")
+        );
+        assert_eq!(book.last_update_id, 48126394521);
+        assert_eq!(
+            book.bids[0],
+            (Decimal::new(6723101, 2), Decimal::new(148362, 5))
+        );
+        assert_eq!(book.asks[0].0, Decimal::new(6723102, 2));
+        assert_eq!((book.bids.len(), book.asks.len()), (5, 5));
+        assert!(book.bids.windows(2).all(|w| w[0].0 > w[1].0));
+        assert!(book.asks.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(book.bids[0].0 < book.asks[0].0);
+    }
+
+    #[tokio::test]
+    async fn test_historical_page_gives_up_on_client_errors() {
+        let (rest_url, requests) =
+            mock_rest_server(vec![("400 Bad Request", r#"{"code":-1121}"#)]).await;
+        let exchange = retrying_exchange(rest_url);
+
+        let result = exchange
+            .get_historical_trades("BTCUSDT", &history_params())
+            .await;
+        assert!(matches!(result, Err(ExchangeError::ApiError(_))));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        // Transient errors stop after the last attempt
+        let (rest_url, requests) =
+            mock_rest_server(vec![("500 Internal Server Error", "{}")]).await;
+        let result = retrying_exchange(rest_url)
+            .get_historical_trades("BTCUSDT", &history_params())
+            .await;
+        assert!(result.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
 }
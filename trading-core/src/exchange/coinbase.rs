@@ -0,0 +1,364 @@
+// exchange/coinbase.rs
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, info, warn};
+
+use super::{
+    errors::ExchangeError,
+    reconnect::run_with_reconnect,
+    traits::Exchange,
+    types::{
+        CoinbaseMarketTradesMessage, CoinbaseSubscribeMessage, HistoricalTradeParams,
+        ReconnectPolicy,
+    },
+    utils::convert_coinbase_to_tick_data,
+    venue::Venue,
+};
+use trading_common::data::types::TickData;
+
+// Constants
+const COINBASE_WS_URL: &str = "wss://advanced-trade-ws.coinbase.com";
+
+type TradeCallback = Box<dyn Fn(TickData) + Send + Sync>;
+
+/// Coinbase exchange implementation (live trades only)
+pub struct CoinbaseExchange {
+    ws_url: String,
+    reconnect_policy: ReconnectPolicy,
+}
+
+impl CoinbaseExchange {
+    /// Create a new Coinbase exchange instance
+    pub fn new() -> Self {
+        Self {
+            ws_url: COINBASE_WS_URL.to_string(),
+            reconnect_policy: ReconnectPolicy::default(),
+        }
+    }
+
+    /// Set how the WebSocket reconnects after a failed connection
+    pub fn with_reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = policy;
+        self
+    }
+
+    /// Parse a WebSocket message into its trades; control messages such as
+    /// subscription confirmations and heartbeats yield none
+    fn parse_message(text: &str) -> Result<Vec<TickData>, ExchangeError> {
+        let value: serde_json::Value = serde_json::from_str(text)?;
+
+        if value.get("type").and_then(|t| t.as_str()) == Some("error") {
+            return Err(ExchangeError::ApiError(format!(
+                "Coinbase rejected the subscription: {}",
+                text
+            )));
+        }
+
+        if value.get("channel").and_then(|c| c.as_str()) == Some("market_trades") {
+            let msg: CoinbaseMarketTradesMessage = serde_json::from_value(value)?;
+            // The snapshot after subscribing replays recent trades newest
+            // first; only live updates are forwarded
+            return msg
+                .events
+                .into_iter()
+                .filter(|event| event.event_type == "update")
+                .flat_map(|event| event.trades)
+                .map(convert_coinbase_to_tick_data)
+                .collect();
+        }
+
+        debug!("Ignoring control message: {}", text);
+        Ok(Vec::new())
+    }
+
+    /// Handle WebSocket connection with reconnection logic
+    async fn handle_websocket_connection(
+        &self,
+        symbols: &[String],
+        callback: TradeCallback,
+        shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<(), ExchangeError> {
+        let product_ids = Venue::Coinbase.trade_streams(symbols)?;
+        // Ticks carry the symbol as configured, not the product ID
+        let configured: HashMap<String, String> = product_ids
+            .iter()
+            .cloned()
+            .zip(symbols.iter().map(|s| s.to_uppercase()))
+            .collect();
+        info!(
+            "Connecting to Coinbase WebSocket for {} products",
+            product_ids.len()
+        );
+
+        let attempts = AtomicU32::new(0);
+        run_with_reconnect(
+            Venue::Coinbase,
+            self.reconnect_policy,
+            &attempts,
+            shutdown_rx,
+            |shutdown_rx| {
+                self.connect_and_subscribe(
+                    &product_ids,
+                    &configured,
+                    &callback,
+                    shutdown_rx,
+                    &attempts,
+                )
+            },
+        )
+        .await
+    }
+
+    /// Connect to WebSocket, subscribe to the market_trades channel and forward trades
+    async fn connect_and_subscribe(
+        &self,
+        product_ids: &[String],
+        configured: &HashMap<String, String>,
+        callback: &TradeCallback,
+        mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+        reconnect_attempts: &AtomicU32,
+    ) -> Result<(), ExchangeError> {
+        let (ws_stream, _) = connect_async(&self.ws_url)
+            .await
+            .map_err(|e| ExchangeError::WebSocketError(format!("Failed to connect: {}", e)))?;
+
+        debug!("WebSocket connected to {}", self.ws_url);
+
+        let (mut write, mut read) = ws_stream.split();
+
+        let subscribe_msg = CoinbaseSubscribeMessage::market_trades(product_ids.to_vec());
+        write
+            .send(Message::Text(serde_json::to_string(&subscribe_msg)?))
+            .await
+            .map_err(|e| {
+                ExchangeError::WebSocketError(format!("Failed to send subscription: {}", e))
+            })?;
+
+        info!("Subscription sent for {} products", product_ids.len());
+        reconnect_attempts.store(0, Ordering::SeqCst);
+
+        loop {
+            tokio::select! {
+                msg = read.next() => {
+                    match msg {
+                        Some(Ok(Message::Text(text))) => match Self::parse_message(&text) {
+                            Ok(ticks) => {
+                                for mut tick in ticks {
+                                    if let Some(symbol) = configured.get(&tick.symbol) {
+                                        tick.symbol = symbol.clone();
+                                    }
+                                    callback(tick);
+                                }
+                            }
+                            Err(e @ ExchangeError::ApiError(_)) => return Err(e),
+                            Err(e) => warn!("Parse error: {}", e),
+                        },
+                        Some(Ok(Message::Ping(ping))) => {
+                            write.send(Message::Pong(ping)).await?;
+                        }
+                        Some(Ok(Message::Close(_))) => {
+                            info!("WebSocket closed by server");
+                            return Ok(());
+                        }
+                        Some(Err(e)) => {
+                            return Err(ExchangeError::WebSocketError(e.to_string()));
+                        }
+                        None => {
+                            info!("WebSocket stream ended");
+                            return Ok(());
+                        }
+                        _ => continue,
+                    }
+                }
+                _ = shutdown_rx.recv() => {
+                    info!("Shutdown signal received, closing WebSocket gracefully");
+                    if let Err(e) = write.send(Message::Close(None)).await {
+                        warn!("Failed to send close frame: {}", e);
+                    }
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Exchange for CoinbaseExchange {
+    fn venue(&self) -> Venue {
+        Venue::Coinbase
+    }
+
+    async fn subscribe_trades(
+        &self,
+        symbols: &[String],
+        callback: Box<dyn Fn(TickData) + Send + Sync>,
+        shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<(), ExchangeError> {
+        if symbols.is_empty() {
+            return Err(ExchangeError::InvalidSymbol(
+                "No symbols provided".to_string(),
+            ));
+        }
+
+        info!(
+            "Starting Coinbase trade subscription for symbols: {:?}",
+            symbols
+        );
+
+        self.handle_websocket_connection(symbols, callback, shutdown_rx.resubscribe())
+            .await
+    }
+
+    async fn get_historical_trades(
+        &self,
+        symbol: &str,
+        _params: &HistoricalTradeParams,
+    ) -> Result<Vec<TickData>, ExchangeError> {
+        Err(ExchangeError::ApiError(format!(
+            "Historical trades are not supported for Coinbase ({})",
+            symbol
+        )))
+    }
+}
+
+impl Default for CoinbaseExchange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_parse_control_and_error_messages() {
+        let subscriptions = r#"{"channel":"subscriptions","client_id":"","timestamp":"2024-01-01T00:00:00.000000Z","sequence_num":0,"events":[{"subscriptions":{"market_trades":["BTC-USD"]}}]}"#;
+        assert!(CoinbaseExchange::parse_message(subscriptions)
+            .unwrap()
+            .is_empty());
+
+        let error = r#"{"type":"error","message":"failure to subscribe"}"#;
+        assert!(matches!(
+            CoinbaseExchange::parse_message(error),
+            Err(ExchangeError::ApiError(_))
+        ));
+    }
+
+    fn market_trades(event_type: &str, trades: &[(u64, &str)]) -> String {
+        let trades: Vec<serde_json::Value> = trades
+            .iter()
+            .map(|(id, product)| {
+                serde_json::json!({
+                    "trade_id": id.to_string(),
+                    "product_id": product,
+                    "price": "100.5",
+                    "size": "0.2",
+                    "side": "SELL",
+                    "time": "2024-01-01T00:00:00.000001Z",
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "channel": "market_trades",
+            "client_id": "",
+            "timestamp": "2024-01-01T00:00:00.000001Z",
+            "sequence_num": 0,
+            "events": [{"type": event_type, "trades": trades}],
+        })
+        .to_string()
+    }
+
+    /// Local WebSocket server that returns the subscription it receives
+    /// after sending `frames` and closing
+    async fn serve_once(frames: Vec<String>) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let subscription = match ws.next().await {
+                Some(Ok(Message::Text(text))) => text,
+                other => panic!("expected subscription, got {:?}", other),
+            };
+            for frame in frames {
+                ws.send(Message::Text(frame)).await.unwrap();
+            }
+            let _ = ws.send(Message::Close(None)).await;
+            subscription
+        });
+        (format!("ws://{}", addr), server)
+    }
+
+    #[tokio::test]
+    async fn test_subscribes_to_market_trades_and_keeps_configured_symbol() {
+        let (ws_url, server) = serve_once(vec![
+            market_trades("snapshot", &[(1, "BTC-USD")]),
+            market_trades("update", &[(2, "BTC-USD"), (3, "ETH-USD")]),
+        ])
+        .await;
+
+        let exchange = CoinbaseExchange {
+            ws_url,
+            ..CoinbaseExchange::new()
+        };
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+
+        exchange
+            .subscribe_trades(
+                &["BTCUSD".to_string(), "eth-usd".to_string()],
+                Box::new(move |tick| sink.lock().unwrap().push(tick)),
+                shutdown_rx,
+            )
+            .await
+            .unwrap();
+
+        let subscription: serde_json::Value = serde_json::from_str(&server.await.unwrap()).unwrap();
+        assert_eq!(subscription["type"], "subscribe");
+        assert_eq!(
+            subscription["product_ids"],
+            serde_json::json!(["BTC-USD", "ETH-USD"])
+        );
+        assert_eq!(subscription["channel"], "market_trades");
+
+        // The snapshot is skipped; both trades of the update are forwarded
+        let ticks = received.lock().unwrap();
+        let ids: Vec<&str> = ticks.iter().map(|t| t.trade_id.as_str()).collect();
+        assert_eq!(ids, ["2", "3"]);
+        let symbols: Vec<&str> = ticks.iter().map(|t| t.symbol.as_str()).collect();
+        assert_eq!(symbols, ["BTCUSD", "ETH-USD"]);
+        assert!(ticks.iter().all(|t| t.is_buyer_maker));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_subscription_is_not_retried() {
+        let (ws_url, server) = serve_once(vec![
+            r#"{"type":"error","message":"failure to subscribe"}"#.to_string(),
+        ])
+        .await;
+        let exchange = CoinbaseExchange {
+            ws_url,
+            ..CoinbaseExchange::new()
+        };
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+
+        // A retry would first wait out the reconnect delay
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            exchange.subscribe_trades(&["BTC-USD".to_string()], Box::new(|_| {}), shutdown_rx),
+        )
+        .await
+        .expect("rejected subscription was retried");
+
+        assert!(matches!(result, Err(ExchangeError::ApiError(_))));
+        server.await.unwrap();
+    }
+}
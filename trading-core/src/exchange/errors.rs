@@ -16,6 +16,12 @@ pub enum ExchangeError {
 
     #[error("Data parsing error: {0}")]
     ParseError(String),
+
+    #[error("API error: {0}")]
+    ApiError(String),
+
+    #[error("Rate limited: {0}")]
+    RateLimit(String),
 }
 
 // Convert from common error types
@@ -25,6 +31,12 @@ impl From<serde_json::Error> for ExchangeError {
     }
 }
 
+impl From<reqwest::Error> for ExchangeError {
+    fn from(err: reqwest::Error) -> Self {
+        ExchangeError::NetworkError(err.to_string())
+    }
+}
+
 impl From<tokio_tungstenite::tungstenite::Error> for ExchangeError {
     fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
         ExchangeError::WebSocketError(err.to_string())
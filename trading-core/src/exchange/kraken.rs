@@ -0,0 +1,331 @@
+// exchange/kraken.rs
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, info, warn};
+
+use super::{
+    errors::ExchangeError,
+    reconnect::run_with_reconnect,
+    traits::Exchange,
+    types::{HistoricalTradeParams, KrakenSubscribeMessage, KrakenTradeMessage, ReconnectPolicy},
+    utils::{convert_kraken_to_tick_data, normalize_kraken_symbol},
+    venue::Venue,
+};
+use trading_common::data::types::TickData;
+
+// Constants
+const KRAKEN_WS_URL: &str = "wss://ws.kraken.com/v2";
+
+type TradeCallback = Box<dyn Fn(TickData) + Send + Sync>;
+
+/// Kraken exchange implementation (live trades only)
+pub struct KrakenExchange {
+    ws_url: String,
+    reconnect_policy: ReconnectPolicy,
+}
+
+impl KrakenExchange {
+    /// Create a new Kraken exchange instance
+    pub fn new() -> Self {
+        Self {
+            ws_url: KRAKEN_WS_URL.to_string(),
+            reconnect_policy: ReconnectPolicy::default(),
+        }
+    }
+
+    /// Set how the WebSocket reconnects after a failed connection
+    pub fn with_reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = policy;
+        self
+    }
+
+    /// Parse a WebSocket message into the trades it carries; heartbeats,
+    /// status updates and subscription acknowledgements yield none
+    fn parse_message(text: &str) -> Result<Vec<TickData>, ExchangeError> {
+        let value: serde_json::Value = serde_json::from_str(text)?;
+
+        if value.get("channel").and_then(|c| c.as_str()) == Some("trade") {
+            let msg: KrakenTradeMessage = serde_json::from_value(value)?;
+            return msg
+                .data
+                .into_iter()
+                .map(convert_kraken_to_tick_data)
+                .collect();
+        }
+
+        if value.get("success").and_then(|s| s.as_bool()) == Some(false) {
+            return Err(ExchangeError::ApiError(format!(
+                "Kraken rejected the subscription: {}",
+                text
+            )));
+        }
+
+        debug!("Ignoring control message: {}", text);
+        Ok(Vec::new())
+    }
+
+    /// Handle WebSocket connection with reconnection logic
+    async fn handle_websocket_connection(
+        &self,
+        symbols: &[String],
+        callback: TradeCallback,
+        shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<(), ExchangeError> {
+        let pairs = symbols
+            .iter()
+            .map(|symbol| normalize_kraken_symbol(symbol))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Ticks carry the symbol as configured, not the Kraken pair
+        let configured: HashMap<String, String> = pairs
+            .iter()
+            .cloned()
+            .zip(symbols.iter().map(|s| s.to_uppercase()))
+            .collect();
+        info!("Connecting to Kraken WebSocket for {} pairs", pairs.len());
+
+        let attempts = AtomicU32::new(0);
+        run_with_reconnect(
+            Venue::Kraken,
+            self.reconnect_policy,
+            &attempts,
+            shutdown_rx,
+            |shutdown_rx| {
+                self.connect_and_subscribe(&pairs, &configured, &callback, shutdown_rx, &attempts)
+            },
+        )
+        .await
+    }
+
+    /// Connect to WebSocket, subscribe to the trade channel and forward trades
+    async fn connect_and_subscribe(
+        &self,
+        pairs: &[String],
+        configured: &HashMap<String, String>,
+        callback: &TradeCallback,
+        mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+        reconnect_attempts: &AtomicU32,
+    ) -> Result<(), ExchangeError> {
+        let (ws_stream, _) = connect_async(&self.ws_url)
+            .await
+            .map_err(|e| ExchangeError::WebSocketError(format!("Failed to connect: {}", e)))?;
+
+        debug!("WebSocket connected to {}", self.ws_url);
+
+        let (mut write, mut read) = ws_stream.split();
+
+        let subscribe_msg = KrakenSubscribeMessage::trades(pairs.to_vec());
+        write
+            .send(Message::Text(serde_json::to_string(&subscribe_msg)?))
+            .await
+            .map_err(|e| {
+                ExchangeError::WebSocketError(format!("Failed to send subscription: {}", e))
+            })?;
+
+        info!("Subscription sent for {} pairs", pairs.len());
+        reconnect_attempts.store(0, Ordering::SeqCst);
+
+        loop {
+            tokio::select! {
+                msg = read.next() => {
+                    match msg {
+                        Some(Ok(Message::Text(text))) => match Self::parse_message(&text) {
+                            Ok(ticks) => {
+                                for mut tick in ticks {
+                                    if let Some(symbol) = configured.get(&tick.symbol) {
+                                        tick.symbol = symbol.clone();
+                                    }
+                                    callback(tick);
+                                }
+                            }
+                            Err(e @ ExchangeError::ApiError(_)) => return Err(e),
+                            Err(e) => warn!("Parse error: {}", e),
+                        },
+                        Some(Ok(Message::Ping(ping))) => {
+                            write.send(Message::Pong(ping)).await?;
+                        }
+                        Some(Ok(Message::Close(_))) => {
+                            info!("WebSocket closed by server");
+                            return Ok(());
+                        }
+                        Some(Err(e)) => {
+                            return Err(ExchangeError::WebSocketError(e.to_string()));
+                        }
+                        None => {
+                            info!("WebSocket stream ended");
+                            return Ok(());
+                        }
+                        _ => continue,
+                    }
+                }
+                _ = shutdown_rx.recv() => {
+                    info!("Shutdown signal received, closing WebSocket gracefully");
+                    if let Err(e) = write.send(Message::Close(None)).await {
+                        warn!("Failed to send close frame: {}", e);
+                    }
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Exchange for KrakenExchange {
+    fn venue(&self) -> Venue {
+        Venue::Kraken
+    }
+
+    async fn subscribe_trades(
+        &self,
+        symbols: &[String],
+        callback: Box<dyn Fn(TickData) + Send + Sync>,
+        shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<(), ExchangeError> {
+        if symbols.is_empty() {
+            return Err(ExchangeError::InvalidSymbol(
+                "No symbols provided".to_string(),
+            ));
+        }
+
+        info!(
+            "Starting Kraken trade subscription for symbols: {:?}",
+            symbols
+        );
+
+        self.handle_websocket_connection(symbols, callback, shutdown_rx.resubscribe())
+            .await
+    }
+
+    async fn get_historical_trades(
+        &self,
+        symbol: &str,
+        _params: &HistoricalTradeParams,
+    ) -> Result<Vec<TickData>, ExchangeError> {
+        Err(ExchangeError::ApiError(format!(
+            "Historical trades are not supported for Kraken ({})",
+            symbol
+        )))
+    }
+}
+
+impl Default for KrakenExchange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+    use trading_common::data::types::TradeSide;
+
+    #[test]
+    fn test_parse_trade_and_control_messages() {
+        // Captured from the v2 trade channel; one message may carry several trades
+        let update = r#"{"channel":"trade","type":"update","data":[{"symbol":"BTC/USD","side":"buy","price":63512.4,"qty":0.0125,"ord_type":"limit","trade_id":73112649,"timestamp":"2024-06-18T09:25:10.401877Z"},{"symbol":"BTC/USD","side":"sell","price":63511.7,"qty":0.00047185,"ord_type":"market","trade_id":73112650,"timestamp":"2024-06-18T09:25:10.402001Z"}]}"#;
+        let ticks = KrakenExchange::parse_message(update).unwrap();
+        let ids: Vec<&str> = ticks.iter().map(|t| t.trade_id.as_str()).collect();
+        assert_eq!(ids, ["73112649", "73112650"]);
+        assert!(!ticks[0].is_buyer_maker);
+        assert!(ticks[1].is_buyer_maker);
+
+        for control in [
+            r#"{"channel":"heartbeat"}"#,
+            r#"{"channel":"status","type":"update","data":[{"version":"2.0.0","system":"online","api_version":"v2","connection_id":1234,"ws_version":"2.0.0"}]}"#,
+            r#"{"method":"subscribe","result":{"channel":"trade","snapshot":false,"symbol":"BTC/USD"},"success":true,"time_in":"2024-06-18T09:25:09.000000Z","time_out":"2024-06-18T09:25:09.000100Z"}"#,
+        ] {
+            assert!(KrakenExchange::parse_message(control).unwrap().is_empty());
+        }
+
+        let error = r#"{"error":"Currency pair not supported ABC/XYZ","method":"subscribe","success":false,"symbol":"ABC/XYZ"}"#;
+        assert!(matches!(
+            KrakenExchange::parse_message(error),
+            Err(ExchangeError::ApiError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_subscribes_to_trades_and_keeps_configured_symbol() {
+        // Local WebSocket server that checks the subscription and sends two trades
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let subscription = match ws.next().await {
+                Some(Ok(Message::Text(text))) => text,
+                other => panic!("expected subscription, got {:?}", other),
+            };
+            for (id, pair) in [(1, "BTC/USD"), (2, "ETH/USD")] {
+                let frame = format!(
+                    r#"{{"channel":"trade","type":"update","data":[{{"symbol":"{}","side":"buy","price":100.5,"qty":0.2,"ord_type":"limit","trade_id":{},"timestamp":"2024-01-01T00:00:00.000001Z"}}]}}"#,
+                    pair, id
+                );
+                ws.send(Message::Text(frame)).await.unwrap();
+            }
+            ws.send(Message::Close(None)).await.unwrap();
+            subscription
+        });
+
+        let exchange = KrakenExchange {
+            ws_url: format!("ws://{}", addr),
+            ..KrakenExchange::new()
+        };
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+
+        exchange
+            .subscribe_trades(
+                &["BTCUSD".to_string(), "ethusd".to_string()],
+                Box::new(move |tick| sink.lock().unwrap().push(tick)),
+                shutdown_rx,
+            )
+            .await
+            .unwrap();
+
+        let subscription: serde_json::Value = serde_json::from_str(&server.await.unwrap()).unwrap();
+        assert_eq!(subscription["method"], "subscribe");
+        assert_eq!(subscription["params"]["channel"], "trade");
+        assert_eq!(
+            subscription["params"]["symbol"],
+            serde_json::json!(["BTC/USD", "ETH/USD"])
+        );
+
+        let ticks = received.lock().unwrap();
+        let symbols: Vec<&str> = ticks.iter().map(|t| t.symbol.as_str()).collect();
+        assert_eq!(symbols, ["BTCUSD", "ETHUSD"]);
+        assert!(ticks.iter().all(|t| t.side == TradeSide::Buy));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_subscription_is_not_retried() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let _subscription = ws.next().await;
+            let error = r#"{"error":"Currency pair not supported ABC/USD","method":"subscribe","success":false,"symbol":"ABC/USD"}"#;
+            ws.send(Message::Text(error.to_string())).await.unwrap();
+        });
+
+        let exchange = KrakenExchange {
+            ws_url: format!("ws://{}", addr),
+            ..KrakenExchange::new()
+        };
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+
+        // The listener only accepts once, so a retry would end in a network error
+        let result = exchange
+            .subscribe_trades(&["ABCUSD".to_string()], Box::new(|_| {}), shutdown_rx)
+            .await;
+        assert!(matches!(result, Err(ExchangeError::ApiError(_))));
+    }
+}
@@ -1,12 +1,21 @@
 // exchange/mod.rs
 pub mod binance;
+pub mod coinbase;
 pub mod errors;
+pub mod kraken;
+pub mod rate_limit;
+mod reconnect;
 pub mod traits;
 pub mod types;
 pub mod utils;
+pub mod venue;
 
 // Re-export main interfaces for easy access
 pub use binance::BinanceExchange;
+pub use coinbase::CoinbaseExchange;
 pub use errors::ExchangeError;
+pub use kraken::KrakenExchange;
+pub use rate_limit::RateLimiter;
 pub use traits::Exchange;
 pub use types::*;
+pub use venue::Venue;
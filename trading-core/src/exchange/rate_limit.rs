@@ -0,0 +1,42 @@
+// exchange/rate_limit.rs
+
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::time::{sleep_until, Instant};
+
+/// Spaces requests evenly so their combined rate stays under a limit
+///
+/// Share one limiter (behind an `Arc`) between every task that talks to the
+/// same exchange; each `acquire` claims the next free slot, so the aggregate
+/// rate holds however many tasks are waiting.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// Allow at most `requests` per second; 0 is treated as 1
+    pub fn per_second(requests: u32) -> Self {
+        Self::with_interval(Duration::from_secs(1) / requests.max(1))
+    }
+
+    pub fn with_interval(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Wait until the caller may send its next request
+    pub async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+        sleep_until(slot).await;
+    }
+}
@@ -0,0 +1,81 @@
+// exchange/reconnect.rs
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use tokio::sync::broadcast;
+use tokio::time::sleep;
+use tracing::{debug, error, info, warn};
+
+use super::{errors::ExchangeError, types::ReconnectPolicy, venue::Venue};
+
+/// Run `connect` until a connection ends normally or shutdown is signalled,
+/// reconnecting after failures as `policy` allows
+///
+/// Each attempt gets its own shutdown receiver. `attempts` counts failures
+/// and may be reset by `connect` once it is subscribed, so only consecutive
+/// failures exhaust the policy. A rejected subscription (`ApiError`) would
+/// fail the same way on every attempt and is returned at once.
+pub(super) async fn run_with_reconnect<F, Fut>(
+    venue: Venue,
+    policy: ReconnectPolicy,
+    attempts: &AtomicU32,
+    mut shutdown_rx: broadcast::Receiver<()>,
+    mut connect: F,
+) -> Result<(), ExchangeError>
+where
+    F: FnMut(broadcast::Receiver<()>) -> Fut,
+    Fut: Future<Output = Result<(), ExchangeError>>,
+{
+    loop {
+        // Check for shutdown signal before each connection attempt
+        if shutdown_rx.try_recv().is_ok() {
+            info!("Shutdown signal received, stopping WebSocket connection attempts");
+            return Ok(());
+        }
+
+        let e = match connect(shutdown_rx.resubscribe()).await {
+            Ok(()) => {
+                info!("{:?} WebSocket connection ended normally", venue);
+                return Ok(());
+            }
+            Err(e @ ExchangeError::ApiError(_)) => {
+                error!("{:?} subscription failed: {}", venue, e);
+                return Err(e);
+            }
+            Err(e) => e,
+        };
+
+        let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        if policy.exhausted(attempt) {
+            error!(
+                "{:?} WebSocket connection failed (attempt {}): {}",
+                venue, attempt, e
+            );
+            return Err(ExchangeError::NetworkError(format!(
+                "Max reconnection attempts ({}) exceeded",
+                attempt
+            )));
+        }
+
+        let delay = policy.delay(attempt);
+        if policy.should_warn(attempt) {
+            warn!(
+                "{:?} WebSocket connection failed (attempt {}): {}; reconnecting in {:?}",
+                venue, attempt, e, delay
+            );
+        } else {
+            debug!(
+                "{:?} WebSocket connection failed (attempt {}): {}; reconnecting in {:?}",
+                venue, attempt, e, delay
+            );
+        }
+
+        tokio::select! {
+            _ = sleep(delay) => continue,
+            _ = shutdown_rx.recv() => {
+                info!("Shutdown signal received during reconnect delay");
+                return Ok(());
+            }
+        }
+    }
+}
@@ -1,12 +1,15 @@
 // exchange/traits.rs
 
-use super::ExchangeError;
+use super::{ExchangeError, HistoricalTradeParams, OrderBook, Venue};
 use async_trait::async_trait;
-use trading_common::data::types::TickData;
+use trading_common::data::types::{OHLCData, SymbolMetadata, TickData, Timeframe};
 
 /// Main exchange interface that all exchange implementations must follow
 #[async_trait]
 pub trait Exchange: Send + Sync {
+    /// Venue whose symbol and stream naming conventions this exchange uses
+    fn venue(&self) -> Venue;
+
     /// Subscribe to real-time trade data streams
     async fn subscribe_trades(
         &self,
@@ -14,4 +17,52 @@ pub trait Exchange: Send + Sync {
         callback: Box<dyn Fn(TickData) + Send + Sync>,
         shutdown_rx: tokio::sync::broadcast::Receiver<()>,
     ) -> Result<(), ExchangeError>;
+
+    /// Subscribe to candlestick streams, receiving each candle once it has closed
+    async fn subscribe_klines(
+        &self,
+        _symbols: &[String],
+        _interval: Timeframe,
+        _callback: Box<dyn Fn(OHLCData) + Send + Sync>,
+        _shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> Result<(), ExchangeError> {
+        Err(ExchangeError::ApiError(format!(
+            "Kline streams are unsupported on {}",
+            self.venue()
+        )))
+    }
+
+    /// Fetch one page of historical trades within the given time range
+    ///
+    /// Trades are returned in ascending time order, at most `params.limit` of them.
+    /// An empty result means there are no trades in the range.
+    async fn get_historical_trades(
+        &self,
+        symbol: &str,
+        params: &HistoricalTradeParams,
+    ) -> Result<Vec<TickData>, ExchangeError>;
+
+    /// Trading rules (increments, minimum order value, status) of the given symbols
+    async fn get_symbol_metadata(
+        &self,
+        _symbols: &[String],
+    ) -> Result<Vec<SymbolMetadata>, ExchangeError> {
+        Err(ExchangeError::ApiError(format!(
+            "Symbol metadata is not available from {}",
+            self.venue()
+        )))
+    }
+
+    /// Snapshot of the top `depth` price levels on each side of the order book
+    async fn get_order_book(&self, _symbol: &str, _depth: u16) -> Result<OrderBook, ExchangeError> {
+        Err(ExchangeError::ApiError(format!(
+            "Order book snapshots are unsupported on {}",
+            self.venue()
+        )))
+    }
+
+    /// Number of incoming frames dropped because the message buffer was full
+    fn dropped_frames(&self) -> u64 {
+        0
+    }
 }
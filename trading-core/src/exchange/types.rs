@@ -1,6 +1,13 @@
 // exchange/types.rs
 
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::RateLimiter;
 
 /// Binance specific trade message format
 #[derive(Debug, Deserialize, Clone)]
@@ -32,13 +39,60 @@ pub struct BinanceTradeMessage {
 
 /// Binance WebSocket stream wrapper for combined streams
 #[derive(Debug, Deserialize)]
-pub struct BinanceStreamMessage {
+pub struct BinanceStreamMessage<T = BinanceTradeMessage> {
     /// Stream name (e.g., "btcusdt@trade")
     #[allow(dead_code)] // Required for JSON deserialization
     pub stream: String,
 
-    /// The actual trade data
-    pub data: BinanceTradeMessage,
+    /// The actual trade or kline data
+    pub data: T,
+}
+
+/// Binance kline (candlestick) stream event
+#[derive(Debug, Deserialize, Clone)]
+pub struct BinanceKlineMessage {
+    /// Symbol
+    #[serde(rename = "s")]
+    pub symbol: String,
+
+    #[serde(rename = "k")]
+    pub kline: BinanceKline,
+}
+
+/// Candle inside a kline event; prices and volume are decimal strings
+#[derive(Debug, Deserialize, Clone)]
+pub struct BinanceKline {
+    /// Candle open time in milliseconds
+    #[serde(rename = "t")]
+    pub open_time: i64,
+
+    #[serde(rename = "o")]
+    pub open: String,
+
+    #[serde(rename = "h")]
+    pub high: String,
+
+    #[serde(rename = "l")]
+    pub low: String,
+
+    #[serde(rename = "c")]
+    pub close: String,
+
+    /// Base asset volume
+    #[serde(rename = "v")]
+    pub volume: String,
+
+    /// Quote asset volume
+    #[serde(rename = "q")]
+    pub quote_volume: String,
+
+    /// Number of trades
+    #[serde(rename = "n")]
+    pub trade_count: u64,
+
+    /// Whether the candle is final; open candles are re-sent as they update
+    #[serde(rename = "x")]
+    pub is_closed: bool,
 }
 
 /// Binance subscription message format
@@ -58,3 +112,379 @@ impl BinanceSubscribeMessage {
         }
     }
 }
+
+/// Binance aggregate trade from the REST `aggTrades` endpoint
+#[derive(Debug, Deserialize, Clone)]
+pub struct BinanceAggTrade {
+    /// Aggregate trade ID, the cursor for `fromId` paging
+    #[serde(rename = "a")]
+    pub agg_trade_id: u64,
+
+    /// Price
+    #[serde(rename = "p")]
+    pub price: String,
+
+    /// Quantity
+    #[serde(rename = "q")]
+    pub quantity: String,
+
+    /// First trade ID in the aggregate
+    #[serde(rename = "f")]
+    pub first_trade_id: u64,
+
+    /// Trade time
+    #[serde(rename = "T")]
+    pub trade_time: u64,
+
+    /// Is the buyer the market maker?
+    #[serde(rename = "m")]
+    pub is_buyer_maker: bool,
+}
+
+/// Binance REST `depth` response; levels are [price, quantity] decimal strings
+#[derive(Debug, Deserialize, Clone)]
+pub struct BinanceDepthResponse {
+    #[serde(rename = "lastUpdateId")]
+    pub last_update_id: u64,
+    pub bids: Vec<[String; 2]>,
+    pub asks: Vec<[String; 2]>,
+}
+
+/// Order book depth snapshot as (price, quantity) levels, best first:
+/// bids by descending price, asks by ascending price
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderBook {
+    pub bids: Vec<(Decimal, Decimal)>,
+    pub asks: Vec<(Decimal, Decimal)>,
+    /// Exchange sequence number the snapshot was taken at
+    pub last_update_id: u64,
+}
+
+/// Binance `exchangeInfo` response, reduced to the fields used here
+#[derive(Debug, Deserialize)]
+pub struct BinanceExchangeInfo {
+    pub symbols: Vec<BinanceSymbolInfo>,
+}
+
+/// Trading rules for one symbol
+#[derive(Debug, Deserialize)]
+pub struct BinanceSymbolInfo {
+    pub symbol: String,
+    /// Trading status, "TRADING" while the symbol is open
+    #[serde(default)]
+    pub status: String,
+    #[serde(rename = "baseAsset", default)]
+    pub base_asset: String,
+    #[serde(rename = "quoteAsset", default)]
+    pub quote_asset: String,
+    pub filters: Vec<BinanceSymbolFilter>,
+}
+
+/// Symbol filters; only increments and the minimum order value matter here
+#[derive(Debug, Deserialize)]
+#[serde(tag = "filterType")]
+pub enum BinanceSymbolFilter {
+    #[serde(rename = "PRICE_FILTER")]
+    Price {
+        #[serde(rename = "tickSize")]
+        tick_size: String,
+    },
+    #[serde(rename = "LOT_SIZE")]
+    LotSize {
+        #[serde(rename = "stepSize")]
+        step_size: String,
+    },
+    /// Spot symbols use NOTIONAL, older listings MIN_NOTIONAL
+    #[serde(rename = "MIN_NOTIONAL", alias = "NOTIONAL")]
+    MinNotional {
+        #[serde(rename = "minNotional")]
+        min_notional: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// A symbol's price tick size and lot step size, normalized so their
+/// scale is the number of decimal places they imply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolPrecision {
+    pub tick_size: Decimal,
+    pub step_size: Decimal,
+}
+
+/// Coinbase Advanced Trade `market_trades` channel message
+#[derive(Debug, Deserialize, Clone)]
+pub struct CoinbaseMarketTradesMessage {
+    pub events: Vec<CoinbaseMarketTradesEvent>,
+}
+
+/// One event of a `market_trades` message
+#[derive(Debug, Deserialize, Clone)]
+pub struct CoinbaseMarketTradesEvent {
+    /// "snapshot" (recent trades, sent after subscribing) or "update"
+    #[serde(rename = "type")]
+    pub event_type: String,
+
+    pub trades: Vec<CoinbaseMarketTrade>,
+}
+
+/// One trade from a `market_trades` event
+#[derive(Debug, Deserialize, Clone)]
+pub struct CoinbaseMarketTrade {
+    pub trade_id: String,
+
+    /// Product ID (e.g., "BTC-USD")
+    pub product_id: String,
+
+    pub price: String,
+
+    pub size: String,
+
+    /// Side of the taker order: "BUY" or "SELL"
+    pub side: String,
+
+    /// Trade time (RFC 3339)
+    pub time: DateTime<Utc>,
+}
+
+/// Coinbase Advanced Trade subscription message format
+#[derive(Debug, Serialize)]
+pub struct CoinbaseSubscribeMessage {
+    #[serde(rename = "type")]
+    pub message_type: String,
+    pub product_ids: Vec<String>,
+    pub channel: String,
+}
+
+impl CoinbaseSubscribeMessage {
+    pub fn market_trades(product_ids: Vec<String>) -> Self {
+        Self {
+            message_type: "subscribe".to_string(),
+            product_ids,
+            channel: "market_trades".to_string(),
+        }
+    }
+}
+
+/// One trade from a Kraken v2 `trade` channel message
+#[derive(Debug, Deserialize, Clone)]
+pub struct KrakenTrade {
+    /// Pair (e.g., "BTC/USD")
+    pub symbol: String,
+
+    /// Side of the taker order: "buy" or "sell"
+    pub side: String,
+
+    pub price: serde_json::Number,
+
+    pub qty: serde_json::Number,
+
+    pub trade_id: u64,
+
+    /// Trade time (RFC 3339)
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Kraken v2 `trade` channel message, snapshot or update alike
+#[derive(Debug, Deserialize, Clone)]
+pub struct KrakenTradeMessage {
+    /// One or more trades, oldest first
+    pub data: Vec<KrakenTrade>,
+}
+
+/// Kraken v2 subscription request
+#[derive(Debug, Serialize)]
+pub struct KrakenSubscribeMessage {
+    pub method: String,
+    pub params: KrakenSubscribeParams,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KrakenSubscribeParams {
+    pub channel: String,
+    pub symbol: Vec<String>,
+    /// Whether to replay the most recent trades after subscribing
+    pub snapshot: bool,
+}
+
+impl KrakenSubscribeMessage {
+    pub fn trades(pairs: Vec<String>) -> Self {
+        Self {
+            method: "subscribe".to_string(),
+            params: KrakenSubscribeParams {
+                channel: "trade".to_string(),
+                symbol: pairs,
+                snapshot: false,
+            },
+        }
+    }
+}
+
+/// Default cap on REST requests issued while paging through history
+pub const DEFAULT_MAX_REQUESTS: u32 = 500;
+
+/// Parameters for fetching one page of historical trades
+#[derive(Debug, Clone)]
+pub struct HistoricalTradeParams {
+    /// Inclusive start of the time range
+    pub start_time: DateTime<Utc>,
+    /// Inclusive end of the time range
+    pub end_time: DateTime<Utc>,
+    /// Maximum number of trades to return
+    pub limit: u32,
+    /// Safety cap on REST requests, time scan and pages alike; `None` means
+    /// unbounded
+    pub max_requests: Option<u32>,
+    /// Requests issued so far against `max_requests`; calls given the same
+    /// counter share one budget
+    pub request_count: Arc<AtomicU32>,
+    /// Waited on before every REST request
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl HistoricalTradeParams {
+    pub fn new(start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> Self {
+        Self {
+            start_time,
+            end_time,
+            limit: 1000,
+            max_requests: Some(DEFAULT_MAX_REQUESTS),
+            request_count: Arc::new(AtomicU32::new(0)),
+            rate_limiter: None,
+        }
+    }
+
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn with_max_requests(mut self, max_requests: Option<u32>) -> Self {
+        self.max_requests = max_requests;
+        self
+    }
+
+    /// Count requests on `counter`, e.g. to cap several calls as one range
+    pub fn with_request_count(mut self, counter: Arc<AtomicU32>) -> Self {
+        self.request_count = counter;
+        self
+    }
+
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Requests issued so far against the cap
+    pub fn requests_issued(&self) -> u32 {
+        self.request_count.load(Ordering::SeqCst)
+    }
+
+    /// Whether the request cap is used up
+    pub fn requests_exhausted(&self) -> bool {
+        self.max_requests
+            .is_some_and(|max| self.requests_issued() >= max)
+    }
+
+    /// Take one request from the cap and wait for the rate limiter; false,
+    /// without waiting, once the cap is used up
+    pub async fn claim_request(&self) -> bool {
+        let claimed = self
+            .request_count
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |issued| {
+                match self.max_requests {
+                    Some(max) if issued >= max => None,
+                    _ => Some(issued + 1),
+                }
+            })
+            .is_ok();
+        if claimed {
+            self.throttle().await;
+        }
+        claimed
+    }
+
+    /// Wait for the rate limiter without touching the cap, e.g. before a retry
+    pub async fn throttle(&self) {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+    }
+}
+
+/// Retry schedule for transient REST failures: up to `max_attempts` tries,
+/// waiting `initial_backoff` after the first failure and doubling after each
+/// further one, capped at `max_backoff`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay before the next try after `attempt` (1-based) failed
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Reconnect schedule for a dropped WebSocket: gives up after `max_attempts`
+/// consecutive failures (`None` retries forever), waiting `initial_delay`
+/// after the first and doubling after each further one, capped at `max_delay`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReconnectPolicy {
+    pub max_attempts: Option<u32>,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Consecutive failures between two warnings in the log
+    pub warn_every: u32,
+}
+
+impl ReconnectPolicy {
+    /// Delay before reconnecting after `attempt` (1-based) consecutive failures
+    pub fn delay(&self, attempt: u32) -> Duration {
+        // Same doubling as REST retries; `exhausted` enforces the attempt limit
+        RetryPolicy {
+            max_attempts: u32::MAX,
+            initial_backoff: self.initial_delay,
+            max_backoff: self.max_delay,
+        }
+        .backoff(attempt)
+    }
+
+    /// Whether `attempt` consecutive failures exhaust the policy
+    pub fn exhausted(&self, attempt: u32) -> bool {
+        self.max_attempts.is_some_and(|max| attempt >= max)
+    }
+
+    /// Whether the `attempt`-th consecutive failure is logged as a warning;
+    /// the first one always is
+    pub fn should_warn(&self, attempt: u32) -> bool {
+        attempt == 1 || attempt.is_multiple_of(self.warn_every.max(1))
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: Some(10),
+            initial_delay: Duration::from_secs(5),
+            max_delay: Duration::from_secs(60),
+            warn_every: 10,
+        }
+    }
+}
@@ -1,10 +1,14 @@
 // exchange/utils.rs
 
-use super::{BinanceTradeMessage, ExchangeError};
+use super::{
+    BinanceAggTrade, BinanceDepthResponse, BinanceKlineMessage, BinanceSymbolFilter,
+    BinanceSymbolInfo, BinanceTradeMessage, CoinbaseMarketTrade, ExchangeError, KrakenTrade,
+    OrderBook, SymbolPrecision, Venue,
+};
 use chrono::DateTime;
 use rust_decimal::Decimal;
 use std::str::FromStr;
-use trading_common::data::types::{TickData, TradeSide};
+use trading_common::data::types::{OHLCData, SymbolMetadata, TickData, Timeframe, TradeSide};
 
 /// Convert Binance trade message to standard TickData format
 pub fn convert_binance_to_tick_data(msg: BinanceTradeMessage) -> Result<TickData, ExchangeError> {
@@ -53,6 +57,300 @@ pub fn convert_binance_to_tick_data(msg: BinanceTradeMessage) -> Result<TickData
     ))
 }
 
+/// Convert Binance REST aggregate trade to standard TickData format
+///
+/// The first trade ID of the aggregate is used as the trade ID, so single-trade
+/// aggregates line up with ticks already collected from the trade stream.
+pub fn convert_binance_agg_trade_to_tick_data(
+    symbol: &str,
+    msg: BinanceAggTrade,
+) -> Result<TickData, ExchangeError> {
+    convert_binance_to_tick_data(BinanceTradeMessage {
+        symbol: symbol.to_string(),
+        trade_id: msg.first_trade_id,
+        price: msg.price,
+        quantity: msg.quantity,
+        trade_time: msg.trade_time,
+        is_buyer_maker: msg.is_buyer_maker,
+    })
+}
+
+/// Convert a Binance depth snapshot into an `OrderBook`, sorting each side
+/// best price first
+pub fn convert_binance_depth(depth: BinanceDepthResponse) -> Result<OrderBook, ExchangeError> {
+    let parse_levels = |levels: Vec<[String; 2]>| {
+        levels
+            .into_iter()
+            .map(|[price, quantity]| {
+                let parse = |value: &str, field: &str| {
+                    Decimal::from_str(value).map_err(|e| {
+                        ExchangeError::ParseError(format!(
+                            "Invalid depth {} '{}': {}",
+                            field, value, e
+                        ))
+                    })
+                };
+                Ok((parse(&price, "price")?, parse(&quantity, "quantity")?))
+            })
+            .collect::<Result<Vec<(Decimal, Decimal)>, ExchangeError>>()
+    };
+
+    let mut bids = parse_levels(depth.bids)?;
+    let mut asks = parse_levels(depth.asks)?;
+    bids.sort_by_key(|&(price, _)| std::cmp::Reverse(price));
+    asks.sort_by_key(|&(price, _)| price);
+    Ok(OrderBook {
+        bids,
+        asks,
+        last_update_id: depth.last_update_id,
+    })
+}
+
+/// Convert one trade of a Kraken `trade` message to standard TickData format
+///
+/// Kraken reports the taker's side, so a "sell" means the buyer was the maker.
+pub fn convert_kraken_to_tick_data(trade: KrakenTrade) -> Result<TickData, ExchangeError> {
+    let decimal = |name: &str, value: &serde_json::Number| {
+        let value = value.to_string();
+        Decimal::from_str(&value)
+            .or_else(|_| Decimal::from_scientific(&value))
+            .map_err(|e| ExchangeError::ParseError(format!("Invalid {} '{}': {}", name, value, e)))
+    };
+    let price = decimal("price", &trade.price)?;
+    let quantity = decimal("qty", &trade.qty)?;
+
+    if price <= Decimal::ZERO {
+        return Err(ExchangeError::ParseError(
+            "Price must be positive".to_string(),
+        ));
+    }
+
+    if quantity <= Decimal::ZERO {
+        return Err(ExchangeError::ParseError(
+            "Quantity must be positive".to_string(),
+        ));
+    }
+
+    let side = match trade.side.as_str() {
+        "buy" => TradeSide::Buy,
+        "sell" => TradeSide::Sell,
+        other => {
+            return Err(ExchangeError::ParseError(format!(
+                "Invalid side '{}'",
+                other
+            )))
+        }
+    };
+
+    Ok(TickData::new(
+        trade.timestamp,
+        trade.symbol,
+        price,
+        quantity,
+        side,
+        trade.trade_id.to_string(),
+        side == TradeSide::Sell,
+    ))
+}
+
+/// Binance kline interval name for a timeframe
+pub fn binance_kline_interval(timeframe: Timeframe) -> &'static str {
+    match timeframe {
+        Timeframe::OneMinute => "1m",
+        Timeframe::FiveMinutes => "5m",
+        Timeframe::FifteenMinutes => "15m",
+        Timeframe::ThirtyMinutes => "30m",
+        Timeframe::OneHour => "1h",
+        Timeframe::FourHours => "4h",
+        Timeframe::OneDay => "1d",
+        Timeframe::OneWeek => "1w",
+    }
+}
+
+/// Kline stream name for one symbol, e.g. `btcusdt@kline_1m`
+pub fn binance_kline_stream(symbol: &str, timeframe: Timeframe) -> Result<String, ExchangeError> {
+    Ok(format!(
+        "{}@kline_{}",
+        validate_binance_symbol(symbol)?.to_lowercase(),
+        binance_kline_interval(timeframe)
+    ))
+}
+
+/// Convert a Binance kline event to OHLCData stamped with the candle's open time
+pub fn convert_binance_kline_to_ohlc(
+    msg: BinanceKlineMessage,
+    timeframe: Timeframe,
+) -> Result<OHLCData, ExchangeError> {
+    let kline = msg.kline;
+    let timestamp = DateTime::from_timestamp_millis(kline.open_time)
+        .ok_or_else(|| ExchangeError::ParseError("Invalid kline open time".to_string()))?;
+    let decimal = |name: &str, value: &str| {
+        Decimal::from_str(value)
+            .map_err(|e| ExchangeError::ParseError(format!("Invalid {} '{}': {}", name, value, e)))
+    };
+
+    Ok(OHLCData::new(
+        timestamp,
+        msg.symbol,
+        timeframe,
+        decimal("open", &kline.open)?,
+        decimal("high", &kline.high)?,
+        decimal("low", &kline.low)?,
+        decimal("close", &kline.close)?,
+        decimal("volume", &kline.volume)?,
+        decimal("quote volume", &kline.quote_volume)?,
+        kline.trade_count,
+    ))
+}
+
+/// Convert a Coinbase `market_trades` trade to standard TickData format
+///
+/// The Advanced Trade feed reports the taker's side, so a "SELL" means the
+/// buyer was the maker.
+pub fn convert_coinbase_to_tick_data(msg: CoinbaseMarketTrade) -> Result<TickData, ExchangeError> {
+    let price = Decimal::from_str(&msg.price)
+        .map_err(|e| ExchangeError::ParseError(format!("Invalid price '{}': {}", msg.price, e)))?;
+
+    let quantity = Decimal::from_str(&msg.size)
+        .map_err(|e| ExchangeError::ParseError(format!("Invalid size '{}': {}", msg.size, e)))?;
+
+    if price <= Decimal::ZERO {
+        return Err(ExchangeError::ParseError(
+            "Price must be positive".to_string(),
+        ));
+    }
+
+    if quantity <= Decimal::ZERO {
+        return Err(ExchangeError::ParseError(
+            "Quantity must be positive".to_string(),
+        ));
+    }
+
+    let is_buyer_maker = match msg.side.as_str() {
+        "BUY" => false,
+        "SELL" => true,
+        other => {
+            return Err(ExchangeError::ParseError(format!(
+                "Invalid side '{}'",
+                other
+            )))
+        }
+    };
+    let side = if is_buyer_maker {
+        TradeSide::Sell
+    } else {
+        TradeSide::Buy
+    };
+
+    Ok(TickData::new(
+        msg.time,
+        msg.product_id,
+        price,
+        quantity,
+        side,
+        msg.trade_id,
+        is_buyer_maker,
+    ))
+}
+
+/// Parse an increment such as "0.01000000" without its trailing zeros
+pub fn parse_increment(step: &str) -> Result<Decimal, ExchangeError> {
+    let step = Decimal::from_str(step)
+        .map_err(|e| ExchangeError::ParseError(format!("Invalid step size '{}': {}", step, e)))?;
+    if step <= Decimal::ZERO {
+        return Err(ExchangeError::ParseError(format!(
+            "Step size must be positive, got {}",
+            step
+        )));
+    }
+    Ok(step.normalize())
+}
+
+/// Extract price/quantity precision from a symbol's `exchangeInfo` filters
+pub fn symbol_precision(info: &BinanceSymbolInfo) -> Result<SymbolPrecision, ExchangeError> {
+    let mut tick = None;
+    let mut step = None;
+
+    for filter in &info.filters {
+        match filter {
+            BinanceSymbolFilter::Price { tick_size } => tick = Some(parse_increment(tick_size)?),
+            BinanceSymbolFilter::LotSize { step_size } => step = Some(parse_increment(step_size)?),
+            BinanceSymbolFilter::MinNotional { .. } | BinanceSymbolFilter::Other => {}
+        }
+    }
+
+    match (tick, step) {
+        (Some(tick_size), Some(step_size)) => Ok(SymbolPrecision {
+            tick_size,
+            step_size,
+        }),
+        _ => Err(ExchangeError::ApiError(format!(
+            "Missing PRICE_FILTER or LOT_SIZE for {}",
+            info.symbol
+        ))),
+    }
+}
+
+/// Build stored trading rules from a symbol's `exchangeInfo` entry
+pub fn symbol_metadata(info: &BinanceSymbolInfo) -> Result<SymbolMetadata, ExchangeError> {
+    let parse = |field: &str, value: &str| {
+        Decimal::from_str(value).map_err(|e| {
+            ExchangeError::ParseError(format!(
+                "Invalid {} '{}' for {}: {}",
+                field, value, info.symbol, e
+            ))
+        })
+    };
+
+    let mut tick_size = None;
+    let mut step_size = None;
+    let mut min_notional = Decimal::ZERO;
+    for filter in &info.filters {
+        match filter {
+            BinanceSymbolFilter::Price { tick_size: value } => {
+                tick_size = Some(parse("tick size", value)?)
+            }
+            BinanceSymbolFilter::LotSize { step_size: value } => {
+                step_size = Some(parse("step size", value)?)
+            }
+            BinanceSymbolFilter::MinNotional {
+                min_notional: value,
+            } => min_notional = parse("min notional", value)?,
+            BinanceSymbolFilter::Other => {}
+        }
+    }
+
+    match (tick_size, step_size) {
+        (Some(tick_size), Some(step_size)) => Ok(SymbolMetadata {
+            symbol: info.symbol.clone(),
+            base_asset: info.base_asset.clone(),
+            quote_asset: info.quote_asset.clone(),
+            tick_size: tick_size.normalize(),
+            step_size: step_size.normalize(),
+            min_notional: min_notional.normalize(),
+            is_active: info.status == "TRADING",
+        }),
+        _ => Err(ExchangeError::ApiError(format!(
+            "Missing PRICE_FILTER or LOT_SIZE for {}",
+            info.symbol
+        ))),
+    }
+}
+
+/// Round price and quantity to the nearest multiple of the symbol's
+/// increments with a fixed scale, so equal values always have the same
+/// representation
+pub fn normalize_tick_precision(tick: &mut TickData, precision: &SymbolPrecision) {
+    tick.price = round_to_increment(tick.price, precision.tick_size);
+    tick.quantity = round_to_increment(tick.quantity, precision.step_size);
+}
+
+fn round_to_increment(value: Decimal, increment: Decimal) -> Decimal {
+    let mut rounded = (value / increment).round() * increment;
+    rounded.rescale(increment.scale());
+    rounded
+}
+
 /// Validate symbol format for Binance
 pub fn validate_binance_symbol(symbol: &str) -> Result<String, ExchangeError> {
     if symbol.is_empty() {
@@ -81,27 +379,42 @@ pub fn validate_binance_symbol(symbol: &str) -> Result<String, ExchangeError> {
     Ok(symbol)
 }
 
-/// Build WebSocket subscription streams for Binance
-pub fn build_binance_trade_streams(symbols: &[String]) -> Result<Vec<String>, ExchangeError> {
-    if symbols.is_empty() {
-        return Err(ExchangeError::InvalidSymbol(
-            "No symbols provided".to_string(),
-        ));
-    }
+/// Kraken v2 pair for a configured symbol, e.g. "BTCUSD" -> "BTC/USD"
+pub fn normalize_kraken_symbol(symbol: &str) -> Result<String, ExchangeError> {
+    Venue::Kraken.trade_stream(symbol)
+}
+
+/// Validate a Coinbase product ID such as "BTC-USD", returning it uppercased
+pub fn validate_coinbase_symbol(symbol: &str) -> Result<String, ExchangeError> {
+    let symbol = symbol.to_uppercase();
 
-    let mut streams = Vec::with_capacity(symbols.len());
+    let valid = symbol.split_once('-').is_some_and(|(base, quote)| {
+        [base, quote]
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(char::is_alphanumeric))
+    });
+    if !valid {
+        return Err(ExchangeError::InvalidSymbol(format!(
+            "Symbol '{}' is not a BASE-QUOTE product ID",
+            symbol
+        )));
+    }
 
-    for symbol in symbols {
-        let validated_symbol = validate_binance_symbol(symbol)?;
-        streams.push(format!("{}@trade", validated_symbol.to_lowercase()));
+    // Stored symbols are limited to 20 characters
+    if symbol.len() > 20 {
+        return Err(ExchangeError::InvalidSymbol(format!(
+            "Symbol '{}' has invalid length",
+            symbol
+        )));
     }
 
-    Ok(streams)
+    Ok(symbol)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::exchange::BinanceExchangeInfo;
 
     #[test]
     fn test_symbol_validation() {
@@ -109,15 +422,211 @@ mod tests {
         assert!(validate_binance_symbol("btcusdt").is_ok());
         assert!(validate_binance_symbol("").is_err());
         assert!(validate_binance_symbol("BTC-USDT").is_err());
+
+        assert_eq!(validate_coinbase_symbol("btc-usd").unwrap(), "BTC-USD");
+        assert!(validate_coinbase_symbol("BTCUSD").is_err());
+        assert!(validate_coinbase_symbol("BTC-").is_err());
+        assert!(validate_coinbase_symbol("BTC-US/D").is_err());
+    }
+
+    #[test]
+    fn test_coinbase_market_trade_conversion() {
+        let json = r#"{
+            "trade_id": "10",
+            "product_id": "BTC-USD",
+            "price": "400.23",
+            "size": "5.23512",
+            "side": "BUY",
+            "time": "2014-11-07T08:19:27.028459Z"
+        }"#;
+
+        let msg: CoinbaseMarketTrade = serde_json::from_str(json).unwrap();
+        let tick = convert_coinbase_to_tick_data(msg.clone()).unwrap();
+
+        assert_eq!(tick.symbol, "BTC-USD");
+        assert_eq!(tick.trade_id, "10");
+        assert_eq!(tick.price, Decimal::from_str("400.23").unwrap());
+        assert_eq!(tick.quantity, Decimal::from_str("5.23512").unwrap());
+        assert_eq!(tick.timestamp.timestamp_micros(), 1415348367028459);
+        // A taker bought from a resting sell
+        assert_eq!(tick.side, TradeSide::Buy);
+        assert!(!tick.is_buyer_maker);
+
+        let bad_side = CoinbaseMarketTrade {
+            side: "short".to_string(),
+            ..msg
+        };
+        assert!(convert_coinbase_to_tick_data(bad_side).is_err());
+    }
+
+    #[test]
+    fn test_kraken_trade_conversion() {
+        // Captured from the v2 trade channel
+        let json = r#"{
+            "symbol": "BTC/USD",
+            "side": "sell",
+            "price": 63511.7,
+            "qty": 0.00047185,
+            "ord_type": "market",
+            "trade_id": 73112648,
+            "timestamp": "2024-06-18T09:25:10.316754Z"
+        }"#;
+
+        let trade: KrakenTrade = serde_json::from_str(json).unwrap();
+        let tick = convert_kraken_to_tick_data(trade.clone()).unwrap();
+
+        assert_eq!(tick.symbol, "BTC/USD");
+        assert_eq!(tick.trade_id, "73112648");
+        assert_eq!(tick.price, Decimal::from_str("63511.7").unwrap());
+        assert_eq!(tick.quantity, Decimal::from_str("0.00047185").unwrap());
+        assert_eq!(tick.timestamp.timestamp_micros(), 1718702710316754);
+        // A taker sold into a resting buy
+        assert_eq!(tick.side, TradeSide::Sell);
+        assert!(tick.is_buyer_maker);
+
+        let bad_side = KrakenTrade {
+            side: "b".to_string(),
+            ..trade
+        };
+        assert!(convert_kraken_to_tick_data(bad_side).is_err());
+    }
+
+    #[test]
+    fn test_kraken_symbol_normalization() {
+        assert_eq!(normalize_kraken_symbol("BTCUSD").unwrap(), "BTC/USD");
+        assert_eq!(normalize_kraken_symbol("ethbtc").unwrap(), "ETH/BTC");
+        assert_eq!(normalize_kraken_symbol("SOLUSDT").unwrap(), "SOL/USDT");
+        assert!(normalize_kraken_symbol("ABCXYZ").is_err());
+    }
+
+    #[test]
+    fn test_agg_trade_conversion() {
+        let json = r#"[{
+            "a": 26129,
+            "p": "0.01633102",
+            "q": "4.70443515",
+            "f": 27781,
+            "l": 27781,
+            "T": 1498793709153,
+            "m": true,
+            "M": true
+        }]"#;
+
+        let trades: Vec<BinanceAggTrade> = serde_json::from_str(json).unwrap();
+        let tick = convert_binance_agg_trade_to_tick_data("BTCUSDT", trades[0].clone()).unwrap();
+
+        assert_eq!(tick.symbol, "BTCUSDT");
+        assert_eq!(tick.trade_id, "27781");
+        assert_eq!(tick.price, Decimal::from_str("0.01633102").unwrap());
+        assert_eq!(tick.timestamp.timestamp_millis(), 1498793709153);
+        assert_eq!(tick.side, TradeSide::Sell);
     }
 
     #[test]
-    fn test_stream_building() {
-        let symbols = vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()];
-        let streams = build_binance_trade_streams(&symbols).unwrap();
+    fn test_symbol_metadata_from_exchange_info() {
+        let json = r#"{"symbols": [{
+            "symbol": "ETHBTC",
+            "status": "TRADING",
+            "baseAsset": "ETH",
+            "quoteAsset": "BTC",
+            "filters": [
+                {"filterType": "PRICE_FILTER", "minPrice": "0.00001000", "maxPrice": "922327.00000000", "tickSize": "0.00001000"},
+                {"filterType": "LOT_SIZE", "minQty": "0.00010000", "maxQty": "100000.00000000", "stepSize": "0.00010000"},
+                {"filterType": "NOTIONAL", "minNotional": "0.00010000", "applyMinToMarket": true}
+            ]
+        }, {
+            "symbol": "OLDUSDT",
+            "status": "BREAK",
+            "baseAsset": "OLD",
+            "quoteAsset": "USDT",
+            "filters": [
+                {"filterType": "PRICE_FILTER", "tickSize": "0.01000000"},
+                {"filterType": "LOT_SIZE", "stepSize": "1.00000000"}
+            ]
+        }]}"#;
+        let info: BinanceExchangeInfo = serde_json::from_str(json).unwrap();
+        let dec = |s: &str| Decimal::from_str(s).unwrap();
+
+        let eth = symbol_metadata(&info.symbols[0]).unwrap();
+        assert_eq!(eth.base_asset, "ETH");
+        assert_eq!(eth.quote_asset, "BTC");
+        assert_eq!(eth.tick_size, dec("0.00001"));
+        assert_eq!(eth.step_size, dec("0.0001"));
+        assert_eq!(eth.min_notional, dec("0.0001"));
+        assert!(eth.is_active);
+
+        let old = symbol_metadata(&info.symbols[1]).unwrap();
+        assert_eq!(old.min_notional, Decimal::ZERO);
+        assert!(!old.is_active);
+    }
+
+    #[test]
+    fn test_equal_prices_normalize_identically() {
+        let json = r#"{"symbols": [{
+            "symbol": "BTCUSDT",
+            "filters": [
+                {"filterType": "PRICE_FILTER", "minPrice": "0.01000000", "maxPrice": "1000000.00000000", "tickSize": "0.01000000"},
+                {"filterType": "LOT_SIZE", "minQty": "0.00001000", "maxQty": "9000.00000000", "stepSize": "0.00001000"},
+                {"filterType": "MAX_NUM_ORDERS", "maxNumOrders": 200}
+            ]
+        }]}"#;
+        let info: BinanceExchangeInfo = serde_json::from_str(json).unwrap();
+        let precision = symbol_precision(&info.symbols[0]).unwrap();
+        assert_eq!(
+            precision,
+            SymbolPrecision {
+                tick_size: Decimal::new(1, 2),
+                step_size: Decimal::new(1, 5)
+            }
+        );
+
+        let formats = [
+            ("50000", "0.1"),
+            ("50000.0", "0.10000"),
+            ("50000.00000000", "0.10000000"),
+        ];
+        let ticks: Vec<TickData> = formats
+            .iter()
+            .map(|(price, quantity)| {
+                let mut tick = convert_binance_to_tick_data(BinanceTradeMessage {
+                    symbol: "BTCUSDT".to_string(),
+                    trade_id: 1,
+                    price: price.to_string(),
+                    quantity: quantity.to_string(),
+                    trade_time: 1_700_000_000_000,
+                    is_buyer_maker: false,
+                })
+                .unwrap();
+                normalize_tick_precision(&mut tick, &precision);
+                tick
+            })
+            .collect();
+
+        for tick in &ticks {
+            assert_eq!(tick.price.to_string(), "50000.00");
+            assert_eq!(tick.quantity.to_string(), "0.10000");
+        }
+
+        // Values finer than the tick size are rounded onto it
+        let mut tick = ticks[0].clone();
+        tick.price = Decimal::from_str("50000.005").unwrap();
+        normalize_tick_precision(&mut tick, &precision);
+        assert_eq!(tick.price.to_string(), "50000.00");
 
-        assert_eq!(streams.len(), 2);
-        assert_eq!(streams[0], "btcusdt@trade");
-        assert_eq!(streams[1], "ethusdt@trade");
+        // Increments that are not a power of ten round to their multiples
+        let coarse = SymbolPrecision {
+            tick_size: parse_increment("0.05000000").unwrap(),
+            step_size: parse_increment("0.25000000").unwrap(),
+        };
+        for (price, quantity, expected_price, expected_quantity) in [
+            ("100.12", "1.1", "100.10", "1.00"),
+            ("100.13", "1.2", "100.15", "1.25"),
+        ] {
+            tick.price = Decimal::from_str(price).unwrap();
+            tick.quantity = Decimal::from_str(quantity).unwrap();
+            normalize_tick_precision(&mut tick, &coarse);
+            assert_eq!(tick.price.to_string(), expected_price);
+            assert_eq!(tick.quantity.to_string(), expected_quantity);
+        }
     }
 }
@@ -0,0 +1,148 @@
+// exchange/venue.rs
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::utils::{validate_binance_symbol, validate_coinbase_symbol};
+use super::ExchangeError;
+
+/// Quote assets recognised when splitting a concatenated symbol like
+/// "BTCUSDT" into base and quote; longer codes come first so "USDT" wins over "USD"
+const QUOTE_ASSETS: &[&str] = &[
+    "FDUSD", "USDT", "USDC", "BUSD", "USD", "EUR", "GBP", "BTC", "ETH",
+];
+
+/// Exchange venue, deciding how a symbol maps to its public trade stream
+///
+/// Symbols are configured in the concatenated form ("BTCUSDT"); each venue
+/// turns that into its own stream, topic or product name. Coinbase also
+/// accepts product IDs ("BTC-USD") as configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Venue {
+    Binance,
+    Coinbase,
+    Kraken,
+    Bybit,
+}
+
+impl Venue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Venue::Binance => "binance",
+            Venue::Coinbase => "coinbase",
+            Venue::Kraken => "kraken",
+            Venue::Bybit => "bybit",
+        }
+    }
+
+    /// Trade stream name for one symbol
+    ///
+    /// - Binance: `btcusdt@trade`
+    /// - Coinbase: `BTC-USDT` product on the `market_trades` channel
+    /// - Kraken: `BTC/USDT` on the `trade` channel
+    /// - Bybit: `publicTrade.BTCUSDT`
+    pub fn trade_stream(&self, symbol: &str) -> Result<String, ExchangeError> {
+        if *self == Venue::Coinbase && symbol.contains('-') {
+            return validate_coinbase_symbol(symbol);
+        }
+
+        let symbol = validate_binance_symbol(symbol)?;
+        let stream = match self {
+            Venue::Binance => format!("{}@trade", symbol.to_lowercase()),
+            Venue::Bybit => format!("publicTrade.{}", symbol),
+            Venue::Coinbase => {
+                let (base, quote) = split_pair(&symbol)?;
+                format!("{}-{}", base, quote)
+            }
+            Venue::Kraken => {
+                let (base, quote) = split_pair(&symbol)?;
+                format!("{}/{}", base, quote)
+            }
+        };
+        Ok(stream)
+    }
+
+    /// Trade stream names for a set of symbols, in order
+    pub fn trade_streams(&self, symbols: &[String]) -> Result<Vec<String>, ExchangeError> {
+        if symbols.is_empty() {
+            return Err(ExchangeError::InvalidSymbol(
+                "No symbols provided".to_string(),
+            ));
+        }
+
+        symbols
+            .iter()
+            .map(|symbol| self.trade_stream(symbol))
+            .collect()
+    }
+}
+
+impl fmt::Display for Venue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Venue {
+    type Err = ExchangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "binance" => Ok(Venue::Binance),
+            "coinbase" => Ok(Venue::Coinbase),
+            "kraken" => Ok(Venue::Kraken),
+            "bybit" => Ok(Venue::Bybit),
+            _ => Err(ExchangeError::InvalidSymbol(format!(
+                "Unknown exchange venue '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Split an uppercase symbol into base and quote assets
+fn split_pair(symbol: &str) -> Result<(&str, &str), ExchangeError> {
+    QUOTE_ASSETS
+        .iter()
+        .find_map(|quote| {
+            symbol
+                .strip_suffix(quote)
+                .filter(|base| !base.is_empty())
+                .map(|base| (base, *quote))
+        })
+        .ok_or_else(|| {
+            ExchangeError::InvalidSymbol(format!(
+                "Cannot split '{}' into base and quote assets",
+                symbol
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn streams(venue: Venue) -> Vec<String> {
+        let symbols = vec!["BTCUSDT".to_string(), "ethbtc".to_string()];
+        venue.trade_streams(&symbols).unwrap()
+    }
+
+    #[test]
+    fn test_stream_naming_per_venue() {
+        assert_eq!(streams(Venue::Binance), ["btcusdt@trade", "ethbtc@trade"]);
+        assert_eq!(streams(Venue::Coinbase), ["BTC-USDT", "ETH-BTC"]);
+        assert_eq!(Venue::Coinbase.trade_stream("btc-usd").unwrap(), "BTC-USD");
+        assert!(Venue::Binance.trade_stream("BTC-USD").is_err());
+        assert_eq!(streams(Venue::Kraken), ["BTC/USDT", "ETH/BTC"]);
+        assert_eq!(
+            streams(Venue::Bybit),
+            ["publicTrade.BTCUSDT", "publicTrade.ETHBTC"]
+        );
+
+        // Venues that split pairs reject symbols without a known quote asset
+        assert!(Venue::Kraken.trade_stream("ABCXYZ").is_err());
+        assert!(Venue::Bybit.trade_stream("ABCXYZ").is_ok());
+        assert!(Venue::Binance.trade_streams(&[]).is_err());
+        assert_eq!("Kraken".parse::<Venue>().unwrap(), Venue::Kraken);
+    }
+}
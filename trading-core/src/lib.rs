@@ -6,5 +6,8 @@ pub mod exchange;
 pub mod live_trading;
 pub mod service;
 
+#[cfg(test)]
+mod test_support;
+
 // Re-export trading-common for convenience
 pub use trading_common::{backtest, data};
@@ -1,3 +1,7 @@
 pub mod paper_trading;
+pub mod strategy_logger;
+pub mod trade_throttle;
 
 pub use paper_trading::PaperTradingProcessor;
+pub use strategy_logger::LiveStrategyLogger;
+pub use trade_throttle::TradeThrottle;
@@ -1,24 +1,61 @@
 // src/live_trading/paper_trading.rs
+use chrono::{DateTime, Duration, Utc};
 use rust_decimal::Decimal;
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Instant;
-use tracing::debug;
+use tracing::{debug, warn};
 
+use super::{LiveStrategyLogger, TradeThrottle};
+use crate::service::BatchConfig;
+
+use trading_common::backtest::portfolio::Portfolio;
 use trading_common::backtest::strategy::{Signal, Strategy};
+use trading_common::backtest::units::Quantity;
 use trading_common::data::cache::TickDataCache;
 use trading_common::data::repository::TickDataRepository;
-use trading_common::data::types::{LiveStrategyLog, TickData};
+use trading_common::data::types::{
+    infer_quote, LivePnlSnapshot, LiveStrategyLog, MarketSnapshot, TickData,
+};
+
+/// Signal waiting out the simulated order latency
+struct PendingOrder {
+    signal: Signal,
+    symbol: String,
+    /// Tick time at which the order reaches the simulated exchange
+    arrives_at: DateTime<Utc>,
+}
 
+/// Runs a strategy against live ticks with simulated fills.
+///
+/// Cash, positions, commission and P&L are tracked by the same
+/// [`Portfolio`] the backtest engine uses, so paper trading and backtests
+/// account for an identical signal sequence identically.
 pub struct PaperTradingProcessor {
     strategy: Box<dyn Strategy + Send>,
     repository: Arc<TickDataRepository>,
     initial_capital: Decimal,
-
-    //Simple status tracking
-    cash: Decimal,
-    position: Decimal,
-    avg_cost: Decimal,
-    total_trades: u64,
+    portfolio: Portfolio,
+    /// Commission charged on every paper fill, which always takes liquidity
+    commission_rate: Decimal,
+    /// Groups this run's P&L snapshots; reuse it to continue a session after a restart
+    session_id: String,
+    /// Tick-time interval between P&L snapshots (disabled when unset)
+    snapshot_interval: Option<Duration>,
+    last_snapshot_at: Option<DateTime<Utc>>,
+    /// Per-symbol cap on executed trades (unlimited when unset)
+    throttle: Option<TradeThrottle>,
+    /// Simulated order round-trip delay (signals fill on their own tick when unset)
+    fill_latency: Option<Duration>,
+    /// Orders in submission order, so also in arrival order
+    pending: VecDeque<PendingOrder>,
+    /// Oldest trade that may set the inferred bid or ask; fills are at the
+    /// tick price when unset
+    quote_max_age: Option<Duration>,
+    /// (bid, ask) inferred from the cache for the tick being processed
+    quote: Option<(Decimal, Decimal)>,
+    /// Batches the per-tick strategy log rows
+    strategy_log: LiveStrategyLogger,
 }
 
 impl PaperTradingProcessor {
@@ -27,17 +64,87 @@ impl PaperTradingProcessor {
         repository: Arc<TickDataRepository>,
         initial_capital: Decimal,
     ) -> Self {
+        let session_id = format!("{}-{}", strategy.name(), Utc::now().format("%Y%m%dT%H%M%S"));
+        let strategy_log = LiveStrategyLogger::new(Arc::clone(&repository), BatchConfig::default());
         Self {
             strategy,
             repository,
             initial_capital,
-            cash: initial_capital,
-            position: Decimal::ZERO,
-            avg_cost: Decimal::ZERO,
-            total_trades: 0,
+            portfolio: Portfolio::new(initial_capital),
+            // Paper fills are commission-free unless configured otherwise
+            commission_rate: Decimal::ZERO,
+            session_id,
+            snapshot_interval: None,
+            last_snapshot_at: None,
+            throttle: None,
+            fill_latency: None,
+            pending: VecDeque::new(),
+            quote_max_age: None,
+            quote: None,
+            strategy_log,
         }
     }
 
+    /// Size and time triggers for writing strategy log rows
+    pub fn with_log_batching(mut self, config: BatchConfig) -> Self {
+        self.strategy_log = LiveStrategyLogger::new(Arc::clone(&self.repository), config);
+        self
+    }
+
+    /// Write the strategy log rows still buffered; call on shutdown
+    pub async fn flush_logs(&self) -> Result<usize, String> {
+        self.strategy_log
+            .flush()
+            .await
+            .map_err(|e| format!("Database error: {}", e))
+    }
+
+    /// Fill each signal at the first tick of its symbol at or after
+    /// `signal tick + latency`, at that tick's price
+    pub fn with_fill_latency(mut self, latency: Duration) -> Self {
+        self.fill_latency = Some(latency).filter(|latency| *latency > Duration::zero());
+        self
+    }
+
+    /// Fill buys at the ask and sells at the bid inferred from the cached
+    /// trades, falling back to the tick price while no side is fresher
+    /// than `max_age`
+    pub fn with_quote_fills(mut self, max_age: Duration) -> Self {
+        self.quote_max_age = Some(max_age);
+        self
+    }
+
+    /// Persist a P&L snapshot at most once per `interval` of tick time
+    pub fn with_snapshot_interval(mut self, interval: Duration) -> Self {
+        self.snapshot_interval = Some(interval);
+        self
+    }
+
+    /// Drop signals once `max_trades` trades of a symbol fall within `window`
+    pub fn with_trade_limit(mut self, max_trades: usize, window: Duration) -> Self {
+        self.throttle = Some(TradeThrottle::new(max_trades, window));
+        self
+    }
+
+    /// Signals dropped by the trade frequency limit
+    pub fn throttled_signals(&self) -> u64 {
+        self.throttle.as_ref().map_or(0, TradeThrottle::throttled)
+    }
+
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = session_id.into();
+        self
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    pub fn with_commission_rate(mut self, rate: Decimal) -> Self {
+        self.commission_rate = rate;
+        self
+    }
+
     pub async fn process_tick(&mut self, tick: &TickData) -> Result<(), String> {
         let start_time = Instant::now();
 
@@ -52,17 +159,42 @@ impl PaperTradingProcessor {
         let cache_hit = !recent_ticks.is_empty();
         let cache_time = cache_start.elapsed().as_micros() as u64;
 
-        // 2. Policy Handle - Using Existing Policies
-        let signal = self.strategy.on_tick(tick);
+        self.quote = self
+            .quote_max_age
+            .and_then(|max_age| infer_quote(&recent_ticks, max_age));
+        if let Some((bid, ask)) = self.quote {
+            debug!(
+                "{} estimated quote {} / {} (spread {})",
+                tick.symbol,
+                bid,
+                ask,
+                ask - bid
+            );
+        }
+
+        // The cache already holds the current tick; aggregate only what came before it
+        let previous_ticks: Vec<TickData> = recent_ticks
+            .into_iter()
+            .filter(|cached| cached.trade_id != tick.trade_id)
+            .collect();
+        let market = MarketSnapshot::from_ticks(&previous_ticks);
+        self.portfolio.update_price(&tick.symbol, tick.price);
+
+        // 2. Fill orders whose simulated latency has elapsed
+        let filled = self.fill_arrived_orders(tick);
+
+        // 3. Policy Handle - Using Existing Policies
+        let signal = self.strategy.on_tick_with_market(tick, market.as_ref());
 
-        // 3. Execution of trading signals
-        let signal_type = self.execute_signal(&signal, tick)?;
+        // 4. Execution of trading signals
+        let submitted = self.execute_signal(signal, tick);
+        let signal_type = filled.unwrap_or(submitted);
 
-        // 4. Calculate Portfolio Value
-        let portfolio_value = self.calculate_portfolio_value(tick.price);
+        // 5. Calculate Portfolio Value
+        let portfolio_value = self.portfolio.total_value();
         let total_pnl = portfolio_value - self.initial_capital;
 
-        // 5. Record to database
+        // 6. Record to database (batched)
         let processing_time = start_time.elapsed().as_micros() as u64;
         let log = LiveStrategyLog {
             timestamp: tick.timestamp,
@@ -76,12 +208,11 @@ impl PaperTradingProcessor {
             processing_time_us: processing_time,
         };
 
-        self.repository
-            .insert_live_strategy_log(&log)
-            .await
-            .map_err(|e| format!("Database error: {}", e))?;
+        self.strategy_log.log(log);
+        self.write_snapshot_if_due(tick.timestamp, portfolio_value)
+            .await?;
 
-        // 6. Real-time output
+        // 7. Real-time output
         self.log_activity(
             &signal_type,
             tick,
@@ -95,69 +226,202 @@ impl PaperTradingProcessor {
         Ok(())
     }
 
-    fn execute_signal(&mut self, signal: &Signal, tick: &TickData) -> Result<String, String> {
+    fn execute_signal(&mut self, signal: Signal, tick: &TickData) -> String {
+        let Some(symbol) = signal.symbol().map(str::to_string) else {
+            return "HOLD".to_string();
+        };
+        if let Some(throttle) = &mut self.throttle {
+            if !throttle.allow(&symbol, tick.timestamp) {
+                warn!(
+                    "{} signal throttled: trade limit reached ({} dropped so far)",
+                    symbol,
+                    throttle.throttled()
+                );
+                return "HOLD".to_string();
+            }
+        }
+
+        if let Some(latency) = self.fill_latency {
+            debug!("{} order submitted, arriving after {}", symbol, latency);
+            self.pending.push_back(PendingOrder {
+                signal,
+                symbol,
+                arrives_at: tick.timestamp + latency,
+            });
+            return "HOLD".to_string();
+        }
+
+        self.fill_and_record(signal, &symbol, tick)
+    }
+
+    /// Fill every pending order for the tick's symbol that has arrived by now,
+    /// returning the last executed side
+    fn fill_arrived_orders(&mut self, tick: &TickData) -> Option<String> {
+        let mut executed = None;
+        let mut waiting = VecDeque::with_capacity(self.pending.len());
+
+        while let Some(order) = self.pending.pop_front() {
+            if order.symbol != tick.symbol || order.arrives_at > tick.timestamp {
+                waiting.push_back(order);
+                continue;
+            }
+            let signal_type = self.fill_and_record(order.signal, &order.symbol, tick);
+            if signal_type != "HOLD" {
+                executed = Some(signal_type);
+            }
+        }
+
+        self.pending = waiting;
+        executed
+    }
+
+    fn fill_and_record(&mut self, signal: Signal, symbol: &str, tick: &TickData) -> String {
+        let signal_type = self.fill_signal(signal, tick);
+        if signal_type != "HOLD" {
+            if let Some(throttle) = &mut self.throttle {
+                throttle.record(symbol, tick.timestamp);
+            }
+        }
+        signal_type
+    }
+
+    fn fill_signal(&mut self, signal: Signal, tick: &TickData) -> String {
+        let (bid, ask) = self.quote.unwrap_or((tick.price, tick.price));
         match signal {
-            Signal::Buy { quantity, .. } => {
-                let cost = quantity * tick.price;
-
-                if cost <= self.cash {
-                    if self.position == Decimal::ZERO {
-                        self.position = *quantity;
-                        self.avg_cost = tick.price;
-                    } else {
-                        let total_cost = (self.position * self.avg_cost) + cost;
-                        self.position += quantity;
-                        self.avg_cost = total_cost / self.position;
+            Signal::Buy { symbol, quantity } => {
+                match self.portfolio.execute_buy(
+                    symbol,
+                    quantity,
+                    ask,
+                    self.commission_rate,
+                    tick.timestamp,
+                ) {
+                    Ok(()) => {
+                        debug!(
+                            "BUY executed: {} @ {}, cash: {}",
+                            quantity, ask, self.portfolio.cash
+                        );
+                        "BUY".to_string()
                     }
+                    Err(e) => {
+                        debug!("BUY signal ignored: {}", e);
+                        "HOLD".to_string()
+                    }
+                }
+            }
 
-                    self.cash -= cost;
-                    self.total_trades += 1;
+            Signal::Sell { symbol, quantity } => {
+                match self.portfolio.execute_sell(
+                    symbol,
+                    quantity,
+                    bid,
+                    self.commission_rate,
+                    tick.timestamp,
+                ) {
+                    Ok(()) => {
+                        debug!(
+                            "SELL executed: {} @ {}, cash: {}",
+                            quantity, bid, self.portfolio.cash
+                        );
+                        "SELL".to_string()
+                    }
+                    Err(e) => {
+                        debug!("SELL signal ignored: {}", e);
+                        "HOLD".to_string()
+                    }
+                }
+            }
 
-                    debug!(
-                        "BUY executed: {} @ {}, position: {}, cash: {}",
-                        quantity, tick.price, self.position, self.cash
-                    );
-                    return Ok("BUY".to_string());
+            // Limit orders are not queued here: one that is marketable at the
+            // fill price fills as a market order, any other is dropped
+            Signal::LimitBuy {
+                symbol,
+                quantity,
+                limit_price,
+            } => {
+                if ask <= limit_price {
+                    self.fill_signal(Signal::Buy { symbol, quantity }, tick)
                 } else {
                     debug!(
-                        "BUY signal ignored: insufficient cash ({} needed, {} available)",
-                        cost, self.cash
+                        "BUY limit {} not marketable at {}, ignored",
+                        limit_price, ask
                     );
+                    "HOLD".to_string()
                 }
             }
 
-            Signal::Sell { quantity, .. } => {
-                if *quantity <= self.position {
-                    let proceeds = quantity * tick.price;
-                    self.cash += proceeds;
-                    self.position -= quantity;
-                    self.total_trades += 1;
-
-                    if self.position == Decimal::ZERO {
-                        self.avg_cost = Decimal::ZERO;
-                    }
-
-                    debug!(
-                        "SELL executed: {} @ {}, position: {}, cash: {}",
-                        quantity, tick.price, self.position, self.cash
-                    );
-                    return Ok("SELL".to_string());
+            Signal::LimitSell {
+                symbol,
+                quantity,
+                limit_price,
+            } => {
+                if bid >= limit_price {
+                    self.fill_signal(Signal::Sell { symbol, quantity }, tick)
                 } else {
                     debug!(
-                        "SELL signal ignored: insufficient position ({} needed, {} available)",
-                        quantity, self.position
+                        "SELL limit {} not marketable at {}, ignored",
+                        limit_price, bid
                     );
+                    "HOLD".to_string()
                 }
             }
 
-            Signal::Hold => return Ok("HOLD".to_string()),
+            // Protective exits and sized buys are only simulated by the backtest engine
+            Signal::StopLoss { .. } | Signal::TakeProfit { .. } | Signal::SizedBuy { .. } => {
+                debug!(
+                    "Signal {:?} not supported in paper trading, ignored",
+                    signal
+                );
+                "HOLD".to_string()
+            }
+
+            Signal::Hold => "HOLD".to_string(),
         }
+    }
 
-        Ok("HOLD".to_string())
+    /// Write a P&L snapshot on the first tick and then once per interval
+    async fn write_snapshot_if_due(
+        &mut self,
+        now: DateTime<Utc>,
+        portfolio_value: Decimal,
+    ) -> Result<(), String> {
+        let Some(interval) = self.snapshot_interval else {
+            return Ok(());
+        };
+        if self
+            .last_snapshot_at
+            .is_some_and(|last| now - last < interval)
+        {
+            return Ok(());
+        }
+
+        let snapshot = LivePnlSnapshot {
+            session_id: self.session_id.clone(),
+            strategy_id: self.strategy.name().to_string(),
+            timestamp: now,
+            portfolio_value,
+            realized_pnl: self.portfolio.total_realized_pnl(),
+            unrealized_pnl: self.portfolio.total_unrealized_pnl(),
+            position_count: self
+                .portfolio
+                .positions
+                .values()
+                .filter(|position| position.quantity != Quantity::ZERO)
+                .count() as u32,
+        };
+        self.repository
+            .insert_live_pnl_snapshot(&snapshot)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        self.last_snapshot_at = Some(now);
+        Ok(())
     }
 
-    fn calculate_portfolio_value(&self, current_price: Decimal) -> Decimal {
-        self.cash + (self.position * current_price)
+    fn position_quantity(&self, symbol: &str) -> Quantity {
+        self.portfolio
+            .positions
+            .get(symbol)
+            .map_or(Quantity::ZERO, |position| position.quantity)
     }
 
     fn log_activity(
@@ -184,9 +448,9 @@ impl PaperTradingProcessor {
                      portfolio_value,
                      total_pnl,
                      return_pct,
-                     self.position,
-                     self.cash,
-                     self.total_trades,
+                     self.position_quantity(&tick.symbol),
+                     self.portfolio.cash,
+                     self.portfolio.trades.len(),
                      if cache_hit { "HIT" } else { "MISS" },
                      cache_time_us,
                      total_time_us);
@@ -206,3 +470,393 @@ impl PaperTradingProcessor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::{Duration, TimeZone, Utc};
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    use trading_common::backtest::engine::{BacktestConfig, BacktestEngine};
+    use trading_common::data::types::TradeSide;
+
+    const STRATEGY_NAME: &str = "Paper Parity Test";
+
+    /// Emits a fixed sequence of signals, one per tick
+    struct ScriptedStrategy {
+        script: Vec<Signal>,
+        index: usize,
+    }
+
+    impl Strategy for ScriptedStrategy {
+        fn name(&self) -> &str {
+            STRATEGY_NAME
+        }
+
+        fn on_tick(&mut self, _tick: &TickData) -> Signal {
+            let signal = self.script.get(self.index).cloned().unwrap_or(Signal::Hold);
+            self.index += 1;
+            signal
+        }
+
+        fn initialize(&mut self, _params: HashMap<String, String>) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    fn script() -> Box<ScriptedStrategy> {
+        let buy = |q: i64| Signal::Buy {
+            symbol: "BTCUSDT".to_string(),
+            quantity: Decimal::from(q),
+        };
+        let sell = |q: i64| Signal::Sell {
+            symbol: "BTCUSDT".to_string(),
+            quantity: Decimal::from(q),
+        };
+        Box::new(ScriptedStrategy {
+            // Includes a buy the cash cannot cover and an oversized sell
+            script: vec![buy(3), Signal::Hold, buy(2), sell(4), buy(1000), sell(10)],
+            index: 0,
+        })
+    }
+
+    async fn create_repository() -> Arc<TickDataRepository> {
+        Arc::new(test_support::create_repository().await)
+    }
+
+    #[tokio::test]
+    async fn test_paper_and_backtest_accounting_match() {
+        let repository = create_repository().await;
+        let initial_capital = Decimal::from(1000);
+        let commission_rate = Decimal::from_str("0.001").unwrap();
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let ticks: Vec<TickData> = [100, 102, 98, 105, 110, 95, 97]
+            .iter()
+            .enumerate()
+            .map(|(i, price)| {
+                TickData::new(
+                    start + Duration::seconds(i as i64),
+                    "BTCUSDT".to_string(),
+                    Decimal::from(*price),
+                    Decimal::ONE,
+                    TradeSide::Buy,
+                    format!("parity{}", i),
+                    false,
+                )
+            })
+            .collect();
+
+        let mut paper =
+            PaperTradingProcessor::new(script(), Arc::clone(&repository), initial_capital)
+                .with_commission_rate(commission_rate);
+        for tick in &ticks {
+            paper.process_tick(tick).await.unwrap();
+        }
+
+        let config = BacktestConfig::new(initial_capital).with_commission_rate(commission_rate);
+        let mut engine = BacktestEngine::new(script(), config).unwrap();
+        let backtest = engine.run(&ticks);
+
+        assert_eq!(paper.portfolio.total_value(), backtest.final_value);
+        assert_eq!(
+            paper.portfolio.total_commission(),
+            backtest.total_commission
+        );
+        assert_eq!(paper.portfolio.trades.len(), backtest.total_trades);
+        assert_eq!(paper.portfolio.trades.len(), 3);
+
+        let paper_position = &paper.portfolio.positions["BTCUSDT"];
+        let backtest_position = &backtest.positions["BTCUSDT"];
+        assert_eq!(paper_position.quantity, backtest_position.quantity);
+        assert_eq!(paper_position.avg_price, backtest_position.avg_price);
+
+        sqlx::query("DELETE FROM live_strategy_log WHERE strategy_id = $1")
+            .bind(STRATEGY_NAME)
+            .execute(repository.get_pool())
+            .await
+            .expect("Failed to clean up strategy logs");
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_follows_ticks() {
+        let repository = create_repository().await;
+        let strategy = Box::new(ScriptedStrategy {
+            script: vec![
+                Signal::Buy {
+                    symbol: "BTCUSDT".to_string(),
+                    quantity: Decimal::from(2),
+                },
+                Signal::Hold,
+                Signal::Sell {
+                    symbol: "BTCUSDT".to_string(),
+                    quantity: Decimal::ONE,
+                },
+                Signal::Sell {
+                    symbol: "BTCUSDT".to_string(),
+                    quantity: Decimal::ONE,
+                },
+            ],
+            index: 0,
+        });
+        let mut paper =
+            PaperTradingProcessor::new(strategy, Arc::clone(&repository), Decimal::from(1000));
+
+        // (price, cash, position, total value) after each tick; fills are
+        // commission-free at the tick price
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let steps = [
+            (100, 800, 2, 1000),
+            (110, 800, 2, 1020),
+            (120, 920, 1, 1040),
+            (90, 1010, 0, 1010),
+        ];
+        for (i, (price, cash, position, value)) in steps.into_iter().enumerate() {
+            let tick = TickData::new(
+                start + Duration::seconds(i as i64),
+                "BTCUSDT".to_string(),
+                Decimal::from(price),
+                Decimal::ONE,
+                TradeSide::Buy,
+                format!("steps{}", i),
+                false,
+            );
+            paper.process_tick(&tick).await.unwrap();
+
+            let held = paper
+                .portfolio
+                .positions
+                .get("BTCUSDT")
+                .map_or(Quantity::ZERO, |p| p.quantity);
+            assert_eq!(
+                paper.portfolio.cash.value(),
+                Decimal::from(cash),
+                "tick {}",
+                i
+            );
+            assert_eq!(held.value(), Decimal::from(position), "tick {}", i);
+            assert_eq!(paper.portfolio.total_value(), Decimal::from(value));
+        }
+        assert_eq!(paper.portfolio.trades.len(), 3);
+        assert_eq!(paper.portfolio.total_pnl(), Decimal::from(10));
+
+        sqlx::query("DELETE FROM live_strategy_log WHERE strategy_id = $1")
+            .bind(STRATEGY_NAME)
+            .execute(repository.get_pool())
+            .await
+            .expect("Failed to clean up strategy logs");
+    }
+
+    #[tokio::test]
+    async fn test_rapid_signals_are_throttled() {
+        let repository = create_repository().await;
+        let strategy = Box::new(ScriptedStrategy {
+            script: vec![
+                Signal::Buy {
+                    symbol: "BTCUSDT".to_string(),
+                    quantity: Decimal::ONE,
+                };
+                12
+            ],
+            index: 0,
+        });
+        let mut paper =
+            PaperTradingProcessor::new(strategy, Arc::clone(&repository), Decimal::from(10000))
+                .with_trade_limit(3, Duration::minutes(1));
+
+        // Ten buys in ten seconds, then two more once the window has passed
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let offsets = (0..10).chain([61, 62]);
+        for (i, offset) in offsets.enumerate() {
+            let tick = TickData::new(
+                start + Duration::seconds(offset),
+                "BTCUSDT".to_string(),
+                Decimal::from(100),
+                Decimal::ONE,
+                TradeSide::Buy,
+                format!("throttle{}", i),
+                false,
+            );
+            paper.process_tick(&tick).await.unwrap();
+        }
+
+        assert_eq!(paper.throttled_signals(), 7);
+        assert_eq!(paper.portfolio.trades.len(), 5);
+
+        sqlx::query("DELETE FROM live_strategy_log WHERE strategy_id = $1")
+            .bind(STRATEGY_NAME)
+            .execute(repository.get_pool())
+            .await
+            .expect("Failed to clean up strategy logs");
+    }
+
+    #[tokio::test]
+    async fn test_fill_latency_fills_at_later_tick() {
+        let repository = create_repository().await;
+        let strategy = Box::new(ScriptedStrategy {
+            script: vec![Signal::Buy {
+                symbol: "BTCUSDT".to_string(),
+                quantity: Decimal::ONE,
+            }],
+            index: 0,
+        });
+        let mut paper =
+            PaperTradingProcessor::new(strategy, Arc::clone(&repository), Decimal::from(1000))
+                .with_fill_latency(Duration::milliseconds(500));
+
+        // Signal at 0ms; the 300ms tick is too early and ETH ticks never fill BTC orders
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let ticks = [
+            (0, "BTCUSDT", 100),
+            (300, "BTCUSDT", 101),
+            (600, "ETHUSDT", 50),
+            (700, "BTCUSDT", 105),
+            (900, "BTCUSDT", 107),
+        ];
+        for (i, (millis, symbol, price)) in ticks.into_iter().enumerate() {
+            let tick = TickData::new(
+                start + Duration::milliseconds(millis),
+                symbol.to_string(),
+                Decimal::from(price),
+                Decimal::ONE,
+                TradeSide::Buy,
+                format!("latency{}", i),
+                false,
+            );
+            paper.process_tick(&tick).await.unwrap();
+            if millis == 300 {
+                assert!(paper.portfolio.trades.is_empty());
+            }
+        }
+
+        assert_eq!(paper.portfolio.trades.len(), 1);
+        let fill = &paper.portfolio.trades[0];
+        assert_eq!(fill.price.value(), Decimal::from(105));
+        assert_eq!(fill.timestamp, start + Duration::milliseconds(700));
+        assert!(paper.pending.is_empty());
+
+        sqlx::query("DELETE FROM live_strategy_log WHERE strategy_id = $1")
+            .bind(STRATEGY_NAME)
+            .execute(repository.get_pool())
+            .await
+            .expect("Failed to clean up strategy logs");
+    }
+
+    #[tokio::test]
+    async fn test_quote_fills_cross_the_spread() {
+        let repository = create_repository().await;
+        let symbol = "QUOTEFILLUSDT";
+        repository.get_cache().clear_symbol(symbol).await.unwrap();
+        let strategy = Box::new(ScriptedStrategy {
+            script: vec![
+                Signal::Hold,
+                Signal::Buy {
+                    symbol: symbol.to_string(),
+                    quantity: Decimal::ONE,
+                },
+                Signal::Sell {
+                    symbol: symbol.to_string(),
+                    quantity: Decimal::ONE,
+                },
+            ],
+            index: 0,
+        });
+        let mut paper =
+            PaperTradingProcessor::new(strategy, Arc::clone(&repository), Decimal::from(1000))
+                .with_quote_fills(Duration::seconds(5));
+
+        // A sell aggressor at 99 sets the bid, buy aggressors at 101 the ask
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        for (i, (price, is_buyer_maker)) in [(99, true), (101, false), (101, false)]
+            .into_iter()
+            .enumerate()
+        {
+            let tick = TickData::new(
+                start + Duration::milliseconds(i as i64 * 100),
+                symbol.to_string(),
+                Decimal::from(price),
+                Decimal::ONE,
+                if is_buyer_maker {
+                    TradeSide::Sell
+                } else {
+                    TradeSide::Buy
+                },
+                format!("quote{}", i),
+                is_buyer_maker,
+            );
+            repository.get_cache().push_tick(&tick).await.unwrap();
+            paper.process_tick(&tick).await.unwrap();
+        }
+
+        let prices: Vec<Decimal> = paper
+            .portfolio
+            .trades
+            .iter()
+            .map(|trade| trade.price.value())
+            .collect();
+        assert_eq!(prices, [Decimal::from(101), Decimal::from(99)]);
+
+        repository.get_cache().clear_symbol(symbol).await.unwrap();
+        sqlx::query("DELETE FROM live_strategy_log WHERE strategy_id = $1")
+            .bind(STRATEGY_NAME)
+            .execute(repository.get_pool())
+            .await
+            .expect("Failed to clean up strategy logs");
+    }
+
+    #[tokio::test]
+    async fn test_pnl_snapshots_follow_interval() {
+        let repository = create_repository().await;
+        let session_id = "paper-snapshot-test";
+        let mut paper =
+            PaperTradingProcessor::new(script(), Arc::clone(&repository), Decimal::from(1000))
+                .with_session_id(session_id)
+                .with_snapshot_interval(Duration::seconds(10));
+
+        // One tick per second for 25 seconds: snapshots at 0s, 10s and 20s
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        for i in 0..25 {
+            let tick = TickData::new(
+                start + Duration::seconds(i),
+                "BTCUSDT".to_string(),
+                Decimal::from(100 + i),
+                Decimal::ONE,
+                TradeSide::Buy,
+                format!("snapshot{}", i),
+                false,
+            );
+            paper.process_tick(&tick).await.unwrap();
+        }
+
+        let snapshots = repository.get_live_pnl_snapshots(session_id).await.unwrap();
+        let times: Vec<_> = snapshots.iter().map(|s| s.timestamp).collect();
+        assert_eq!(
+            times,
+            vec![
+                start,
+                start + Duration::seconds(10),
+                start + Duration::seconds(20)
+            ]
+        );
+        // The scripted buys leave one open position that has gained since
+        let last = snapshots.last().unwrap();
+        assert_eq!(last.position_count, 1);
+        assert!(last.unrealized_pnl > Decimal::ZERO);
+        assert_eq!(
+            last.portfolio_value,
+            Decimal::from(1000) + last.realized_pnl + last.unrealized_pnl
+        );
+
+        sqlx::query("DELETE FROM live_pnl_snapshot WHERE session_id = $1")
+            .bind(session_id)
+            .execute(repository.get_pool())
+            .await
+            .expect("Failed to clean up snapshots");
+        sqlx::query("DELETE FROM live_strategy_log WHERE strategy_id = $1")
+            .bind(STRATEGY_NAME)
+            .execute(repository.get_pool())
+            .await
+            .expect("Failed to clean up strategy logs");
+    }
+}
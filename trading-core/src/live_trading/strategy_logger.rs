@@ -0,0 +1,237 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{interval_at, sleep, Instant};
+use tokio::{select, spawn};
+use tracing::{debug, error, warn};
+
+use crate::service::BatchConfig;
+use trading_common::data::repository::TickDataRepository;
+use trading_common::data::types::{DataError, LiveStrategyLog};
+
+/// Rows waiting for the writer task before new ones are dropped
+const LOG_QUEUE_CAPACITY: usize = 10_000;
+
+enum LogCommand {
+    Log(LiveStrategyLog),
+    Flush(oneshot::Sender<Result<usize, DataError>>),
+}
+
+/// Buffers live strategy log rows and writes them to the database in batches
+///
+/// Rows are handed to a background writer task, so logging never waits on
+/// the database. A batch is written once it holds `max_batch_size` rows and
+/// every `max_batch_time` seconds otherwise. Call [`flush`](Self::flush) on
+/// shutdown to write whatever is left; dropping the logger also writes the
+/// remaining rows in the background.
+pub struct LiveStrategyLogger {
+    commands: mpsc::Sender<LogCommand>,
+}
+
+impl LiveStrategyLogger {
+    /// Start the writer task; must be called inside a Tokio runtime
+    pub fn new(repository: Arc<TickDataRepository>, batch_config: BatchConfig) -> Self {
+        let (commands, rx) = mpsc::channel(LOG_QUEUE_CAPACITY);
+        spawn(Self::run_writer(repository, batch_config, rx));
+        Self { commands }
+    }
+
+    /// Queue a row; it is dropped with a warning if the writer has fallen
+    /// `LOG_QUEUE_CAPACITY` rows behind
+    pub fn log(&self, entry: LiveStrategyLog) {
+        if let Err(e) = self.commands.try_send(LogCommand::Log(entry)) {
+            warn!("Dropping strategy log row: {}", e);
+        }
+    }
+
+    /// Write every row logged so far, returning how many were written
+    pub async fn flush(&self) -> Result<usize, DataError> {
+        let (reply, written) = oneshot::channel();
+        if self.commands.send(LogCommand::Flush(reply)).await.is_err() {
+            return Ok(0);
+        }
+        // The writer only goes away if it panicked, taking its rows with it
+        written.await.unwrap_or(Ok(0))
+    }
+
+    async fn run_writer(
+        repository: Arc<TickDataRepository>,
+        config: BatchConfig,
+        mut commands: mpsc::Receiver<LogCommand>,
+    ) {
+        let mut buffer = Vec::with_capacity(config.max_batch_size);
+        let period = Duration::from_secs(config.max_batch_time);
+        let mut flush_timer = interval_at(Instant::now() + period, period);
+
+        loop {
+            select! {
+                command = commands.recv() => match command {
+                    Some(LogCommand::Log(entry)) => {
+                        buffer.push(entry);
+                        if buffer.len() >= config.max_batch_size {
+                            let _ = Self::write_batch(&repository, &config, &mut buffer).await;
+                        }
+                    }
+                    Some(LogCommand::Flush(reply)) => {
+                        let _ = reply.send(Self::write_batch(&repository, &config, &mut buffer).await);
+                    }
+                    None => {
+                        let _ = Self::write_batch(&repository, &config, &mut buffer).await;
+                        break;
+                    }
+                },
+                _ = flush_timer.tick() => {
+                    let _ = Self::write_batch(&repository, &config, &mut buffer).await;
+                }
+            }
+        }
+    }
+
+    /// Write every buffered row, retrying as configured; the rows are
+    /// discarded after the last failed attempt
+    async fn write_batch(
+        repository: &TickDataRepository,
+        config: &BatchConfig,
+        buffer: &mut Vec<LiveStrategyLog>,
+    ) -> Result<usize, DataError> {
+        if buffer.is_empty() {
+            return Ok(0);
+        }
+
+        let mut attempt = 0;
+        let result = loop {
+            match repository.batch_insert_live_strategy_logs(buffer).await {
+                Ok(inserted) => {
+                    debug!("Flushed {} strategy log rows", inserted);
+                    break Ok(inserted);
+                }
+                Err(e) => {
+                    attempt += 1;
+                    if attempt >= config.max_retry_attempts {
+                        error!(
+                            "Strategy log insert failed after {} attempts, discarding {} rows: {}",
+                            attempt,
+                            buffer.len(),
+                            e
+                        );
+                        break Err(e);
+                    }
+                    warn!(
+                        "Strategy log insert failed (attempt {}/{}), retrying in {}ms: {}",
+                        attempt, config.max_retry_attempts, config.retry_delay_ms, e
+                    );
+                    sleep(Duration::from_millis(config.retry_delay_ms)).await;
+                }
+            }
+        };
+
+        buffer.clear();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    const STRATEGY_ID: &str = "test-batched-log";
+
+    async fn create_repository() -> Arc<TickDataRepository> {
+        Arc::new(test_support::create_repository().await)
+    }
+
+    async fn stored_rows(repository: &TickDataRepository) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM live_strategy_log WHERE strategy_id = $1")
+            .bind(STRATEGY_ID)
+            .fetch_one(repository.get_pool())
+            .await
+            .unwrap()
+    }
+
+    async fn cleanup(repository: &TickDataRepository) {
+        sqlx::query("DELETE FROM live_strategy_log WHERE strategy_id = $1")
+            .bind(STRATEGY_ID)
+            .execute(repository.get_pool())
+            .await
+            .unwrap();
+    }
+
+    fn entry(signal_type: &str) -> LiveStrategyLog {
+        LiveStrategyLog {
+            timestamp: Utc::now(),
+            strategy_id: STRATEGY_ID.to_string(),
+            symbol: "BTCUSDT".to_string(),
+            current_price: Decimal::from(100),
+            signal_type: signal_type.to_string(),
+            portfolio_value: Decimal::from(10000),
+            total_pnl: Decimal::ZERO,
+            cache_hit: true,
+            processing_time_us: 42,
+        }
+    }
+
+    /// Poll until `expected` rows are stored, failing after two seconds
+    async fn wait_for_rows(repository: &TickDataRepository, expected: i64) {
+        for _ in 0..100 {
+            if stored_rows(repository).await == expected {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(stored_rows(repository).await, expected);
+    }
+
+    #[tokio::test]
+    async fn test_flushes_on_size_and_on_demand() {
+        let repository = create_repository().await;
+        cleanup(&repository).await;
+
+        let config = BatchConfig {
+            max_batch_size: 3,
+            max_batch_time: 3600,
+            ..BatchConfig::default()
+        };
+        let logger = LiveStrategyLogger::new(Arc::clone(&repository), config);
+
+        logger.log(entry("HOLD"));
+        logger.log(entry("BUY"));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(stored_rows(&repository).await, 0);
+
+        // Third row fills the batch
+        logger.log(entry("HOLD"));
+        wait_for_rows(&repository, 3).await;
+
+        // A partial batch waits for an explicit flush
+        logger.log(entry("SELL"));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(stored_rows(&repository).await, 3);
+        assert_eq!(logger.flush().await.unwrap(), 1);
+        assert_eq!(stored_rows(&repository).await, 4);
+        assert_eq!(logger.flush().await.unwrap(), 0);
+
+        cleanup(&repository).await;
+    }
+
+    #[tokio::test]
+    async fn test_flushes_on_timer_without_new_rows() {
+        let repository = create_repository().await;
+        cleanup(&repository).await;
+
+        let config = BatchConfig {
+            max_batch_size: 100,
+            max_batch_time: 1,
+            ..BatchConfig::default()
+        };
+        let logger = LiveStrategyLogger::new(Arc::clone(&repository), config);
+
+        // Nothing else is logged, yet the row is written once the timer fires
+        logger.log(entry("BUY"));
+        wait_for_rows(&repository, 1).await;
+
+        cleanup(&repository).await;
+    }
+}
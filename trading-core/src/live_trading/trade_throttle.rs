@@ -0,0 +1,55 @@
+// src/live_trading/trade_throttle.rs
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, VecDeque};
+
+/// Caps executed trades per symbol within a sliding time window.
+///
+/// A safety rail against a misbehaving strategy flooding the paper account
+/// with orders; signals over the cap are dropped, not queued.
+#[derive(Debug)]
+pub struct TradeThrottle {
+    max_trades: usize,
+    window: Duration,
+    recent: HashMap<String, VecDeque<DateTime<Utc>>>,
+    throttled: u64,
+}
+
+impl TradeThrottle {
+    pub fn new(max_trades: usize, window: Duration) -> Self {
+        Self {
+            max_trades,
+            window,
+            recent: HashMap::new(),
+            throttled: 0,
+        }
+    }
+
+    /// Whether a trade in `symbol` at `now` stays within the cap; counts a
+    /// throttled signal when it does not
+    pub fn allow(&mut self, symbol: &str, now: DateTime<Utc>) -> bool {
+        let trades = self.recent.entry(symbol.to_string()).or_default();
+        while trades.front().is_some_and(|&t| now - t >= self.window) {
+            trades.pop_front();
+        }
+
+        if trades.len() < self.max_trades {
+            true
+        } else {
+            self.throttled += 1;
+            false
+        }
+    }
+
+    /// Record an executed trade against the symbol's window
+    pub fn record(&mut self, symbol: &str, at: DateTime<Utc>) {
+        self.recent
+            .entry(symbol.to_string())
+            .or_default()
+            .push_back(at);
+    }
+
+    /// Signals dropped since start
+    pub fn throttled(&self) -> u64 {
+        self.throttled
+    }
+}
@@ -11,14 +11,19 @@ mod config;
 mod exchange;
 mod live_trading;
 mod service;
+#[cfg(test)]
+mod test_support;
 
 // Import from trading-common
 use trading_common::backtest;
 use trading_common::data;
 
 use config::Settings;
-use data::{cache::TieredCache, repository::TickDataRepository};
-use exchange::BinanceExchange;
+use data::{cache::TieredCache, repository::TickDataRepository, types::NewBacktestResult};
+use exchange::{
+    BinanceExchange, CoinbaseExchange, Exchange, KrakenExchange, ReconnectPolicy, RetryPolicy,
+    Venue,
+};
 use live_trading::PaperTradingProcessor;
 use service::MarketDataService;
 
@@ -29,16 +34,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = std::env::args().collect();
 
     match args.get(1).map(|s| s.as_str()) {
-        Some("backtest") => run_backtest_mode().await,
+        Some("backtest") => run_backtest_mode(&args[2..]).await,
+        Some("backfill") => run_backfill_mode(&args[2..]).await,
+        Some("backtest-diff") => run_backtest_diff(&args[2..]),
+        Some("klines") => run_klines_mode(&args[2..]).await,
+        Some("depth") => run_depth_mode(&args[2..]).await,
+        Some("paper") => run_live_with_paper_trading().await,
         Some("live") => {
             // Check if paper trading is enabled
             if args.contains(&"--paper-trading".to_string()) {
                 run_live_with_paper_trading().await
             } else {
-                run_live_mode().await
+                run_live_mode(args.contains(&"--stdout-json".to_string())).await
             }
         }
-        None => run_live_mode().await,
+        None => run_live_mode(false).await,
         Some("--help") | Some("-h") => {
             print_usage();
             Ok(())
@@ -57,7 +67,27 @@ fn print_usage() {
     println!("Usage:");
     println!("  cargo run                # Run live data collection");
     println!("  cargo run live           # Run live data collection");
+    println!("  cargo run live --stdout-json");
+    println!("                           # Also print each tick to stdout as a line of JSON");
+    println!("  cargo run paper          # Run live data collection with paper trading");
+    println!("                           # (same as live --paper-trading)");
     println!("  cargo run backtest       # Run backtesting mode");
+    println!("  cargo run backtest --save");
+    println!("                           # Also save the run under the configured results_dir");
+    println!("  cargo run backtest --params <params.json>");
+    println!("                           # Take the strategy and its params from a JSON file");
+    println!("  cargo run backtest export");
+    println!(
+        "                           # Run a backtest, then write trades and equity curve as CSV"
+    );
+    println!("  cargo run backtest-diff <a.json> <b.json>");
+    println!("                           # Compare two saved backtest results (files or run directories)");
+    println!("  cargo run backfill --symbol BTCUSDT[,ETHUSDT...] --from 2024-01-01 --to 2024-01-02 [--fill-gaps] [--min-gap <secs>] [--max-requests <n>] [--concurrency <n>] [--max-rps <n>]");
+    println!("                           # Download historical trades (only missing ranges with --fill-gaps)");
+    println!("  cargo run klines --symbol BTCUSDT[,ETHUSDT...] [--interval 1m]");
+    println!("                           # Print closed candles from the exchange kline stream");
+    println!("  cargo run depth --symbol BTCUSDT [--limit 10]");
+    println!("                           # Print an order book snapshot from the exchange");
     println!("  cargo run --help         # Show this help message");
     println!();
 }
@@ -75,7 +105,7 @@ async fn run_live_with_paper_trading() -> Result<(), Box<dyn std::error::Error>>
     if !settings.paper_trading.enabled {
         warn!("⚠️ Paper trading is disabled in config. Set paper_trading.enabled = true");
         warn!("⚠️ Falling back to live data collection only...");
-        return run_live_mode().await;
+        return run_live_mode(false).await;
     }
 
     info!("📋 Configuration loaded successfully");
@@ -123,8 +153,9 @@ async fn run_live_with_paper_trading() -> Result<(), Box<dyn std::error::Error>>
 
     // Create exchange connection
     info!("📡 Initializing exchange connection...");
-    let exchange = Arc::new(BinanceExchange::new());
+    let exchange = create_exchange(&settings)?;
     info!("✅ Exchange connection ready");
+    sync_symbol_metadata(exchange.as_ref(), &repository, &settings.symbols).await;
 
     // Create strategy
     info!(
@@ -137,15 +168,63 @@ async fn run_live_with_paper_trading() -> Result<(), Box<dyn std::error::Error>>
     // Create paper trading processor
     let initial_capital = Decimal::try_from(settings.paper_trading.initial_capital)
         .map_err(|e| format!("Invalid initial capital: {}", e))?;
-    let paper_trading = Arc::new(tokio::sync::Mutex::new(PaperTradingProcessor::new(
-        strategy,
-        Arc::clone(&repository),
-        initial_capital,
-    )));
+    let commission_rate = Decimal::try_from(settings.paper_trading.commission_rate)
+        .map_err(|e| format!("Invalid commission rate: {}", e))?;
+    let mut processor =
+        PaperTradingProcessor::new(strategy, Arc::clone(&repository), initial_capital)
+            .with_commission_rate(commission_rate)
+            .with_log_batching(service::BatchConfig {
+                max_batch_size: settings.paper_trading.log_batch_size.max(1),
+                max_batch_time: settings.paper_trading.log_batch_time_secs,
+                ..Default::default()
+            });
+    if let Some(session_id) = &settings.paper_trading.session_id {
+        processor = processor.with_session_id(session_id.clone());
+    }
+    if let Some(max_trades) = settings.paper_trading.max_trades_per_window {
+        let window_secs = settings.paper_trading.trade_window_secs;
+        processor =
+            processor.with_trade_limit(max_trades, chrono::Duration::seconds(window_secs as i64));
+        info!(
+            "🚦 Paper trades limited to {} per symbol every {}s",
+            max_trades, window_secs
+        );
+    }
+    if let Some(millis) = settings.paper_trading.fill_latency_ms {
+        processor = processor.with_fill_latency(chrono::Duration::milliseconds(millis as i64));
+        info!("⏱️ Paper fills delayed by {}ms", millis);
+    }
+    if let Some(millis) = settings.paper_trading.quote_max_age_ms {
+        processor = processor.with_quote_fills(chrono::Duration::milliseconds(millis as i64));
+        info!(
+            "📖 Paper fills at the inferred bid/ask (max age {}ms)",
+            millis
+        );
+    }
+    if let Some(secs) = settings.paper_trading.snapshot_interval_secs {
+        processor = processor.with_snapshot_interval(chrono::Duration::seconds(secs as i64));
+        info!(
+            "📸 P&L snapshots every {}s for session {}",
+            secs,
+            processor.session_id()
+        );
+    }
+    let paper_trading = Arc::new(tokio::sync::Mutex::new(processor));
 
     // Create market data service
-    let service = MarketDataService::new(exchange, repository, settings.symbols.clone())
-        .with_paper_trading(paper_trading);
+    let service =
+        MarketDataService::new(exchange, Arc::clone(&repository), settings.symbols.clone())
+            .with_paper_trading(Arc::clone(&paper_trading))
+            .with_coalescing(create_coalesce_config(&settings)?)
+            .with_heartbeat(create_heartbeat_config(&settings))
+            .with_metrics_config(service::MetricsConfig {
+                max_flush_age_secs: settings.health.max_flush_age_secs,
+            })
+            .with_channel_config(service::ChannelConfig {
+                shutdown_capacity: settings.channels.shutdown_capacity,
+                price_capacity: settings.channels.price_capacity,
+            })
+            .with_batch_config(create_batch_config(&settings));
 
     info!(
         "🎯 Starting market data collection with paper trading for {} symbols",
@@ -159,16 +238,30 @@ async fn run_live_with_paper_trading() -> Result<(), Box<dyn std::error::Error>>
     println!("{}", "=".repeat(80));
 
     // Start service
-    run_live_application_with_service(service).await?;
+    run_live_application_with_service(&settings, service, repository).await?;
 
+    match paper_trading.lock().await.flush_logs().await {
+        Ok(rows) => info!("📝 Flushed {} remaining strategy log rows", rows),
+        Err(e) => error!("Failed to flush strategy logs: {}", e),
+    }
+    let throttled = paper_trading.lock().await.throttled_signals();
+    if throttled > 0 {
+        info!("🚦 {} signals were dropped by the trade limit", throttled);
+    }
     info!("✅ Application stopped gracefully");
     Ok(())
 }
 
 async fn run_live_application_with_service(
+    settings: &Settings,
     service: MarketDataService,
+    repository: Arc<TickDataRepository>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let service = Arc::new(service);
     let service_shutdown_tx = service.get_shutdown_tx();
+    spawn_symbol_reload_handler(Arc::clone(&service));
+    spawn_metrics_endpoint(settings, &service).await?;
+    spawn_readiness_probe(settings, Arc::clone(&service), repository).await?;
 
     // Start signal forwarding task
     tokio::spawn(async move {
@@ -191,8 +284,8 @@ async fn run_live_application_with_service(
     }
 }
 
-/// Real-time mode entry
-async fn run_live_mode() -> Result<(), Box<dyn std::error::Error>> {
+/// Real-time mode entry; `stdout_json` also streams ticks to stdout as NDJSON
+async fn run_live_mode(stdout_json: bool) -> Result<(), Box<dyn std::error::Error>> {
     // Initialize environment and logging
     init_application().await?;
 
@@ -216,14 +309,14 @@ async fn run_live_mode() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     // Create and start the application
-    run_live_application(settings).await?;
+    run_live_application(settings, stdout_json).await?;
 
     info!("✅ Application stopped gracefully");
     Ok(())
 }
 
 /// Backtesting mode entry
-async fn run_backtest_mode() -> Result<(), Box<dyn std::error::Error>> {
+async fn run_backtest_mode(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     init_application().await?;
 
     info!("🔬 Starting Trading Core Application (Backtest Mode)");
@@ -231,6 +324,22 @@ async fn run_backtest_mode() -> Result<(), Box<dyn std::error::Error>> {
     let settings = Settings::new()?;
     info!("📋 Configuration loaded successfully");
 
+    let save = settings.backtest.auto_save || args.iter().any(|arg| arg == "--save");
+    let save_dir = save.then(|| std::path::PathBuf::from(&settings.backtest.results_dir));
+    let export_csv = args.first().is_some_and(|arg| arg == "export");
+    let strategy_params = match cli_flag_value(args, "--params") {
+        Some(path) => {
+            let params = backtest::StrategyParams::load(std::path::Path::new(path))
+                .map_err(|e| format!("Failed to read params file {}: {}", path, e))?;
+            params
+                .create_strategy()
+                .map_err(|e| format!("Invalid params file {}: {}", path, e))?;
+            info!("📋 Loaded {} params from {}", params.strategy_id, path);
+            Some(params)
+        }
+        None => None,
+    };
+
     let pool = create_database_pool(&settings).await?;
     test_database_connection(&pool).await?;
     info!("✅ Database connection established");
@@ -240,7 +349,7 @@ async fn run_backtest_mode() -> Result<(), Box<dyn std::error::Error>> {
 
     let repository = TickDataRepository::new(pool, cache);
 
-    run_backtest_interactive(repository).await?;
+    run_backtest_interactive(repository, save_dir, export_csv, strategy_params).await?;
 
     info!("✅ Backtest completed successfully");
     Ok(())
@@ -249,11 +358,15 @@ async fn run_backtest_mode() -> Result<(), Box<dyn std::error::Error>> {
 /// Backtesting interactive interface
 async fn run_backtest_interactive(
     repository: TickDataRepository,
+    save_dir: Option<std::path::PathBuf>,
+    export_csv: bool,
+    strategy_params: Option<backtest::StrategyParams>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     use backtest::{
         engine::{BacktestConfig, BacktestEngine},
         strategy::{create_strategy, list_strategies},
     };
+    use data::types::{BacktestDataInfo, Timeframe};
     use rust_decimal::Decimal;
     use std::io::{self, Write};
     use std::str::FromStr;
@@ -297,27 +410,39 @@ async fn run_backtest_interactive(
         );
     }
 
-    // Strategy Selection
-    println!("\n🎯 Available Strategies:");
+    // Strategy Selection, skipped when a params file names the strategy
     let strategies = list_strategies();
-    for (i, strategy) in strategies.iter().enumerate() {
-        println!("  {}) {} - {}", i + 1, strategy.name, strategy.description);
-    }
-
-    print!("\nSelect strategy (1-{}): ", strategies.len());
-    io::stdout().flush()?;
+    let selected_strategy = match &strategy_params {
+        Some(params) => strategies
+            .iter()
+            .find(|info| info.id == params.strategy_id)
+            .ok_or_else(|| format!("Unknown strategy: {}", params.strategy_id))?,
+        None => {
+            println!("\n🎯 Available Strategies:");
+            for (i, strategy) in strategies.iter().enumerate() {
+                println!("  {}) {} - {}", i + 1, strategy.name, strategy.description);
+            }
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let choice: usize = input.trim().parse().unwrap_or(0);
+            print!("\nSelect strategy (1-{}): ", strategies.len());
+            io::stdout().flush()?;
 
-    if choice == 0 || choice > strategies.len() {
-        println!("❌ Invalid selection");
-        return Ok(());
-    }
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let choice: usize = input.trim().parse().unwrap_or(0);
 
-    let selected_strategy = &strategies[choice - 1];
+            if choice == 0 || choice > strategies.len() {
+                println!("❌ Invalid selection");
+                return Ok(());
+            }
+            &strategies[choice - 1]
+        }
+    };
     println!("✅ Selected Strategy: {}", selected_strategy.name);
+    let strategy_params = strategy_params.unwrap_or_else(|| backtest::StrategyParams {
+        strategy_id: selected_strategy.id.clone(),
+        params: Default::default(),
+    });
+    let warmup_period = strategy_params.create_strategy()?.warmup_period();
 
     // Trading pair selection
     println!("\n📊 Symbol Selection:");
@@ -358,10 +483,11 @@ async fn run_backtest_interactive(
     };
 
     // Verify whether the selected transaction pair has data
-    if !data_info.has_sufficient_data(&symbol, 100) {
+    if !data_info.has_sufficient_data(&symbol, 100, warmup_period) {
         println!(
-            "❌ Insufficient data for symbol: {} (minimum 100 records required)",
-            symbol
+            "❌ Insufficient data for symbol: {} (minimum {} records required)",
+            symbol,
+            BacktestDataInfo::required_records(100, warmup_period)
         );
         return Ok(());
     }
@@ -391,6 +517,14 @@ async fn run_backtest_interactive(
             .min(symbol_info.records_count as i64)
     };
 
+    if (data_count as usize) < warmup_period {
+        println!(
+            "❌ {} records is below the strategy warmup period of {} records",
+            data_count, warmup_period
+        );
+        return Ok(());
+    }
+
     // Initial Funding Setup
     print!("\nEnter initial capital (default: $10000): $");
     io::stdout().flush()?;
@@ -412,88 +546,194 @@ async fn run_backtest_interactive(
     let commission_rate = if input.trim().is_empty() {
         Decimal::from_str("0.001").unwrap() // 0.1%
     } else {
-        let rate = input.trim().parse::<f64>().unwrap_or(0.1);
-        Decimal::from_str(&format!("{}", rate / 100.0))
-            .unwrap_or(Decimal::from_str("0.001").unwrap())
+        match trading_common::parse::parse_percentage(&input) {
+            Ok(rate) => rate,
+            Err(e) => {
+                println!("❌ Invalid commission rate: {}", e);
+                return Ok(());
+            }
+        }
     };
 
-    // Check if strategy supports OHLC
+    // Backtest mode: OHLC is the default for strategies built for candles;
+    // tick-only strategies see each candle as one trade at its close
     let temp_strategy = create_strategy(&selected_strategy.id)?;
-    if temp_strategy.supports_ohlc() {
-        if let Some(timeframe) = temp_strategy.preferred_timeframe() {
-            println!(
-                "\n🔄 Strategy supports OHLC, using {} timeframe for better performance",
-                timeframe.as_str()
-            );
-
-            // Estimate candle count needed (roughly data_count / 50, minimum 100)
-            let candle_count = (data_count / 50).max(100) as u32;
-
-            println!("🔍 Loading {} OHLC candles for {}...", candle_count, symbol);
-
-            match repository
-                .generate_recent_ohlc_for_backtest(&symbol, timeframe, candle_count)
-                .await
-            {
-                Ok(ohlc_data) if !ohlc_data.is_empty() => {
-                    println!("✅ Loaded {} OHLC candles", ohlc_data.len());
-                    println!(
-                        "📅 Data range: {} to {}",
-                        ohlc_data
-                            .first()
-                            .unwrap()
-                            .timestamp
-                            .format("%Y-%m-%d %H:%M:%S"),
-                        ohlc_data
-                            .last()
-                            .unwrap()
-                            .timestamp
-                            .format("%Y-%m-%d %H:%M:%S")
-                    );
-
-                    let config =
-                        BacktestConfig::new(initial_capital).with_commission_rate(commission_rate);
+    let default_mode = if temp_strategy.supports_ohlc() {
+        "ohlc"
+    } else {
+        "tick"
+    };
+    print!(
+        "\nBacktest mode, tick or ohlc (default: {}): ",
+        default_mode
+    );
+    io::stdout().flush()?;
 
-                    let strategy = create_strategy(&selected_strategy.id)?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let mode = match input.trim().to_lowercase().as_str() {
+        "" => default_mode.to_string(),
+        mode @ ("tick" | "ohlc") => mode.to_string(),
+        other => {
+            println!("❌ Invalid mode: {}", other);
+            return Ok(());
+        }
+    };
 
-                    println!("\n{}", "=".repeat(60));
-                    let mut engine = BacktestEngine::new(strategy, config)?;
-                    let result = engine.run_with_ohlc(ohlc_data);
+    let ohlc_request = if mode == "ohlc" {
+        let default_timeframe = temp_strategy
+            .preferred_timeframe()
+            .unwrap_or(Timeframe::OneHour);
+        print!(
+            "\nEnter timeframe, 1m/5m/15m/30m/1h/4h/1d/1w (default: {}): ",
+            default_timeframe.as_str()
+        );
+        io::stdout().flush()?;
 
-                    // Show results
-                    println!("\n");
-                    result.print_summary();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let timeframe = if input.trim().is_empty() {
+            default_timeframe
+        } else {
+            match Timeframe::from_str(input.trim()) {
+                Ok(timeframe) => timeframe,
+                Err(e) => {
+                    println!("❌ {}", e);
+                    return Ok(());
+                }
+            }
+        };
+
+        // Rough candle estimate for the chosen record count (minimum 100)
+        let default_candles = (data_count / 50).max(100) as u32;
+        print!("\nEnter number of candles (default: {}): ", default_candles);
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let candle_count: u32 = match input.trim() {
+            "" => default_candles,
+            count => match count.parse() {
+                Ok(count) if count > 0 => count,
+                _ => {
+                    println!("❌ Invalid number of candles: {}", count);
+                    return Ok(());
+                }
+            },
+        };
+        if (candle_count as usize) < warmup_period {
+            println!(
+                "❌ {} candles is below the strategy warmup period of {} candles",
+                candle_count, warmup_period
+            );
+            return Ok(());
+        }
+        Some((timeframe, candle_count))
+    } else {
+        None
+    };
 
-                    // Ask whether to display detailed transaction analysis
-                    print!("\nShow detailed trade analysis? (y/N): ");
-                    io::stdout().flush()?;
+    if let Some((timeframe, candle_count)) = ohlc_request {
+        if let Ok((available, Some(earliest), Some(latest))) =
+            repository.get_ohlc_data_info(&symbol, timeframe).await
+        {
+            println!(
+                "📊 {} {} candles available ({} to {})",
+                available,
+                timeframe.as_str(),
+                earliest.format("%Y-%m-%d %H:%M"),
+                latest.format("%Y-%m-%d %H:%M")
+            );
+        }
 
-                    let mut input = String::new();
-                    io::stdin().read_line(&mut input)?;
-                    if input.trim().to_lowercase() == "y" || input.trim().to_lowercase() == "yes" {
-                        result.print_trade_analysis();
-                    }
+        println!("🔍 Loading {} OHLC candles for {}...", candle_count, symbol);
 
-                    println!("\n🎉 Backtest completed successfully!");
-                    return Ok(());
+        match repository
+            .generate_recent_ohlc_for_backtest(&symbol, timeframe, candle_count)
+            .await
+        {
+            Ok((ohlc_data, coverage)) if !ohlc_data.is_empty() => {
+                println!("✅ Loaded {} OHLC candles", ohlc_data.len());
+                if !coverage.is_complete() {
+                    println!("⚠️ Data only covers {}", coverage);
                 }
-                Ok(_) => {
-                    println!(
-                        "⚠️ No OHLC data available for timeframe {}, falling back to tick data",
-                        timeframe.as_str()
-                    );
+                println!(
+                    "📅 Data range: {} to {}",
+                    ohlc_data
+                        .first()
+                        .unwrap()
+                        .timestamp
+                        .format("%Y-%m-%d %H:%M:%S"),
+                    ohlc_data
+                        .last()
+                        .unwrap()
+                        .timestamp
+                        .format("%Y-%m-%d %H:%M:%S")
+                );
+
+                let config = strategy_params.apply(
+                    BacktestConfig::new(initial_capital)
+                        .with_commission_rate(commission_rate)
+                        .with_symbol_metadata(load_symbol_metadata(&repository).await),
+                );
+
+                let strategy = create_strategy(&selected_strategy.id)?;
+
+                println!("\n{}", "=".repeat(60));
+                let data_points = ohlc_data.len();
+                let mut engine = BacktestEngine::new(strategy, config)?;
+                let result = engine.run_with_ohlc(&ohlc_data);
+
+                // Show results
+                println!("\n");
+                result.print_summary();
+                store_backtest_result(&repository, &result, &selected_strategy.id, &symbol).await;
+
+                if let Some(dir) = &save_dir {
+                    let run_config = serde_json::json!({
+                        "strategy_id": selected_strategy.id,
+                        "symbol": symbol,
+                        "data": format!("ohlc:{}", timeframe.as_str()),
+                        "data_points": data_points,
+                        "initial_capital": initial_capital,
+                        "commission_rate": commission_rate,
+                        "strategy_params": strategy_params.params,
+                    });
+                    save_backtest_run(dir, &result, &run_config);
                 }
-                Err(e) => {
-                    println!(
-                        "⚠️ OHLC generation failed: {}, falling back to tick data",
-                        e
-                    );
+                if export_csv {
+                    prompt_csv_export(&result)?;
+                }
+
+                // Ask whether to display detailed transaction analysis
+                print!("\nShow detailed trade analysis? (y/N): ");
+                io::stdout().flush()?;
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                if input.trim().to_lowercase() == "y" || input.trim().to_lowercase() == "yes" {
+                    result.print_trade_analysis();
                 }
+
+                println!("\n🎉 Backtest completed successfully!");
+                return Ok(());
+            }
+            Ok(_) => {
+                println!(
+                    "⚠️ No OHLC data available for timeframe {}, falling back to tick data",
+                    timeframe.as_str()
+                );
+            }
+            Err(e) => {
+                println!(
+                    "⚠️ OHLC generation failed: {}, falling back to tick data",
+                    e
+                );
             }
         }
     }
 
-    // Fallback to tick data (original logic)
+    // Tick mode, or the fallback when no candles could be built
     println!(
         "\n🔍 Loading historical tick data: {} latest {} records...",
         symbol, data_count
@@ -515,17 +755,39 @@ async fn run_backtest_interactive(
         data.last().unwrap().timestamp.format("%Y-%m-%d %H:%M:%S")
     );
 
-    let config = BacktestConfig::new(initial_capital).with_commission_rate(commission_rate);
+    let config = strategy_params.apply(
+        BacktestConfig::new(initial_capital)
+            .with_commission_rate(commission_rate)
+            .with_symbol_metadata(load_symbol_metadata(&repository).await),
+    );
 
     let strategy = create_strategy(&selected_strategy.id)?;
 
     println!("\n{}", "=".repeat(60));
+    let data_points = data.len();
     let mut engine = BacktestEngine::new(strategy, config)?;
-    let result = engine.run(data);
+    let result = engine.run(&data);
 
     // Show results
     println!("\n");
     result.print_summary();
+    store_backtest_result(&repository, &result, &selected_strategy.id, &symbol).await;
+
+    if let Some(dir) = &save_dir {
+        let run_config = serde_json::json!({
+            "strategy_id": selected_strategy.id,
+            "symbol": symbol,
+            "data": "ticks",
+            "data_points": data_points,
+            "initial_capital": initial_capital,
+            "commission_rate": commission_rate,
+            "strategy_params": strategy_params.params,
+        });
+        save_backtest_run(dir, &result, &run_config);
+    }
+    if export_csv {
+        prompt_csv_export(&result)?;
+    }
 
     // Ask whether to display detailed transaction analysis
     print!("\nShow detailed trade analysis? (y/N): ");
@@ -542,6 +804,318 @@ async fn run_backtest_interactive(
     Ok(())
 }
 
+/// Record a completed run in the backtest_results table, reporting (but not
+/// failing on) database errors
+async fn store_backtest_result(
+    repository: &TickDataRepository,
+    result: &backtest::BacktestResult,
+    strategy_id: &str,
+    symbol: &str,
+) {
+    let trades = match serde_json::to_value(&result.trades) {
+        Ok(trades) => trades,
+        Err(e) => {
+            println!("⚠️ Failed to store backtest result: {}", e);
+            return;
+        }
+    };
+    let record = NewBacktestResult {
+        strategy_id: strategy_id.to_string(),
+        strategy_name: result.strategy_name.clone(),
+        symbol: symbol.to_string(),
+        initial_capital: result.initial_capital,
+        final_value: result.final_value,
+        return_percentage: result.return_percentage,
+        sharpe_ratio: result.sharpe_ratio,
+        max_drawdown: result.max_drawdown,
+        total_trades: result.total_trades,
+        trades,
+    };
+    match repository.save_backtest_result(&record).await {
+        Ok(id) => println!("💾 Stored backtest result #{}", id),
+        Err(e) => println!("⚠️ Failed to store backtest result: {}", e),
+    }
+}
+
+/// Save a backtest run, reporting (but not failing on) I/O errors
+fn save_backtest_run(
+    results_dir: &std::path::Path,
+    result: &backtest::BacktestResult,
+    run_config: &serde_json::Value,
+) {
+    match backtest::results::save_run(results_dir, result, run_config) {
+        Ok(path) => println!("💾 Saved backtest run to {}", path.display()),
+        Err(e) => println!("⚠️ Failed to save backtest run: {}", e),
+    }
+}
+
+/// Ask for a directory and write the run's trades and equity curve there as CSV
+fn prompt_csv_export(result: &backtest::BacktestResult) -> std::io::Result<()> {
+    use std::io::{self, Write};
+
+    print!("\nCSV output directory [backtest_csv]: ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let dir = match input.trim() {
+        "" => "backtest_csv",
+        dir => dir,
+    };
+
+    let dir = std::path::Path::new(dir);
+    match result.export_csv(dir) {
+        Ok(()) => println!(
+            "📄 Exported {} and {} to {}",
+            backtest::results::TRADES_FILE,
+            backtest::results::EQUITY_CURVE_FILE,
+            dir.display()
+        ),
+        Err(e) => println!("⚠️ Failed to export CSV: {}", e),
+    }
+    Ok(())
+}
+
+/// Compare two saved backtest results
+fn run_backtest_diff(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (a, b) = match args {
+        [a, b, ..] => (a, b),
+        _ => return Err("Usage: backtest-diff <a.json> <b.json>".into()),
+    };
+
+    // Accept either result.json files or saved run directories
+    let load = |path: &str| {
+        let path = std::path::Path::new(path);
+        if path.is_dir() {
+            backtest::results::load_result(&path.join(backtest::results::RESULT_FILE))
+        } else {
+            backtest::results::load_result(path)
+        }
+        .map_err(|e| format!("Failed to load {}: {}", path.display(), e))
+    };
+
+    let diff = backtest::diff::compare(&load(a)?, &load(b)?);
+    diff.print();
+    Ok(())
+}
+
+/// Historical backfill entry
+async fn run_backfill_mode(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    use chrono::Duration as ChronoDuration;
+    use exchange::RateLimiter;
+    use service::BackfillService;
+
+    init_application().await?;
+
+    let symbols: Vec<String> = cli_flag_value(args, "--symbol")
+        .ok_or("Missing --symbol")?
+        .split(',')
+        .map(|symbol| symbol.trim().to_uppercase())
+        .filter(|symbol| !symbol.is_empty())
+        .collect();
+    if symbols.is_empty() {
+        return Err("Missing --symbol".into());
+    }
+    let start_time = parse_cli_datetime(cli_flag_value(args, "--from").ok_or("Missing --from")?)?;
+    let end_time = parse_cli_datetime(cli_flag_value(args, "--to").ok_or("Missing --to")?)?;
+    let fill_gaps = args.iter().any(|arg| arg == "--fill-gaps");
+
+    if start_time >= end_time {
+        return Err("--from must be before --to".into());
+    }
+
+    info!("📥 Starting Trading Core Application (Backfill Mode)");
+
+    let settings = Settings::new()?;
+    let pool = create_database_pool(&settings).await?;
+    test_database_connection(&pool).await?;
+    info!("✅ Database connection established");
+
+    let cache = create_backtest_cache(&settings).await?;
+    let repository = Arc::new(TickDataRepository::new(pool, cache));
+    let exchange = create_exchange(&settings)?;
+
+    let mut backfill = BackfillService::new(exchange, repository);
+    if let Some(min_gap) = cli_flag_value(args, "--min-gap") {
+        let seconds: i64 = min_gap.parse().map_err(|_| "Invalid --min-gap")?;
+        backfill = backfill.with_min_gap(ChronoDuration::seconds(seconds));
+    }
+    if let Some(max_requests) = cli_flag_value(args, "--max-requests") {
+        let max: u32 = max_requests.parse().map_err(|_| "Invalid --max-requests")?;
+        backfill = backfill.with_max_requests(Some(max));
+    }
+    if let Some(concurrency) = cli_flag_value(args, "--concurrency") {
+        let concurrency: usize = concurrency.parse().map_err(|_| "Invalid --concurrency")?;
+        backfill = backfill.with_concurrency(concurrency);
+    }
+    let max_rps = match cli_flag_value(args, "--max-rps") {
+        Some(max_rps) => max_rps.parse().map_err(|_| "Invalid --max-rps")?,
+        None => service::backfill::DEFAULT_MAX_REQUESTS_PER_SECOND,
+    };
+    // One limiter for all symbols keeps the combined request rate under the cap
+    backfill = backfill.with_rate_limiter(Arc::new(RateLimiter::per_second(max_rps)));
+
+    let mut failed = 0;
+    if fill_gaps {
+        for run in backfill
+            .fill_gaps_symbols(&symbols, start_time, end_time)
+            .await
+        {
+            match run.result {
+                Ok(report) => {
+                    println!("✅ Gap fill complete for {}", run.symbol);
+                    println!("  Gaps found:     {}", report.gaps_found);
+                    println!("  Gaps filled:    {}", report.gaps_filled);
+                    println!("  Gaps remaining: {}", report.gaps_remaining);
+                    println!("  Ticks inserted: {}", report.ticks_inserted);
+                }
+                Err(e) => {
+                    failed += 1;
+                    println!("❌ Gap fill failed for {}: {}", run.symbol, e);
+                }
+            }
+        }
+    } else {
+        let mut total_inserted = 0;
+        for run in backfill
+            .backfill_symbols(&symbols, start_time, end_time)
+            .await
+        {
+            match run.result {
+                Ok(inserted) => {
+                    total_inserted += inserted;
+                    println!(
+                        "✅ Backfill complete for {}: {} ticks inserted",
+                        run.symbol, inserted
+                    );
+                }
+                Err(e) => {
+                    failed += 1;
+                    println!("❌ Backfill failed for {}: {}", run.symbol, e);
+                }
+            }
+        }
+        if symbols.len() > 1 {
+            println!(
+                "📊 {} symbols, {} failed, {} ticks inserted",
+                symbols.len(),
+                failed,
+                total_inserted
+            );
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!(
+            "Backfill failed for {} of {} symbols",
+            failed,
+            symbols.len()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Kline watch entry: print each closed candle until Ctrl+C
+async fn run_klines_mode(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    use data::types::Timeframe;
+
+    init_application().await?;
+
+    let symbols: Vec<String> = cli_flag_value(args, "--symbol")
+        .ok_or("Missing --symbol")?
+        .split(',')
+        .map(|symbol| symbol.trim().to_uppercase())
+        .filter(|symbol| !symbol.is_empty())
+        .collect();
+    let interval = cli_flag_value(args, "--interval").unwrap_or("1m");
+    let interval: Timeframe = interval
+        .parse()
+        .map_err(|_| format!("Invalid --interval: {}", interval))?;
+
+    info!("🕯️ Starting Trading Core Application (Kline Mode)");
+
+    let settings = Settings::new()?;
+    let exchange = create_exchange(&settings)?;
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+    tokio::spawn(async move {
+        signal::ctrl_c().await.expect("Failed to listen for ctrl-c");
+        info!("Received Ctrl+C signal, closing kline stream");
+        let _ = shutdown_tx.send(());
+    });
+
+    exchange
+        .subscribe_klines(
+            &symbols,
+            interval,
+            Box::new(|candle| {
+                println!(
+                    "{} {} {} O:{} H:{} L:{} C:{} V:{} ({} trades)",
+                    candle.timestamp.format("%Y-%m-%d %H:%M"),
+                    candle.symbol,
+                    candle.timeframe.as_str(),
+                    candle.open,
+                    candle.high,
+                    candle.low,
+                    candle.close,
+                    candle.volume,
+                    candle.trade_count
+                )
+            }),
+            shutdown_rx,
+        )
+        .await?;
+    Ok(())
+}
+
+/// Depth entry: print one order book snapshot, asks above bids
+async fn run_depth_mode(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    init_application().await?;
+
+    let symbol = cli_flag_value(args, "--symbol").ok_or("Missing --symbol")?;
+    let limit = cli_flag_value(args, "--limit").unwrap_or("10");
+    let limit: u16 = limit
+        .parse()
+        .map_err(|_| format!("Invalid --limit: {}", limit))?;
+
+    let settings = Settings::new()?;
+    let exchange = create_exchange(&settings)?;
+    let book = exchange.get_order_book(symbol, limit).await?;
+
+    println!(
+        "{} order book (update {})",
+        symbol.to_uppercase(),
+        book.last_update_id
+    );
+    for (price, quantity) in book.asks.iter().rev() {
+        println!("  ask {:>20} {:>20}", price, quantity);
+    }
+    for (price, quantity) in &book.bids {
+        println!("  bid {:>20} {:>20}", price, quantity);
+    }
+    Ok(())
+}
+
+/// Get the value following a `--flag` argument
+fn cli_flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|value| value.as_str())
+}
+
+/// Parse a CLI date (`YYYY-MM-DD`, midnight UTC) or RFC 3339 timestamp
+fn parse_cli_datetime(value: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|naive| naive.and_utc())
+        .ok_or_else(|| format!("Invalid date '{}', expected YYYY-MM-DD or RFC 3339", value))
+}
+
 /// Initialize application environment and logging
 async fn init_application() -> Result<(), Box<dyn std::error::Error>> {
     // Load environment variables from .env file
@@ -570,6 +1144,7 @@ fn init_tracing() -> Result<(), Box<dyn std::error::Error>> {
                 .with_thread_ids(true)
                 .with_file(true)
                 .with_line_number(true)
+                .with_writer(std::io::stderr)
                 .compact(),
         )
         .init();
@@ -578,7 +1153,10 @@ fn init_tracing() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 /// Main application runtime (original live mode)
-async fn run_live_application(settings: Settings) -> Result<(), Box<dyn std::error::Error>> {
+async fn run_live_application(
+    settings: Settings,
+    stdout_json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Validate basic configuration
     if settings.symbols.is_empty() {
         error!("❌ No symbols configured for monitoring");
@@ -608,11 +1186,27 @@ async fn run_live_application(settings: Settings) -> Result<(), Box<dyn std::err
 
     // Create exchange
     info!("📡 Initializing exchange connection...");
-    let exchange = Arc::new(BinanceExchange::new());
+    let exchange = create_exchange(&settings)?;
     info!("✅ Exchange connection ready");
+    sync_symbol_metadata(exchange.as_ref(), &repository, &settings.symbols).await;
 
     // Create market data service
-    let service = MarketDataService::new(exchange, repository, settings.symbols.clone());
+    let mut service =
+        MarketDataService::new(exchange, Arc::clone(&repository), settings.symbols.clone())
+            .with_coalescing(create_coalesce_config(&settings)?)
+            .with_heartbeat(create_heartbeat_config(&settings))
+            .with_metrics_config(service::MetricsConfig {
+                max_flush_age_secs: settings.health.max_flush_age_secs,
+            })
+            .with_channel_config(service::ChannelConfig {
+                shutdown_capacity: settings.channels.shutdown_capacity,
+                price_capacity: settings.channels.price_capacity,
+            })
+            .with_batch_config(create_batch_config(&settings));
+    if stdout_json {
+        // Logs go to stderr, so stdout carries only the tick stream
+        service = service.with_ndjson_output(std::io::stdout());
+    }
 
     info!(
         "🎯 Starting market data collection for {} symbols",
@@ -620,10 +1214,14 @@ async fn run_live_application(settings: Settings) -> Result<(), Box<dyn std::err
     );
 
     // Setup signal forwarding to service
+    let service = Arc::new(service);
     let service_shutdown_tx = service.get_shutdown_tx();
+    spawn_symbol_reload_handler(Arc::clone(&service));
+    spawn_metrics_endpoint(&settings, &service).await?;
+    spawn_readiness_probe(&settings, Arc::clone(&service), repository).await?;
     tokio::spawn(async move {
         signal::ctrl_c().await.expect("Failed to listen for ctrl-c");
-        println!("\nReceived Ctrl+C signal, forwarding to service...");
+        eprintln!("\nReceived Ctrl+C signal, forwarding to service...");
         info!("Received Ctrl+C signal, forwarding to service");
         let _ = service_shutdown_tx.send(());
     });
@@ -641,6 +1239,70 @@ async fn run_live_application(settings: Settings) -> Result<(), Box<dyn std::err
     }
 }
 
+/// Serve `GET /ready` when a readiness port is configured
+async fn spawn_readiness_probe(
+    settings: &Settings,
+    service: Arc<MarketDataService>,
+    repository: Arc<TickDataRepository>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use service::{ReadinessConfig, ReadinessProbe};
+
+    let Some(port) = settings.health.readiness_port else {
+        return Ok(());
+    };
+
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port)).await?;
+    let probe = Arc::new(ReadinessProbe::new(ReadinessConfig {
+        startup_grace_secs: settings.health.startup_grace_secs,
+        max_tick_age_secs: settings.health.max_tick_age_secs,
+    }));
+    let shutdown_rx = service.get_shutdown_tx().subscribe();
+    tokio::spawn(probe.serve(listener, service, repository, shutdown_rx));
+    Ok(())
+}
+
+/// Serve `GET /metrics` and `GET /health` when a metrics port is configured
+async fn spawn_metrics_endpoint(
+    settings: &Settings,
+    service: &MarketDataService,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(port) = settings.health.metrics_port {
+        service.serve_metrics(([0, 0, 0, 0], port).into()).await?;
+    }
+    Ok(())
+}
+
+/// Re-read the configured symbols on SIGHUP and apply them to the running service
+fn spawn_symbol_reload_handler(service: Arc<MarketDataService>) {
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        use tokio::signal::unix::{signal as unix_signal, SignalKind};
+
+        let mut hangup = match unix_signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(e) => {
+                warn!("Symbol reload on SIGHUP unavailable: {}", e);
+                return;
+            }
+        };
+
+        while hangup.recv().await.is_some() {
+            info!("🔄 Received SIGHUP, reloading symbols from configuration");
+            match Settings::new() {
+                Ok(settings) => {
+                    if let Err(e) = service.reload_symbols(settings.symbols).await {
+                        error!("❌ Symbol reload failed: {}", e);
+                    }
+                }
+                Err(e) => error!("❌ Failed to reload configuration: {}", e),
+            }
+        }
+    });
+
+    #[cfg(not(unix))]
+    let _ = service;
+}
+
 /// Create database connection pool
 async fn create_database_pool(settings: &Settings) -> Result<PgPool, Box<dyn std::error::Error>> {
     let pool = sqlx::postgres::PgPoolOptions::new()
@@ -681,6 +1343,128 @@ async fn test_database_connection(pool: &PgPool) -> Result<(), Box<dyn std::erro
     Ok(())
 }
 
+/// Build storage coalescing thresholds from settings (disabled unless enabled)
+fn create_coalesce_config(
+    settings: &Settings,
+) -> Result<service::CoalesceConfig, Box<dyn std::error::Error>> {
+    let coalescing = &settings.coalescing;
+    if !coalescing.enabled {
+        return Ok(service::CoalesceConfig::default());
+    }
+
+    let to_decimal = |value: Option<f64>, name: &str| {
+        value
+            .map(Decimal::try_from)
+            .transpose()
+            .map_err(|e| format!("Invalid coalescing {}: {}", name, e))
+    };
+
+    let config = service::CoalesceConfig {
+        min_price_change: to_decimal(coalescing.min_price_change, "min_price_change")?,
+        min_quantity: to_decimal(coalescing.min_quantity, "min_quantity")?,
+        min_interval_ms: coalescing.min_interval_ms,
+    };
+    info!("🗜️  Tick storage coalescing enabled: {:?}", config);
+    Ok(config)
+}
+
+/// Build the sampled tick heartbeat from settings (disabled unless a cadence is set)
+fn create_heartbeat_config(settings: &Settings) -> service::HeartbeatConfig {
+    let config = service::HeartbeatConfig {
+        every_ticks: settings.heartbeat.every_ticks,
+        every_secs: settings.heartbeat.every_secs,
+    };
+    if config.is_enabled() {
+        info!("💓 Tick heartbeat enabled: {:?}", config);
+    }
+    config
+}
+
+/// Build the tick storage batching from settings
+fn create_batch_config(settings: &Settings) -> service::BatchConfig {
+    if settings.database.copy_ingest {
+        info!("📥 Storing tick batches with COPY");
+    }
+    service::BatchConfig {
+        use_copy: settings.database.copy_ingest,
+        ..Default::default()
+    }
+}
+
+/// Create the exchange client named in the configuration
+fn create_exchange(settings: &Settings) -> Result<Arc<dyn Exchange>, Box<dyn std::error::Error>> {
+    let exchange: Arc<dyn Exchange> = match settings.exchange.name.parse()? {
+        Venue::Binance => Arc::new(
+            BinanceExchange::new()
+                .with_message_buffer(settings.exchange.message_buffer_size)
+                .with_precision_normalization(settings.exchange.normalize_precision)
+                .with_request_timeout(Duration::from_secs(settings.exchange.request_timeout_secs))
+                .with_retry_policy(RetryPolicy {
+                    max_attempts: settings.exchange.max_request_attempts.max(1),
+                    ..RetryPolicy::default()
+                })
+                .with_reconnect_policy(reconnect_policy(&settings.exchange)),
+        ),
+        Venue::Coinbase => Arc::new(
+            CoinbaseExchange::new().with_reconnect_policy(reconnect_policy(&settings.exchange)),
+        ),
+        Venue::Kraken => Arc::new(
+            KrakenExchange::new().with_reconnect_policy(reconnect_policy(&settings.exchange)),
+        ),
+        venue => {
+            return Err(format!("No exchange client is implemented for '{}'", venue).into());
+        }
+    };
+    info!("📡 Using {} exchange", exchange.venue());
+    Ok(exchange)
+}
+
+/// WebSocket reconnects from settings, where 0 attempts means no limit
+fn reconnect_policy(settings: &config::ExchangeSettings) -> ReconnectPolicy {
+    let defaults = ReconnectPolicy::default();
+    ReconnectPolicy {
+        max_attempts: Some(settings.max_reconnect_attempts).filter(|&max| max > 0),
+        max_delay: Duration::from_secs(settings.max_reconnect_delay_secs)
+            .max(defaults.initial_delay),
+        ..defaults
+    }
+}
+
+/// Store the exchange's current trading rules for the configured symbols
+async fn sync_symbol_metadata(
+    exchange: &dyn Exchange,
+    repository: &TickDataRepository,
+    symbols: &[String],
+) {
+    let metadata = match exchange.get_symbol_metadata(symbols).await {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            warn!("Could not load symbol metadata: {}", e);
+            return;
+        }
+    };
+    for rules in &metadata {
+        if let Err(e) = repository.upsert_symbol_metadata(rules).await {
+            warn!("Could not store metadata for {}: {}", rules.symbol, e);
+        }
+    }
+    info!("📐 Stored trading rules for {} symbols", metadata.len());
+}
+
+/// Stored trading rules of active symbols, empty if they cannot be read
+async fn load_symbol_metadata(repository: &TickDataRepository) -> Vec<data::types::SymbolMetadata> {
+    repository
+        .list_symbol_metadata(true)
+        .await
+        .unwrap_or_else(|e| {
+            warn!(
+                "Could not load symbol metadata, lot sizes are not applied: {}",
+                e
+            );
+            Vec::new()
+        })
+}
+
 /// Create cache instance (original live mode)
 async fn create_cache(settings: &Settings) -> Result<TieredCache, Box<dyn std::error::Error>> {
     let memory_config = (
@@ -0,0 +1,570 @@
+use chrono::{DateTime, Duration, Utc};
+use futures_util::{stream, Future, StreamExt};
+use std::collections::HashSet;
+use std::sync::atomic::AtomicU32;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use super::{BackfillReport, ServiceError};
+use crate::exchange::{Exchange, HistoricalTradeParams, RateLimiter, DEFAULT_MAX_REQUESTS};
+use trading_common::data::repository::TickDataRepository;
+use trading_common::data::types::DataGap;
+
+/// Default page size for historical trade requests
+const DEFAULT_PAGE_SIZE: u32 = 1000;
+/// Default minimum silence between ticks that counts as a gap
+const DEFAULT_MIN_GAP_SECONDS: i64 = 60;
+/// Default number of symbols backfilled at once
+const DEFAULT_CONCURRENCY: usize = 4;
+/// REST requests per second across all symbols; Binance allows 6000 request
+/// weight per minute and an aggTrades page costs 2, so this leaves headroom
+pub const DEFAULT_MAX_REQUESTS_PER_SECOND: u32 = 10;
+
+/// Outcome of backfilling one symbol in a multi-symbol run
+#[derive(Debug)]
+pub struct SymbolBackfill<T> {
+    pub symbol: String,
+    pub result: Result<T, ServiceError>,
+}
+
+/// Fetches historical trades from the exchange and stores them
+pub struct BackfillService {
+    /// Exchange implementation
+    exchange: Arc<dyn Exchange>,
+    /// Data repository
+    repository: Arc<TickDataRepository>,
+    /// Trades requested per page
+    page_size: u32,
+    /// Minimum hole size considered a gap
+    min_gap: Duration,
+    /// Cap on REST requests per range; `None` means unbounded
+    max_requests: Option<u32>,
+    /// Symbols fetched at once by the multi-symbol methods
+    concurrency: usize,
+    /// Gates every REST request, shared by all symbols in flight
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl BackfillService {
+    /// Create a new backfill service
+    pub fn new(exchange: Arc<dyn Exchange>, repository: Arc<TickDataRepository>) -> Self {
+        Self {
+            exchange,
+            repository,
+            page_size: DEFAULT_PAGE_SIZE,
+            min_gap: Duration::seconds(DEFAULT_MIN_GAP_SECONDS),
+            max_requests: Some(DEFAULT_MAX_REQUESTS),
+            concurrency: DEFAULT_CONCURRENCY,
+            rate_limiter: None,
+        }
+    }
+
+    pub fn with_min_gap(mut self, min_gap: Duration) -> Self {
+        self.min_gap = min_gap;
+        self
+    }
+
+    /// Cap the number of REST requests one range may issue, counting the
+    /// exchange's own time-scan requests as well as pages
+    pub fn with_max_requests(mut self, max_requests: Option<u32>) -> Self {
+        self.max_requests = max_requests;
+        self
+    }
+
+    /// Number of symbols the multi-symbol methods work on at once (at least 1)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Wait on `rate_limiter` before every REST request the exchange sends
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Download and store every trade in [start_time, end_time]
+    ///
+    /// Returns the number of newly inserted ticks. If the request cap is hit
+    /// first, the ticks fetched so far are kept and a warning is logged.
+    pub async fn backfill_range(
+        &self,
+        symbol: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<usize, ServiceError> {
+        if start_time > end_time {
+            return Err(ServiceError::Config(
+                "Backfill start must not be after end".to_string(),
+            ));
+        }
+
+        info!("Backfilling {} from {} to {}", symbol, start_time, end_time);
+
+        let mut cursor = start_time;
+        let mut inserted = 0;
+        // Trade IDs seen at the cursor millisecond, to skip page-boundary repeats
+        let mut boundary_ids: HashSet<String> = HashSet::new();
+        // Every page request of the range draws on one request budget
+        let requests = Arc::new(AtomicU32::new(0));
+
+        loop {
+            let mut params = HistoricalTradeParams::new(cursor, end_time)
+                .with_limit(self.page_size)
+                .with_max_requests(self.max_requests)
+                .with_request_count(Arc::clone(&requests));
+            if params.requests_exhausted() {
+                warn!(
+                    "Backfill for {} stopped at {} after {} requests (cap reached); range is incomplete",
+                    symbol,
+                    cursor,
+                    params.requests_issued()
+                );
+                break;
+            }
+            // The exchange waits on the limiter before each REST request it
+            // sends, time scan and retries included
+            if let Some(limiter) = &self.rate_limiter {
+                params = params.with_rate_limiter(Arc::clone(limiter));
+            }
+            // The exchange claims its requests, time scan included, from the same budget
+            let page = self.exchange.get_historical_trades(symbol, &params).await?;
+            if page.is_empty() {
+                break;
+            }
+
+            let page_len = page.len();
+            let last_timestamp = page[page_len - 1].timestamp;
+
+            let new_ticks: Vec<_> = page
+                .into_iter()
+                .filter(|tick| !(tick.timestamp == cursor && boundary_ids.contains(&tick.trade_id)))
+                .collect();
+
+            if new_ticks.is_empty() {
+                // A full page inside one millisecond; step past it to guarantee progress
+                cursor = last_timestamp + Duration::milliseconds(1);
+                boundary_ids.clear();
+                continue;
+            }
+
+            boundary_ids = new_ticks
+                .iter()
+                .filter(|tick| tick.timestamp == last_timestamp)
+                .map(|tick| tick.trade_id.clone())
+                .collect();
+
+            let merged = self
+                .repository
+                .insert_ticks_skipping_existing(new_ticks)
+                .await?;
+            inserted += merged.inserted;
+            debug!(
+                "Backfilled page of {} ticks up to {} ({} already stored)",
+                page_len, last_timestamp, merged.filtered
+            );
+
+            if last_timestamp >= end_time {
+                break;
+            }
+
+            // A full page may have more trades in the same millisecond, so re-request it
+            cursor = if page_len as u32 >= self.page_size {
+                last_timestamp
+            } else {
+                boundary_ids.clear();
+                last_timestamp + Duration::milliseconds(1)
+            };
+        }
+
+        info!("Backfill for {} inserted {} ticks", symbol, inserted);
+        Ok(inserted)
+    }
+
+    /// Fetch only the ranges in [start_time, end_time] that have no stored ticks
+    pub async fn fill_gaps(
+        &self,
+        symbol: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<BackfillReport, ServiceError> {
+        let gaps = self
+            .repository
+            .detect_gaps(symbol, start_time, end_time, self.min_gap)
+            .await?;
+
+        info!("Found {} gaps for {}", gaps.len(), symbol);
+
+        let mut ticks_inserted = 0;
+        for gap in &gaps {
+            let (fetch_start, fetch_end) = Self::fetch_bounds(gap, start_time, end_time);
+            if fetch_start > fetch_end {
+                continue;
+            }
+            ticks_inserted += self.backfill_range(symbol, fetch_start, fetch_end).await?;
+        }
+
+        let remaining = self
+            .repository
+            .detect_gaps(symbol, start_time, end_time, self.min_gap)
+            .await?;
+
+        let report = BackfillReport {
+            gaps_found: gaps.len(),
+            gaps_filled: gaps.len().saturating_sub(remaining.len()),
+            gaps_remaining: remaining.len(),
+            ticks_inserted,
+        };
+        info!(
+            "Gap fill for {}: {} filled, {} remaining, {} ticks inserted",
+            symbol, report.gaps_filled, report.gaps_remaining, report.ticks_inserted
+        );
+        Ok(report)
+    }
+
+    /// Backfill [start_time, end_time] for every symbol, up to `concurrency`
+    /// symbols at a time
+    ///
+    /// A failing symbol does not stop the others. Results come back in the
+    /// order of `symbols`.
+    pub async fn backfill_symbols(
+        &self,
+        symbols: &[String],
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Vec<SymbolBackfill<usize>> {
+        self.for_each_symbol(symbols, |symbol| {
+            self.backfill_range(symbol, start_time, end_time)
+        })
+        .await
+    }
+
+    /// `fill_gaps` for every symbol, up to `concurrency` symbols at a time
+    pub async fn fill_gaps_symbols(
+        &self,
+        symbols: &[String],
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Vec<SymbolBackfill<BackfillReport>> {
+        self.for_each_symbol(symbols, |symbol| {
+            self.fill_gaps(symbol, start_time, end_time)
+        })
+        .await
+    }
+
+    async fn for_each_symbol<'a, T, F, Fut>(
+        &self,
+        symbols: &'a [String],
+        run: F,
+    ) -> Vec<SymbolBackfill<T>>
+    where
+        F: Fn(&'a str) -> Fut,
+        Fut: Future<Output = Result<T, ServiceError>>,
+    {
+        let total = symbols.len();
+        let mut done = 0;
+        let mut finished: Vec<(usize, SymbolBackfill<T>)> =
+            stream::iter(symbols.iter().enumerate())
+                .map(|(index, symbol)| {
+                    let run = &run;
+                    async move {
+                        let result = run(symbol).await;
+                        (
+                            index,
+                            SymbolBackfill {
+                                symbol: symbol.clone(),
+                                result,
+                            },
+                        )
+                    }
+                })
+                .buffer_unordered(self.concurrency)
+                .inspect(|(_, finished)| {
+                    done += 1;
+                    match &finished.result {
+                        Ok(_) => info!("[{}/{}] Backfilled {}", done, total, finished.symbol),
+                        Err(e) => warn!(
+                            "[{}/{}] Backfill for {} failed: {}",
+                            done, total, finished.symbol, e
+                        ),
+                    }
+                })
+                .collect()
+                .await;
+
+        finished.sort_by_key(|(index, _)| *index);
+        finished.into_iter().map(|(_, run)| run).collect()
+    }
+
+    /// Interior gap bounds are stored ticks, so exclude them from the fetch
+    fn fetch_bounds(
+        gap: &DataGap,
+        range_start: DateTime<Utc>,
+        range_end: DateTime<Utc>,
+    ) -> (DateTime<Utc>, DateTime<Utc>) {
+        let one_ms = Duration::milliseconds(1);
+        let start = if gap.start == range_start {
+            gap.start
+        } else {
+            gap.start + one_ms
+        };
+        let end = if gap.end == range_end {
+            gap.end
+        } else {
+            gap.end - one_ms
+        };
+        (start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::{ExchangeError, Venue};
+    use crate::test_support;
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+    use rust_decimal::Decimal;
+    use sqlx::PgPool;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use tokio::time::Instant;
+    use trading_common::data::types::{TickData, TradeSide};
+
+    /// Exchange serving historical trades from an in-memory dataset
+    #[derive(Default)]
+    struct MockExchange {
+        trades: Vec<TickData>,
+        requested_ranges: Mutex<Vec<(DateTime<Utc>, DateTime<Utc>)>>,
+        /// Time each request takes to answer
+        latency: std::time::Duration,
+        request_times: Mutex<Vec<Instant>>,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Exchange for MockExchange {
+        fn venue(&self) -> Venue {
+            Venue::Binance
+        }
+
+        async fn subscribe_trades(
+            &self,
+            _symbols: &[String],
+            _callback: Box<dyn Fn(TickData) + Send + Sync>,
+            _shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+        ) -> Result<(), ExchangeError> {
+            Ok(())
+        }
+
+        async fn get_historical_trades(
+            &self,
+            symbol: &str,
+            params: &HistoricalTradeParams,
+        ) -> Result<Vec<TickData>, ExchangeError> {
+            // Like a real client, one REST request per call
+            if !params.claim_request().await {
+                return Ok(Vec::new());
+            }
+            self.requested_ranges
+                .lock()
+                .unwrap()
+                .push((params.start_time, params.end_time));
+            self.request_times.lock().unwrap().push(Instant::now());
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(self.latency).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(self
+                .trades
+                .iter()
+                .filter(|t| {
+                    t.symbol == symbol
+                        && t.timestamp >= params.start_time
+                        && t.timestamp <= params.end_time
+                })
+                .take(params.limit as usize)
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn create_tick(symbol: &str, base: DateTime<Utc>, second: i64) -> TickData {
+        TickData::new(
+            base + Duration::seconds(second),
+            symbol.to_string(),
+            Decimal::from(50000 + second),
+            Decimal::ONE,
+            TradeSide::Buy,
+            format!("bf{}", second),
+            false,
+        )
+    }
+
+    async fn create_repository() -> Arc<TickDataRepository> {
+        Arc::new(test_support::create_repository().await)
+    }
+
+    async fn cleanup_database(pool: &PgPool, symbol: &str) {
+        sqlx::query("DELETE FROM tick_data WHERE symbol = $1")
+            .bind(symbol)
+            .execute(pool)
+            .await
+            .expect("Failed to clean up database");
+    }
+
+    #[tokio::test]
+    async fn test_fill_gaps_fetches_only_missing_ranges() {
+        let symbol = "BTCUSDT_TEST_BF";
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let repository = create_repository().await;
+        cleanup_database(repository.get_pool(), symbol).await;
+
+        // Exchange has one trade per second for a minute
+        let all_ticks: Vec<TickData> = (0..60).map(|s| create_tick(symbol, base, s)).collect();
+
+        // Database is missing seconds 10..20 and 40..50
+        let stored: Vec<TickData> = all_ticks
+            .iter()
+            .filter(|t| {
+                let s = (t.timestamp - base).num_seconds();
+                !(10..20).contains(&s) && !(40..50).contains(&s)
+            })
+            .cloned()
+            .collect();
+        repository.batch_insert_historical(stored).await.unwrap();
+
+        let exchange = Arc::new(MockExchange {
+            trades: all_ticks,
+            ..Default::default()
+        });
+        // Small pages exercise the pagination path
+        let service = BackfillService {
+            page_size: 4,
+            ..BackfillService::new(exchange.clone(), Arc::clone(&repository))
+        }
+        .with_min_gap(Duration::seconds(5));
+
+        let start = base;
+        let end = base + Duration::seconds(59);
+        let gaps = repository
+            .detect_gaps(symbol, start, end, Duration::seconds(5))
+            .await
+            .unwrap();
+        assert_eq!(gaps.len(), 2);
+        assert_eq!(gaps[0].start, base + Duration::seconds(9));
+        assert_eq!(gaps[0].end, base + Duration::seconds(20));
+
+        let report = service.fill_gaps(symbol, start, end).await.unwrap();
+
+        assert_eq!(report.gaps_found, 2);
+        assert_eq!(report.gaps_filled, 2);
+        assert_eq!(report.gaps_remaining, 0);
+        assert_eq!(report.ticks_inserted, 20);
+
+        // Every request stayed inside one of the holes
+        let in_gap = |t: DateTime<Utc>| {
+            let ms = (t - base).num_milliseconds();
+            (9_000 < ms && ms < 20_000) || (39_000 < ms && ms < 50_000)
+        };
+        for (req_start, req_end) in exchange.requested_ranges.lock().unwrap().iter() {
+            assert!(in_gap(*req_start) && in_gap(*req_end));
+        }
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tick_data WHERE symbol = $1")
+            .bind(symbol)
+            .fetch_one(repository.get_pool())
+            .await
+            .unwrap();
+        assert_eq!(count, 60);
+
+        cleanup_database(repository.get_pool(), symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_request_cap_returns_partial_backfill() {
+        let symbol = "BTCUSDT_TEST_CAP";
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let repository = create_repository().await;
+        cleanup_database(repository.get_pool(), symbol).await;
+
+        let exchange = Arc::new(MockExchange {
+            trades: (0..60).map(|s| create_tick(symbol, base, s)).collect(),
+            ..Default::default()
+        });
+        let service = BackfillService {
+            page_size: 4,
+            ..BackfillService::new(exchange.clone(), Arc::clone(&repository))
+        }
+        .with_max_requests(Some(3));
+
+        let inserted = service
+            .backfill_range(symbol, base, base + Duration::seconds(59))
+            .await
+            .unwrap();
+
+        // Three pages of four, each after the first repeating its boundary
+        // tick, then paging stops with what it has
+        assert_eq!(inserted, 10);
+        assert_eq!(exchange.requested_ranges.lock().unwrap().len(), 3);
+
+        cleanup_database(repository.get_pool(), symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_backfill_shares_rate_limit() {
+        let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let symbols: Vec<String> = (0..4).map(|i| format!("BTCUSDT_TEST_CC{}", i)).collect();
+        let repository = create_repository().await;
+        for symbol in &symbols {
+            cleanup_database(repository.get_pool(), symbol).await;
+        }
+
+        let exchange = Arc::new(MockExchange {
+            trades: symbols
+                .iter()
+                .flat_map(|symbol| (0..8).map(move |s| create_tick(symbol, base, s)))
+                .collect(),
+            latency: std::time::Duration::from_millis(100),
+            ..Default::default()
+        });
+        let interval = std::time::Duration::from_millis(20);
+        let service = BackfillService {
+            page_size: 4,
+            ..BackfillService::new(exchange.clone(), Arc::clone(&repository))
+        }
+        .with_concurrency(2)
+        .with_rate_limiter(Arc::new(RateLimiter::with_interval(interval)));
+
+        let started = Instant::now();
+        let runs = service
+            .backfill_symbols(&symbols, base, base + Duration::seconds(7))
+            .await;
+
+        // Every symbol finished, reported in input order
+        let reported: Vec<(&str, usize)> = runs
+            .iter()
+            .map(|run| (run.symbol.as_str(), *run.result.as_ref().unwrap()))
+            .collect();
+        let expected: Vec<(&str, usize)> = symbols.iter().map(|s| (s.as_str(), 8)).collect();
+        assert_eq!(reported, expected);
+
+        // Never more than two symbols talking to the exchange at once
+        assert_eq!(exchange.max_in_flight.load(Ordering::SeqCst), 2);
+
+        // Three pages per symbol, and the shared limiter spaced all twelve
+        // requests one interval apart regardless of which symbol sent them
+        let mut times = exchange.request_times.lock().unwrap().clone();
+        assert_eq!(times.len(), 12);
+        times.sort();
+        for (i, time) in times.iter().enumerate() {
+            assert!(*time - started >= interval * i as u32);
+        }
+
+        for symbol in &symbols {
+            cleanup_database(repository.get_pool(), symbol).await;
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use super::CoalesceConfig;
+use trading_common::data::types::TickData;
+
+/// Decides per symbol whether a tick is worth persisting
+///
+/// A tick is stored when any enabled threshold is met relative to the last
+/// stored tick of the same symbol. The first tick of each symbol is always
+/// stored, and with no thresholds configured every tick is stored.
+pub struct TickCoalescer {
+    config: CoalesceConfig,
+    last_stored: HashMap<String, TickData>,
+}
+
+impl TickCoalescer {
+    pub fn new(config: CoalesceConfig) -> Self {
+        Self {
+            config,
+            last_stored: HashMap::new(),
+        }
+    }
+
+    /// Returns true if the tick should be persisted, recording it as the
+    /// new reference point for its symbol
+    pub fn should_persist(&mut self, tick: &TickData) -> bool {
+        if !self.config.is_enabled() {
+            return true;
+        }
+
+        let persist = match self.last_stored.get(&tick.symbol) {
+            None => true,
+            Some(last) => self.exceeds_thresholds(last, tick),
+        };
+
+        if persist {
+            self.last_stored.insert(tick.symbol.clone(), tick.clone());
+        }
+        persist
+    }
+
+    fn exceeds_thresholds(&self, last: &TickData, tick: &TickData) -> bool {
+        let price_moved = self.config.min_price_change.is_some_and(|threshold| {
+            last.price > Decimal::ZERO
+                && ((tick.price - last.price) / last.price).abs() >= threshold
+        });
+        let large_trade = self
+            .config
+            .min_quantity
+            .is_some_and(|threshold| tick.quantity >= threshold);
+        let interval_elapsed = self.config.min_interval_ms.is_some_and(|interval_ms| {
+            (tick.timestamp - last.timestamp).num_milliseconds() >= interval_ms as i64
+        });
+
+        price_moved || large_trade || interval_elapsed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use std::str::FromStr;
+    use trading_common::data::types::TradeSide;
+
+    fn create_tick(symbol: &str, millis: i64, price: &str, quantity: &str) -> TickData {
+        TickData::new(
+            Utc::now() + Duration::milliseconds(millis),
+            symbol.to_string(),
+            Decimal::from_str(price).unwrap(),
+            Decimal::from_str(quantity).unwrap(),
+            TradeSide::Buy,
+            format!("co{}", millis),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_thresholds_are_relative_to_last_stored_tick() {
+        let mut coalescer = TickCoalescer::new(CoalesceConfig {
+            min_price_change: Some(Decimal::from_str("0.001").unwrap()),
+            min_quantity: Some(Decimal::from(5)),
+            min_interval_ms: Some(1000),
+        });
+
+        assert!(coalescer.should_persist(&create_tick("BTCUSDT", 0, "100.00", "1")));
+        // 0.05% move, small trade, 100ms later: coalesced
+        assert!(!coalescer.should_persist(&create_tick("BTCUSDT", 100, "100.05", "1")));
+        // Cumulative 0.1% move from the last stored tick
+        assert!(coalescer.should_persist(&create_tick("BTCUSDT", 200, "100.10", "1")));
+        // Large trade
+        assert!(coalescer.should_persist(&create_tick("BTCUSDT", 300, "100.10", "5")));
+        // Interval elapsed since the last stored tick
+        assert!(!coalescer.should_persist(&create_tick("BTCUSDT", 900, "100.10", "1")));
+        assert!(coalescer.should_persist(&create_tick("BTCUSDT", 1300, "100.10", "1")));
+        // Symbols are tracked independently
+        assert!(coalescer.should_persist(&create_tick("ETHUSDT", 1400, "100.10", "1")));
+    }
+
+    #[test]
+    fn test_disabled_config_persists_everything() {
+        let mut coalescer = TickCoalescer::new(CoalesceConfig::default());
+        for millis in 0..5 {
+            assert!(coalescer.should_persist(&create_tick("BTCUSDT", millis, "100", "1")));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::{select, spawn};
+use tracing::{debug, info, warn};
+
+use super::{MarketDataService, ReadinessConfig};
+use trading_common::data::cache::TickDataCache;
+use trading_common::data::clock::{system_clock, SharedClock};
+use trading_common::data::repository::TickDataRepository;
+
+/// Result of a readiness check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Readiness {
+    Ready,
+    NotReady(String),
+}
+
+/// Decides whether the collector should receive traffic
+///
+/// During the startup grace period only DB and cache connectivity are
+/// required, so a quiet symbol does not keep a fresh collector unready.
+/// After it, a tick no older than `max_tick_age_secs` is also required.
+pub struct ReadinessProbe {
+    config: ReadinessConfig,
+    started_at: DateTime<Utc>,
+    clock: SharedClock,
+}
+
+impl ReadinessProbe {
+    pub fn new(config: ReadinessConfig) -> Self {
+        Self::with_clock(config, system_clock())
+    }
+
+    /// Create a probe whose grace period starts at the clock's current time
+    pub fn with_clock(config: ReadinessConfig, clock: SharedClock) -> Self {
+        Self {
+            config,
+            started_at: clock.now(),
+            clock,
+        }
+    }
+
+    /// Evaluate readiness from already gathered connectivity and tick state
+    pub fn evaluate(
+        &self,
+        db_ok: bool,
+        cache_ok: bool,
+        last_tick: Option<DateTime<Utc>>,
+    ) -> Readiness {
+        if !db_ok {
+            return Readiness::NotReady("database unreachable".to_string());
+        }
+        if !cache_ok {
+            return Readiness::NotReady("cache unreachable".to_string());
+        }
+
+        let now = self.clock.now();
+        let grace = Duration::seconds(self.config.startup_grace_secs as i64);
+        if now - self.started_at < grace {
+            return Readiness::Ready;
+        }
+
+        let max_age = Duration::seconds(self.config.max_tick_age_secs as i64);
+        match last_tick {
+            Some(last) if now - last <= max_age => Readiness::Ready,
+            Some(last) => {
+                Readiness::NotReady(format!("last tick {}s ago", (now - last).num_seconds()))
+            }
+            None => Readiness::NotReady("no ticks received".to_string()),
+        }
+    }
+
+    /// Check DB and cache connectivity, then evaluate
+    pub async fn check(
+        &self,
+        repository: &TickDataRepository,
+        last_tick: Option<DateTime<Utc>>,
+    ) -> Readiness {
+        let db_ok = sqlx::query("SELECT 1")
+            .execute(repository.get_pool())
+            .await
+            .is_ok();
+        let cache_ok = repository.get_cache().get_symbols().await.is_ok();
+        self.evaluate(db_ok, cache_ok, last_tick)
+    }
+
+    /// Answer `GET /ready` with 200 or 503 until shutdown
+    pub async fn serve(
+        self: Arc<Self>,
+        listener: TcpListener,
+        service: Arc<MarketDataService>,
+        repository: Arc<TickDataRepository>,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) {
+        if let Ok(addr) = listener.local_addr() {
+            info!("Readiness probe listening on {}", addr);
+        }
+
+        loop {
+            select! {
+                accepted = listener.accept() => {
+                    match accepted {
+                        Ok((stream, _)) => {
+                            let probe = Arc::clone(&self);
+                            let service = Arc::clone(&service);
+                            let repository = Arc::clone(&repository);
+                            spawn(async move {
+                                if let Err(e) = probe.respond(stream, &service, &repository).await {
+                                    debug!("Readiness request failed: {}", e);
+                                }
+                            });
+                        }
+                        Err(e) => warn!("Readiness probe accept failed: {}", e),
+                    }
+                }
+                _ = shutdown_rx.recv() => break,
+            }
+        }
+    }
+
+    async fn respond(
+        &self,
+        mut stream: TcpStream,
+        service: &MarketDataService,
+        repository: &TickDataRepository,
+    ) -> std::io::Result<()> {
+        let mut buf = [0u8; 1024];
+        let n = stream.read(&mut buf).await?;
+        let request = String::from_utf8_lossy(&buf[..n]);
+
+        let (status, body) = if request.starts_with("GET /ready ") {
+            let last_tick = service.get_stats().await.last_tick_time;
+            match self.check(repository, last_tick).await {
+                Readiness::Ready => ("200 OK", "ready".to_string()),
+                Readiness::NotReady(reason) => ("503 Service Unavailable", reason),
+            }
+        } else {
+            ("404 Not Found", "not found".to_string())
+        };
+
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use trading_common::data::clock::{Clock, MockClock};
+
+    #[test]
+    fn test_ready_during_grace_without_ticks() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        let probe = ReadinessProbe::with_clock(
+            ReadinessConfig {
+                startup_grace_secs: 60,
+                max_tick_age_secs: 10,
+            },
+            Arc::new(clock.clone()),
+        );
+
+        // Inside the grace period connectivity is enough
+        clock.advance(Duration::seconds(30));
+        assert_eq!(probe.evaluate(true, true, None), Readiness::Ready);
+        assert_ne!(probe.evaluate(false, true, None), Readiness::Ready);
+        assert_ne!(probe.evaluate(true, false, None), Readiness::Ready);
+
+        // After it, a missing or stale tick makes the probe unready
+        clock.advance(Duration::seconds(31));
+        assert_eq!(
+            probe.evaluate(true, true, None),
+            Readiness::NotReady("no ticks received".to_string())
+        );
+        let stale = clock.now() - Duration::seconds(11);
+        assert_ne!(probe.evaluate(true, true, Some(stale)), Readiness::Ready);
+
+        let fresh = clock.now() - Duration::seconds(5);
+        assert_eq!(probe.evaluate(true, true, Some(fresh)), Readiness::Ready);
+    }
+}
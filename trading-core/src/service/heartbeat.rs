@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+
+use super::HeartbeatConfig;
+use trading_common::data::clock::{system_clock, SharedClock};
+use trading_common::data::types::TickData;
+
+/// Summary emitted when a symbol's heartbeat is due
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heartbeat {
+    pub symbol: String,
+    pub price: Decimal,
+    /// Ticks seen for the symbol since the service started
+    pub tick_count: u64,
+}
+
+#[derive(Default)]
+struct SymbolPulse {
+    tick_count: u64,
+    ticks_since_beat: u64,
+    last_beat: Option<DateTime<Utc>>,
+}
+
+/// Samples the tick stream into an info-level pulse per symbol
+///
+/// A heartbeat fires every `every_ticks` ticks or once `every_secs` have
+/// passed since the symbol's last heartbeat, whichever comes first. The first
+/// tick of a symbol starts its timer without firing.
+pub struct TickHeartbeat {
+    config: HeartbeatConfig,
+    symbols: HashMap<String, SymbolPulse>,
+    clock: SharedClock,
+}
+
+impl TickHeartbeat {
+    pub fn new(config: HeartbeatConfig) -> Self {
+        Self::with_clock(config, system_clock())
+    }
+
+    pub fn with_clock(config: HeartbeatConfig, clock: SharedClock) -> Self {
+        Self {
+            config,
+            symbols: HashMap::new(),
+            clock,
+        }
+    }
+
+    /// Count a tick, returning a heartbeat if one is due for its symbol
+    pub fn observe(&mut self, tick: &TickData) -> Option<Heartbeat> {
+        if !self.config.is_enabled() {
+            return None;
+        }
+
+        let now = self.clock.now();
+        let pulse = self.symbols.entry(tick.symbol.clone()).or_default();
+        pulse.tick_count += 1;
+        pulse.ticks_since_beat += 1;
+
+        let last_beat = *pulse.last_beat.get_or_insert(now);
+        let count_due = self
+            .config
+            .every_ticks
+            .is_some_and(|n| n > 0 && pulse.ticks_since_beat >= n);
+        let time_due = self
+            .config
+            .every_secs
+            .is_some_and(|secs| now - last_beat >= Duration::seconds(secs as i64));
+
+        if !(count_due || time_due) {
+            return None;
+        }
+
+        pulse.ticks_since_beat = 0;
+        pulse.last_beat = Some(now);
+        Some(Heartbeat {
+            symbol: tick.symbol.clone(),
+            price: tick.price,
+            tick_count: pulse.tick_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::sync::Arc;
+    use trading_common::data::clock::MockClock;
+    use trading_common::data::types::TradeSide;
+
+    fn tick(symbol: &str, price: i64) -> TickData {
+        TickData::new(
+            Utc::now(),
+            symbol.to_string(),
+            Decimal::from(price),
+            Decimal::ONE,
+            TradeSide::Buy,
+            price.to_string(),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_heartbeat_cadence_by_count_and_time() {
+        let clock = MockClock::new(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        let mut heartbeat = TickHeartbeat::with_clock(
+            HeartbeatConfig {
+                every_ticks: Some(3),
+                every_secs: Some(60),
+            },
+            Arc::new(clock.clone()),
+        );
+
+        // Every third BTC tick fires, counted independently of ETH
+        let fired: Vec<Option<u64>> = (1..=6)
+            .map(|i| {
+                heartbeat.observe(&tick("ETHUSDT", i));
+                heartbeat
+                    .observe(&tick("BTCUSDT", 100 + i))
+                    .map(|beat| beat.tick_count)
+            })
+            .collect();
+        assert_eq!(fired, vec![None, None, Some(3), None, None, Some(6)]);
+
+        // A quiet symbol still pulses once the interval passes
+        clock.advance(Duration::seconds(59));
+        assert_eq!(heartbeat.observe(&tick("BTCUSDT", 107)), None);
+        clock.advance(Duration::seconds(1));
+        assert_eq!(
+            heartbeat.observe(&tick("BTCUSDT", 108)),
+            Some(Heartbeat {
+                symbol: "BTCUSDT".to_string(),
+                price: Decimal::from(108),
+                tick_count: 8,
+            })
+        );
+
+        // Disabled by default
+        let mut silent = TickHeartbeat::new(HeartbeatConfig::default());
+        assert!((0..10).all(|i| silent.observe(&tick("BTCUSDT", i)).is_none()));
+    }
+}
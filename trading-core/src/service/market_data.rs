@@ -1,16 +1,42 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio::task::JoinHandle;
 use tokio::time::{interval, sleep};
 use tokio::{select, spawn};
 use tracing::{debug, error, info, warn};
 
-use super::{BatchConfig, BatchStats, ServiceError};
+use super::{
+    metrics, BatchConfig, BatchStats, ChannelConfig, CoalesceConfig, HeartbeatConfig,
+    MetricsConfig, NdjsonWriter, PriceFeed, PriceUpdate, ServiceError, TickCoalescer,
+    TickHeartbeat,
+};
 use crate::exchange::Exchange;
 use crate::live_trading::PaperTradingProcessor;
+use trading_common::data::clock::{system_clock, SharedClock};
 use trading_common::data::types::TickData;
 use trading_common::data::{cache::TickDataCache, repository::TickDataRepository};
 
+/// Maximum symbols subscribed over a single exchange connection
+const MAX_SYMBOLS_PER_CONNECTION: usize = 100;
+
+/// Symbol set change sent to the running collection task
+#[derive(Debug)]
+struct SymbolUpdate {
+    added: Vec<String>,
+    removed: Vec<String>,
+}
+
+/// One exchange connection covering a group of symbols
+struct StreamChunk {
+    symbols: Vec<String>,
+    shutdown_tx: broadcast::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
 /// Market data service that coordinates between exchange and data storage
 pub struct MarketDataService {
     /// Exchange implementation
@@ -18,15 +44,31 @@ pub struct MarketDataService {
     /// Data repository (wrapped in Arc for sharing across tasks)
     repository: Arc<TickDataRepository>,
     /// Symbols to monitor
-    symbols: Vec<String>,
+    symbols: Mutex<Vec<String>>,
     /// Batch processing configuration
     batch_config: BatchConfig,
     /// Shutdown signal sender
     shutdown_tx: broadcast::Sender<()>,
+    /// Price update publisher; see `PriceFeed` for the lag policy
+    price_tx: broadcast::Sender<PriceUpdate>,
     /// Processing statistics
     stats: Arc<Mutex<BatchStats>>,
     /// Paper trading processor
     paper_trading: Option<Arc<Mutex<PaperTradingProcessor>>>,
+    /// Storage coalescing thresholds (disabled by default)
+    coalesce_config: CoalesceConfig,
+    /// Sampled info-level tick logging (disabled by default)
+    heartbeat_config: HeartbeatConfig,
+    /// Health threshold of the metrics endpoint
+    metrics_config: MetricsConfig,
+    /// Symbol updates for the collection task
+    control_tx: mpsc::UnboundedSender<SymbolUpdate>,
+    /// Receiving end, taken by the collection task on start
+    control_rx: Mutex<Option<mpsc::UnboundedReceiver<SymbolUpdate>>>,
+    /// NDJSON sink for every processed tick, taken by the processing task on start
+    tick_output: Mutex<Option<NdjsonWriter<Box<dyn Write + Send>>>>,
+    /// Time source for the stats stamps and flush health
+    clock: SharedClock,
 }
 
 impl MarketDataService {
@@ -36,16 +78,27 @@ impl MarketDataService {
         repository: Arc<TickDataRepository>,
         symbols: Vec<String>,
     ) -> Self {
-        let (shutdown_tx, _) = broadcast::channel(16);
+        let channels = ChannelConfig::default();
+        let (shutdown_tx, _) = broadcast::channel(channels.shutdown_capacity);
+        let (price_tx, _) = broadcast::channel(channels.price_capacity);
+        let (control_tx, control_rx) = mpsc::unbounded_channel();
 
         Self {
             exchange,
             repository,
-            symbols,
+            symbols: Mutex::new(symbols),
             batch_config: BatchConfig::default(),
             shutdown_tx,
+            price_tx,
             stats: Arc::new(Mutex::new(BatchStats::default())),
             paper_trading: None,
+            coalesce_config: CoalesceConfig::default(),
+            heartbeat_config: HeartbeatConfig::default(),
+            metrics_config: MetricsConfig::default(),
+            control_tx,
+            control_rx: Mutex::new(Some(control_rx)),
+            tick_output: Mutex::new(None),
+            clock: system_clock(),
         }
     }
 
@@ -54,26 +107,162 @@ impl MarketDataService {
         self
     }
 
+    /// Persist only ticks that move past the given thresholds; every tick
+    /// is still cached and passed to paper trading
+    pub fn with_coalescing(mut self, config: CoalesceConfig) -> Self {
+        self.coalesce_config = config;
+        self
+    }
+
+    /// Log a per-symbol summary at info level every N ticks or M seconds
+    pub fn with_heartbeat(mut self, config: HeartbeatConfig) -> Self {
+        self.heartbeat_config = config;
+        self
+    }
+
+    /// Flush age past which the metrics endpoint reports unhealthy
+    pub fn with_metrics_config(mut self, config: MetricsConfig) -> Self {
+        self.metrics_config = config;
+        self
+    }
+
+    /// Batch size, flush interval, retries and insert method for tick storage
+    pub fn with_batch_config(mut self, config: BatchConfig) -> Self {
+        self.batch_config = config;
+        self
+    }
+
+    /// Resize the shutdown and price broadcasts; call before handing out
+    /// shutdown senders or price subscriptions. Capacities below 1 are
+    /// raised to 1.
+    pub fn with_channel_config(mut self, config: ChannelConfig) -> Self {
+        self.shutdown_tx = broadcast::channel(config.shutdown_capacity.max(1)).0;
+        self.price_tx = broadcast::channel(config.price_capacity.max(1)).0;
+        self
+    }
+
+    /// Also write every processed tick to `writer` as a line of JSON; a
+    /// failed write (e.g. a closed pipe) shuts the service down
+    pub fn with_ndjson_output(mut self, writer: impl Write + Send + 'static) -> Self {
+        self.tick_output = Mutex::new(Some(NdjsonWriter::new(Box::new(writer))));
+        self
+    }
+
+    /// Replace the clock used for "now" (defaults to system time)
+    #[allow(dead_code)] // Library API; the binary always runs on system time
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub fn get_shutdown_tx(&self) -> broadcast::Sender<()> {
         self.shutdown_tx.clone()
     }
 
-    /// Start the market data service
-    pub async fn start(&self) -> Result<(), ServiceError> {
-        if self.symbols.is_empty() {
+    /// Subscribe to the price of every processed tick
+    #[allow(dead_code)] // Library API; the binary itself has no price subscriber
+    pub fn subscribe_prices(&self) -> PriceFeed {
+        PriceFeed::new(self.price_tx.subscribe())
+    }
+
+    /// Snapshot of processing statistics, including exchange-side dropped
+    /// frames and the cache's publish failures
+    pub async fn get_stats(&self) -> BatchStats {
+        let mut stats = self.stats.lock().await.clone();
+        stats.dropped_frames = self.exchange.dropped_frames();
+        stats.publish_failures = self.repository.get_cache().publish_failures();
+        stats
+    }
+
+    /// Serve Prometheus counters on `GET /metrics` and flush health on
+    /// `GET /health` until shutdown, returning the bound address
+    pub async fn serve_metrics(&self, addr: SocketAddr) -> Result<SocketAddr, ServiceError> {
+        let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| {
+            ServiceError::Config(format!("Cannot bind metrics endpoint to {}: {}", addr, e))
+        })?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| ServiceError::Config(e.to_string()))?;
+
+        spawn(metrics::serve(
+            listener,
+            Arc::clone(&self.stats),
+            Arc::clone(&self.exchange),
+            Arc::clone(&self.repository),
+            self.metrics_config.clone(),
+            Arc::clone(&self.clock),
+            self.shutdown_tx.subscribe(),
+        ));
+        Ok(local_addr)
+    }
+
+    /// Replace the monitored symbol set while the service runs
+    ///
+    /// Streams for removed symbols are torn down and added symbols get new
+    /// subscriptions; connections that only carry unchanged symbols are left
+    /// alone. Can be called before `start`, in which case the new set is used
+    /// from the beginning.
+    pub async fn reload_symbols(&self, new_symbols: Vec<String>) -> Result<(), ServiceError> {
+        if new_symbols.is_empty() {
             return Err(ServiceError::Config("No symbols configured".to_string()));
         }
+        self.check_streams(&new_symbols)?;
+
+        let mut symbols = self.symbols.lock().await;
+        let current: HashSet<&String> = symbols.iter().collect();
+        let wanted: HashSet<&String> = new_symbols.iter().collect();
+
+        let added: Vec<String> = new_symbols
+            .iter()
+            .filter(|s| !current.contains(s))
+            .cloned()
+            .collect();
+        let removed: Vec<String> = symbols
+            .iter()
+            .filter(|s| !wanted.contains(s))
+            .cloned()
+            .collect();
+
+        if added.is_empty() && removed.is_empty() {
+            debug!("Symbol reload made no changes");
+            return Ok(());
+        }
 
         info!(
-            "Starting market data service for symbols: {:?}",
-            self.symbols
+            "Reloading symbols: added {:?}, removed {:?}",
+            added, removed
         );
+        *symbols = new_symbols;
+
+        // Updates are idempotent, so one queued before start is harmless
+        self.control_tx
+            .send(SymbolUpdate { added, removed })
+            .map_err(|_| ServiceError::Task("Data collection task has stopped".to_string()))
+    }
+
+    /// Reject symbols the exchange's venue cannot map to a trade stream
+    fn check_streams(&self, symbols: &[String]) -> Result<(), ServiceError> {
+        let venue = self.exchange.venue();
+        let streams = venue.trade_streams(symbols)?;
+        debug!("{} trade streams: {:?}", venue, streams);
+        Ok(())
+    }
+
+    /// Start the market data service
+    pub async fn start(&self) -> Result<(), ServiceError> {
+        let symbols = self.symbols.lock().await.clone();
+        if symbols.is_empty() {
+            return Err(ServiceError::Config("No symbols configured".to_string()));
+        }
+        self.check_streams(&symbols)?;
+
+        info!("Starting market data service for symbols: {:?}", symbols);
 
         // Create data processing pipeline
         let (tick_tx, tick_rx) = mpsc::channel::<TickData>(1000);
 
         // Start data collection task
-        let collection_task = self.start_data_collection(tick_tx).await?;
+        let collection_task = self.start_data_collection(symbols, tick_tx).await?;
 
         // Start data processing task
         let processing_task = self.start_data_processing(tick_rx).await?;
@@ -81,6 +270,15 @@ impl MarketDataService {
         // Wait for tasks to complete
         let result = tokio::try_join!(collection_task, processing_task);
 
+        let stats = self.get_stats().await;
+        info!(
+            "Service stats: {} ticks processed, {} coalesced, {} batches flushed, {} frames dropped",
+            stats.total_ticks_processed,
+            stats.coalesced_ticks,
+            stats.total_batches_flushed,
+            stats.dropped_frames
+        );
+
         match result {
             Ok(_) => {
                 info!("Market data service stopped normally");
@@ -91,19 +289,120 @@ impl MarketDataService {
     }
 
     /// Start data collection from exchange
+    ///
+    /// Symbols are split across connections of at most
+    /// `MAX_SYMBOLS_PER_CONNECTION`; symbol reloads add or restart only the
+    /// connections they touch.
     async fn start_data_collection(
         &self,
+        symbols: Vec<String>,
         tick_tx: mpsc::Sender<TickData>,
-    ) -> Result<tokio::task::JoinHandle<()>, ServiceError> {
+    ) -> Result<JoinHandle<()>, ServiceError> {
         let exchange = Arc::clone(&self.exchange);
-        let symbols = self.symbols.clone();
         let mut shutdown_rx = self.shutdown_tx.subscribe();
+        let mut control_rx = self
+            .control_rx
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| ServiceError::Task("Service already started".to_string()))?;
 
         let handle = spawn(async move {
+            let mut chunks: Vec<StreamChunk> = symbols
+                .chunks(MAX_SYMBOLS_PER_CONNECTION)
+                .map(|chunk| Self::spawn_stream(&exchange, chunk.to_vec(), &tick_tx))
+                .collect();
+
+            loop {
+                select! {
+                    Some(update) = control_rx.recv() => {
+                        chunks = Self::apply_symbol_update(&exchange, chunks, update, &tick_tx).await;
+                    }
+                    _ = shutdown_rx.recv() => {
+                        break;
+                    }
+                }
+            }
+
+            for chunk in chunks {
+                Self::stop_stream(chunk).await;
+            }
+
+            info!("Data collection stopped");
+        });
+
+        Ok(handle)
+    }
+
+    /// Tear down streams for removed symbols and subscribe added ones
+    async fn apply_symbol_update(
+        exchange: &Arc<dyn Exchange>,
+        chunks: Vec<StreamChunk>,
+        update: SymbolUpdate,
+        tick_tx: &mpsc::Sender<TickData>,
+    ) -> Vec<StreamChunk> {
+        let removed: HashSet<&String> = update.removed.iter().collect();
+        let mut kept = Vec::with_capacity(chunks.len());
+
+        for chunk in chunks {
+            if !chunk.symbols.iter().any(|s| removed.contains(s)) {
+                kept.push(chunk);
+                continue;
+            }
+
+            // Restart the connection without the removed symbols
+            let remaining: Vec<String> = chunk
+                .symbols
+                .iter()
+                .filter(|s| !removed.contains(s))
+                .cloned()
+                .collect();
+            Self::stop_stream(chunk).await;
+            if !remaining.is_empty() {
+                kept.push(Self::spawn_stream(exchange, remaining, tick_tx));
+            }
+        }
+
+        let subscribed: HashSet<&String> = kept.iter().flat_map(|c| c.symbols.iter()).collect();
+        let added: Vec<String> = update
+            .added
+            .iter()
+            .filter(|s| !subscribed.contains(s))
+            .cloned()
+            .collect();
+
+        for chunk in added.chunks(MAX_SYMBOLS_PER_CONNECTION) {
+            kept.push(Self::spawn_stream(exchange, chunk.to_vec(), tick_tx));
+        }
+
+        kept
+    }
+
+    /// Signal a stream to stop and wait for it
+    async fn stop_stream(chunk: StreamChunk) {
+        let _ = chunk.shutdown_tx.send(());
+        if let Err(e) = chunk.handle.await {
+            warn!("Stream task for {:?} failed: {}", chunk.symbols, e);
+        }
+    }
+
+    /// Subscribe one group of symbols, retrying until it completes or is stopped
+    fn spawn_stream(
+        exchange: &Arc<dyn Exchange>,
+        symbols: Vec<String>,
+        tick_tx: &mpsc::Sender<TickData>,
+    ) -> StreamChunk {
+        let (shutdown_tx, mut shutdown_rx) = broadcast::channel(1);
+        let exchange = Arc::clone(exchange);
+        let tick_tx = tick_tx.clone();
+        let stream_symbols = symbols.clone();
+
+        let handle = spawn(async move {
+            let symbols = stream_symbols;
             loop {
                 // Check for shutdown signal before attempting connection
                 if shutdown_rx.try_recv().is_ok() {
-                    info!("Data collection shutdown requested before connection attempt");
+                    info!("Stream for {:?} stopped before connection attempt", symbols);
                     break;
                 }
 
@@ -127,15 +426,15 @@ impl MarketDataService {
                     .await
                 {
                     Ok(()) => {
-                        info!("Exchange subscription completed normally");
+                        info!("Exchange subscription for {:?} completed normally", symbols);
                         break; // Normal completion, exit loop
                     }
                     Err(e) => {
-                        error!("Exchange subscription failed: {}", e);
+                        error!("Exchange subscription for {:?} failed: {}", symbols, e);
 
                         // Check if shutdown was requested before attempting retry
                         if shutdown_rx.try_recv().is_ok() {
-                            info!("Data collection shutdown requested, canceling retry");
+                            info!("Stream for {:?} stopped, canceling retry", symbols);
                             break;
                         }
 
@@ -146,30 +445,42 @@ impl MarketDataService {
                                 continue; // Retry connection
                             }
                             _ = shutdown_rx.recv() => {
-                                info!("Data collection shutdown requested during retry delay");
+                                info!("Stream for {:?} stopped during retry delay", symbols);
                                 break;
                             }
                         }
                     }
                 }
             }
-
-            info!("Data collection stopped");
         });
 
-        Ok(handle)
+        StreamChunk {
+            symbols,
+            shutdown_tx,
+            handle,
+        }
     }
 
     /// Start data processing pipeline
     async fn start_data_processing(
         &self,
         mut tick_rx: mpsc::Receiver<TickData>,
-    ) -> Result<tokio::task::JoinHandle<()>, ServiceError> {
+    ) -> Result<JoinHandle<()>, ServiceError> {
         let repository = Arc::clone(&self.repository);
         let batch_config = self.batch_config.clone();
         let stats = Arc::clone(&self.stats);
         let mut shutdown_rx = self.shutdown_tx.subscribe();
         let paper_trading = self.paper_trading.clone();
+        let mut tick_output = self
+            .tick_output
+            .lock()
+            .await
+            .take()
+            .map(|writer| writer.spawn(self.shutdown_tx.clone()));
+        let mut coalescer = TickCoalescer::new(self.coalesce_config.clone());
+        let price_tx = self.price_tx.clone();
+        let mut heartbeat = TickHeartbeat::new(self.heartbeat_config.clone());
+        let clock = Arc::clone(&self.clock);
 
         let handle = spawn(async move {
             let mut batch_buffer = Vec::with_capacity(batch_config.max_batch_size);
@@ -182,25 +493,29 @@ impl MarketDataService {
                     tick_opt = tick_rx.recv() => {
                         match tick_opt {
                             Some(tick) => {
-                                // Update cache immediately
-                                Self::update_cache_async(&repository, &tick, &stats).await;
-
-                                // Paper transaction processing
-                                if let Some(paper_trading_processor) = &paper_trading {
-                                    let mut processor = paper_trading_processor.lock().await;
-                                    if let Err(e) = processor.process_tick(&tick).await {
-                                        warn!("Paper trading processing failed: {}", e);
-                                    }
+                                if let Some(beat) = heartbeat.observe(&tick) {
+                                    info!(
+                                        "💓 {} @ {} | {} ticks received",
+                                        beat.symbol, beat.price, beat.tick_count
+                                    );
                                 }
-
-                                // Add to batch buffer
-                                batch_buffer.push(tick);
-
-                                // Update stats
-                                {
-                                    let mut s = stats.lock().await;
-                                    s.total_ticks_processed += 1;
+                                // Sending only fails when nobody is subscribed
+                                let _ = price_tx.send(PriceUpdate::from(&tick));
+                                // The writer closes its queue after a failed write
+                                if let Some(output) = &tick_output {
+                                    if output.send(tick.clone()).await.is_err() {
+                                        tick_output = None;
+                                    }
                                 }
+                                Self::handle_tick(
+                                    &repository,
+                                    paper_trading.as_ref(),
+                                    &mut coalescer,
+                                    &mut batch_buffer,
+                                    &stats,
+                                    &clock,
+                                    tick,
+                                ).await;
 
                                 // Check if the batch is full
                                 if batch_buffer.len() >= batch_config.max_batch_size {
@@ -209,6 +524,7 @@ impl MarketDataService {
                                         &mut batch_buffer,
                                         &batch_config,
                                         &stats,
+                                        &clock,
                                     ).await;
                                     last_flush = Instant::now();
                                 }
@@ -228,6 +544,7 @@ impl MarketDataService {
                                 &mut batch_buffer,
                                 &batch_config,
                                 &stats,
+                                &clock,
                             ).await;
                             last_flush = Instant::now();
                         }
@@ -241,6 +558,7 @@ impl MarketDataService {
                                 &mut batch_buffer,
                                 &batch_config,
                                 &stats,
+                                &clock,
                             ).await;
                         }
                         break;
@@ -254,13 +572,53 @@ impl MarketDataService {
         Ok(handle)
     }
 
-    /// Update cache asynchronously (non-blocking)
+    /// Cache a tick, run paper trading on it, and queue it for storage unless coalesced
+    async fn handle_tick(
+        repository: &TickDataRepository,
+        paper_trading: Option<&Arc<Mutex<PaperTradingProcessor>>>,
+        coalescer: &mut TickCoalescer,
+        batch_buffer: &mut Vec<TickData>,
+        stats: &Arc<Mutex<BatchStats>>,
+        clock: &SharedClock,
+        tick: TickData,
+    ) {
+        // Update cache immediately
+        Self::update_cache_async(repository, &tick, stats).await;
+
+        // Paper transaction processing
+        if let Some(paper_trading_processor) = paper_trading {
+            let mut processor = paper_trading_processor.lock().await;
+            if let Err(e) = processor.process_tick(&tick).await {
+                warn!("Paper trading processing failed: {}", e);
+            }
+        }
+
+        let persist = coalescer.should_persist(&tick);
+        if persist {
+            // Add to batch buffer
+            batch_buffer.push(tick);
+        }
+
+        // Update stats
+        {
+            let mut s = stats.lock().await;
+            s.total_ticks_processed += 1;
+            s.last_tick_time = Some(clock.now());
+            if !persist {
+                s.coalesced_ticks += 1;
+            }
+        }
+    }
+
+    /// Update cache and queue the tick for downstream consumers; both are
+    /// best-effort, so failures are only logged and counted
     async fn update_cache_async(
         repository: &TickDataRepository,
         tick: &TickData,
         stats: &Arc<Mutex<BatchStats>>,
     ) {
-        if let Err(e) = repository.get_cache().push_tick(tick).await {
+        let cache = repository.get_cache();
+        if let Err(e) = cache.push_tick(tick).await {
             warn!("Failed to update cache for tick {}: {}", tick.trade_id, e);
 
             // Update failure stats
@@ -271,6 +629,11 @@ impl MarketDataService {
         } else {
             debug!("Cache updated for symbol: {}", tick.symbol);
         }
+
+        // The cache counts publish failures itself; see `get_stats`
+        if let Err(e) = cache.publish_tick(tick) {
+            warn!("Failed to publish tick {}: {}", tick.trade_id, e);
+        }
     }
 
     /// Flush batch to database with retry logic
@@ -279,6 +642,7 @@ impl MarketDataService {
         batch_buffer: &mut Vec<TickData>,
         config: &BatchConfig,
         stats: &Arc<Mutex<BatchStats>>,
+        clock: &SharedClock,
     ) {
         if batch_buffer.is_empty() {
             return;
@@ -288,7 +652,15 @@ impl MarketDataService {
         let mut attempt = 0;
 
         loop {
-            match repository.batch_insert(batch_buffer.clone()).await {
+            let result = if config.use_copy {
+                repository
+                    .copy_insert(batch_buffer)
+                    .await
+                    .map(|rows| rows as usize)
+            } else {
+                repository.batch_insert(batch_buffer.clone()).await
+            };
+            match result {
                 Ok(inserted_count) => {
                     info!(
                         "Successfully flushed batch: {} ticks inserted",
@@ -299,7 +671,7 @@ impl MarketDataService {
                     {
                         let mut s = stats.lock().await;
                         s.total_batches_flushed += 1;
-                        s.last_flush_time = Some(chrono::Utc::now());
+                        s.last_flush_time = Some(clock.now());
                     }
 
                     batch_buffer.clear();
@@ -342,3 +714,284 @@ impl MarketDataService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::{ExchangeError, HistoricalTradeParams, Venue};
+    use crate::test_support::create_repository;
+    use async_trait::async_trait;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+    use trading_common::data::clock::MockClock;
+    use trading_common::data::types::TradeSide;
+
+    /// Exchange that records subscriptions and holds them open until shut down
+    #[derive(Default)]
+    struct MockExchange {
+        subscriptions: std::sync::Mutex<Vec<Vec<String>>>,
+        active: std::sync::Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Exchange for MockExchange {
+        fn venue(&self) -> Venue {
+            Venue::Binance
+        }
+
+        async fn subscribe_trades(
+            &self,
+            symbols: &[String],
+            _callback: Box<dyn Fn(TickData) + Send + Sync>,
+            mut shutdown_rx: broadcast::Receiver<()>,
+        ) -> Result<(), ExchangeError> {
+            self.subscriptions.lock().unwrap().push(symbols.to_vec());
+            self.active.lock().unwrap().push(symbols.to_vec());
+            let _ = shutdown_rx.recv().await;
+            self.active.lock().unwrap().retain(|s| s != symbols);
+            Ok(())
+        }
+
+        async fn get_historical_trades(
+            &self,
+            _symbol: &str,
+            _params: &HistoricalTradeParams,
+        ) -> Result<Vec<TickData>, ExchangeError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn symbols(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    async fn wait_for_active(exchange: &MockExchange, expected: &[Vec<String>]) {
+        for _ in 0..100 {
+            if exchange.active.lock().unwrap().as_slice() == expected {
+                return;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        panic!(
+            "expected active streams {:?}, got {:?}",
+            expected,
+            exchange.active.lock().unwrap()
+        );
+    }
+    async fn cleanup(repository: &TickDataRepository, symbol: &str) {
+        sqlx::query("DELETE FROM tick_data WHERE symbol = $1")
+            .bind(symbol)
+            .execute(repository.get_pool())
+            .await
+            .expect("Failed to clean up database");
+        repository
+            .get_cache()
+            .clear_symbol(symbol)
+            .await
+            .expect("Failed to clear cache");
+    }
+
+    #[tokio::test]
+    async fn test_coalesced_ticks_are_cached_but_not_persisted() {
+        let symbol = "BTCUSDT_TEST_COAL";
+        let repository = create_repository().await;
+        cleanup(&repository, symbol).await;
+
+        let mut coalescer = TickCoalescer::new(CoalesceConfig {
+            min_price_change: Some(Decimal::from_str("0.01").unwrap()),
+            ..CoalesceConfig::default()
+        });
+        let stats = Arc::new(Mutex::new(BatchStats::default()));
+        let mut batch_buffer = Vec::new();
+        let stamped_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock: SharedClock = Arc::new(MockClock::new(stamped_at));
+
+        // Only the first tick and the 1% move clear the threshold
+        let base_time = Utc::now();
+        let prices = ["100", "100.2", "100.5", "101", "100.9"];
+        for (i, price) in prices.iter().enumerate() {
+            let tick = TickData::new(
+                base_time + chrono::Duration::milliseconds(i as i64),
+                symbol.to_string(),
+                Decimal::from_str(price).unwrap(),
+                Decimal::ONE,
+                TradeSide::Buy,
+                format!("coal{}", i),
+                false,
+            );
+            MarketDataService::handle_tick(
+                &repository,
+                None,
+                &mut coalescer,
+                &mut batch_buffer,
+                &stats,
+                &clock,
+                tick,
+            )
+            .await;
+        }
+
+        // Every tick reached the cache
+        let cached = repository
+            .get_cache()
+            .get_recent_ticks(symbol, 10)
+            .await
+            .unwrap();
+        assert_eq!(cached.len(), 5);
+
+        MarketDataService::flush_batch_with_retry(
+            &repository,
+            &mut batch_buffer,
+            &BatchConfig::default(),
+            &stats,
+            &clock,
+        )
+        .await;
+
+        // Only threshold-crossing ticks were persisted
+        let stored: Vec<String> = sqlx::query_scalar(
+            "SELECT trade_id FROM tick_data WHERE symbol = $1 ORDER BY timestamp",
+        )
+        .bind(symbol)
+        .fetch_all(repository.get_pool())
+        .await
+        .unwrap();
+        assert_eq!(stored, vec!["coal0", "coal3"]);
+
+        let stats = stats.lock().await;
+        assert_eq!(stats.total_ticks_processed, 5);
+        assert_eq!(stats.coalesced_ticks, 3);
+        assert_eq!(stats.last_tick_time, Some(stamped_at));
+        assert_eq!(stats.last_flush_time, Some(stamped_at));
+
+        cleanup(&repository, symbol).await;
+    }
+
+    #[tokio::test]
+    async fn test_zero_channel_capacity_is_raised_to_one() {
+        let service = MarketDataService::new(
+            Arc::new(MockExchange::default()),
+            Arc::new(create_repository().await),
+            symbols(&["BTCUSDT"]),
+        )
+        .with_channel_config(ChannelConfig {
+            shutdown_capacity: 0,
+            price_capacity: 0,
+        });
+
+        let mut shutdown_rx = service.get_shutdown_tx().subscribe();
+        service.get_shutdown_tx().send(()).unwrap();
+        assert!(shutdown_rx.recv().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reload_symbols_keeps_existing_streams() {
+        let exchange = Arc::new(MockExchange::default());
+        let service = Arc::new(MarketDataService::new(
+            exchange.clone(),
+            Arc::new(create_repository().await),
+            symbols(&["BTCUSDT", "ETHUSDT"]),
+        ));
+        let shutdown_tx = service.get_shutdown_tx();
+        let running = spawn({
+            let service = Arc::clone(&service);
+            async move { service.start().await }
+        });
+
+        wait_for_active(&exchange, &[symbols(&["BTCUSDT", "ETHUSDT"])]).await;
+
+        // Adding a symbol opens a new stream next to the existing one
+        service
+            .reload_symbols(symbols(&["BTCUSDT", "ETHUSDT", "SOLUSDT"]))
+            .await
+            .unwrap();
+        wait_for_active(
+            &exchange,
+            &[symbols(&["BTCUSDT", "ETHUSDT"]), symbols(&["SOLUSDT"])],
+        )
+        .await;
+
+        // Removing one only restarts the connection that carried it
+        service
+            .reload_symbols(symbols(&["BTCUSDT", "SOLUSDT"]))
+            .await
+            .unwrap();
+        wait_for_active(&exchange, &[symbols(&["SOLUSDT"]), symbols(&["BTCUSDT"])]).await;
+
+        assert_eq!(
+            *exchange.subscriptions.lock().unwrap(),
+            vec![
+                symbols(&["BTCUSDT", "ETHUSDT"]),
+                symbols(&["SOLUSDT"]),
+                symbols(&["BTCUSDT"]),
+            ]
+        );
+        assert!(service.reload_symbols(Vec::new()).await.is_err());
+
+        shutdown_tx.send(()).unwrap();
+        running.await.unwrap().unwrap();
+        assert!(exchange.active.lock().unwrap().is_empty());
+    }
+
+    async fn http_get(addr: SocketAddr, path: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_exposes_counters() {
+        let service = MarketDataService::new(
+            Arc::new(MockExchange::default()),
+            Arc::new(create_repository().await),
+            symbols(&["BTCUSDT"]),
+        );
+        service.stats.lock().await.total_ticks_processed = 42;
+
+        let addr = service
+            .serve_metrics("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+
+        let metrics = http_get(addr, "/metrics").await;
+        assert!(metrics.starts_with("HTTP/1.1 200 OK"));
+        for counter in [
+            "ticks_processed_total",
+            "batches_flushed_total",
+            "failed_batches_total",
+            "cache_update_failures_total",
+            "tick_publish_failures_total",
+        ] {
+            assert!(
+                metrics.contains(&format!("# TYPE {} counter", counter)),
+                "missing {}",
+                counter
+            );
+        }
+        assert!(metrics.contains("\nticks_processed_total 42\n"));
+
+        // Just started, so the missing first flush is not stale yet
+        assert!(http_get(addr, "/health")
+            .await
+            .starts_with("HTTP/1.1 200 OK"));
+        assert!(http_get(addr, "/other").await.starts_with("HTTP/1.1 404"));
+
+        // The endpoint stops with the service
+        service.get_shutdown_tx().send(()).unwrap();
+        for _ in 0..100 {
+            if tokio::net::TcpStream::connect(addr).await.is_err() {
+                return;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        panic!("metrics endpoint still accepting after shutdown");
+    }
+}
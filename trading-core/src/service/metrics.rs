@@ -0,0 +1,178 @@
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, Mutex};
+use tokio::{select, spawn};
+use tracing::{debug, info, warn};
+
+use super::{BatchStats, MetricsConfig};
+use crate::exchange::Exchange;
+use trading_common::data::clock::SharedClock;
+use trading_common::data::repository::TickDataRepository;
+
+/// Render the counters in the Prometheus text exposition format
+pub fn render_prometheus(stats: &BatchStats) -> String {
+    let counters = [
+        (
+            "ticks_processed_total",
+            "Ticks received from the exchange",
+            stats.total_ticks_processed,
+        ),
+        (
+            "batches_flushed_total",
+            "Tick batches written to the database",
+            stats.total_batches_flushed,
+        ),
+        (
+            "failed_batches_total",
+            "Tick batches dropped after all retries",
+            stats.total_failed_batches,
+        ),
+        (
+            "batch_retries_total",
+            "Retried tick batch writes",
+            stats.total_retry_attempts,
+        ),
+        (
+            "cache_update_failures_total",
+            "Ticks that could not be cached",
+            stats.cache_update_failures,
+        ),
+        (
+            "tick_publish_failures_total",
+            "Live ticks that could not be published to Redis pub/sub",
+            stats.publish_failures,
+        ),
+        (
+            "coalesced_ticks_total",
+            "Ticks cached but not persisted due to coalescing",
+            stats.coalesced_ticks,
+        ),
+        (
+            "dropped_frames_total",
+            "Exchange frames dropped due to a full message buffer",
+            stats.dropped_frames,
+        ),
+    ];
+
+    let mut output = String::new();
+    for (name, help, value) in counters {
+        let _ = writeln!(output, "# HELP {} {}", name, help);
+        let _ = writeln!(output, "# TYPE {} counter", name);
+        let _ = writeln!(output, "{} {}", name, value);
+    }
+    output
+}
+
+/// Whether the last batch flush (or the server start, before the first
+/// flush) is at most `max_flush_age_secs` old
+pub fn is_healthy(
+    stats: &BatchStats,
+    config: &MetricsConfig,
+    started_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> bool {
+    let last_flush = stats.last_flush_time.unwrap_or(started_at);
+    now - last_flush <= Duration::seconds(config.max_flush_age_secs as i64)
+}
+
+/// Answer `GET /metrics` and `GET /health` until shutdown
+pub(super) async fn serve(
+    listener: TcpListener,
+    stats: Arc<Mutex<BatchStats>>,
+    exchange: Arc<dyn Exchange>,
+    repository: Arc<TickDataRepository>,
+    config: MetricsConfig,
+    clock: SharedClock,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) {
+    if let Ok(addr) = listener.local_addr() {
+        info!("Metrics endpoint listening on {}", addr);
+    }
+    let started_at = clock.now();
+
+    loop {
+        select! {
+            accepted = listener.accept() => {
+                match accepted {
+                    Ok((stream, _)) => {
+                        let mut snapshot = stats.lock().await.clone();
+                        snapshot.dropped_frames = exchange.dropped_frames();
+                        snapshot.publish_failures = repository.get_cache().publish_failures();
+                        let healthy = is_healthy(&snapshot, &config, started_at, clock.now());
+                        spawn(async move {
+                            if let Err(e) = respond(stream, &snapshot, healthy).await {
+                                debug!("Metrics request failed: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Metrics endpoint accept failed: {}", e),
+                }
+            }
+            _ = shutdown_rx.recv() => break,
+        }
+    }
+}
+
+async fn respond(mut stream: TcpStream, stats: &BatchStats, healthy: bool) -> std::io::Result<()> {
+    let mut buf = [0u8; 1024];
+    let n = stream.read(&mut buf).await?;
+    let request = String::from_utf8_lossy(&buf[..n]);
+
+    let (status, content_type, body) = if request.starts_with("GET /metrics ") {
+        (
+            "200 OK",
+            "text/plain; version=0.0.4",
+            render_prometheus(stats),
+        )
+    } else if request.starts_with("GET /health ") {
+        match healthy {
+            true => ("200 OK", "text/plain", "ok".to_string()),
+            false => (
+                "503 Service Unavailable",
+                "text/plain",
+                "no recent flush".to_string(),
+            ),
+        }
+    } else {
+        ("404 Not Found", "text/plain", "not found".to_string())
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_healthy_only_with_recent_flush() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let config = MetricsConfig {
+            max_flush_age_secs: 60,
+        };
+        let healthy_at = |stats: &BatchStats, secs| {
+            is_healthy(stats, &config, start, start + Duration::seconds(secs))
+        };
+        let mut stats = BatchStats::default();
+
+        // Before the first flush, the server start counts as one
+        assert!(healthy_at(&stats, 60));
+        assert!(!healthy_at(&stats, 61));
+
+        stats.last_flush_time = Some(start + Duration::seconds(100));
+        assert!(healthy_at(&stats, 150));
+        assert!(!healthy_at(&stats, 161));
+    }
+}
@@ -1,8 +1,22 @@
+pub mod backfill;
+pub mod coalesce;
 pub mod errors;
+pub mod health;
+pub mod heartbeat;
 pub mod market_data;
+pub mod metrics;
+pub mod ndjson;
+#[allow(dead_code)] // PriceFeed is only consumed by library users
+pub mod price_feed;
 pub mod types;
 
 // Re-export main interfaces
+pub use backfill::BackfillService;
+pub use coalesce::TickCoalescer;
 pub use errors::ServiceError;
+pub use health::ReadinessProbe;
+pub use heartbeat::TickHeartbeat;
 pub use market_data::MarketDataService;
+pub use ndjson::NdjsonWriter;
+pub use price_feed::{PriceFeed, PriceUpdate};
 pub use types::*;
@@ -0,0 +1,130 @@
+use std::io::{self, Write};
+
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::spawn_blocking;
+use tracing::error;
+
+use trading_common::data::types::TickData;
+
+/// Ticks queued for the writer thread before the processing loop waits
+const OUTPUT_QUEUE_CAPACITY: usize = 1024;
+
+/// Writes ticks as newline-delimited JSON, one object per line
+///
+/// Each line is flushed as it is written so downstream readers of a pipe
+/// see ticks as they arrive.
+pub struct NdjsonWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn write_tick(&mut self, tick: &TickData) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, tick)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+impl<W: Write + Send + 'static> NdjsonWriter<W> {
+    /// Write ticks sent to the returned queue from a blocking thread, so a
+    /// slow reader never stalls the async runtime
+    ///
+    /// A failed write (e.g. a closed pipe) stops the thread, which closes the
+    /// queue, and sends on `shutdown_tx`.
+    pub fn spawn(mut self, shutdown_tx: broadcast::Sender<()>) -> mpsc::Sender<TickData> {
+        let (tx, mut rx) = mpsc::channel::<TickData>(OUTPUT_QUEUE_CAPACITY);
+        spawn_blocking(move || {
+            while let Some(tick) = rx.blocking_recv() {
+                if let Err(e) = self.write_tick(&tick) {
+                    error!("NDJSON output failed, shutting down: {}", e);
+                    let _ = shutdown_tx.send(());
+                    break;
+                }
+            }
+        });
+        tx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal::Decimal;
+    use trading_common::data::types::TradeSide;
+
+    #[test]
+    fn test_each_tick_is_one_json_line() {
+        let tick = |trade_id: &str, side| {
+            TickData::new(
+                Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                "BTCUSDT".to_string(),
+                Decimal::new(5000012, 2),
+                Decimal::new(15, 3),
+                side,
+                trade_id.to_string(),
+                side == TradeSide::Sell,
+            )
+        };
+
+        let mut writer = NdjsonWriter::new(Vec::new());
+        writer.write_tick(&tick("1", TradeSide::Buy)).unwrap();
+        writer.write_tick(&tick("2", TradeSide::Sell)).unwrap();
+
+        let output = String::from_utf8(writer.writer).unwrap();
+        assert!(output.ends_with('\n'));
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        let first = &lines[0];
+        assert_eq!(first["timestamp"], "2024-01-01T00:00:00Z");
+        assert_eq!(first["symbol"], "BTCUSDT");
+        assert_eq!(first["price"], "50000.12");
+        assert_eq!(first["quantity"], "0.015");
+        assert_eq!(first["side"], "BUY");
+        assert_eq!(first["trade_id"], "1");
+        assert_eq!(first["is_buyer_maker"], false);
+        assert_eq!(lines[1]["side"], "SELL");
+        assert_eq!(lines[1]["is_buyer_maker"], true);
+    }
+
+    /// Pipe whose reader has gone away
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_stops_writer_and_signals_shutdown() {
+        let (shutdown_tx, mut shutdown_rx) = broadcast::channel(1);
+        let output = NdjsonWriter::new(ClosedPipe).spawn(shutdown_tx);
+
+        let tick = TickData::new(
+            Utc::now(),
+            "BTCUSDT".to_string(),
+            Decimal::ONE,
+            Decimal::ONE,
+            TradeSide::Buy,
+            "1".to_string(),
+            false,
+        );
+        output.send(tick).await.unwrap();
+
+        shutdown_rx.recv().await.unwrap();
+        output.closed().await;
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+use trading_common::data::types::TickData;
+
+/// Latest trade price of a symbol, published for every processed tick
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceUpdate {
+    pub symbol: String,
+    pub price: Decimal,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<&TickData> for PriceUpdate {
+    fn from(tick: &TickData) -> Self {
+        Self {
+            symbol: tick.symbol.clone(),
+            price: tick.price,
+            timestamp: tick.timestamp,
+        }
+    }
+}
+
+/// Subscriber to the service's price broadcast
+///
+/// The broadcast never blocks the publisher: a subscriber that falls more
+/// than the channel capacity behind loses the oldest updates. `recv` handles
+/// that lag by skipping ahead to the oldest update still buffered, so a slow
+/// subscriber always converges back to the latest prices. Consumers that
+/// need every update should use a larger `price_capacity` instead.
+pub struct PriceFeed {
+    rx: broadcast::Receiver<PriceUpdate>,
+    skipped: u64,
+}
+
+impl PriceFeed {
+    pub fn new(rx: broadcast::Receiver<PriceUpdate>) -> Self {
+        Self { rx, skipped: 0 }
+    }
+
+    /// Next price update, or None once the service has dropped the feed
+    pub async fn recv(&mut self) -> Option<PriceUpdate> {
+        loop {
+            match self.rx.recv().await {
+                Ok(update) => return Some(update),
+                Err(RecvError::Lagged(missed)) => {
+                    self.skipped += missed;
+                    warn!("Price subscriber lagged, skipped {} updates", missed);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Updates lost to lag since subscribing
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn update(i: i64) -> PriceUpdate {
+        PriceUpdate {
+            symbol: "BTCUSDT".to_string(),
+            price: Decimal::from(i),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_recovers_from_lag() {
+        let (tx, rx) = broadcast::channel(8);
+        let mut feed = PriceFeed::new(rx);
+
+        // A fast publisher never waits on the subscriber
+        let publisher = tokio::spawn(async move {
+            for i in 1..=1000 {
+                let _ = tx.send(update(i));
+                if i % 100 == 0 {
+                    tokio::task::yield_now().await;
+                }
+            }
+        });
+
+        let mut received = Vec::new();
+        let drained = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(update) = feed.recv().await {
+                received.push(update.price);
+                // Read slowly so the buffer overflows
+                tokio::time::sleep(Duration::from_micros(50)).await;
+            }
+        })
+        .await;
+        publisher.await.unwrap();
+
+        assert!(drained.is_ok(), "subscriber deadlocked");
+        assert!(feed.skipped() > 0);
+        assert_eq!(received.len() as u64 + feed.skipped(), 1000);
+        assert_eq!(received.last(), Some(&Decimal::from(1000)));
+        assert!(received.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 
 /// Batch processing configuration
 #[derive(Debug, Clone)]
@@ -11,6 +12,8 @@ pub struct BatchConfig {
     pub max_retry_attempts: u32,
     /// Delay between retry attempts (in milliseconds)
     pub retry_delay_ms: u64,
+    /// Write tick batches with COPY instead of multi-row INSERT
+    pub use_copy: bool,
 }
 
 impl Default for BatchConfig {
@@ -20,10 +23,66 @@ impl Default for BatchConfig {
             max_batch_time: 1,
             max_retry_attempts: 3,
             retry_delay_ms: 1000,
+            use_copy: false,
         }
     }
 }
 
+/// Capacities of the service's broadcast channels
+#[derive(Debug, Clone)]
+pub struct ChannelConfig {
+    /// Buffered shutdown signals
+    pub shutdown_capacity: usize,
+    /// Price updates buffered per subscriber before it lags and skips ahead
+    pub price_capacity: usize,
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        Self {
+            shutdown_capacity: 16,
+            price_capacity: 1024,
+        }
+    }
+}
+
+/// Storage coalescing thresholds; a tick is persisted when any enabled
+/// threshold is met relative to the last stored tick of its symbol.
+/// All ticks still reach the cache and strategies.
+#[derive(Debug, Clone, Default)]
+pub struct CoalesceConfig {
+    /// Relative price change, e.g. 0.0005 for 5 bps
+    pub min_price_change: Option<Decimal>,
+    /// Trade quantity at or above which a tick is always stored
+    pub min_quantity: Option<Decimal>,
+    /// Time since the last stored tick (in milliseconds)
+    pub min_interval_ms: Option<u64>,
+}
+
+impl CoalesceConfig {
+    /// Coalescing is active when at least one threshold is set
+    pub fn is_enabled(&self) -> bool {
+        self.min_price_change.is_some()
+            || self.min_quantity.is_some()
+            || self.min_interval_ms.is_some()
+    }
+}
+
+/// Sampled info-level tick logging; disabled when neither cadence is set
+#[derive(Debug, Clone, Default)]
+pub struct HeartbeatConfig {
+    /// Log a symbol's pulse every N ticks
+    pub every_ticks: Option<u64>,
+    /// Log a symbol's pulse at least this often (in seconds) while ticks arrive
+    pub every_secs: Option<u64>,
+}
+
+impl HeartbeatConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.every_ticks.is_some() || self.every_secs.is_some()
+    }
+}
+
 /// Batch processing statistics
 #[derive(Debug, Clone, Default)]
 pub struct BatchStats {
@@ -37,6 +96,60 @@ pub struct BatchStats {
     pub total_failed_batches: u64,
     /// Cache update failures
     pub cache_update_failures: u64,
+    /// Live ticks that could not be published to Redis pub/sub
+    pub publish_failures: u64,
+    /// Exchange frames dropped due to a full message buffer
+    pub dropped_frames: u64,
+    /// Ticks cached but not persisted due to coalescing
+    pub coalesced_ticks: u64,
     /// Last flush time
     pub last_flush_time: Option<DateTime<Utc>>,
+    /// When the most recent tick was received
+    pub last_tick_time: Option<DateTime<Utc>>,
+}
+
+/// Readiness probe thresholds
+#[derive(Debug, Clone)]
+pub struct ReadinessConfig {
+    /// Time after start during which no tick is required (in seconds)
+    pub startup_grace_secs: u64,
+    /// Maximum age of the last tick once the grace period is over (in seconds)
+    pub max_tick_age_secs: u64,
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        Self {
+            startup_grace_secs: 120,
+            max_tick_age_secs: 60,
+        }
+    }
+}
+
+/// Settings of the `/metrics` and `/health` endpoint
+#[derive(Debug, Clone)]
+pub struct MetricsConfig {
+    /// Maximum age of the last batch flush for `/health` to answer 200 (in seconds)
+    pub max_flush_age_secs: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            max_flush_age_secs: 60,
+        }
+    }
+}
+
+/// Outcome of a gap-filling backfill run
+#[derive(Debug, Clone, Default)]
+pub struct BackfillReport {
+    /// Gaps detected before fetching
+    pub gaps_found: usize,
+    /// Gaps that no longer exist after fetching
+    pub gaps_filled: usize,
+    /// Gaps still present (e.g. the exchange had no trades there either)
+    pub gaps_remaining: usize,
+    /// Newly inserted ticks
+    pub ticks_inserted: usize,
 }
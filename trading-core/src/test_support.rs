@@ -0,0 +1,18 @@
+// Shared fixtures for tests that need the database and Redis
+
+use sqlx::PgPool;
+use trading_common::data::{cache::TieredCache, repository::TickDataRepository};
+
+/// Repository on the `DATABASE_URL` database with a `REDIS_URL` cache
+pub async fn create_repository() -> TickDataRepository {
+    dotenv::dotenv().ok();
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let redis_url = std::env::var("REDIS_URL").expect("REDIS_URL must be set");
+    let pool = PgPool::connect(&database_url)
+        .await
+        .expect("Failed to connect to database");
+    let cache = TieredCache::new((100, 300), (&redis_url, 1000, 3600))
+        .await
+        .expect("Failed to create cache");
+    TickDataRepository::new(pool, cache)
+}